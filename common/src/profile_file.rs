@@ -0,0 +1,96 @@
+use crate::types::Profile;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// A single profile as a file, for sharing tuned fan curves and CPU settings between machines.
+// The format marker keeps other JSON from being taken for a profile, the version lets later
+// releases read older files. Files from a newer release are refused rather than half-read.
+pub const FORMAT: &str = "tuxedo-control-center-profile";
+pub const VERSION: u32 = 1;
+pub const EXTENSION: &str = "tccprofile.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFile {
+    pub format: String,
+    pub version: u32,
+    pub profile: Profile,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Parse(String),
+    NotAProfile,
+    NewerVersion(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Parse(e) => write!(f, "Not a valid profile file: {}", e),
+            ImportError::NotAProfile => write!(f, "Not a TUXEDO Control Center profile"),
+            ImportError::NewerVersion(version) => write!(f,
+                "Profile file version {} is from a newer release, this one reads up to version {}", version, VERSION),
+            ImportError::Invalid(e) => write!(f, "Invalid profile: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Pretty-printed JSON, machine specific flags like is_default are cleared
+pub fn export(profile: &Profile) -> String {
+    let mut profile = profile.clone();
+    profile.is_default = false;
+    let file = ProfileFile { format: FORMAT.to_string(), version: VERSION, profile };
+    serde_json::to_string_pretty(&file).unwrap_or_default()
+}
+
+pub fn import(text: &str) -> Result<Profile, ImportError> {
+    // The marker and version first, so a newer file gets a useful message instead of a field error
+    let header: serde_json::Value = serde_json::from_str(text).map_err(|e| ImportError::Parse(e.to_string()))?;
+    if header.get("format").and_then(|f| f.as_str()) != Some(FORMAT) {
+        return Err(ImportError::NotAProfile);
+    }
+    match header.get("version").and_then(|v| v.as_u64()) {
+        Some(version) if version > VERSION as u64 => return Err(ImportError::NewerVersion(version as u32)),
+        Some(version) if version >= 1 => {}
+        _ => return Err(ImportError::NotAProfile),
+    }
+
+    let file: ProfileFile = serde_json::from_value(header).map_err(|e| ImportError::Parse(e.to_string()))?;
+    validate(&file.profile).map_err(ImportError::Invalid)?;
+    let mut profile = file.profile;
+    profile.is_default = false;
+    Ok(profile)
+}
+
+fn validate(profile: &Profile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("the profile has no name".to_string());
+    }
+
+    let curves = &profile.fan_settings.curves;
+    for (i, curve) in curves.iter().enumerate() {
+        if curves[..i].iter().any(|other| other.fan_id == curve.fan_id) {
+            return Err(format!("fan {} has two curves", curve.fan_id));
+        }
+        if let Some((temp, speed)) = curve.points.iter().find(|(temp, speed)| *temp > 100 || *speed > 100) {
+            return Err(format!("fan {}: point [{}, {}] is above 100 °C / 100 %", curve.fan_id, temp, speed));
+        }
+        if curve.points.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(format!("fan {}: temperatures must increase from point to point", curve.fan_id));
+        }
+    }
+    if let Some(offset) = profile.fan_settings.offsets.iter().find(|o| !(-100..=100).contains(&o.percent)) {
+        return Err(format!("fan {}: offset {} is out of range (-100 to 100)", offset.fan_id, offset.percent));
+    }
+
+    let cpu = &profile.cpu_settings;
+    if let (Some(min), Some(max)) = (cpu.min_frequency, cpu.max_frequency) {
+        if min > max {
+            return Err("the minimum CPU frequency is above the maximum".to_string());
+        }
+    }
+    Ok(())
+}
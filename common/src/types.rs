@@ -1,10 +1,186 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub product_name: String,
     pub manufacturer: String,
     pub bios_version: String,
+    #[serde(default)]
+    pub microcode: Option<String>,
+    #[serde(default)]
+    pub vulnerabilities: Vec<CpuVulnerability>,
+    #[serde(default)]
+    pub memory_modules: Vec<MemoryModule>,
+}
+
+// Installed RAM module from SMBIOS type 17, empty slots are left out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryModule {
+    pub slot: String,
+    pub size_mb: u64,
+    pub memory_type: Option<String>,    // e.g. "DDR5"
+    pub speed_mts: Option<u32>,         // Maximum rated speed
+    pub configured_speed_mts: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+}
+
+// Panel or monitor as described by its EDID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    pub connector: String,        // e.g. "eDP-1"
+    pub internal: bool,
+    pub manufacturer: String,     // PNP ID, e.g. "BOE"
+    pub model: Option<String>,
+    pub native_resolution: Option<(u32, u32)>,
+    pub refresh_rates: Vec<u32>,  // Hz, highest first
+    pub max_brightness_nits: Option<u32>,  // From HDR static metadata, if the panel reports it
+}
+
+// USB-C charging controls of tuxedo-drivers, empty lists if the model has none
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChargingOptions {
+    pub priorities: Vec<String>,  // e.g. "charge_battery", "performance"
+    pub profiles: Vec<String>,    // e.g. "high_capacity", "balanced", "stationary"
+    pub priority: Option<String>,
+    pub profile: Option<String>,
+}
+
+// Mechanism used to limit battery charging, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeBackend {
+    Flexicharger,      // tuxedo-drivers battery hook, thresholds only apply with charge_type "Custom"
+    KernelThresholds,  // power_supply charge_control_start/end_threshold
+    ChargeBehaviour,   // Only charge_behaviour, no thresholds
+}
+
+impl ChargeBackend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChargeBackend::Flexicharger => "TUXEDO flexicharger",
+            ChargeBackend::KernelThresholds => "Kernel charge thresholds",
+            ChargeBackend::ChargeBehaviour => "Kernel charge behaviour",
+        }
+    }
+}
+
+// What the daemon found for battery charge limiting, backend None if nothing is supported
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChargeControlInfo {
+    pub backend: Option<ChargeBackend>,
+    pub battery: String,             // power_supply name, e.g. "BAT0"
+    pub start_threshold: bool,       // Some drivers only limit the end of charge
+    pub behaviours: Vec<String>,     // charge_behaviour values, e.g. "auto", "inhibit-charge"
+    pub behaviour: Option<String>,
+}
+
+// Charger currently feeding the laptop, as far as power_supply describes it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AcAdapterInfo {
+    pub online: bool,
+    pub name: String,                   // power_supply name, e.g. "ADP1" or "ucsi-source-psy-USBC000:001"
+    pub usb_c: bool,
+    pub watts: Option<u32>,             // None if the supply doesn't report its limits
+    pub required_watts: Option<u32>,    // Sustained CPU + dGPU limits plus platform overhead
+    pub tdp_capped_watts: Option<u32>,  // CPU limit while the daemon caps it for a weak charger
+    #[serde(default)]
+    pub can_set_limit: bool,            // RAPL PL1 or the Uniwill TDP is there to cap
+}
+
+impl AcAdapterInfo {
+    pub fn underpowered(&self) -> bool {
+        match (self.watts, self.required_watts) {
+            (Some(watts), Some(required)) => self.online && watts < required,
+            _ => false,
+        }
+    }
+}
+
+// What apply_profile would do, produced by a dry run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplyPreview {
+    pub writes: Vec<PlannedWrite>,
+    pub error: Option<String>,  // Where the real apply would have stopped
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedWrite {
+    pub kind: WriteKind,
+    pub target: String,             // File path, ioctl or program name
+    pub old_value: Option<String>,  // Current file contents, if readable
+    pub new_value: String,          // Value written or command arguments
+}
+
+// Profiles owned by the daemon, managed by the administrator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemProfiles {
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub boot_profile: Option<String>,  // Applied when the daemon first starts after boot
+}
+
+// Profiles the daemon applies when the power source changes, copies of the ones picked in
+// the GUI so switching works without it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerSourceProfiles {
+    pub enabled: bool,
+    pub on_ac: Option<Profile>,
+    pub on_battery: Option<Profile>,
+}
+
+// App rules as sent to the daemon, with copies of the profiles they name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppProfiles {
+    pub rules: Vec<AppProfileRule>,
+    pub profiles: Vec<Profile>,
+}
+
+// Timings of the last apply_profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplyReport {
+    pub profile: String,
+    pub steps: Vec<ApplyStepTiming>,
+    pub slow_writes: Vec<SlowWrite>,   // Writes that took long enough to point at a firmware problem
+    pub total_ms: f64,
+    pub error: Option<String>,
+}
+
+// Sent while a profile is being applied, and once more when it is done
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ApplyProgress {
+    pub profile: String,
+    pub running: bool,
+    pub step: String,  // Step running now, empty before the first one
+    pub steps_done: u32,
+    pub error: Option<String>,  // Why the last apply failed, timed out or was cancelled
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyStepTiming {
+    pub name: String,
+    pub millis: f64,
+    pub failed: bool,   // The apply stopped here
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowWrite {
+    pub target: String,
+    pub millis: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WriteKind {
+    File,
+    Ioctl,
+    Command,
+}
+
+// One entry of /sys/devices/system/cpu/vulnerabilities
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuVulnerability {
+    pub name: String,
+    pub status: String,  // e.g. "Not affected", "Mitigation: ...", "Vulnerable"
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +196,10 @@ pub struct CpuInfo {
     pub governor: String,
     pub available_governors: Vec<String>,
     pub boost_enabled: bool,
+    #[serde(default)]
+    pub boost_policies: Vec<BoostPolicyGroup>,  // Empty without per-policy boost files
+    #[serde(default)]
+    pub time_in_state: Vec<FrequencyResidency>,  // Empty if the cpufreq driver keeps no stats
     pub smt_enabled: bool,
     pub scaling_driver: String,
     pub amd_pstate_status: Option<String>,
@@ -47,6 +227,37 @@ pub struct CpuCapabilities {
     pub has_scaling_max_freq: bool,
     pub has_available_governors: bool,
     pub has_amd_pstate: bool,
+    #[serde(default)]
+    pub has_per_policy_boost: bool,  // cpufreq/policyN/boost
+}
+
+// Time spent at one frequency since boot, summed over all cpufreq policies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequencyResidency {
+    pub freq_khz: u64,
+    pub time_ms: u64,
+}
+
+// cpufreq policies of one core type or cluster, e.g. the P-cores or E-cores of a hybrid CPU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoostPolicyGroup {
+    pub id: String,         // "cpu_core", "cpu_atom", "cluster<n>" or "cpu", key in CpuSettings::boost_groups
+    pub max_freq: u64,      // kHz, highest in the group
+    pub policies: Vec<u32>,
+    pub enabled: Option<bool>,  // None if the policies in the group disagree
+}
+
+impl BoostPolicyGroup {
+    pub fn label(&self) -> String {
+        let first = self.policies.first().copied().unwrap_or(0);
+        let last = self.policies.last().copied().unwrap_or(0);
+        let range = if first == last { format!("policy {}", first) } else { format!("policies {}–{}", first, last) };
+        match self.id.as_str() {
+            "cpu_core" => format!("Performance cores, {} MHz ({})", self.max_freq / 1000, range),
+            "cpu_atom" => format!("Efficiency cores, {} MHz ({})", self.max_freq / 1000, range),
+            _ => format!("{} MHz cores ({})", self.max_freq / 1000, range),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,16 +277,34 @@ pub struct CoreInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuInfo {
+    #[serde(default)]
+    pub id: String,  // PCI address, e.g. "0000:01:00.0"
     pub name: String,
     pub gpu_type: GpuType,
+    #[serde(default)]
+    pub driver: String,
+    #[serde(default)]
+    pub external: bool,  // Behind a Thunderbolt/USB4 port, e.g. a dock
+    #[serde(default)]
+    pub power_limit_control: bool,
     pub status: String,
     pub frequency: Option<u64>,
+    #[serde(default)]
+    pub memory_frequency: Option<u64>,  // MHz, amdgpu only
     pub temperature: Option<f32>,
     pub load: Option<f32>,
     pub power: Option<f32>,
     pub voltage: Option<f32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgpuFrequencyInfo {
+    pub driver: String,  // "i915" or "amdgpu"
+    pub min_mhz: u32,
+    pub max_mhz: u32,
+    pub current_max_mhz: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum GpuType {
     Integrated,
@@ -85,13 +314,33 @@ pub enum GpuType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatteryInfo {
     pub voltage_mv: u64,
-    pub current_ma: i64,
+    pub current_ma: i64,  // Positive while charging, negative while discharging
     pub charge_percent: u64,
     pub capacity_mah: u64,
     pub manufacturer: String,
     pub model: String,
     pub charge_start_threshold: Option<u8>,
     pub charge_end_threshold: Option<u8>,
+    #[serde(default)]
+    pub session: Option<SessionPowerReport>,  // None while on AC
+    #[serde(default)]
+    pub temperature: Option<f32>,  // °C, only some fuel gauges report it
+    #[serde(default)]
+    pub full_charge_since: Option<i64>,  // Unix seconds, set while charging to 100% once
+    #[serde(default)]
+    pub status: String,  // power_supply status: "Charging", "Discharging", "Full", "Not charging"
+    #[serde(default)]
+    pub on_ac_power: bool,
+    #[serde(default)]
+    pub power_draw_w: f64,  // Same sign as current_ma
+}
+
+// Energy drawn from the battery since it was last unplugged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPowerReport {
+    pub duration_secs: u64,
+    pub energy_used_wh: f64,
+    pub average_draw_w: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +362,8 @@ pub struct WiFiInfo {
     pub channel_width: Option<u32>,     // Channel width in MHz (20/40/80/160)
     pub tx_rate: Option<f64>,           // Upload rate in Mbps
     pub rx_rate: Option<f64>,           // Download rate in Mbps
+    #[serde(default)]
+    pub power_save: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,6 +372,30 @@ pub struct StorageDevice {
     pub model: String,
     pub size_gb: u64,
     pub temperature: Option<f32>,
+    #[serde(default)]
+    pub read_mb_s: Option<f64>,   // None until two samples have been taken
+    #[serde(default)]
+    pub write_mb_s: Option<f64>,
+    #[serde(default)]
+    pub smart: Option<SmartInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartInfo {
+    pub passed: bool,
+    pub temperature: Option<f32>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,  // SATA only
+    pub percentage_used: Option<u8>,       // NVMe only, wear estimate
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureSensor {
+    pub id: String,                 // Stable identifier, e.g. "hwmon:k10temp:temp1"
+    pub label: String,
+    pub source: String,             // "hwmon" or "thermal_zone"
+    pub temperature: f32,
+    pub critical: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +416,20 @@ pub struct Profile {
     pub keyboard_settings: KeyboardSettings,
     pub screen_settings: ScreenSettings,
     pub fan_settings: FanSettings,
+    #[serde(default)]
+    pub clamshell_override: ClamshellOverride,
+    #[serde(default)]
+    pub input_settings: InputSettings,
+    #[serde(default)]
+    pub network_settings: NetworkSettings,
+    #[serde(default)]
+    pub vm_settings: VmSettings,
+    #[serde(default)]
+    pub lightbar_settings: LightBarSettings,
+    #[serde(default)]
+    pub indicator_settings: IndicatorSettings,
+    #[serde(default)]
+    pub aquaris_settings: Option<AquarisSettings>,  // None leaves the cooler alone
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,25 +438,104 @@ pub struct CpuSettings {
     pub min_frequency: Option<u64>,
     pub max_frequency: Option<u64>,
     pub boost: Option<bool>,
+    #[serde(default)]
+    pub boost_groups: BTreeMap<String, bool>,  // Per policy group, overrides boost for that group
     pub smt: Option<bool>,
     pub performance_profile: Option<String>,
     pub tdp_profile: Option<String>,              // ADD
     pub energy_performance_preference: Option<String>,  // ADD
     pub tdp: Option<u32>,
     pub amd_pstate_status: Option<String>,
+    #[serde(default)]
+    pub on_battery: Option<CpuBatterySettings>,  // Overrides the values above on battery
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CpuBatterySettings {
+    pub governor: Option<String>,
+    pub energy_performance_preference: Option<String>,
+    pub max_frequency: Option<u64>,
+    #[serde(default)]
+    pub tdp: Option<u32>,  // Sustained CPU power limit in W
+}
+
+// Sustained CPU power limit range reported by the EC, with named steps inside it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TdpLimits {
+    pub min_watts: u32,
+    pub max_watts: u32,
+    pub current_watts: u32,
+    pub presets: Vec<TdpPreset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TdpPreset {
+    pub name: String,
+    pub watts: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuSettings {
     pub dgpu_tdp: Option<u32>,
+    #[serde(default)]
+    pub dynamic_power_limit: Option<DynamicPowerLimit>,  // Replaces dgpu_tdp when set
+    #[serde(default)]
+    pub igpu_max_freq: Option<u32>,  // MHz, None = hardware maximum
+    #[serde(default)]
+    pub dgpu_target: Option<String>,  // PCI address the dGPU limits apply to, None = first one found
+}
+
+// Closed loop: lower the dGPU power limit above target_temp, raise it again with headroom
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DynamicPowerLimit {
+    pub target_temp: u8,
+    pub min_watts: u32,
+    pub max_watts: u32,
+    pub step_watts: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyboardSettings {
     pub control_enabled: bool,
+    pub mode: KeyboardMode,  // Applied to every backlight without its own entry
+    #[serde(default)]
+    pub devices: Vec<KeyboardDeviceSettings>,
+    #[serde(default)]
+    pub ambient: AmbientKeyboardSettings,
+}
+
+// Dims the keyboard backlight as the ambient light sensor reads brighter surroundings:
+// the profile's brightness at or below dark_lux, off at or above bright_lux
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AmbientKeyboardSettings {
+    pub enabled: bool,
+    pub dark_lux: u32,
+    pub bright_lux: u32,
+}
+
+impl Default for AmbientKeyboardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dark_lux: 10,
+            bright_lux: 400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardDeviceSettings {
+    pub device: String,  // LED class name, e.g. "rgb:kbd_backlight_1"
     pub mode: KeyboardMode,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyboardDevice {
+    pub name: String,
+    pub external: bool,  // Attached over USB rather than built in
+    pub rgb: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum KeyboardMode {
     SingleColor { r: u8, g: u8, b: u8, brightness: u8 },  // CUSTOM (0) - Static color
@@ -184,12 +552,63 @@ pub enum KeyboardMode {
 pub struct ScreenSettings {
     pub brightness: u8,
     pub system_control: bool,
+    #[serde(default)]
+    pub blank_timeout: Option<u32>,  // Seconds, applied through the desktop session
+    #[serde(default)]
+    pub ambient: AmbientScreenSettings,
+    #[serde(default)]
+    pub color_temperature: Option<u32>,  // Kelvin, applied through the desktop session, None = neutral
+    #[serde(default)]
+    pub refresh_rate: Option<u32>,  // Hz of the built-in panel, applied through the desktop session
+}
+
+// Screen brightness following the ambient light sensor, only without system_control
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AmbientScreenSettings {
+    pub enabled: bool,
+    pub curve: Vec<(u32, u8)>,  // (lux, brightness %), interpolated on a log lux scale
+}
+
+impl Default for AmbientScreenSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            curve: vec![(0, 15), (10, 30), (100, 55), (1000, 85), (10000, 100)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmbientLightReading {
+    pub lux: Option<f64>,
+    pub screen_percent: Option<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FanSettings {
     pub control_enabled: bool,
     pub curves: Vec<FanCurve>,
+    #[serde(default)]
+    pub offsets: Vec<FanOffset>,  // Shift of a fan's whole curve, missing fans are unshifted
+    #[serde(default)]
+    pub smoothing: FanSmoothing,
+}
+
+// Moves a custom curve up or down for one fan without editing its points
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FanOffset {
+    pub fan_id: u32,
+    pub percent: i8,
+}
+
+// How the daemon follows a custom curve, so the fans don't hunt around a curve point
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FanSmoothing {
+    pub interval_ms: u32,  // Temperature sampling period
+    pub hysteresis: u8,  // °C the temperature has to fall before the fan slows down again
+    pub ramp_up: u8,  // % per second, 0 = jump straight to the curve
+    pub ramp_down: u8,  // % per second, 0 = jump straight to the curve
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -197,12 +616,417 @@ pub struct BatterySettings {
     pub control_enabled: bool,
     pub charge_start_threshold: u8,
     pub charge_end_threshold: u8,
+    #[serde(default)]
+    pub charging_priority: Option<String>,  // None = leave the firmware setting alone
+    #[serde(default)]
+    pub charging_profile: Option<String>,
+    #[serde(default)]
+    pub cap_tdp_to_adapter: bool,  // Lower the CPU power limit while on a charger that can't sustain it
+    #[serde(default)]
+    pub charge_behaviour: Option<String>,  // None = leave charge_behaviour alone
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FanCurve {
     pub fan_id: u32,
     pub points: Vec<(u8, u8)>, // (temperature, speed) - 8 points
+    #[serde(default)]
+    pub temp_source: Option<String>, // TemperatureSensor id, None = fan's own sensor
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct InputSettings {
+    pub mouse_poll_interval: Option<u32>,  // ms (usbhid mousepoll), None = driver default
+    #[serde(default)]
+    pub usb_hid_autosuspend: Option<bool>,  // None = leave udev / TLP rules alone
+    pub keyboard_backlight_off_on_battery: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum RadioType {
+    Wifi,
+    Bluetooth,
+    Wwan,
+}
+
+impl KeyboardMode {
+    pub fn brightness(&self) -> u8 {
+        match self {
+            KeyboardMode::SingleColor { brightness, .. }
+            | KeyboardMode::Breathe { brightness, .. }
+            | KeyboardMode::Cycle { brightness, .. }
+            | KeyboardMode::Dance { brightness, .. }
+            | KeyboardMode::Flash { brightness, .. }
+            | KeyboardMode::RandomColor { brightness, .. }
+            | KeyboardMode::Tempo { brightness, .. }
+            | KeyboardMode::Wave { brightness, .. } => *brightness,
+        }
+    }
+    
+    pub fn set_brightness(&mut self, value: u8) {
+        match self {
+            KeyboardMode::SingleColor { brightness, .. }
+            | KeyboardMode::Breathe { brightness, .. }
+            | KeyboardMode::Cycle { brightness, .. }
+            | KeyboardMode::Dance { brightness, .. }
+            | KeyboardMode::Flash { brightness, .. }
+            | KeyboardMode::RandomColor { brightness, .. }
+            | KeyboardMode::Tempo { brightness, .. }
+            | KeyboardMode::Wave { brightness, .. } => *brightness = value,
+        }
+    }
+}
+
+impl KeyboardSettings {
+    // Copy with every backlight's brightness scaled to `percent` of its configured value
+    pub fn scaled(&self, percent: u8) -> KeyboardSettings {
+        let scale = |mode: &mut KeyboardMode| {
+            let value = (mode.brightness() as u32 * percent.min(100) as u32 + 50) / 100;
+            mode.set_brightness(value as u8);
+        };
+        let mut settings = self.clone();
+        scale(&mut settings.mode);
+        for device in &mut settings.devices {
+            scale(&mut device.mode);
+        }
+        settings
+    }
+}
+
+impl RadioType {
+    // Type name used in /sys/class/rfkill/*/type
+    pub fn rfkill_type(&self) -> &'static str {
+        match self {
+            RadioType::Wifi => "wlan",
+            RadioType::Bluetooth => "bluetooth",
+            RadioType::Wwan => "wwan",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NetworkSettings {
+    pub wifi_power_save: Option<bool>,  // None = leave the driver setting alone
+    #[serde(default)]
+    pub disabled_interfaces: Vec<String>,  // Brought down on apply, only physical interfaces
+    #[serde(default)]
+    pub blocked_radios: Vec<RadioType>,    // rfkill soft block
+}
+
+// None leaves the kernel default (or the value found at daemon start) in place
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct VmSettings {
+    pub swappiness: Option<u8>,
+    pub dirty_writeback_centisecs: Option<u32>,
+}
+
+// Front lightbar found on some Clevo/Uniwill chassis
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LightBarSettings {
+    pub control_enabled: bool,
+    pub on: bool,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub animation: bool,  // Firmware color cycle instead of a fixed color
+}
+
+// Platform status LEDs (mute, power, charging), listed ones are turned off
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct IndicatorSettings {
+    pub disabled_leds: Vec<String>,
+}
+
+// TUXEDO Aquaris water cooler, driven over Bluetooth LE while it's connected
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AquarisSettings {
+    pub fan_duty: u8,   // Percent, 0 = off
+    pub pump_duty: u8,  // Percent, 0 = off
+    pub pump_voltage: PumpVoltage,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum PumpVoltage {
+    V7,
+    V8,
+    #[default]
+    V11,
+    V12,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AquarisStatus {
+    pub present: bool,    // Paired or seen by BlueZ
+    pub connected: bool,
+    pub name: String,
+    pub address: String,
+}
+
+// Applied on top of the profile while the lid is closed with an external display or dock
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ClamshellOverride {
+    pub enabled: bool,
+    pub fan_min_speed: u8,  // Floor for custom fan curves in percent, the keyboard vent is covered
+    pub tdp_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LidStatus {
+    pub lid_closed: bool,
+    pub docked: bool,
+    pub external_display: bool,
+    pub clamshell: bool,
+    pub override_active: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FanControlMode {
+    Auto,    // EC / firmware controls the fans
+    Manual,  // Fixed duty written by software
+    Unknown,
+}
+
+// Changes made behind the daemon's back, e.g. by Fn hotkeys handled in the EC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HardwareEvent {
+    PerformanceProfile(String),
+    FanMode(FanControlMode),
+    SafeMode(SafeModeStatus),
+    FanFailure(Vec<FanFailure>),  // All fans currently considered failed, empty once they recover
+    ApplyProgress(ApplyProgress),
+}
+
+// A fan whose tachometer stayed at 0 RPM although it was driven hard enough to spin
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FanFailure {
+    pub fan_id: u32,
+    pub duty: u32,   // Duty cycle in percent when it was flagged
+    pub since: i64,  // Unix seconds
+}
+
+// Local counters per profile, kept by the daemon and never sent anywhere
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileUsage {
+    pub since: i64,  // Unix seconds, when counting started or was last reset
+    pub profiles: Vec<ProfileUsageEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileUsageEntry {
+    pub name: String,
+    pub switches: u32,         // Times another profile was replaced by this one
+    pub active_secs: u64,      // Time applied while the daemon ran
+    pub average_cpu_temp: Option<f32>,
+}
+
+// The daemon skips restoring the last profile after it crashed repeatedly right after applying it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SafeModeStatus {
+    pub active: bool,
+    pub profile: Option<String>,  // Profile that was applied before the crashes
+    pub crash_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FanModeSource {
+    Daemon,        // Fan curves or a DBus request handled by this daemon
+    DustCleaning,
+    EcDefault,     // Nothing has touched the fans since the daemon started
+    External,      // Duty differs from what this daemon wrote last
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FanModeStatus {
+    pub mode: FanControlMode,
+    pub set_by: FanModeSource,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Feature {
+    FanControl,
+    TdpProfiles,
+    CpuFrequency,
+    KeyboardBacklight,
+    BatteryCharging,
+    GpuPowerLimit,
+    Webcam,
+    LightBar,
+    AmbientLight,  // Only reported when an ambient light sensor exists
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureStatus {
+    pub feature: Feature,
+    pub available: bool,
+    pub reason: Option<String>,  // Why the daemon can't control it
+}
+
+// One row of the capability matrix: a control and the kernel interface behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub name: String,
+    pub supported: bool,
+    pub interface: String,       // sysfs path or ioctl device
+    pub reason: Option<String>,  // Why it isn't supported
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTiming {
+    pub name: String,
+    pub ticks: u64,
+    pub last_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbusCallStats {
+    pub method: String,
+    pub count: u64,
+    pub per_minute: f64,  // Averaged over the daemon uptime
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStats {
+    pub uptime_secs: u64,
+    pub tasks: Vec<TaskTiming>,
+    pub sysfs_read_errors: u64,  // Missing files are expected and not counted
+    pub dbus_calls: Vec<DbusCallStats>,
+    #[serde(default)]
+    pub sensor_failures: Vec<SensorFailureStats>,
+    #[serde(default)]
+    pub charge_watchdog: ChargeWatchdogStats,
+}
+
+// Charge thresholds the firmware reset behind the daemon's back
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChargeWatchdogStats {
+    pub resets: u64,
+    pub last_reset: Option<i64>,  // Unix seconds
+    pub last_drift: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorFailureStats {
+    pub sensor: String,
+    pub total_failures: u64,
+    pub consecutive_failures: u64,  // Recent failures, halves every 30 s the sensor reads fine
+    pub last_error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanCurveCapabilities {
+    pub max_points: Option<u32>,  // None = unlimited, the daemon evaluates curves in software
+    pub hardware_table_points: Option<u32>,  // Size of a driver-exposed curve table, if any
+    #[serde(default)]
+    pub fan_count: u32,  // Fans reported by the active fan backend
+    #[serde(default)]
+    pub min_duty: Vec<FanMinDuty>,  // Per-fan duty floors, fans without an entry have none
+}
+
+// Lowest duty a fan keeps spinning at, computed duties between 0 and this are raised to it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FanMinDuty {
+    pub fan_id: u32,
+    pub percent: u8,
+    pub calibrated: bool,  // Measured by the daemon rather than set by hand
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FanCalibrationStatus {
+    pub running: bool,
+    pub fan_id: Option<u32>,
+    pub progress_percent: u8,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DustCleaningStatus {
+    pub running: bool,
+    pub current_cycle: u32,
+    pub total_cycles: u32,
+    pub progress_percent: u8,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EventKind {
+    ThermalThrottle,
+    FanFailsafe,    // Fans forced to full speed or handed back to the EC for safety
+    ProfileSwitch,
+    SensorFailure,
+    ChargeThresholdReset,  // Firmware dropped the custom charge thresholds, re-applied
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogEntry {
+    #[serde(default)]
+    pub seq: u64,        // Increases by one per event, kept in the log file across restarts
+    pub timestamp: i64,  // Unix seconds
+    pub kind: EventKind,
+    pub message: String,
+}
+
+// Ordered like the log crate, more severe levels compare smaller
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub seq: u64,           // Increases by one per record, restarts with the daemon
+    pub timestamp_ms: i64,  // Unix milliseconds
+    pub level: LogLevel,
+    pub target: String,     // Module that logged the record
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonLog {
+    pub started: i64,  // Unix seconds, changes whenever the daemon was restarted
+    pub entries: Vec<LogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoTuneSample {
+    pub tdp_profile: Option<String>,
+    pub fan_duty: u8,
+    pub avg_temp: f32,
+    pub avg_frequency: u64,  // MHz
+    pub too_hot: bool,       // Step ended early at the temperature limit
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoTuneStatus {
+    pub running: bool,
+    pub progress_percent: u8,
+    pub message: String,
+    pub samples: Vec<AutoTuneSample>,
+    pub proposals: Vec<Profile>,
+}
+
+// Averages over one A/B test session, or over all sessions of a profile in the summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbTestSession {
+    pub profile: String,
+    pub avg_temp: f32,
+    pub avg_fan_duty: Option<f32>,  // None without fan duty readings
+    pub avg_drain_w: Option<f32>,   // None if the session ran on AC
+    pub avg_load: f32,              // To tell whether the workloads were comparable
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AbTestStatus {
+    pub running: bool,
+    pub progress_percent: u8,
+    pub message: String,
+    pub sessions: Vec<AbTestSession>,
+    pub summary: Vec<AbTestSession>,  // Profile A first, filled in when the test finishes
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,6 +1043,74 @@ pub struct AppConfig {
     pub profiles: Vec<Profile>,
     pub current_profile: String,
     pub battery_settings: BatterySettings,
+    #[serde(default)]
+    pub history_settings: HistorySettings,
+    #[serde(default)]
+    pub fan_curve_observed_axis: bool,  // Scale the curve editor's temperature axis to observed readings
+    #[serde(default)]
+    pub auto_switch: AutoSwitchSettings,
+    #[serde(default)]
+    pub read_only: bool,  // Monitoring only: statistics are shown, every control is hidden
+    #[serde(default)]
+    pub last_seen_version: Option<String>,  // The What's new page opens once when this is older
+    #[serde(default)]
+    pub battery_temp_alarm: Option<u8>,  // °C, notifies when the fuel gauge reports more
+}
+
+// Profiles switched to automatically
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AutoSwitchSettings {
+    pub gamemode_enabled: bool,
+    pub gamemode_profile: String,          // Applied while Feral GameMode has a game registered
+    #[serde(default)]
+    pub gamemode_priority: GameModePriority,
+    #[serde(default)]
+    pub steam_rules: Vec<SteamAppRule>,   // Checked before the game mode profile, first match wins
+    #[serde(default)]
+    pub power_source_enabled: bool,
+    #[serde(default)]
+    pub ac_profile: String,               // Applied by the daemon when the charger is plugged in, empty for none
+    #[serde(default)]
+    pub battery_profile: String,          // Applied when it is unplugged, empty for none
+    #[serde(default)]
+    pub app_rules: Vec<AppProfileRule>,   // Switched by the daemon, earlier rules win when several apps run
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppProfileRule {
+    pub process: String,   // Executable name, as ps shows it ("blender", "darktable")
+    pub profile: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SteamAppRule {
+    pub appid: u32,
+    pub name: String,      // From the appmanifest when the rule was added
+    pub profile: String,
+}
+
+// What happens when the profile is changed by hand while a game is running
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum GameModePriority {
+    #[default]
+    Manual,     // The hand-picked profile stays after the game
+    GameMode,   // The profile from before the game is restored regardless
+}
+
+// Statistics history kept by the GUI across restarts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistorySettings {
+    pub retention_days: u32,
+    pub resolution_secs: u32,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            retention_days: 7,
+            resolution_secs: 60,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -244,6 +1136,14 @@ pub struct StatisticsSections {
     pub show_wifi: bool,
     pub show_storage: bool,
     pub show_fans: bool,
+    #[serde(default = "default_true")]
+    pub show_temperatures: bool,
+    #[serde(default = "default_true")]
+    pub show_history: bool,
+    #[serde(default = "default_true")]
+    pub show_display: bool,
+    #[serde(default)]
+    pub gpu_filter: Option<String>,  // PCI address of the only GPU shown, None = all
     pub section_order: Vec<String>,
     // Polling rates in milliseconds
     pub cpu_poll_rate: u64,
@@ -254,6 +1154,10 @@ pub struct StatisticsSections {
     pub fans_poll_rate: u64,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -275,6 +1179,12 @@ impl Default for AppConfig {
             profiles: vec![Profile::default()],
             current_profile: "Standard".to_string(),
             battery_settings: BatterySettings::default(),
+            history_settings: HistorySettings::default(),
+            fan_curve_observed_axis: false,
+            auto_switch: AutoSwitchSettings::default(),
+            read_only: false,
+            last_seen_version: None,
+            battery_temp_alarm: None,
         }
     }
 }
@@ -285,6 +1195,10 @@ impl Default for BatterySettings {
             control_enabled: false,
             charge_start_threshold: 40,
             charge_end_threshold: 80,
+            charging_priority: None,
+            charging_profile: None,
+            cap_tdp_to_adapter: false,
+            charge_behaviour: None,
         }
     }
 }
@@ -299,14 +1213,21 @@ impl Default for StatisticsSections {
             show_wifi: true,
             show_storage: true,
             show_fans: true,
+            show_temperatures: true,
+            show_history: true,
+            show_display: true,
+            gpu_filter: None,
             section_order: vec![
                 "SystemInfo".to_string(),
+                "Display".to_string(),
                 "CPU".to_string(),
                 "GPU".to_string(),
                 "Battery".to_string(),
                 "WiFi".to_string(),
                 "Storage".to_string(),
                 "Fans".to_string(),
+                "Temperatures".to_string(),
+                "History".to_string(),
             ],
             cpu_poll_rate: 1000,            // 1 second
             gpu_poll_rate: 2000,            // 2 seconds
@@ -328,6 +1249,13 @@ impl Default for Profile {
             keyboard_settings: KeyboardSettings::default(),
             screen_settings: ScreenSettings::default(),
             fan_settings: FanSettings::default(),
+            clamshell_override: ClamshellOverride::default(),
+            input_settings: InputSettings::default(),
+            network_settings: NetworkSettings::default(),
+            vm_settings: VmSettings::default(),
+            lightbar_settings: LightBarSettings::default(),
+            indicator_settings: IndicatorSettings::default(),
+            aquaris_settings: None,
         }
     }
 }
@@ -339,10 +1267,12 @@ impl Default for CpuSettings {
             min_frequency: None,
             max_frequency: None,
             boost: None,
+            boost_groups: BTreeMap::new(),
             smt: None,
             performance_profile: None,
             tdp: None,
             amd_pstate_status: None,
+            on_battery: None,
             tdp_profile: None,                          // ADD
             energy_performance_preference: None,        // ADD
         }
@@ -351,7 +1281,12 @@ impl Default for CpuSettings {
 
 impl Default for GpuSettings {
     fn default() -> Self {
-        Self { dgpu_tdp: None }
+        Self {
+            dgpu_tdp: None,
+            dynamic_power_limit: None,
+            igpu_max_freq: None,
+            dgpu_target: None,
+        }
     }
 }
 
@@ -365,6 +1300,8 @@ impl Default for KeyboardSettings {
                 b: 255,
                 brightness: 50,
             },
+            devices: Vec::new(),
+            ambient: AmbientKeyboardSettings::default(),
         }
     }
 }
@@ -374,6 +1311,42 @@ impl Default for ScreenSettings {
         Self {
             brightness: 50,
             system_control: true,
+            blank_timeout: None,
+            ambient: AmbientScreenSettings::default(),
+            color_temperature: None,
+            refresh_rate: None,
+        }
+    }
+}
+
+impl FanSettings {
+    // Copies the points of one fan's curve onto another fan, keeping the target's sensor
+    pub fn copy_curve(&mut self, from_fan: u32, to_fan: u32) -> bool {
+        let points = match self.curves.iter().find(|c| c.fan_id == from_fan) {
+            Some(curve) => curve.points.clone(),
+            None => return false,
+        };
+        
+        match self.curves.iter_mut().find(|c| c.fan_id == to_fan) {
+            Some(curve) => curve.points = points,
+            None => self.curves.push(FanCurve { fan_id: to_fan, points, temp_source: None }),
+        }
+        true
+    }
+    
+    // Replaces all curves with the ones of another profile, whether they are used stays as it is
+    pub fn copy_curves_from(&mut self, other: &FanSettings) {
+        self.curves = other.curves.clone();
+    }
+    
+    pub fn offset(&self, fan_id: u32) -> i8 {
+        self.offsets.iter().find(|o| o.fan_id == fan_id).map(|o| o.percent).unwrap_or(0)
+    }
+    
+    pub fn set_offset(&mut self, fan_id: u32, percent: i8) {
+        self.offsets.retain(|o| o.fan_id != fan_id);
+        if percent != 0 {
+            self.offsets.push(FanOffset { fan_id, percent });
         }
     }
 }
@@ -383,6 +1356,19 @@ impl Default for FanSettings {
         Self {
             control_enabled: false,
             curves: vec![],
+            offsets: vec![],
+            smoothing: FanSmoothing::default(),
+        }
+    }
+}
+
+impl Default for FanSmoothing {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            hysteresis: 3,
+            ramp_up: 10,
+            ramp_down: 3,
         }
     }
 }
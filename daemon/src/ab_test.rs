@@ -0,0 +1,239 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{AbTestSession, AbTestStatus, EventKind, Profile};
+use zbus::object_server::InterfaceRef;
+use crate::dbus_interface::ControlInterface;
+
+// Helps choosing between two candidate profiles: they are applied in turn (A, B, A, B, ...)
+// for sessions of the same length while the user runs the workload they care about, and the
+// averages per profile are compared at the end. Unlike the auto-tuner there is no synthetic
+// load, so the CPU load is recorded too, sessions with very different loads don't compare.
+// While a test runs the daemon's AC/battery and per-application switching and the GUI's game
+// mode and Steam switching hold off, and ApplyProfile is refused until the test is aborted.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+// The start of each session still carries the heat of the previous profile
+const MAX_SETTLE: Duration = Duration::from_secs(60);
+const MAX_SESSION_MINUTES: u32 = 120;
+const MAX_ROUNDS: u32 = 10;
+// Average CPU load difference (percentage points) above which the result gets a warning
+const COMPARABLE_LOAD: f32 = 10.0;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static ABORT_REQUESTED: AtomicBool = AtomicBool::new(false);
+static STATUS: once_cell::sync::Lazy<Mutex<AbTestStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(AbTestStatus::default()));
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+pub fn get_status() -> AbTestStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+/// `rounds` sessions per profile, each `session_minutes` long
+pub fn start(iface: InterfaceRef<ControlInterface>, a: Profile, b: Profile, session_minutes: u32, rounds: u32) -> Result<()> {
+    if a.name == b.name {
+        return Err(anyhow!("Pick two different profiles"));
+    }
+    if !(1..=MAX_SESSION_MINUTES).contains(&session_minutes) {
+        return Err(anyhow!("Sessions last 1 to {} minutes", MAX_SESSION_MINUTES));
+    }
+    if !(1..=MAX_ROUNDS).contains(&rounds) {
+        return Err(anyhow!("1 to {} rounds", MAX_ROUNDS));
+    }
+    if crate::dust_cleaning::is_running() {
+        return Err(anyhow!("Dust cleaning is running"));
+    }
+    if crate::auto_tuner::is_running() {
+        return Err(anyhow!("Auto-tuning is running"));
+    }
+    if crate::fan_min_duty::is_calibrating() {
+        return Err(anyhow!("Fan calibration is running"));
+    }
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(anyhow!("An A/B test is already running"));
+    }
+
+    ABORT_REQUESTED.store(false, Ordering::SeqCst);
+    *STATUS.lock().unwrap() = AbTestStatus {
+        running: true,
+        message: format!("Comparing '{}' and '{}'", a.name, b.name),
+        ..Default::default()
+    };
+    log::info!("Starting A/B test of '{}' and '{}', {} x {} min", a.name, b.name, rounds, session_minutes);
+
+    let previous = crate::safe_mode::last_applied();
+    let session = Duration::from_secs(session_minutes as u64 * 60);
+    tokio::spawn(async move {
+        let _inhibitor = crate::sleep_inhibit::acquire("A/B test").await;
+        let result = run_test(&iface, &a, &b, session, rounds).await;
+
+        // Back to what was applied before the test
+        if let Some(profile) = previous {
+            let name = profile.name.clone();
+            if let Err(e) = crate::apply_pipeline::apply_announced(profile, &iface).await {
+                log::warn!("Failed to restore '{}' after the A/B test: {}", name, e);
+            }
+        }
+
+        let mut status = STATUS.lock().unwrap();
+        status.running = false;
+        match result {
+            Ok(()) => {
+                let summary = [&a.name, &b.name].iter()
+                    .filter_map(|name| summarize(name, &status.sessions))
+                    .collect();
+                status.summary = summary;
+                status.progress_percent = 100;
+                status.message = match status.summary.as_slice() {
+                    [a, b] if (a.avg_load - b.avg_load).abs() > COMPARABLE_LOAD => format!(
+                        "Finished, but the workload differed ({:.0}% vs {:.0}% CPU load), compare with care",
+                        a.avg_load, b.avg_load),
+                    _ => "Finished".to_string(),
+                };
+                log::info!("A/B test finished");
+                crate::event_log::record(EventKind::ProfileSwitch,
+                    format!("A/B test of '{}' and '{}' finished", a.name, b.name));
+            }
+            Err(e) => {
+                log::warn!("A/B test stopped: {}", e);
+                status.message = e.to_string();
+            }
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(())
+}
+
+pub fn abort() -> Result<()> {
+    if !is_running() {
+        return Err(anyhow!("No A/B test is running"));
+    }
+
+    log::info!("A/B test abort requested");
+    ABORT_REQUESTED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+async fn run_test(iface: &InterfaceRef<ControlInterface>, a: &Profile, b: &Profile, session: Duration, rounds: u32) -> Result<()> {
+    let total = rounds as usize * 2;
+    for i in 0..total {
+        let profile = if i % 2 == 0 { a } else { b };
+        {
+            let mut status = STATUS.lock().unwrap();
+            status.progress_percent = ((i * 100) / total) as u8;
+            status.message = format!("Session {}/{}: '{}'", i + 1, total, profile.name);
+        }
+
+        crate::apply_pipeline::apply_announced(profile.clone(), iface).await?;
+        let result = run_session(profile, session, |elapsed| {
+            let done = i as f64 + elapsed.as_secs_f64() / session.as_secs_f64();
+            STATUS.lock().unwrap().progress_percent = (done * 100.0 / total as f64) as u8;
+        }).await?;
+        log::info!("A/B test: {:?}", result);
+        STATUS.lock().unwrap().sessions.push(result);
+    }
+    Ok(())
+}
+
+async fn run_session(profile: &Profile, session: Duration, progress: impl Fn(Duration)) -> Result<AbTestSession> {
+    let backend = crate::fan_backend::detect();
+    let settle = MAX_SETTLE.min(session / 4);
+    let mut elapsed = Duration::ZERO;
+    let mut temps = Vec::new();
+    let mut loads = Vec::new();
+    let mut duties = Vec::new();
+    let mut drains = Vec::new();
+
+    while elapsed < session {
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        elapsed += SAMPLE_INTERVAL;
+        if ABORT_REQUESTED.load(Ordering::SeqCst) {
+            return Err(anyhow!("Aborted by user"));
+        }
+        progress(elapsed);
+        if elapsed <= settle {
+            continue;
+        }
+
+        let cpu = crate::hardware_detection::get_cpu_info()?;
+        temps.push(cpu.package_temp);
+        loads.push(cpu.median_load);
+        if let Some(ref backend) = backend {
+            let duty = (0..backend.fan_count())
+                .filter_map(|fan_id| backend.get_duty(fan_id).ok())
+                .max();
+            duties.extend(duty.map(|d| d as f32));
+        }
+        if !crate::hardware_detection::is_on_ac_power() {
+            if let Ok(battery) = crate::hardware_detection::get_battery_info() {
+                drains.push(battery.power_draw_w.abs() as f32);
+            }
+        }
+    }
+
+    Ok(AbTestSession {
+        profile: profile.name.clone(),
+        avg_temp: average(&temps).unwrap_or(0.0),
+        avg_fan_duty: average(&duties),
+        avg_drain_w: average(&drains),
+        avg_load: average(&loads).unwrap_or(0.0),
+    })
+}
+
+fn summarize(name: &str, sessions: &[AbTestSession]) -> Option<AbTestSession> {
+    let sessions: Vec<&AbTestSession> = sessions.iter().filter(|s| s.profile == name).collect();
+    if sessions.is_empty() {
+        return None;
+    }
+    let temps: Vec<f32> = sessions.iter().map(|s| s.avg_temp).collect();
+    let duties: Vec<f32> = sessions.iter().filter_map(|s| s.avg_fan_duty).collect();
+    let drains: Vec<f32> = sessions.iter().filter_map(|s| s.avg_drain_w).collect();
+    let loads: Vec<f32> = sessions.iter().map(|s| s.avg_load).collect();
+    Some(AbTestSession {
+        profile: name.to_string(),
+        avg_temp: average(&temps)?,
+        avg_fan_duty: average(&duties),
+        avg_drain_w: average(&drains),
+        avg_load: average(&loads)?,
+    })
+}
+
+fn average(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(profile: &str, temp: f32, drain: Option<f32>) -> AbTestSession {
+        AbTestSession { profile: profile.to_string(), avg_temp: temp, avg_fan_duty: Some(50.0), avg_drain_w: drain, avg_load: 30.0 }
+    }
+
+    #[test]
+    fn summary_per_profile() {
+        let sessions = [
+            session("Quiet", 60.0, Some(10.0)),
+            session("Performance", 80.0, None),
+            session("Quiet", 70.0, None),
+            session("Performance", 84.0, Some(20.0)),
+        ];
+
+        let quiet = summarize("Quiet", &sessions).unwrap();
+        assert_eq!(quiet.avg_temp, 65.0);
+        // Sessions on AC don't count towards the drain
+        assert_eq!(quiet.avg_drain_w, Some(10.0));
+
+        let performance = summarize("Performance", &sessions).unwrap();
+        assert_eq!(performance.avg_temp, 82.0);
+        assert_eq!(performance.avg_drain_w, Some(20.0));
+
+        assert!(summarize("Standard", &sessions).is_none());
+        assert_eq!(average(&[]), None);
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::AcAdapterInfo;
+use crate::gpu_power::DgpuPowerControl;
+use crate::hardware_detection::sorted_dir_entries;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+// Display, SSD, RAM and conversion losses on top of the CPU and dGPU limits
+const PLATFORM_OVERHEAD_W: u32 = 15;
+// Never cap the CPU below this, the system has to stay usable
+const MIN_CPU_LIMIT_W: u32 = 10;
+
+struct TdpCap {
+    original_watts: u32,
+    capped_watts: u32,
+}
+
+static AUTO_CAP: once_cell::sync::Lazy<Mutex<bool>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(false));
+static ACTIVE_CAP: once_cell::sync::Lazy<Mutex<Option<TdpCap>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+// Refreshed by the monitor, reading the dGPU limit can be slow and wake the GPU
+static STATUS: once_cell::sync::Lazy<Mutex<AcAdapterInfo>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(AcAdapterInfo::default()));
+// The dGPU counted in the required wattage, kept while it stays the profile's target, and
+// its last limit read while it was awake, reused while it is runtime-suspended
+struct Dgpu {
+    control: Option<DgpuPowerControl>,
+    watts: u32,
+}
+
+static DGPU: Mutex<Dgpu> = Mutex::new(Dgpu { control: None, watts: 0 });
+
+// Called from apply_battery_settings
+pub fn set_auto_cap(enabled: bool) {
+    *AUTO_CAP.lock().unwrap() = enabled;
+    if !enabled {
+        lift_cap();
+    }
+}
+
+pub fn auto_cap_enabled() -> bool {
+    *AUTO_CAP.lock().unwrap()
+}
+
+pub fn get_info() -> AcAdapterInfo {
+    STATUS.lock().unwrap().clone()
+}
+
+fn read_info() -> AcAdapterInfo {
+    let mut info = AcAdapterInfo {
+        can_set_limit: cpu_limit_settable(),
+        ..Default::default()
+    };
+
+    // USB-C supplies report their negotiated limits, the barrel-jack Mains supply usually doesn't
+    for supply in sorted_dir_entries("/sys/class/power_supply") {
+        let kind = read_string(&supply.join("type")).unwrap_or_default();
+        if kind != "Mains" && kind != "USB" {
+            continue;
+        }
+        if read_string(&supply.join("online")).as_deref() != Some("1") {
+            continue;
+        }
+
+        let watts = supply_watts(&supply);
+        if !info.online || (info.watts.is_none() && watts.is_some()) {
+            info.online = true;
+            info.name = supply.file_name().unwrap_or_default().to_string_lossy().to_string();
+            info.usb_c = kind == "USB";
+            info.watts = watts;
+        }
+    }
+
+    if !info.online {
+        return info;
+    }
+
+    let cap = ACTIVE_CAP.lock().unwrap();
+    info.required_watts = required_watts(cap.as_ref().map(|c| c.original_watts));
+    info.tdp_capped_watts = cap.as_ref().map(|c| c.capped_watts);
+    info
+}
+
+pub async fn monitor_task() {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut was_underpowered = false;
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("ac_adapter");
+
+        // Reading the dGPU limit runs nvidia-smi, keep it off the runtime threads
+        was_underpowered = match tokio::task::spawn_blocking(move || poll(was_underpowered)).await {
+            Ok(underpowered) => underpowered,
+            Err(e) => {
+                log::warn!("Charger monitor stopped: {}", e);
+                return;
+            }
+        };
+    }
+}
+
+// Returns whether the charger is too weak, to warn only when that starts
+fn poll(was_underpowered: bool) -> bool {
+    let info = read_info();
+    *STATUS.lock().unwrap() = info.clone();
+    let underpowered = info.underpowered();
+    if underpowered && !was_underpowered {
+        log::warn!("Charger {} provides {} W, the current limits need about {} W",
+            info.name, info.watts.unwrap_or(0), info.required_watts.unwrap_or(0));
+    }
+
+    if !*AUTO_CAP.lock().unwrap() {
+        return underpowered;
+    }
+
+    match (underpowered, info.watts, info.required_watts) {
+        (true, Some(adapter), Some(required)) => apply_cap(adapter, required),
+        _ if !info.online || info.watts.is_some() => lift_cap(),
+        // Adapter wattage unknown, leave things as they are
+        _ => {}
+    }
+    underpowered
+}
+
+fn apply_cap(adapter_watts: u32, required_watts: u32) {
+    let current = match read_cpu_limit() {
+        Ok(watts) => watts,
+        Err(e) => {
+            log::warn!("Cannot cap CPU power limit: {}", e);
+            return;
+        }
+    };
+
+    let mut cap = ACTIVE_CAP.lock().unwrap();
+    // A limit other than ours means a profile was applied meanwhile, that is the original now
+    let original = cap.as_ref()
+        .filter(|c| c.capped_watts == current)
+        .map(|c| c.original_watts)
+        .unwrap_or(current);
+    let target = original
+        .saturating_sub(required_watts.saturating_sub(adapter_watts))
+        .max(MIN_CPU_LIMIT_W);
+
+    if current == target {
+        return;
+    }
+
+    match write_cpu_limit(target) {
+        Ok(()) => {
+            log::info!("Capped CPU power limit from {} W to {} W for a {} W charger",
+                original, target, adapter_watts);
+            *cap = Some(TdpCap { original_watts: original, capped_watts: target });
+        }
+        Err(e) => log::warn!("Failed to cap CPU power limit: {}", e),
+    }
+}
+
+fn lift_cap() {
+    if let Some(cap) = ACTIVE_CAP.lock().unwrap().take() {
+        // Only undo our own cap, a limit written since by a profile apply stays
+        match read_cpu_limit() {
+            Ok(current) if current == cap.capped_watts => {}
+            Ok(current) => {
+                log::info!("CPU power limit changed to {} W while capped, leaving it", current);
+                return;
+            }
+            Err(e) => {
+                log::warn!("Cannot read CPU power limit, not restoring it: {}", e);
+                return;
+            }
+        }
+        match write_cpu_limit(cap.original_watts) {
+            Ok(()) => log::info!("Restored CPU power limit to {} W", cap.original_watts),
+            Err(e) => log::warn!("Failed to restore CPU power limit: {}", e),
+        }
+    }
+}
+
+// What the machine may draw at its sustained limits, None if the CPU limit is unknown
+fn required_watts(original_cpu_watts: Option<u32>) -> Option<u32> {
+    let cpu = original_cpu_watts.or_else(|| read_cpu_limit().ok())?;
+    Some(cpu + dgpu_watts() + PLATFORM_OVERHEAD_W)
+}
+
+fn dgpu_watts() -> u32 {
+    let mut guard = DGPU.lock().unwrap();
+    let dgpu = &mut *guard;
+    // Looked for again while there is none, a docked GPU may only show up later
+    if !dgpu.control.as_ref().is_some_and(DgpuPowerControl::is_target) {
+        dgpu.control = DgpuPowerControl::new().ok();
+        dgpu.watts = 0;
+    }
+    let Some(ref control) = dgpu.control else {
+        return 0;
+    };
+    if !control.is_suspended() {
+        dgpu.watts = control.get_power_limit().unwrap_or(dgpu.watts);
+    }
+    dgpu.watts
+}
+
+fn supply_watts(supply: &Path) -> Option<u32> {
+    // µV * µA
+    let voltage = read_u64(&supply.join("voltage_max"))?;
+    let current = read_u64(&supply.join("current_max"))?;
+    Some((voltage * current / 1_000_000_000_000) as u32).filter(|w| *w > 0)
+}
+
+// Sustained (PL1) package limit, through RAPL or the Uniwill TDP ioctl
+fn rapl_limit_path() -> Option<PathBuf> {
+    sorted_dir_entries("/sys/class/powercap")
+        .into_iter()
+        .find(|path| read_string(&path.join("name")).as_deref() == Some("package-0"))
+        .map(|path| path.join("constraint_0_power_limit_uw"))
+        .filter(|path| path.exists())
+}
+
+fn cpu_limit_settable() -> bool {
+    rapl_limit_path().is_some()
+        || crate::tuxedo_io::TuxedoIo::new().is_ok_and(|io| io.get_tdp(0).is_ok())
+}
+
+fn read_cpu_limit() -> Result<u32> {
+    if let Some(path) = rapl_limit_path() {
+        let microwatts = read_u64(&path).ok_or_else(|| anyhow!("Unreadable {}", path.display()))?;
+        return Ok((microwatts / 1_000_000) as u32);
+    }
+
+    let io = crate::tuxedo_io::TuxedoIo::new()?;
+    Ok(io.get_tdp(0)?.max(0) as u32)
+}
+
+fn write_cpu_limit(watts: u32) -> Result<()> {
+    if let Some(path) = rapl_limit_path() {
+        fs::write(&path, (watts as u64 * 1_000_000).to_string())?;
+        return Ok(());
+    }
+
+    let io = crate::tuxedo_io::TuxedoIo::new()?;
+    io.set_tdp(0, watts as i32)
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_string(path)?.parse().ok()
+}
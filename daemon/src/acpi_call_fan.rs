@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::sync::Mutex;
+use crate::fan_backend::FanBackend;
+use crate::tuxedo_io::ClevoFanInfo;
+
+// EXPERIMENTAL: drives the Clevo WMI fan methods through the acpi_call module for
+// machines where tuxedo_io doesn't load. Only used when the daemon is started with
+// --experimental-acpi-call, a wrong ACPI call can hang the EC until the next power cycle.
+
+pub const OPT_IN_FLAG: &str = "--experimental-acpi-call";
+const ACPI_CALL_PATH: &str = "/proc/acpi/call";
+
+// Known locations of the Clevo WMI method, differs between board generations
+const WMBB_METHODS: [&str; 2] = ["\\_SB.WMI.WMBB", "\\_SB.PCI0.WMI.WMBB"];
+
+// Clevo WMI commands, same ones tuxedo_io issues
+const CMD_GET_FANINFO: [u32; 3] = [0x63, 0x64, 0x6e];
+const CMD_SET_FANSPEED_VALUE: u32 = 0x68;
+const CMD_SET_FANSPEED_AUTO: u32 = 0x69;
+
+pub struct AcpiCallFanBackend {
+    method: &'static str,
+    fan_count: u32,
+    // /proc/acpi/call holds a single result, writes and reads must not interleave
+    lock: Mutex<()>,
+}
+
+impl AcpiCallFanBackend {
+    pub fn new() -> Result<Self> {
+        if !opted_in() {
+            return Err(anyhow!("acpi_call fan backend not enabled ({})", OPT_IN_FLAG));
+        }
+        if !std::path::Path::new(ACPI_CALL_PATH).exists() {
+            return Err(anyhow!("{} not found, is acpi_call loaded?", ACPI_CALL_PATH));
+        }
+
+        for method in WMBB_METHODS {
+            let mut backend = Self { method, fan_count: 0, lock: Mutex::new(()) };
+            if backend.fan_info(0).is_err() {
+                continue;
+            }
+
+            // Missing fans report a temperature of 0 or 1
+            backend.fan_count = (0..CMD_GET_FANINFO.len() as u32)
+                .take_while(|fan| backend.fan_info(*fan).is_ok_and(|info| info.exists()))
+                .count() as u32;
+            if backend.fan_count == 0 {
+                continue;
+            }
+
+            log::warn!("Using EXPERIMENTAL acpi_call fan backend via {}", method);
+            return Ok(backend);
+        }
+
+        Err(anyhow!("No Clevo WMI fan method found"))
+    }
+
+    pub fn is_available() -> bool {
+        opted_in() && std::path::Path::new(ACPI_CALL_PATH).exists()
+    }
+
+    fn call(&self, command: u32, arg: u32) -> Result<u32> {
+        let _guard = self.lock.lock().unwrap();
+
+        crate::dry_run::write(ACPI_CALL_PATH, format!("{} 0 {:#x} {:#x}", self.method, command, arg))?;
+        let result = fs::read_to_string(ACPI_CALL_PATH)?;
+        let result = result.trim_end_matches('\0').trim();
+
+        // Successful calls return the integer as hex, e.g. "0x1a2b00ff"
+        result.strip_prefix("0x")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| anyhow!("acpi_call {} {:#x} failed: {}", self.method, command, result))
+    }
+
+    /// Same layout tuxedo_io passes through from the WMI method
+    fn fan_info(&self, fan_id: u32) -> Result<ClevoFanInfo> {
+        let command = CMD_GET_FANINFO.get(fan_id as usize)
+            .ok_or_else(|| anyhow!("Invalid Clevo fan ID: {}", fan_id))?;
+        self.call(*command, 0).map(|raw| ClevoFanInfo::from_raw(raw as i32))
+    }
+}
+
+impl FanBackend for AcpiCallFanBackend {
+    fn name(&self) -> &'static str {
+        "acpi_call (experimental)"
+    }
+
+    fn fan_count(&self) -> u32 {
+        self.fan_count
+    }
+
+    fn set_duty(&self, fan_id: u32, percent: u32) -> Result<()> {
+        if fan_id >= self.fan_count {
+            return Err(anyhow!("Invalid Clevo fan ID: {}", fan_id));
+        }
+
+        // All fans are set with one packed value, keep the others where they are
+        let mut raw = [0u32; 3];
+        for fan in 0..self.fan_count {
+            raw[fan as usize] = self.fan_info(fan)?.raw_duty as u32;
+        }
+        raw[fan_id as usize] = (percent.min(100) * 255 + 50) / 100;
+
+        let packed = raw[0] | (raw[1] << 8) | (raw[2] << 16);
+        self.call(CMD_SET_FANSPEED_VALUE, packed)?;
+        Ok(())
+    }
+
+    fn get_duty(&self, fan_id: u32) -> Result<u32> {
+        Ok(self.fan_info(fan_id)?.duty_percent())
+    }
+
+    fn set_auto(&self) -> Result<()> {
+        self.call(CMD_SET_FANSPEED_AUTO, 0xF)?;
+        Ok(())
+    }
+
+    fn get_temperature(&self, fan_id: u32) -> Result<f32> {
+        self.fan_info(fan_id)?
+            .temperature()
+            .map(|temp| temp as f32)
+            .ok_or_else(|| anyhow!("Fan {} not available", fan_id))
+    }
+}
+
+fn opted_in() -> bool {
+    std::env::args().any(|arg| arg == OPT_IN_FLAG)
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Ambient light sensors are IIO devices with an illuminance channel, either already in lux
+// (_input) or raw counts with a scale and offset (hid-sensor-als, acpi-als, ...)
+const IIO_PATH: &str = "/sys/bus/iio/devices";
+const CHANNELS: [&str; 2] = ["in_illuminance", "in_illuminance0"];
+// Weight of a new sample, readings flicker with every shadow and screen change
+const SMOOTHING: f64 = 0.3;
+
+static SENSOR: once_cell::sync::Lazy<Option<PathBuf>> = once_cell::sync::Lazy::new(|| {
+    let sensor = crate::hardware_detection::sorted_dir_entries(IIO_PATH)
+        .into_iter()
+        .find(|device| CHANNELS.iter().any(|channel| {
+            device.join(format!("{}_input", channel)).exists() || device.join(format!("{}_raw", channel)).exists()
+        }));
+    if let Some(ref path) = sensor {
+        let name = fs::read_to_string(path.join("name")).unwrap_or_default();
+        log::info!("Ambient light sensor: {} ({})", name.trim(), path.display());
+    }
+    sensor
+});
+
+pub fn is_available() -> bool {
+    SENSOR.is_some()
+}
+
+fn read_f64(path: &Path) -> Option<f64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Current illuminance in lux
+pub fn read_lux() -> Option<f64> {
+    let device = SENSOR.as_ref()?;
+    for channel in CHANNELS {
+        if let Some(lux) = read_f64(&device.join(format!("{}_input", channel))) {
+            return Some(lux.max(0.0));
+        }
+        if let Some(raw) = read_f64(&device.join(format!("{}_raw", channel))) {
+            let scale = read_f64(&device.join(format!("{}_scale", channel))).unwrap_or(1.0);
+            let offset = read_f64(&device.join(format!("{}_offset", channel))).unwrap_or(0.0);
+            return Some(((raw + offset) * scale).max(0.0));
+        }
+    }
+    None
+}
+
+/// Exponential moving average over log(lux), so a lamp switched on in a dark room
+/// moves the value as much as daylight through a window does
+#[derive(Default)]
+pub struct Smoothed {
+    log_lux: Option<f64>,
+}
+
+impl Smoothed {
+    pub fn update(&mut self, lux: f64) -> f64 {
+        let sample = lux.ln_1p();
+        let value = match self.log_lux {
+            Some(previous) => previous + SMOOTHING * (sample - previous),
+            None => sample,
+        };
+        self.log_lux = Some(value);
+        value.exp_m1()
+    }
+}
+
+/// Where `lux` lies between `low` and `high` on a log scale, 0.0 at or below low, 1.0 at or above high
+pub fn position(lux: f64, low: u32, high: u32) -> f64 {
+    let (low, high) = ((low as f64).ln_1p(), (high as f64).ln_1p());
+    if high <= low {
+        return if lux.ln_1p() >= high { 1.0 } else { 0.0 };
+    }
+    ((lux.ln_1p() - low) / (high - low)).clamp(0.0, 1.0)
+}
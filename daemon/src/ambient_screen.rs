@@ -0,0 +1,157 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{AmbientLightReading, AmbientScreenSettings};
+use crate::ambient_light::Smoothed;
+
+// Screen brightness following the ambient light sensor along the profile's lux → % curve.
+// Written to the backlight's sysfs file: logind's Session.SetBrightness only serves the
+// session's owner, never the tuxedo-daemon user. Desktops pick the change up from the
+// backlight device's uevent and move their slider along.
+const BACKLIGHT_PATH: &str = "/sys/class/backlight";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Smaller steps are invisible and would only fight with the desktop's fades
+const MIN_STEP: u8 = 2;
+// A brightness the user set with the keys or the desktop slider is left alone for a while
+const MANUAL_HOLD: Duration = Duration::from_secs(10 * 60);
+
+struct Policy {
+    curve: Vec<(u32, u8)>,
+    lux: Smoothed,
+    // Raw brightness last written, to notice changes made by someone else
+    written: Option<u32>,
+    held_since: Option<Instant>,
+}
+
+static POLICY: once_cell::sync::Lazy<Mutex<Option<Policy>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn is_active() -> bool {
+    POLICY.lock().unwrap().is_some()
+}
+
+/// Called by apply_profile
+pub fn set_policy(settings: &AmbientScreenSettings, system_control: bool) {
+    let mut policy = POLICY.lock().unwrap();
+    *policy = (settings.enabled && !system_control && !settings.curve.is_empty()
+        && crate::ambient_light::is_available())
+        .then(|| {
+            let mut curve = settings.curve.clone();
+            curve.sort_by_key(|p| p.0);
+            Policy { curve, lux: Smoothed::default(), written: None, held_since: None }
+        });
+}
+
+/// Current sensor reading and screen brightness, for calibrating the curve
+pub fn get_reading() -> AmbientLightReading {
+    AmbientLightReading {
+        lux: crate::ambient_light::read_lux(),
+        screen_percent: find_backlight().and_then(|device| {
+            let max = read_u32(&device, "max_brightness")?.max(1);
+            Some((read_u32(&device, "brightness")? * 100 / max) as u8)
+        }),
+    }
+}
+
+// Linear between the curve's points on a log lux scale, flat beyond its ends
+fn curve_percent(curve: &[(u32, u8)], lux: f64) -> u8 {
+    let first = curve[0];
+    let last = curve[curve.len() - 1];
+    if lux <= first.0 as f64 {
+        return first.1;
+    }
+    if lux >= last.0 as f64 {
+        return last.1;
+    }
+    for pair in curve.windows(2) {
+        let ((lux1, pct1), (lux2, pct2)) = (pair[0], pair[1]);
+        if lux <= lux2 as f64 {
+            let ratio = crate::ambient_light::position(lux, lux1, lux2);
+            return (pct1 as f64 + ratio * (pct2 as f64 - pct1 as f64)).round() as u8;
+        }
+    }
+    last.1
+}
+
+// Firmware and platform interfaces are preferred over raw, like the desktops do
+fn find_backlight() -> Option<PathBuf> {
+    let devices = crate::hardware_detection::sorted_dir_entries(BACKLIGHT_PATH);
+    ["firmware", "platform", "raw"].iter()
+        .find_map(|kind| devices.iter().find(|device| {
+            fs::read_to_string(device.join("type")).map(|t| t.trim() == *kind).unwrap_or(false)
+        }))
+        .cloned()
+}
+
+fn read_u32(device: &Path, file: &str) -> Option<u32> {
+    fs::read_to_string(device.join(file)).ok()?.trim().parse().ok()
+}
+
+fn set_brightness(device: &Path, value: u32) -> Result<()> {
+    crate::dry_run::write(device.join("brightness"), value.to_string())?;
+    Ok(())
+}
+
+pub async fn monitor_task() {
+    if !crate::ambient_light::is_available() {
+        return;
+    }
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        if !is_active() {
+            continue;
+        }
+        let _timer = crate::daemon_stats::TickTimer::new("ambient_screen");
+
+        let (device, lux) = match (find_backlight(), crate::ambient_light::read_lux()) {
+            (Some(device), Some(lux)) => (device, lux),
+            _ => continue,
+        };
+        let (max, current) = match (read_u32(&device, "max_brightness"), read_u32(&device, "brightness")) {
+            (Some(max), Some(current)) if max > 0 => (max, current),
+            _ => continue,
+        };
+
+        let target = {
+            let mut policy = POLICY.lock().unwrap();
+            let policy = match policy.as_mut() {
+                Some(policy) => policy,
+                None => continue,
+            };
+            let lux = policy.lux.update(lux);
+
+            // Drivers may round what was written, only a real difference counts
+            if policy.written.is_some_and(|written| written.abs_diff(current) > max / 100) {
+                log::info!("Screen brightness changed by hand, pausing ambient brightness");
+                policy.held_since = Some(Instant::now());
+                policy.written = None;
+            }
+            if policy.held_since.is_some_and(|since| since.elapsed() < MANUAL_HOLD) {
+                continue;
+            }
+            policy.held_since = None;
+
+            let percent = curve_percent(&policy.curve, lux);
+            let current_percent = (current * 100 / max) as u8;
+            if percent.abs_diff(current_percent) < MIN_STEP {
+                continue;
+            }
+            log::debug!("Ambient light {:.0} lx, screen brightness {}%", lux, percent);
+            // Never fully dark, a 0 on some panels turns the backlight off
+            (max * percent as u32 / 100).max(1)
+        };
+
+        match set_brightness(&device, target) {
+            Ok(()) => {
+                if let Some(policy) = POLICY.lock().unwrap().as_mut() {
+                    policy.written = Some(target);
+                }
+            }
+            Err(e) => log::warn!("Failed to set screen brightness: {}", e),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::EventKind;
+
+// When a failsafe or alert fires, the minutes before it are what explains it. Sensor samples
+// are kept in memory for that and written out together with the daemon log and events.
+const CAPTURE_DIR: &str = "/var/log/tuxedo-control-center";
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+const WINDOW: Duration = Duration::from_secs(10 * 60);
+// A failing sensor or fan tends to trigger repeatedly, one bundle per incident is enough
+const MIN_CAPTURE_GAP: Duration = Duration::from_secs(5 * 60);
+const MAX_BUNDLES: usize = 20;
+const MAX_BUNDLE_AGE: Duration = Duration::from_secs(30 * 24 * 3600);
+
+#[derive(Serialize)]
+struct FanSample {
+    fan_id: u32,
+    duty: Option<u32>,
+    rpm: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Sample {
+    timestamp: i64,
+    temperatures: Vec<(String, f32)>,
+    fans: Vec<FanSample>,
+    battery_power_w: Option<f64>,
+    on_ac_power: bool,
+}
+
+#[derive(Serialize)]
+struct Trigger<'a> {
+    timestamp: i64,
+    kind: EventKind,
+    message: &'a str,
+}
+
+static SAMPLES: once_cell::sync::Lazy<Mutex<VecDeque<Sample>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(VecDeque::new()));
+static LAST_CAPTURE: once_cell::sync::Lazy<Mutex<Option<i64>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+// Failsafes and alerts only, throttling and threshold resets are routine
+fn captures(kind: EventKind) -> bool {
+    matches!(kind, EventKind::FanFailsafe | EventKind::SensorFailure)
+}
+
+/// Called by event_log::record for every event
+pub fn on_event(kind: EventKind, message: &str) {
+    if !captures(kind) {
+        return;
+    }
+
+    let timestamp = now();
+    {
+        let mut last = LAST_CAPTURE.lock().unwrap();
+        if last.is_some_and(|last| timestamp - last < MIN_CAPTURE_GAP.as_secs() as i64) {
+            return;
+        }
+        *last = Some(timestamp);
+    }
+
+    match write_bundle(&Trigger { timestamp, kind, message }) {
+        Ok(path) => log::info!("Captured sensor history and logs to {}", path.display()),
+        Err(e) => log::warn!("Failed to capture anomaly bundle: {}", e),
+    }
+    prune();
+}
+
+fn write_bundle(trigger: &Trigger) -> anyhow::Result<PathBuf> {
+    let dir = Path::new(CAPTURE_DIR).join(format!("anomaly-{}-{:?}", trigger.timestamp, trigger.kind));
+    fs::create_dir_all(&dir)?;
+
+    fs::write(dir.join("trigger.json"), serde_json::to_string_pretty(trigger)?)?;
+
+    let mut sensors = String::new();
+    for sample in SAMPLES.lock().unwrap().iter() {
+        sensors.push_str(&serde_json::to_string(sample)?);
+        sensors.push('\n');
+    }
+    fs::write(dir.join("sensors.jsonl"), sensors)?;
+
+    let since_ms = (trigger.timestamp - WINDOW.as_secs() as i64) * 1000;
+    let mut log = String::new();
+    for entry in crate::log_buffer::get_entries(0).entries.iter().filter(|e| e.timestamp_ms >= since_ms) {
+        log.push_str(&format!("{} {:?} {}: {}\n", entry.timestamp_ms, entry.level, entry.target, entry.message));
+    }
+    fs::write(dir.join("daemon.log"), log)?;
+
+    let mut events = String::new();
+    for event in crate::event_log::get_events_after(trigger.timestamp - WINDOW.as_secs() as i64) {
+        events.push_str(&serde_json::to_string(&event)?);
+        events.push('\n');
+    }
+    fs::write(dir.join("events.jsonl"), events)?;
+
+    // Developer machines only, see ec_dump
+    if crate::ec_dump::enabled() {
+        fs::write(dir.join("ec_registers.json"), serde_json::to_string_pretty(&crate::ec_dump::dump())?)?;
+    }
+
+    Ok(dir)
+}
+
+// Keeps the newest MAX_BUNDLES, and none older than MAX_BUNDLE_AGE
+fn prune() {
+    let mut bundles: Vec<(i64, PathBuf)> = fs::read_dir(CAPTURE_DIR)
+        .map(|rd| rd.flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let timestamp = name.strip_prefix("anomaly-")?.split('-').next()?.parse().ok()?;
+                Some((timestamp, e.path()))
+            })
+            .collect())
+        .unwrap_or_default();
+    bundles.sort_by_key(|bundle| std::cmp::Reverse(bundle.0));
+
+    let oldest = now() - MAX_BUNDLE_AGE.as_secs() as i64;
+    for (i, (timestamp, path)) in bundles.iter().enumerate() {
+        if i >= MAX_BUNDLES || *timestamp < oldest {
+            if let Err(e) = fs::remove_dir_all(path) {
+                log::warn!("Failed to remove old anomaly bundle {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+pub async fn sampler_task() {
+    let backend = crate::fan_backend::detect();
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    let max_samples = (WINDOW.as_secs() / SAMPLE_INTERVAL.as_secs()) as usize;
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("anomaly_sampler");
+
+        let fans = backend.as_ref()
+            .map(|backend| (0..backend.fan_count())
+                .map(|fan_id| FanSample {
+                    fan_id,
+                    duty: backend.get_duty(fan_id).ok(),
+                    rpm: backend.get_rpm(fan_id),
+                })
+                .collect())
+            .unwrap_or_default();
+        let sample = Sample {
+            timestamp: now(),
+            temperatures: crate::hardware_detection::get_all_temperatures()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.id, s.temperature))
+                .collect(),
+            fans,
+            battery_power_w: crate::hardware_detection::get_battery_info().ok().map(|b| b.power_draw_w),
+            on_ac_power: crate::hardware_detection::is_on_ac_power(),
+        };
+
+        let mut samples = SAMPLES.lock().unwrap();
+        samples.push_back(sample);
+        while samples.len() > max_samples {
+            samples.pop_front();
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{AppProfiles, EventKind, Profile};
+use zbus::Connection;
+use crate::dbus_interface::ControlInterface;
+
+// Applies a profile while a matching application runs and goes back to the previous one when
+// it exits. Rules are ordered, with several matching applications the first rule wins. A
+// profile picked by hand while an application runs stays, it isn't reverted afterwards.
+//
+// /proc is scanned rather than listening to the kernel's process connector, which only reports
+// pids, so the names would come from /proc anyway, and misses processes started before the
+// daemon. Only comm and cmdline are read, both are readable without ptrace rights.
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const SETTINGS_FILE: &str = "/var/lib/tuxedo-control-center/app_profiles.json";
+const PROC_PATH: &str = "/proc";
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+static SETTINGS: once_cell::sync::Lazy<Mutex<AppProfiles>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+pub fn get() -> AppProfiles {
+    SETTINGS.lock().unwrap().clone()
+}
+
+/// Rules are configured, the daemon has to stay up to watch the processes
+pub fn is_enabled() -> bool {
+    !SETTINGS.lock().unwrap().rules.is_empty()
+}
+
+pub fn set(settings: AppProfiles) -> Result<()> {
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(SETTINGS_FILE, serde_json::to_string_pretty(&settings)?)?;
+    log::info!("{} application profile rules", settings.rules.len());
+    *SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
+
+fn load() -> AppProfiles {
+    match fs::read_to_string(SETTINGS_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid {}: {}", SETTINGS_FILE, e);
+            AppProfiles::default()
+        }),
+        Err(_) => AppProfiles::default(),
+    }
+}
+
+// comm is cut to 15 characters, argv[0] is what the application was started as
+fn running_processes() -> HashSet<String> {
+    let mut names = HashSet::new();
+    let Ok(entries) = fs::read_dir(PROC_PATH) else {
+        return names;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        if let Ok(comm) = fs::read_to_string(entry.path().join("comm")) {
+            names.insert(comm.trim_end().to_string());
+        }
+        if let Ok(cmdline) = fs::read(entry.path().join("cmdline")) {
+            let arg0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+            let name = arg0.rsplit(|&b| b == b'/').next().unwrap_or_default();
+            if !name.is_empty() {
+                names.insert(String::from_utf8_lossy(name).into_owned());
+            }
+        }
+    }
+    names
+}
+
+fn is_running(process: &str, running: &HashSet<String>) -> bool {
+    running.contains(process)
+        || (process.len() > 15 && process.is_char_boundary(15) && running.contains(&process[..15]))
+}
+
+/// The profile of the first rule whose application runs
+fn target(settings: &AppProfiles, running: &HashSet<String>) -> Option<(String, Profile)> {
+    settings.rules.iter()
+        .filter(|rule| !rule.process.is_empty() && is_running(&rule.process, running))
+        .find_map(|rule| {
+            let profile = settings.profiles.iter().find(|p| p.name == rule.profile)?;
+            Some((rule.process.clone(), profile.clone()))
+        })
+}
+
+// Runs on the service connection, ActiveProfile and ProfileApplied come from the bus name owner
+pub async fn monitor_task(connection: Connection) {
+    let iface = match connection.object_server().interface::<_, ControlInterface>("/com/tuxedo/Control").await {
+        Ok(iface) => iface,
+        Err(e) => {
+            log::warn!("Application profile switching disabled: {}", e);
+            return;
+        }
+    };
+    // Name of the profile applied for an application, and the one to go back to
+    let mut switched: Option<String> = None;
+    let mut previous: Option<Profile> = None;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let settings = get();
+        if settings.rules.is_empty() && switched.is_none() {
+            continue;
+        }
+        // The A/B test switches profiles itself, and restores the previous one when done
+        if crate::safe_mode::is_active() || crate::ab_test::is_running() {
+            continue;
+        }
+        let _timer = crate::daemon_stats::TickTimer::new("app_profiles");
+
+        let running = tokio::task::spawn_blocking(running_processes).await.unwrap_or_default();
+        let target = target(&settings, &running);
+        let applied = crate::safe_mode::applied_profile();
+
+        // Switched by hand in the meantime, that choice wins over the rules until the next change
+        if switched.is_some() && applied != switched {
+            log::info!("Profile changed by hand, not reverting to the one before the application");
+            switched = None;
+            previous = None;
+        }
+
+        match target {
+            Some((process, profile)) if switched.as_deref() != Some(profile.name.as_str()) => {
+                if applied.as_deref() == Some(profile.name.as_str()) {
+                    continue;
+                }
+                if switched.is_none() {
+                    previous = crate::safe_mode::last_applied();
+                }
+                let name = profile.name.clone();
+                log::info!("{} is running, switching to '{}'", process, name);
+                match crate::apply_pipeline::apply_announced(profile, &iface).await {
+                    Ok(()) => {
+                        crate::event_log::record(EventKind::ProfileSwitch,
+                            format!("{} started, switched to '{}'", process, name));
+                        switched = Some(name);
+                    }
+                    Err(e) => log::warn!("Failed to switch to '{}' for {}: {}", name, process, e),
+                }
+            }
+            Some(_) => {}
+            None if switched.is_some() => {
+                switched = None;
+                let Some(profile) = previous.take() else {
+                    continue;
+                };
+                let name = profile.name.clone();
+                log::info!("No matching application runs anymore, back to '{}'", name);
+                match crate::apply_pipeline::apply_announced(profile, &iface).await {
+                    Ok(()) => crate::event_log::record(EventKind::ProfileSwitch,
+                        format!("Application exited, back to '{}'", name)),
+                    Err(e) => log::warn!("Failed to switch back to '{}': {}", name, e),
+                }
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tuxedo_common::types::{ApplyProgress, ApplyReport, HardwareEvent, Profile};
+use zbus::object_server::InterfaceRef;
+use zbus::SignalContext;
+use crate::dbus_interface::ControlInterface;
+
+// apply_profile writes to sysfs, issues ioctls and runs nvidia-smi, any of which blocks for
+// as long as the driver or the firmware behind it takes. It runs on tokio's blocking pool,
+// one apply at a time, and reports each step as it starts. A step that doesn't finish
+// within STEP_TIMEOUT fails the apply: a write stuck in the kernel can't be interrupted, so
+// its thread is left behind, but the DBus service, the fan loop and later applies go on.
+// A newer apply or CancelApply stops the running one before its next step.
+const STEP_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum Event {
+    Step(String),
+    Done(Result<()>, Option<ApplyReport>),
+}
+
+struct Worker {
+    cancelled: Arc<AtomicBool>,
+    events: mpsc::UnboundedSender<Event>,
+}
+
+thread_local! {
+    static WORKER: RefCell<Option<Worker>> = const { RefCell::new(None) };
+}
+
+static APPLY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+// Applies waiting for the lock give up once a newer one was requested
+static LATEST: AtomicU64 = AtomicU64::new(0);
+static RUNNING: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+static PROGRESS: once_cell::sync::Lazy<Mutex<ApplyProgress>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(ApplyProgress::default()));
+
+pub fn get_progress() -> ApplyProgress {
+    PROGRESS.lock().unwrap().clone()
+}
+
+/// Stop the running apply before its next step, false if none is running
+pub fn cancel() -> bool {
+    match RUNNING.lock().unwrap().as_ref() {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Apply a profile off the runtime threads, with progress signalled on `ctxt` if given
+pub async fn apply(profile: Profile, ctxt: Option<&SignalContext<'_>>) -> Result<()> {
+    apply_reported(profile, ctxt).await.0
+}
+
+/// apply() along with the step timings of this apply, None if it never got to run
+pub async fn apply_reported(profile: Profile, ctxt: Option<&SignalContext<'_>>)
+    -> (Result<()>, Option<ApplyReport>)
+{
+    let id = LATEST.fetch_add(1, Ordering::SeqCst) + 1;
+    cancel();
+    let _lock = APPLY_LOCK.lock().await;
+    if LATEST.load(Ordering::SeqCst) != id {
+        return (Err(anyhow!("Cancelled, another profile is being applied")), None);
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    *RUNNING.lock().unwrap() = Some(cancelled.clone());
+    let (events, mut receiver) = mpsc::unbounded_channel();
+    let mut progress = ApplyProgress {
+        profile: profile.name.clone(),
+        running: true,
+        ..Default::default()
+    };
+    report(ctxt, &progress).await;
+
+    let worker = Worker { cancelled: cancelled.clone(), events: events.clone() };
+    tokio::task::spawn_blocking(move || {
+        WORKER.with(|w| *w.borrow_mut() = Some(worker));
+        let result = crate::hardware_control::apply_profile(&profile);
+        let timings = crate::apply_timing::take_finished();
+        // Blocking pool threads are reused
+        WORKER.with(|w| *w.borrow_mut() = None);
+        let _ = events.send(Event::Done(result, timings));
+    });
+
+    let mut timings = None;
+    let result = loop {
+        match tokio::time::timeout(STEP_TIMEOUT, receiver.recv()).await {
+            Ok(Some(Event::Step(step))) => {
+                if !progress.step.is_empty() {
+                    progress.steps_done += 1;
+                }
+                progress.step = step;
+                report(ctxt, &progress).await;
+            }
+            Ok(Some(Event::Done(result, report))) => {
+                timings = report;
+                break result;
+            }
+            Ok(None) => break Err(anyhow!("Applying the profile stopped unexpectedly")),
+            Err(_) => {
+                cancelled.store(true, Ordering::SeqCst);
+                log::error!("'{}' hangs in step {}, giving up after {} s",
+                    progress.profile, progress.step, STEP_TIMEOUT.as_secs());
+                break Err(anyhow!("{} did not finish within {} s, the driver may be stuck",
+                    progress.step, STEP_TIMEOUT.as_secs()));
+            }
+        }
+    };
+
+    *RUNNING.lock().unwrap() = None;
+    progress.running = false;
+    progress.error = result.as_ref().err().map(|e| e.to_string());
+    report(ctxt, &progress).await;
+    (result, timings)
+}
+
+/// Put back the driver-held parts of the last applied profile, after the running apply and
+/// never alongside one. None if no profile was applied yet.
+pub async fn reapply_driver_settings() -> Option<(String, Result<()>)> {
+    let _lock = APPLY_LOCK.lock().await;
+    let profile = crate::safe_mode::last_applied()?;
+    let name = profile.name.clone();
+    let task = tokio::task::spawn_blocking(move || crate::hardware_control::reapply_driver_settings(&profile));
+    let result = match tokio::time::timeout(STEP_TIMEOUT, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(anyhow!("Re-applying stopped unexpectedly: {}", e)),
+        Err(_) => Err(anyhow!("Re-applying did not finish within {} s, the driver may be stuck",
+            STEP_TIMEOUT.as_secs())),
+    };
+    Some((name, result))
+}
+
+/// apply() for switches the daemon makes itself, announced like ApplyProfile calls are
+pub async fn apply_announced(profile: Profile, iface: &InterfaceRef<ControlInterface>) -> Result<()> {
+    let name = profile.name.clone();
+    let ctxt = iface.signal_context();
+    apply(profile, Some(ctxt)).await?;
+    if let Err(e) = iface.get().await.active_profile_changed(ctxt).await {
+        log::debug!("Failed to signal active profile change: {}", e);
+    }
+    if let Err(e) = ControlInterface::profile_applied(ctxt, &name).await {
+        log::debug!("Failed to signal applied profile: {}", e);
+    }
+    Ok(())
+}
+
+/// Called by apply_timing::step on the worker thread, fails once the apply was cancelled
+pub fn step_started(name: &str) -> Result<()> {
+    WORKER.with(|w| match w.borrow().as_ref() {
+        Some(worker) if worker.cancelled.load(Ordering::SeqCst) => {
+            Err(anyhow!("Cancelled before {}", name))
+        }
+        Some(worker) => {
+            let _ = worker.events.send(Event::Step(name.to_string()));
+            Ok(())
+        }
+        // Previews and re-applies outside the pipeline
+        None => Ok(()),
+    })
+}
+
+async fn report(ctxt: Option<&SignalContext<'_>>, progress: &ApplyProgress) {
+    *PROGRESS.lock().unwrap() = progress.clone();
+    if let Some(ctxt) = ctxt {
+        crate::hotkey_monitor::emit(ctxt, HardwareEvent::ApplyProgress(progress.clone())).await;
+    }
+}
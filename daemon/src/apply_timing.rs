@@ -0,0 +1,92 @@
+use anyhow::Result;
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{ApplyReport, ApplyStepTiming, SlowWrite};
+
+// How long each step of apply_profile took, and the writes within it that were unusually slow.
+// A sysfs write normally returns within a millisecond, one taking a tenth of a second or more
+// usually means the driver is waiting on the EC or an ACPI method that is misbehaving.
+const SLOW_WRITE: Duration = Duration::from_millis(100);
+
+thread_local! {
+    static CURRENT: RefCell<Option<ApplyReport>> = const { RefCell::new(None) };
+    // Picked up by the apply pipeline for the caller of this very apply
+    static FINISHED: RefCell<Option<ApplyReport>> = const { RefCell::new(None) };
+}
+
+static LAST: Mutex<Option<ApplyReport>> = Mutex::new(None);
+
+/// Report of the last real apply, previews don't replace it
+pub fn last() -> Option<ApplyReport> {
+    LAST.lock().unwrap().clone()
+}
+
+/// Run `apply` with its steps and writes timed
+pub fn record(profile: &str, apply: impl FnOnce() -> Result<()>) -> Result<()> {
+    if crate::dry_run::is_active() {
+        return apply();
+    }
+    CURRENT.with(|c| *c.borrow_mut() = Some(ApplyReport {
+        profile: profile.to_string(),
+        ..Default::default()
+    }));
+    let start = Instant::now();
+    let result = apply();
+    let mut report = CURRENT.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    report.total_ms = millis(start.elapsed());
+    report.error = result.as_ref().err().map(|e| e.to_string());
+
+    if let Some(slowest) = report.steps.iter().max_by(|a, b| a.millis.total_cmp(&b.millis)) {
+        log::debug!("Applied '{}' in {:.1} ms, slowest step {} ({:.1} ms)",
+            report.profile, report.total_ms, slowest.name, slowest.millis);
+    }
+    FINISHED.with(|f| *f.borrow_mut() = Some(report.clone()));
+    *LAST.lock().unwrap() = Some(report);
+    result
+}
+
+/// Report of the apply that just ran on this thread
+pub fn take_finished() -> Option<ApplyReport> {
+    FINISHED.with(|f| f.borrow_mut().take())
+}
+
+/// One named step of the apply
+pub fn step<T>(name: &str, run: impl FnOnce() -> Result<T>) -> Result<T> {
+    crate::apply_pipeline::step_started(name)?;
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+    CURRENT.with(|c| {
+        if let Some(report) = c.borrow_mut().as_mut() {
+            report.steps.push(ApplyStepTiming {
+                name: name.to_string(),
+                millis: millis(elapsed),
+                failed: result.is_err(),
+            });
+        }
+    });
+    result
+}
+
+/// Called by dry_run::write for every real write
+pub fn write_took(target: &Path, elapsed: Duration) {
+    if elapsed < SLOW_WRITE {
+        return;
+    }
+    // Outside of an apply (fan daemon, monitors) a slow write would warn on every tick
+    CURRENT.with(|c| {
+        if let Some(report) = c.borrow_mut().as_mut() {
+            log::warn!("Slow write to {}: {:.0} ms, possibly a firmware issue", target.display(), millis(elapsed));
+            report.slow_writes.push(SlowWrite {
+                target: target.display().to_string(),
+                millis: millis(elapsed),
+            });
+        }
+    });
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,146 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use tuxedo_common::types::{AquarisSettings, AquarisStatus, PumpVoltage};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+// TUXEDO Aquaris external water cooler (LCT21001). It only speaks Bluetooth LE: a serial-style
+// GATT characteristic takes 8-byte frames, 0xfe <command> <on> <arguments> 0xef, one for the
+// fan and one for the pump. Pairing and connecting are left to the desktop's Bluetooth
+// settings, the daemon drives a connected cooler through BlueZ. The cooler reports nothing
+// back, so there is no speed or temperature to show, and a cooler that isn't connected is
+// skipped rather than failing the apply.
+const NAME_PREFIXES: [&str; 2] = ["LCT21001", "Aquaris"];
+const WRITE_UUID: &str = "0000ffe1-0000-1000-8000-00805f9b34fb";
+const FRAME_START: u8 = 0xfe;
+const FRAME_END: u8 = 0xef;
+const COMMAND_FAN: u8 = 0x1b;
+const COMMAND_PUMP: u8 = 0x1c;
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+struct Cooler {
+    name: String,
+    address: String,
+    connected: bool,
+    characteristic: Option<OwnedObjectPath>,
+}
+
+pub fn get_status() -> AquarisStatus {
+    match Connection::system().map_err(anyhow::Error::from).and_then(|c| find(&c)) {
+        Ok(Some(cooler)) => AquarisStatus {
+            present: true,
+            connected: cooler.connected && cooler.characteristic.is_some(),
+            name: cooler.name,
+            address: cooler.address,
+        },
+        Ok(None) => AquarisStatus::default(),
+        Err(e) => {
+            log::debug!("Cannot look for an Aquaris: {}", e);
+            AquarisStatus::default()
+        }
+    }
+}
+
+/// None leaves the cooler as it is
+pub fn apply(settings: Option<&AquarisSettings>) -> Result<()> {
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+    let connection = Connection::system()?;
+    let cooler = match find(&connection)? {
+        Some(cooler) if cooler.connected => cooler,
+        _ => {
+            log::info!("No Aquaris connected, skipping its settings");
+            return Ok(());
+        }
+    };
+    let characteristic = cooler.characteristic
+        .ok_or_else(|| anyhow!("{} has no writable characteristic, is the firmware supported?", cooler.name))?;
+
+    let fan = match settings.fan_duty {
+        0 => frame(COMMAND_FAN, false, &[]),
+        duty => frame(COMMAND_FAN, true, &[duty.min(100)]),
+    };
+    let pump = match settings.pump_duty {
+        0 => frame(COMMAND_PUMP, false, &[]),
+        duty => frame(COMMAND_PUMP, true, &[duty.min(100), voltage_code(settings.pump_voltage)]),
+    };
+    write(&connection, &characteristic, &fan)?;
+    write(&connection, &characteristic, &pump)?;
+    log::info!("Set {} fan to {}% and pump to {}%", cooler.name, settings.fan_duty, settings.pump_duty);
+    Ok(())
+}
+
+fn frame(command: u8, on: bool, arguments: &[u8]) -> [u8; 8] {
+    let mut frame = [FRAME_START, command, on as u8, 0, 0, 0, 0, FRAME_END];
+    frame[3..3 + arguments.len()].copy_from_slice(arguments);
+    frame
+}
+
+fn voltage_code(voltage: PumpVoltage) -> u8 {
+    match voltage {
+        PumpVoltage::V11 => 0x00,
+        PumpVoltage::V12 => 0x01,
+        PumpVoltage::V7 => 0x02,
+        PumpVoltage::V8 => 0x03,
+    }
+}
+
+fn write(connection: &Connection, characteristic: &OwnedObjectPath, frame: &[u8; 8]) -> Result<()> {
+    let hex: Vec<String> = frame.iter().map(|b| format!("{:02x}", b)).collect();
+    if crate::dry_run::command("aquaris", &[characteristic.as_str(), &hex.join(" ")]) {
+        return Ok(());
+    }
+    let proxy = Proxy::new(connection, "org.bluez", characteristic.as_str(), "org.bluez.GattCharacteristic1")?;
+    let options: HashMap<&str, Value> = HashMap::new();
+    proxy.call_method("WriteValue", &(frame.to_vec(), options))?;
+    Ok(())
+}
+
+// The first paired or discovered cooler BlueZ knows about
+fn find(connection: &Connection) -> Result<Option<Cooler>> {
+    let manager = Proxy::new(connection, "org.bluez", "/", "org.freedesktop.DBus.ObjectManager")?;
+    let objects: ManagedObjects = manager.call("GetManagedObjects", &())?;
+
+    for (path, interfaces) in &objects {
+        let Some(device) = interfaces.get("org.bluez.Device1") else {
+            continue;
+        };
+        let name = device.get("Name").and_then(|v| <&str>::try_from(v).ok()).unwrap_or_default();
+        if !NAME_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+
+        // GATT objects sit below their device, /org/bluez/hci0/dev_XX/service0010/char0011
+        let prefix = format!("{}/", path.as_str());
+        let characteristic = objects.iter()
+            .filter(|(child, _)| child.as_str().starts_with(&prefix))
+            .find(|(_, interfaces)| interfaces.get("org.bluez.GattCharacteristic1")
+                .and_then(|c| c.get("UUID"))
+                .and_then(|v| <&str>::try_from(v).ok())
+                .is_some_and(|uuid| uuid.eq_ignore_ascii_case(WRITE_UUID)))
+            .map(|(child, _)| child.clone());
+
+        return Ok(Some(Cooler {
+            name: name.to_string(),
+            address: device.get("Address").and_then(|v| <&str>::try_from(v).ok()).unwrap_or_default().to_string(),
+            connected: device.get("Connected").and_then(|v| bool::try_from(v).ok()).unwrap_or(false),
+            characteristic,
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_carry_command_and_arguments() {
+        assert_eq!(frame(COMMAND_FAN, true, &[60]), [0xfe, 0x1b, 0x01, 60, 0, 0, 0, 0xef]);
+        assert_eq!(frame(COMMAND_PUMP, true, &[80, voltage_code(PumpVoltage::V12)]),
+            [0xfe, 0x1c, 0x01, 80, 0x01, 0, 0, 0xef]);
+        assert_eq!(frame(COMMAND_PUMP, false, &[]), [0xfe, 0x1c, 0x00, 0, 0, 0, 0, 0xef]);
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tuxedo_common::types::{AutoTuneSample, AutoTuneStatus, EventKind, FanCurve, FanModeSource, FanSettings, Profile};
+use crate::fan_backend::FanBackend;
+
+// Fixed fan duties standing in for noise levels, quietest first
+const FAN_DUTIES: [u8; 3] = [40, 70, 100];
+// Each step heats up first, then measures the steady state
+const WARMUP: Duration = Duration::from_secs(15);
+const MEASURE: Duration = Duration::from_secs(15);
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+// A combination that gets this hot is not proposed
+const ABORT_TEMP: f32 = 95.0;
+// Balanced is the quietest setting within this share of the best clock
+const BALANCED_SHARE: f64 = 0.9;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static ABORT_REQUESTED: AtomicBool = AtomicBool::new(false);
+static STATUS: once_cell::sync::Lazy<Mutex<AutoTuneStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(AutoTuneStatus::default()));
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+pub fn get_status() -> AutoTuneStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+pub fn start() -> Result<()> {
+    if crate::dust_cleaning::is_running() {
+        return Err(anyhow!("Dust cleaning is running"));
+    }
+    if crate::fan_min_duty::is_calibrating() {
+        return Err(anyhow!("Fan calibration is running"));
+    }
+    if crate::ab_test::is_running() {
+        return Err(anyhow!("An A/B test is running"));
+    }
+
+    let backend: Arc<dyn FanBackend> = crate::fan_backend::detect()
+        .ok_or_else(|| anyhow!("Fan control not available"))?;
+
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(anyhow!("Auto-tuning is already running"));
+    }
+
+    // Without TDP profiles only the fan duty is varied
+    let tdp_profiles: Vec<Option<String>> = match crate::hardware_detection::get_tdp_profiles() {
+        Ok(profiles) if !profiles.is_empty() => profiles.into_iter().map(Some).collect(),
+        _ => vec![None],
+    };
+
+    ABORT_REQUESTED.store(false, Ordering::SeqCst);
+    *STATUS.lock().unwrap() = AutoTuneStatus {
+        running: true,
+        message: "Starting auto-tuning".to_string(),
+        ..Default::default()
+    };
+
+    log::info!("Starting auto-tuning over {} TDP profiles", tdp_profiles.len());
+
+    tokio::spawn(async move {
+        let _inhibitor = crate::sleep_inhibit::acquire("Auto-tuning").await;
+        let result = run_tuning(backend.as_ref(), &tdp_profiles).await;
+        restore(backend.as_ref());
+
+        let mut status = STATUS.lock().unwrap();
+        status.running = false;
+        match result {
+            Ok(()) => {
+                status.proposals = propose_profiles(&status.samples, backend.fan_count());
+                status.progress_percent = 100;
+                status.message = format!("Finished, {} profiles proposed", status.proposals.len());
+                log::info!("Auto-tuning finished");
+            }
+            Err(e) => {
+                log::warn!("Auto-tuning stopped: {}", e);
+                status.message = e.to_string();
+            }
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(())
+}
+
+pub fn abort() -> Result<()> {
+    if !is_running() {
+        return Err(anyhow!("Auto-tuning is not running"));
+    }
+
+    log::info!("Auto-tuning abort requested");
+    ABORT_REQUESTED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+async fn run_tuning(backend: &dyn FanBackend, tdp_profiles: &[Option<String>]) -> Result<()> {
+    let steps: Vec<(Option<String>, u8)> = tdp_profiles.iter()
+        .flat_map(|tdp| FAN_DUTIES.iter().map(|duty| (tdp.clone(), *duty)))
+        .collect();
+    let total_steps = steps.len();
+    // Stopped when dropped, also on errors
+    let _load = CpuLoad::start();
+
+    for (i, (tdp_profile, fan_duty)) in steps.into_iter().enumerate() {
+        {
+            let mut status = STATUS.lock().unwrap();
+            status.progress_percent = ((i * 100) / total_steps) as u8;
+            status.message = format!("Step {}/{}: TDP {}, fans at {}%", i + 1, total_steps,
+                tdp_profile.as_deref().unwrap_or("unchanged"), fan_duty);
+        }
+
+        if let Some(ref tdp) = tdp_profile {
+            crate::hardware_control::set_tdp_profile(tdp)?;
+        }
+
+        let sample = run_step(backend, tdp_profile, fan_duty).await?;
+        log::info!("Auto-tune: {:?}", sample);
+        STATUS.lock().unwrap().samples.push(sample);
+    }
+
+    Ok(())
+}
+
+async fn run_step(backend: &dyn FanBackend, tdp_profile: Option<String>, fan_duty: u8) -> Result<AutoTuneSample> {
+    let mut elapsed = Duration::ZERO;
+    let mut temps = Vec::new();
+    let mut frequencies = Vec::new();
+
+    while elapsed < WARMUP + MEASURE {
+        // The EC may take over again, so keep writing the duty
+        set_all_fans(backend, fan_duty as u32)?;
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        elapsed += SAMPLE_INTERVAL;
+
+        if ABORT_REQUESTED.load(Ordering::SeqCst) {
+            return Err(anyhow!("Aborted by user"));
+        }
+
+        let cpu = crate::hardware_detection::get_cpu_info()?;
+        if cpu.package_temp >= ABORT_TEMP {
+            log::info!("Auto-tune: {:.0}°C at fan duty {}%, skipping the rest of this step",
+                cpu.package_temp, fan_duty);
+            set_all_fans(backend, 100)?;
+            crate::event_log::record(EventKind::FanFailsafe,
+                format!("Auto-tune: fans to 100% at {:.0}°C", cpu.package_temp));
+            return Ok(AutoTuneSample {
+                tdp_profile,
+                fan_duty,
+                avg_temp: cpu.package_temp,
+                avg_frequency: cpu.median_frequency / 1000,
+                too_hot: true,
+            });
+        }
+
+        if elapsed > WARMUP {
+            temps.push(cpu.package_temp);
+            frequencies.push(cpu.median_frequency / 1000);
+        }
+    }
+
+    Ok(AutoTuneSample {
+        tdp_profile,
+        fan_duty,
+        avg_temp: temps.iter().sum::<f32>() / temps.len().max(1) as f32,
+        avg_frequency: frequencies.iter().sum::<u64>() / frequencies.len().max(1) as u64,
+        too_hot: false,
+    })
+}
+
+/// Quiet, Balanced and Performance picks from the measured combinations
+fn propose_profiles(samples: &[AutoTuneSample], fan_count: u32) -> Vec<Profile> {
+    let usable: Vec<&AutoTuneSample> = samples.iter().filter(|s| !s.too_hot).collect();
+    let best_frequency = match usable.iter().map(|s| s.avg_frequency).max() {
+        Some(best) => best,
+        None => return Vec::new(),
+    };
+
+    // Fastest at the lowest fan duty, fastest overall, quietest close to the fastest
+    let quietest_duty = usable.iter().map(|s| s.fan_duty).min().unwrap_or(100);
+    let quiet = usable.iter()
+        .filter(|s| s.fan_duty == quietest_duty)
+        .max_by_key(|s| s.avg_frequency);
+    let performance = usable.iter()
+        .filter(|s| s.avg_frequency == best_frequency)
+        .min_by_key(|s| s.fan_duty);
+    let balanced = usable.iter()
+        .filter(|s| s.avg_frequency as f64 >= best_frequency as f64 * BALANCED_SHARE)
+        .min_by_key(|s| (s.fan_duty, std::cmp::Reverse(s.avg_frequency)));
+
+    let mut picked: Vec<&AutoTuneSample> = Vec::new();
+    let mut proposals = Vec::new();
+    for (name, sample) in [("Auto Quiet", quiet), ("Auto Balanced", balanced), ("Auto Performance", performance)] {
+        let sample = match sample {
+            Some(sample) => *sample,
+            None => continue,
+        };
+        // Identical picks would only produce duplicate profiles
+        if picked.iter().any(|p| std::ptr::eq(*p, sample)) {
+            continue;
+        }
+        picked.push(sample);
+        proposals.push(profile_from_sample(name, sample, fan_count));
+    }
+
+    proposals
+}
+
+fn profile_from_sample(name: &str, sample: &AutoTuneSample, fan_count: u32) -> Profile {
+    let mut profile = Profile {
+        name: name.to_string(),
+        is_default: false,
+        ..Profile::default()
+    };
+    profile.cpu_settings.tdp_profile = sample.tdp_profile.clone();
+
+    // Capped at the tested duty under load, with full speed as a last resort near the limit
+    let cap = sample.fan_duty;
+    let points = vec![
+        (40, 20.min(cap)), (50, 30.min(cap)), (60, 45.min(cap)), (70, 60.min(cap)),
+        (80, cap), (88, cap), (92, cap.max(80)), (95, 100),
+    ];
+    profile.fan_settings = FanSettings {
+        control_enabled: true,
+        curves: (0..fan_count).map(|fan_id| FanCurve {
+            fan_id,
+            points: points.clone(),
+            temp_source: None,
+        }).collect(),
+        offsets: vec![],
+        smoothing: profile.fan_settings.smoothing.clone(),
+    };
+
+    profile
+}
+
+fn set_all_fans(backend: &dyn FanBackend, duty: u32) -> Result<()> {
+    for fan_id in 0..backend.fan_count() {
+        backend.set_duty(fan_id, duty)?;
+        crate::fan_mode::record_manual(FanModeSource::Daemon, fan_id, duty);
+    }
+    Ok(())
+}
+
+fn restore(backend: &dyn FanBackend) {
+    // Custom curves are picked up again by the fan daemon on its next tick
+    let curves_active = crate::FAN_DAEMON_STATE.lock().unwrap()
+        .as_ref()
+        .map(|s| s.control_enabled)
+        .unwrap_or(false);
+
+    if !curves_active {
+        match backend.set_auto() {
+            Ok(()) => crate::fan_mode::record_auto(FanModeSource::Daemon),
+            Err(e) => log::error!("Failed to restore fan auto mode after auto-tuning: {}", e),
+        }
+    }
+
+    if let Some(tdp) = crate::cpu_power_source::profile_tdp_profile() {
+        if let Err(e) = crate::hardware_control::set_tdp_profile(&tdp) {
+            log::warn!("Failed to restore TDP profile after auto-tuning: {}", e);
+        }
+    }
+}
+
+/// Busy loop on every CPU, stopped when dropped
+struct CpuLoad {
+    stop: Arc<AtomicBool>,
+}
+
+impl CpuLoad {
+    fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+        for _ in 0..threads {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut x: u64 = 1;
+                while !stop.load(Ordering::Relaxed) {
+                    x = std::hint::black_box(x.wrapping_mul(6364136223846793005).wrapping_add(1));
+                }
+            });
+        }
+
+        Self { stop }
+    }
+}
+
+impl Drop for CpuLoad {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
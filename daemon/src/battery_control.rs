@@ -1,40 +1,103 @@
 use anyhow::{anyhow, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tuxedo_common::types::{ChargeBackend, ChargeControlInfo};
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+// The flexicharger battery hook is registered by tuxedo-drivers on Clevo models, it
+// is only reachable through power_supply, tuxedo_io has no ioctl for it
+const TUXEDO_PLATFORM: &str = "/sys/devices/platform/tuxedo_keyboard";
 
 pub struct BatteryControl {
     battery_path: PathBuf,
+    backend: ChargeBackend,
 }
 
 impl BatteryControl {
     pub fn new() -> Result<Self> {
-        let battery_path = Self::find_battery_path()?;
-        Ok(Self { battery_path })
+        let (battery_path, backend) = Self::find_battery()?;
+        Ok(Self { battery_path, backend })
     }
     
     pub fn is_available() -> bool {
-        Self::find_battery_path().is_ok()
+        Self::find_battery().is_ok()
     }
     
-    fn find_battery_path() -> Result<PathBuf> {
-        for bat in &["BAT0", "BAT1"] {
-            let path = PathBuf::from(format!("/sys/class/power_supply/{}", bat));
-            if path.exists() {
-                // Check if charge control is available
-                let charge_type_path = path.join("charge_type");
-                if charge_type_path.exists() {
-                    return Ok(path);
-                }
+    // Picks the battery with the best backend, batteries are tried in name order
+    fn find_battery() -> Result<(PathBuf, ChargeBackend)> {
+        let mut batteries: Vec<PathBuf> = fs::read_dir(POWER_SUPPLY)
+            .map(|rd| rd.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        batteries.retain(|path| fs::read_to_string(path.join("type"))
+            .map(|t| t.trim() == "Battery")
+            .unwrap_or(false));
+        batteries.sort();
+        
+        batteries.into_iter()
+            .filter_map(|path| Self::detect_backend(&path).map(|backend| (path, backend)))
+            .min_by_key(|(_, backend)| match backend {
+                ChargeBackend::Flexicharger => 0,
+                ChargeBackend::KernelThresholds => 1,
+                ChargeBackend::ChargeBehaviour => 2,
+            })
+            .ok_or_else(|| anyhow!("No battery with charge control found"))
+    }
+    
+    fn detect_backend(path: &Path) -> Option<ChargeBackend> {
+        if path.join("charge_control_end_threshold").exists() {
+            if path.join("charge_type").exists() && Path::new(TUXEDO_PLATFORM).exists() {
+                return Some(ChargeBackend::Flexicharger);
             }
+            return Some(ChargeBackend::KernelThresholds);
+        }
+        path.join("charge_behaviour").exists().then_some(ChargeBackend::ChargeBehaviour)
+    }
+    
+    pub fn backend(&self) -> ChargeBackend {
+        self.backend
+    }
+    
+    /// The file whose writability decides whether charge control works
+    pub fn control_path(&self) -> PathBuf {
+        self.battery_path.join(match self.backend {
+            ChargeBackend::Flexicharger => "charge_type",
+            ChargeBackend::KernelThresholds => "charge_control_end_threshold",
+            ChargeBackend::ChargeBehaviour => "charge_behaviour",
+        })
+    }
+    
+    pub fn has_thresholds(&self) -> bool {
+        self.backend != ChargeBackend::ChargeBehaviour
+    }
+    
+    pub fn has_start_threshold(&self) -> bool {
+        self.battery_path.join("charge_control_start_threshold").exists()
+    }
+    
+    pub fn get_info(&self) -> ChargeControlInfo {
+        let (behaviours, behaviour) = self.get_charge_behaviours();
+        ChargeControlInfo {
+            backend: Some(self.backend),
+            battery: self.battery_path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            start_threshold: self.has_start_threshold(),
+            behaviours,
+            behaviour,
         }
-        Err(anyhow!("No battery with charge control found"))
     }
     
     /// Get charge control mode: "Standard" or "Custom"
     pub fn get_charge_type(&self) -> Result<String> {
         let path = self.battery_path.join("charge_type");
-        let content = fs::read_to_string(&path)?;
-        Ok(content.trim().to_string())
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            return Ok(content.trim().to_string());
+        }
+        
+        // Without charge_type the thresholds are always in effect, a full end threshold means no limit
+        let limited = self.has_thresholds() && self.get_charge_control_end_threshold()? < 100;
+        Ok(if limited { "Custom" } else { "Standard" }.to_string())
     }
     
     /// Set charge control mode: "Standard" or "Custom"
@@ -44,7 +107,17 @@ impl BatteryControl {
         }
         
         let path = self.battery_path.join("charge_type");
-        fs::write(&path, charge_type)?;
+        if path.exists() {
+            crate::dry_run::write(&path, charge_type)?;
+        } else if charge_type == "Standard" && self.has_thresholds() {
+            self.set_charge_control_end_threshold(100)?;
+            if self.has_start_threshold() {
+                // Drivers without a notion of "off" reject 0, the end threshold already lifts the limit
+                if let Err(e) = self.set_charge_control_start_threshold(0) {
+                    log::debug!("Could not reset charge start threshold: {}", e);
+                }
+            }
+        }
         Ok(())
     }
     
@@ -63,7 +136,7 @@ impl BatteryControl {
         }
         
         let path = self.battery_path.join("charge_control_start_threshold");
-        fs::write(&path, threshold.to_string())?;
+        crate::dry_run::write(&path, threshold.to_string())?;
         Ok(())
     }
     
@@ -82,10 +155,29 @@ impl BatteryControl {
         }
         
         let path = self.battery_path.join("charge_control_end_threshold");
-        fs::write(&path, threshold.to_string())?;
+        crate::dry_run::write(&path, threshold.to_string())?;
         Ok(())
     }
     
+    /// Set both thresholds, the start threshold is skipped if the driver has none
+    pub fn set_thresholds(&self, start: u8, end: u8) -> Result<()> {
+        if !self.has_thresholds() {
+            return Err(anyhow!("{} has no charge thresholds", self.backend.label()));
+        }
+        if !self.has_start_threshold() {
+            return self.set_charge_control_end_threshold(end);
+        }
+        
+        let current_end = self.get_charge_control_end_threshold().unwrap_or(100);
+        if end_first(start, current_end) {
+            self.set_charge_control_end_threshold(end)?;
+            self.set_charge_control_start_threshold(start)
+        } else {
+            self.set_charge_control_start_threshold(start)?;
+            self.set_charge_control_end_threshold(end)
+        }
+    }
+    
     /// Get available start thresholds
     pub fn get_available_start_thresholds(&self) -> Result<Vec<u8>> {
         let path = self.battery_path.join("charge_control_start_available_thresholds");
@@ -118,6 +210,34 @@ impl BatteryControl {
         Ok(thresholds)
     }
     
+    /// Supported charge_behaviour values and the active one, the file marks it as "[auto]"
+    pub fn get_charge_behaviours(&self) -> (Vec<String>, Option<String>) {
+        let content = fs::read_to_string(self.battery_path.join("charge_behaviour")).unwrap_or_default();
+        let mut current = None;
+        let behaviours = content.split_whitespace()
+            .map(|value| match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                Some(active) => {
+                    current = Some(active.to_string());
+                    active.to_string()
+                }
+                None => value.to_string(),
+            })
+            .collect();
+        (behaviours, current)
+    }
+    
+    /// Set charge_behaviour: "auto", "inhibit-charge" or "force-discharge"
+    pub fn set_charge_behaviour(&self, behaviour: &str) -> Result<()> {
+        let (behaviours, _) = self.get_charge_behaviours();
+        if !behaviours.iter().any(|b| b == behaviour) {
+            return Err(anyhow!("Unsupported charge behaviour '{}', expected one of {}",
+                behaviour, behaviours.join(", ")));
+        }
+        
+        crate::dry_run::write(self.battery_path.join("charge_behaviour"), behaviour)?;
+        Ok(())
+    }
+    
     /// Get battery status
     pub fn get_status(&self) -> Result<String> {
         let path = self.battery_path.join("status");
@@ -147,3 +267,54 @@ impl BatteryControl {
         Ok(content.trim().to_string())
     }
 }
+
+pub fn get_info() -> ChargeControlInfo {
+    BatteryControl::new().map(|battery| battery.get_info()).unwrap_or_default()
+}
+
+// Kernel drivers reject a start threshold above the current end threshold, so the end
+// threshold is written first when the new start wouldn't fit below the old end
+fn end_first(start: u8, current_end: u8) -> bool {
+    start >= current_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // What a driver accepts, checked after every single write
+    fn write_all(current: (u8, u8), target: (u8, u8)) -> Result<(u8, u8), (u8, u8)> {
+        let (mut start, mut end) = current;
+        let mut write = |is_end: bool| {
+            if is_end { end = target.1 } else { start = target.0 }
+            if start < end { Ok(()) } else { Err((start, end)) }
+        };
+        if end_first(target.0, current.1) {
+            write(true)?;
+            write(false)?;
+        } else {
+            write(false)?;
+            write(true)?;
+        }
+        Ok((start, end))
+    }
+
+    fn thresholds() -> impl Strategy<Value = (u8, u8)> {
+        (0u8..100).prop_flat_map(|start| (Just(start), start + 1..=100))
+    }
+
+    proptest! {
+        #[test]
+        fn valid_thresholds_never_pass_an_invalid_state(current in thresholds(), target in thresholds()) {
+            prop_assert_eq!(write_all(current, target), Ok(target));
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(write_all((0, 100), (99, 100)), Ok((99, 100)));
+        assert_eq!(write_all((99, 100), (0, 1)), Ok((0, 1)));
+        assert_eq!(write_all((0, 1), (99, 100)), Ok((99, 100)));
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use tuxedo_common::types::{CpuInfo, FanInfo};
+use zbus::{Connection, Proxy};
+
+// Small command line client for the daemon, meant for scripts and status bars:
+//
+//   tuxedoctl status                    one line of text
+//   tuxedoctl status --format waybar    JSON lines for a waybar custom module
+//   tuxedoctl status --format polybar   text lines for a polybar script module (tail = true)
+//
+// The bar formats keep running and print a new line whenever the status changes.
+
+// Fan duty isn't signalled, it is polled alongside the signals
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const WARM_TEMP: f32 = 70.0;
+const CRITICAL_TEMP: f32 = 85.0;
+
+const USAGE: &str = "Usage: tuxedoctl status [--format text|waybar|polybar] [--once]";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Waybar,
+    Polybar,
+}
+
+struct Status {
+    profile: String,
+    cpu: Option<CpuInfo>,
+    fans: Vec<FanInfo>,
+    headroom: Option<(f64, String)>,
+}
+
+impl Status {
+    fn class(&self) -> &'static str {
+        match self.cpu.as_ref().map(|c| c.package_temp) {
+            Some(t) if t >= CRITICAL_TEMP => "critical",
+            Some(t) if t >= WARM_TEMP => "warm",
+            Some(_) => "normal",
+            None => "unknown",
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(cpu) = &self.cpu {
+            parts.push(format!("{:.0}°C", cpu.package_temp));
+        }
+        if let Some(duty) = self.fans.iter().map(|f| f.rpm_or_percent).max() {
+            parts.push(format!("{}%", duty));
+        }
+        if !self.profile.is_empty() {
+            parts.push(self.profile.clone());
+        }
+        if parts.is_empty() {
+            "n/a".to_string()
+        } else {
+            parts.join(" ")
+        }
+    }
+
+    fn tooltip(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.profile.is_empty() {
+            lines.push(format!("Profile: {}", self.profile));
+        }
+        if let Some(cpu) = &self.cpu {
+            lines.push(format!("CPU: {:.1}°C, {:.0}% load, {} MHz",
+                cpu.package_temp, cpu.median_load, cpu.median_frequency));
+        }
+        for fan in &self.fans {
+            lines.push(format!("{}: {}{}", fan.name, fan.rpm_or_percent, if fan.is_rpm { " RPM" } else { "%" }));
+        }
+        if let Some((degrees, sensor)) = &self.headroom {
+            lines.push(format!("Thermal headroom: {:.0}°C ({})", degrees, sensor));
+        }
+        lines.join("\n")
+    }
+
+    fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text | Format::Polybar => self.text(),
+            Format::Waybar => json!({
+                "text": self.text(),
+                "tooltip": self.tooltip(),
+                "class": self.class(),
+                "percentage": self.cpu.as_ref().map(|c| c.package_temp.clamp(0.0, 100.0).round() as u32),
+            }).to_string(),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("status") {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    }
+
+    let format = match args.iter().position(|a| a == "--format").and_then(|i| args.get(i + 1)).map(String::as_str) {
+        None | Some("text") => Format::Text,
+        Some("waybar") => Format::Waybar,
+        Some("polybar") => Format::Polybar,
+        Some(other) => {
+            eprintln!("Unknown format '{}'\n{}", other, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let once = format == Format::Text || args.iter().any(|a| a == "--once");
+
+    if let Err(e) = status(format, once).await {
+        eprintln!("tuxedoctl: {}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn status(format: Format, once: bool) -> Result<()> {
+    let connection = Connection::system().await?;
+    let proxy = Proxy::new(
+        &connection,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    if once {
+        println!("{}", read_status(&proxy).await?.render(format));
+        return Ok(());
+    }
+
+    let mut events = proxy.receive_signal("HardwareStateChanged").await?;
+    let mut profile_changes = proxy.receive_property_changed::<String>("ActiveProfile").await;
+    let mut headroom_changes = proxy.receive_property_changed::<f64>("ThermalHeadroom").await;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last = String::new();
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            Some(_) = events.next() => {}
+            Some(_) = profile_changes.next() => {}
+            Some(_) = headroom_changes.next() => {}
+        }
+
+        // Bars keep the previous line while the daemon is restarting
+        let line = match read_status(&proxy).await {
+            Ok(status) => status.render(format),
+            Err(_) if !last.is_empty() => continue,
+            Err(e) => return Err(e),
+        };
+        if line != last {
+            println!("{}", line);
+            last = line;
+        }
+    }
+}
+
+async fn read_status(proxy: &Proxy<'_>) -> Result<Status> {
+    let cpu: Option<CpuInfo> = match proxy.call::<_, _, String>("GetCpuInfo", &()).await {
+        Ok(json) => serde_json::from_str(&json).ok(),
+        Err(zbus::Error::MethodError(..)) => None,
+        Err(e) => return Err(anyhow!("Daemon not reachable: {}", e)),
+    };
+    let fans: Vec<FanInfo> = proxy.call::<_, _, String>("GetFanInfo", &()).await
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let profile: String = proxy.get_property("ActiveProfile").await.unwrap_or_default();
+    let headroom = match proxy.get_property::<f64>("ThermalHeadroom").await {
+        Ok(degrees) if degrees.is_finite() => {
+            let sensor: String = proxy.get_property("ThermalHeadroomSensor").await.unwrap_or_default();
+            Some((degrees, sensor))
+        }
+        _ => None,
+    };
+
+    Ok(Status { profile, cpu, fans, headroom })
+}
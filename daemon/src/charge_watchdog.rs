@@ -0,0 +1,144 @@
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::{ChargeWatchdogStats, EventKind};
+use crate::battery_control::BatteryControl;
+
+// Some firmwares drop custom thresholds after suspend, a BIOS update or a full charge
+// without telling anyone, so read them back now and then
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// A firmware that undoes every write isn't fought forever: the wait doubles after each
+// re-apply that didn't stick, and the watchdog stops after this many until the next apply
+const MAX_ATTEMPTS: u32 = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+struct Thresholds {
+    start: u8,
+    end: u8,
+}
+
+static EXPECTED: once_cell::sync::Lazy<Mutex<Option<Thresholds>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+static STATS: once_cell::sync::Lazy<Mutex<ChargeWatchdogStats>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(ChargeWatchdogStats::default()));
+
+// Present while the daemon holds the firmware at custom thresholds, across restarts too
+const THRESHOLDS_SET_FILE: &str = "/var/lib/tuxedo-control-center/charge_thresholds_set";
+
+/// Record whether the daemon set custom thresholds, returns whether it had before
+pub fn mark_thresholds_set(set: bool) -> bool {
+    let path = std::path::Path::new(THRESHOLDS_SET_FILE);
+    let was_set = path.exists();
+    if set == was_set || crate::dry_run::is_active() {
+        return was_set;
+    }
+    let result = if set {
+        std::fs::create_dir_all("/var/lib/tuxedo-control-center").and_then(|_| std::fs::write(path, ""))
+    } else {
+        std::fs::remove_file(path)
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to update {}: {}", THRESHOLDS_SET_FILE, e);
+    }
+    was_set
+}
+
+// Called from apply_battery_settings, None once threshold control is turned off
+pub fn set_expected(thresholds: Option<(u8, u8)>) {
+    *EXPECTED.lock().unwrap() = thresholds.map(|(start, end)| Thresholds { start, end });
+}
+
+pub fn expected() -> Option<(u8, u8)> {
+    EXPECTED.lock().unwrap().map(|t| (t.start, t.end))
+}
+
+pub fn is_active() -> bool {
+    EXPECTED.lock().unwrap().is_some()
+}
+
+pub fn get_stats() -> ChargeWatchdogStats {
+    STATS.lock().unwrap().clone()
+}
+
+pub async fn watchdog_task() {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    let mut watched: Option<Thresholds> = None;
+    let mut attempts = 0u32;
+    let mut skip_checks = 0u32;
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("charge_watchdog");
+
+        let expected = match *EXPECTED.lock().unwrap() {
+            Some(expected) => expected,
+            None => continue,
+        };
+        // New thresholds from a profile start over
+        if watched != Some(expected) {
+            watched = Some(expected);
+            attempts = 0;
+            skip_checks = 0;
+        }
+        if attempts >= MAX_ATTEMPTS {
+            continue;
+        }
+        if skip_checks > 0 {
+            skip_checks -= 1;
+            continue;
+        }
+        // The thresholds are lifted on purpose until the battery is full
+        if crate::full_charge::is_active() {
+            continue;
+        }
+        let battery = match BatteryControl::new() {
+            Ok(battery) if battery.has_thresholds() => battery,
+            _ => continue,
+        };
+
+        let charge_type = battery.get_charge_type().unwrap_or_default();
+        // Drivers with only an end threshold can't drift on the start
+        let start = if battery.has_start_threshold() {
+            battery.get_charge_control_start_threshold()
+        } else {
+            Ok(expected.start)
+        };
+        let actual = match (start, battery.get_charge_control_end_threshold()) {
+            (Ok(start), Ok(end)) => Thresholds { start, end },
+            _ => continue,
+        };
+
+        if charge_type == "Custom" && actual == expected {
+            attempts = 0;
+            continue;
+        }
+
+        let drift = format!("charge type {}, thresholds {}–{}% instead of {}–{}%",
+            charge_type, actual.start, actual.end, expected.start, expected.end);
+        attempts += 1;
+        if attempts >= MAX_ATTEMPTS {
+            log::warn!("Battery charge thresholds keep being reset ({}), giving up until the next profile apply", drift);
+            crate::event_log::record(EventKind::ChargeThresholdReset,
+                format!("Thresholds reset {} times in a row ({}), no longer re-applied", attempts, drift));
+            continue;
+        }
+        // 1, 2, 4, 8 intervals between the checks of one streak
+        skip_checks = (1 << (attempts - 1)) - 1;
+        log::warn!("Battery charge thresholds were reset by the firmware ({}), re-applying", drift);
+        if attempts == 1 {
+            crate::event_log::record(EventKind::ChargeThresholdReset, drift.clone());
+        }
+
+        {
+            let mut stats = STATS.lock().unwrap();
+            stats.resets += 1;
+            stats.last_reset = SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs() as i64);
+            stats.last_drift = Some(drift);
+        }
+
+        let result = battery.set_charge_type("Custom")
+            .and_then(|_| battery.set_thresholds(expected.start, expected.end));
+        if let Err(e) = result {
+            log::warn!("Failed to re-apply battery charge thresholds: {}", e);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+use tuxedo_common::types::ChargingOptions;
+
+// tuxedo-drivers expose these on models with USB-C power delivery. The priority decides
+// whether a weak charger feeds the battery or the CPU/GPU, the profile trades charge
+// speed and cell wear.
+const PRIORITY: &str = "/sys/devices/platform/tuxedo_keyboard/charging_priority/charging_prio";
+const PRIORITIES_AVAILABLE: &str = "/sys/devices/platform/tuxedo_keyboard/charging_priority/charging_prios_available";
+const PROFILE: &str = "/sys/devices/platform/tuxedo_keyboard/charging_profile/charging_profile";
+const PROFILES_AVAILABLE: &str = "/sys/devices/platform/tuxedo_keyboard/charging_profile/charging_profiles_available";
+
+pub fn priority_path() -> &'static str {
+    PRIORITY
+}
+
+pub fn profile_path() -> &'static str {
+    PROFILE
+}
+
+pub fn get_options() -> ChargingOptions {
+    ChargingOptions {
+        priorities: read_list(PRIORITIES_AVAILABLE),
+        profiles: read_list(PROFILES_AVAILABLE),
+        priority: read_value(PRIORITY),
+        profile: read_value(PROFILE),
+    }
+}
+
+pub fn set_priority(priority: &str) -> Result<()> {
+    write_option(PRIORITY, PRIORITIES_AVAILABLE, priority)?;
+    log::info!("Set charging priority to {}", priority);
+    Ok(())
+}
+
+pub fn set_profile(profile: &str) -> Result<()> {
+    write_option(PROFILE, PROFILES_AVAILABLE, profile)?;
+    log::info!("Set charging profile to {}", profile);
+    Ok(())
+}
+
+fn write_option(path: &str, available_path: &str, value: &str) -> Result<()> {
+    if !Path::new(path).exists() {
+        return Err(anyhow!("{} not found", path));
+    }
+
+    let available = read_list(available_path);
+    if !available.is_empty() && !available.iter().any(|v| v == value) {
+        return Err(anyhow!("Unsupported value '{}', expected one of {}", value, available.join(", ")));
+    }
+
+    crate::dry_run::write(path, value)?;
+    Ok(())
+}
+
+fn read_value(path: &str) -> Option<String> {
+    fs::read_to_string(path).ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+// The *_available files are space separated
+fn read_list(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|s| s.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::CpuSettings;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+struct CpuPolicy {
+    settings: CpuSettings,
+    on_ac: bool,
+}
+
+static POLICY: once_cell::sync::Lazy<Mutex<Option<CpuPolicy>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+/// Profile CPU settings with the battery overrides applied when running on battery
+pub fn effective_settings(settings: &CpuSettings, on_ac: bool) -> CpuSettings {
+    let mut effective = settings.clone();
+
+    if let (false, Some(battery)) = (on_ac, settings.on_battery.as_ref()) {
+        if battery.governor.is_some() {
+            effective.governor = battery.governor.clone();
+        }
+        if battery.energy_performance_preference.is_some() {
+            effective.energy_performance_preference = battery.energy_performance_preference.clone();
+        }
+        if let Some(max) = battery.max_frequency {
+            effective.max_frequency = Some(max);
+            // The AC minimum may be above the battery cap
+            effective.min_frequency = effective.min_frequency.map(|min| min.min(max));
+        }
+        if battery.tdp.is_some() {
+            effective.tdp = battery.tdp;
+        }
+    }
+
+    effective
+}
+
+// Called from apply_profile, returns the settings to apply right now
+pub fn register(settings: &CpuSettings) -> CpuSettings {
+    let on_ac = crate::hardware_detection::is_on_ac_power();
+    if !crate::dry_run::is_active() {
+        *POLICY.lock().unwrap() = Some(CpuPolicy { settings: settings.clone(), on_ac });
+    }
+    effective_settings(settings, on_ac)
+}
+
+pub fn has_battery_overrides() -> bool {
+    POLICY.lock().unwrap().as_ref().is_some_and(|p| p.settings.on_battery.is_some())
+}
+
+/// TDP profile of the last applied profile
+pub fn profile_tdp_profile() -> Option<String> {
+    POLICY.lock().unwrap().as_ref().and_then(|p| p.settings.tdp_profile.clone())
+}
+
+pub async fn power_source_task() {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("cpu_power_source");
+
+        let on_ac = crate::hardware_detection::is_on_ac_power();
+        let mut policy = POLICY.lock().unwrap();
+        let policy = match policy.as_mut() {
+            Some(policy) if policy.on_ac != on_ac => policy,
+            _ => continue,
+        };
+
+        policy.on_ac = on_ac;
+        if policy.settings.on_battery.is_none() {
+            continue;
+        }
+
+        log::info!("Power source changed to {}, re-applying CPU settings",
+            if on_ac { "AC" } else { "battery" });
+        if let Err(e) = apply_power_dependent(&effective_settings(&policy.settings, on_ac)) {
+            log::warn!("Failed to re-apply CPU settings: {}", e);
+        }
+    }
+}
+
+// Only the settings that can differ between AC and battery
+fn apply_power_dependent(settings: &CpuSettings) -> Result<()> {
+    if let Some(ref governor) = settings.governor {
+        crate::hardware_control::set_cpu_governor(governor)?;
+    }
+
+    if let Some(ref epp) = settings.energy_performance_preference {
+        crate::hardware_control::set_energy_performance_preference(epp)?;
+    }
+
+    crate::hardware_control::set_cpu_frequency_bounds(settings.min_frequency, settings.max_frequency)?;
+
+    if let Some(watts) = settings.tdp {
+        crate::hardware_control::set_cpu_tdp(watts)?;
+    }
+
+    Ok(())
+}
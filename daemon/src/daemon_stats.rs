@@ -0,0 +1,120 @@
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{DaemonStats, DbusCallStats, TaskTiming};
+use zbus::Connection;
+
+const INTERFACE: &str = "com.tuxedo.Control";
+
+#[derive(Default)]
+struct TaskCounters {
+    ticks: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+static STARTED: once_cell::sync::Lazy<Instant> = once_cell::sync::Lazy::new(Instant::now);
+static SYSFS_READ_ERRORS: AtomicU64 = AtomicU64::new(0);
+static TASKS: once_cell::sync::Lazy<Mutex<HashMap<&'static str, TaskCounters>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+static DBUS_CALLS: once_cell::sync::Lazy<Mutex<HashMap<String, u64>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+static LAST_CALL: once_cell::sync::Lazy<Mutex<Instant>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Instant::now()));
+
+/// Records how long one loop iteration of a background task took, when dropped
+pub struct TickTimer {
+    task: &'static str,
+    started: Instant,
+}
+
+impl TickTimer {
+    pub fn new(task: &'static str) -> Self {
+        Self { task, started: Instant::now() }
+    }
+}
+
+impl Drop for TickTimer {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        let mut tasks = TASKS.lock().unwrap();
+        let counters = tasks.entry(self.task).or_default();
+        counters.ticks += 1;
+        counters.total += elapsed;
+        counters.last = elapsed;
+        counters.max = counters.max.max(elapsed);
+    }
+}
+
+pub fn record_sysfs_error() {
+    SYSFS_READ_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+// Counts method calls by watching the connection instead of touching every interface method
+pub async fn dbus_call_counter(connection: Connection) {
+    // Started together with the DBus service, uptime counts from here
+    once_cell::sync::Lazy::force(&STARTED);
+    let mut stream = zbus::MessageStream::from(&connection);
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        let header = message.header();
+        if header.message_type() != zbus::message::Type::MethodCall
+            || header.interface().map(|i| i.as_str()) != Some(INTERFACE)
+        {
+            continue;
+        }
+
+        if let Some(member) = header.member() {
+            *DBUS_CALLS.lock().unwrap().entry(member.to_string()).or_default() += 1;
+        }
+        *LAST_CALL.lock().unwrap() = Instant::now();
+    }
+}
+
+/// Time since the last method call on the control interface, or since startup
+pub fn idle_for() -> Duration {
+    LAST_CALL.lock().unwrap().elapsed()
+}
+
+pub fn get_stats() -> DaemonStats {
+    let uptime = STARTED.elapsed();
+    let minutes = (uptime.as_secs_f64() / 60.0).max(1.0 / 60.0);
+    let as_ms = |d: Duration| d.as_secs_f64() * 1000.0;
+
+    let mut tasks: Vec<TaskTiming> = TASKS.lock().unwrap().iter()
+        .map(|(name, c)| TaskTiming {
+            name: name.to_string(),
+            ticks: c.ticks,
+            last_ms: as_ms(c.last),
+            avg_ms: if c.ticks > 0 { as_ms(c.total) / c.ticks as f64 } else { 0.0 },
+            max_ms: as_ms(c.max),
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut dbus_calls: Vec<DbusCallStats> = DBUS_CALLS.lock().unwrap().iter()
+        .map(|(method, count)| DbusCallStats {
+            method: method.clone(),
+            count: *count,
+            per_minute: *count as f64 / minutes,
+        })
+        .collect();
+    dbus_calls.sort_by_key(|call| std::cmp::Reverse(call.count));
+
+    DaemonStats {
+        uptime_secs: uptime.as_secs(),
+        tasks,
+        sysfs_read_errors: SYSFS_READ_ERRORS.load(Ordering::Relaxed),
+        dbus_calls,
+        sensor_failures: crate::sensor_health::failure_stats(),
+        charge_watchdog: crate::charge_watchdog::get_stats(),
+    }
+}
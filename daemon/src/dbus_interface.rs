@@ -1,9 +1,20 @@
 use anyhow::Result;
 use tuxedo_common::types::*;
-use zbus::{interface, Connection, ConnectionBuilder};
+use zbus::message::Header;
+use zbus::{interface, Connection, ConnectionBuilder, SignalContext};
 
 pub struct ControlInterface;
 
+// An ApplyProfile that ran and failed answers with the ApplyReport as the error message, so the
+// caller sees which step failed and how long the others took without asking again
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "com.tuxedo.Control.Error")]
+pub enum ApplyError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    ApplyFailed(String),
+}
+
 #[interface(name = "com.tuxedo.Control")]
 impl ControlInterface {
     async fn get_system_info(&self) -> Result<String, zbus::fdo::Error> {
@@ -62,7 +73,29 @@ impl ControlInterface {
         }
     }
 
-    async fn set_cpu_governor(&self, governor: &str) -> Result<(), zbus::fdo::Error> {
+    async fn get_all_temperatures(&self) -> Result<String, zbus::fdo::Error> {
+        match crate::hardware_detection::get_all_temperatures() {
+            Ok(sensors) => serde_json::to_string(&sensors)
+                .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
+            Err(e) => Err(zbus::fdo::Error::Failed(e.to_string())),
+        }
+    }
+
+    async fn get_igpu_frequency_info(&self) -> Result<String, zbus::fdo::Error> {
+        match crate::hardware_detection::get_igpu_frequency_info() {
+            Ok(info) => serde_json::to_string(&info)
+                .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
+            Err(e) => Err(zbus::fdo::Error::Failed(e.to_string())),
+        }
+    }
+
+    async fn set_cpu_governor(
+        &self,
+        governor: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_cpu_governor(governor)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
@@ -71,31 +104,80 @@ impl ControlInterface {
         &self,
         min_freq: u64,
         max_freq: u64,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
     ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_cpu_frequency_limits(min_freq, max_freq)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    async fn set_cpu_boost(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+    async fn set_cpu_boost(
+        &self,
+        enabled: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_cpu_boost(enabled)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    async fn set_smt(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+    async fn set_smt(
+        &self,
+        enabled: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_smt(enabled)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    async fn set_amd_pstate_status(&self, status: &str) -> Result<(), zbus::fdo::Error> {
+    async fn set_amd_pstate_status(
+        &self,
+        status: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_amd_pstate_status(status)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    async fn apply_profile(&self, profile_json: &str) -> Result<(), zbus::fdo::Error> {
+    /// Returns the ApplyReport of this apply as JSON, a failed apply carries it in ApplyFailed
+    async fn apply_profile(
+        &self,
+        profile_json: &str,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<String, ApplyError> {
+        crate::write_policy::check(&header, connection).await
+            .map_err(|e| ApplyError::ZBus(e.into()))?;
+        if crate::ab_test::is_running() {
+            return Err(ApplyError::ZBus(zbus::fdo::Error::Failed(
+                "An A/B test is running, abort it before applying another profile".to_string()).into()));
+        }
         let profile: Profile = serde_json::from_str(profile_json)
-            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
-        crate::hardware_control::apply_profile(&profile)
-            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+            .map_err(|e| ApplyError::ZBus(zbus::fdo::Error::Failed(e.to_string()).into()))?;
+        let profile_name = profile.name.clone();
+        let (result, report) = crate::apply_pipeline::apply_reported(profile, Some(&ctxt)).await;
+        if let Err(e) = result {
+            return Err(match report {
+                Some(report) => ApplyError::ApplyFailed(serde_json::to_string(&report)
+                    .unwrap_or_else(|_| e.to_string())),
+                None => ApplyError::ZBus(zbus::fdo::Error::Failed(e.to_string()).into()),
+            });
+        }
+        if let Err(e) = self.active_profile_changed(&ctxt).await {
+            log::debug!("Failed to signal active profile change: {}", e);
+        }
+        if let Err(e) = Self::profile_applied(&ctxt, &profile_name).await {
+            log::debug!("Failed to signal applied profile: {}", e);
+        }
+        serde_json::to_string(&report)
+            .map_err(|e| ApplyError::ZBus(zbus::fdo::Error::Failed(e.to_string()).into()))
     }
 
     async fn get_tdp_profiles(&self) -> Result<String, zbus::fdo::Error> {
@@ -106,6 +188,14 @@ impl ControlInterface {
     }
 }
 
+    async fn get_tdp_limits(&self) -> Result<String, zbus::fdo::Error> {
+        match crate::hardware_detection::get_tdp_limits() {
+            Ok(limits) => serde_json::to_string(&limits)
+                .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
+            Err(e) => Err(zbus::fdo::Error::Failed(e.to_string())),
+        }
+    }
+
     async fn get_current_tdp_profile(&self) -> Result<String, zbus::fdo::Error> {
         match crate::hardware_detection::get_current_tdp_profile() {
             Ok(profile) => Ok(profile),
@@ -113,7 +203,13 @@ impl ControlInterface {
         }
     }
 
-async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
+async fn set_tdp_profile(
+    &self,
+    profile: &str,
+    #[zbus(header)] header: Header<'_>,
+    #[zbus(connection)] connection: &Connection,
+) -> Result<(), zbus::fdo::Error> {
+    crate::write_policy::check(&header, connection).await?;
     crate::hardware_control::set_tdp_profile(profile)
         .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
 }
@@ -127,33 +223,40 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
 }
 
     async fn get_fan_info(&self) -> Result<String, zbus::fdo::Error> {
-        if !crate::tuxedo_io::TuxedoIo::is_available() {
-            return Ok("[]".to_string());
-        }
-        
-        match crate::tuxedo_io::TuxedoIo::new() {
-            Ok(io) => {
-                let mut fans_info = Vec::new();
-                for fan_id in 0..io.get_fan_count() {
-                    let speed = io.get_fan_speed(fan_id).ok();
-                    let temperature = io.get_fan_temperature(fan_id).ok().map(|t| t as f32);
-                    
-                    let info = FanInfo {
-                        id: fan_id,
-                        name: format!("Fan {}", fan_id),
-                        rpm_or_percent: speed.unwrap_or(0),
-                        temperature,
-                        is_rpm: false,  // Currently returning percentage
-                    };
-                    fans_info.push(info);
-                }
-                serde_json::to_string(&fans_info)
-                    .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
-            }
+        let backend = match crate::fan_backend::detect() {
+            Some(backend) => backend,
+            None => return Ok("[]".to_string()),
+        };
+        serde_json::to_string(&crate::telemetry::fan_info(backend.as_ref()))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_fan_failures(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::fan_failure::get_failures())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_fan_curve_capabilities(&self) -> Result<String, zbus::fdo::Error> {
+        match crate::hardware_detection::get_fan_curve_capabilities() {
+            Ok(caps) => serde_json::to_string(&caps)
+                .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
             Err(e) => Err(zbus::fdo::Error::Failed(e.to_string())),
         }
     }
 
+    async fn get_aquaris_status(&self) -> Result<String, zbus::fdo::Error> {
+        // BlueZ is asked over a blocking connection
+        let status = tokio::task::spawn_blocking(crate::aquaris::get_status).await
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        serde_json::to_string(&status)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_fan_mode(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::fan_mode::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
     async fn get_fan_temperature(&self, fan_id: u32) -> Result<u32, zbus::fdo::Error> {
         if !crate::tuxedo_io::TuxedoIo::is_available() {
             return Err(zbus::fdo::Error::Failed("tuxedo_io not available".to_string()));
@@ -166,12 +269,25 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
-    async fn set_fan_speed(&self, fan_id: u32, speed: u32) -> Result<(), zbus::fdo::Error> {
+    async fn set_fan_speed(
+        &self,
+        fan_id: u32,
+        speed: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_fan_speed(fan_id, speed)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
     
-    async fn set_fan_auto(&self, fan_id: u32) -> Result<(), zbus::fdo::Error> {
+    async fn set_fan_auto(
+        &self,
+        fan_id: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_fan_auto(fan_id)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
@@ -181,7 +297,13 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
     
-    async fn set_webcam_state(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+    async fn set_webcam_state(
+        &self,
+        enabled: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         crate::hardware_control::set_webcam_state(enabled)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
@@ -195,7 +317,13 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
-    async fn set_battery_charge_type(&self, charge_type: &str) -> Result<(), zbus::fdo::Error> {
+    async fn set_battery_charge_type(
+        &self,
+        charge_type: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         match crate::battery_control::BatteryControl::new() {
             Ok(battery) => battery.set_charge_type(charge_type)
                 .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
@@ -211,7 +339,13 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
-    async fn set_battery_charge_start_threshold(&self, threshold: u8) -> Result<(), zbus::fdo::Error> {
+    async fn set_battery_charge_start_threshold(
+        &self,
+        threshold: u8,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         match crate::battery_control::BatteryControl::new() {
             Ok(battery) => battery.set_charge_control_start_threshold(threshold)
                 .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
@@ -227,7 +361,13 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
-    async fn set_battery_charge_end_threshold(&self, threshold: u8) -> Result<(), zbus::fdo::Error> {
+    async fn set_battery_charge_end_threshold(
+        &self,
+        threshold: u8,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         match crate::battery_control::BatteryControl::new() {
             Ok(battery) => battery.set_charge_control_end_threshold(threshold)
                 .map_err(|e| zbus::fdo::Error::Failed(e.to_string())),
@@ -235,6 +375,31 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
+    async fn start_full_charge(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::full_charge::start()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+    
+    async fn cancel_full_charge(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::full_charge::cancel()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+    
+    async fn get_charge_control_info(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::battery_control::get_info())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+    
     async fn get_battery_available_start_thresholds(&self) -> Result<String, zbus::fdo::Error> {
         match crate::battery_control::BatteryControl::new() {
             Ok(battery) => {
@@ -278,29 +443,497 @@ async fn set_tdp_profile(&self, profile: &str) -> Result<(), zbus::fdo::Error> {
         }
     }
     
+    /// JSON encoded raw tuxedo_io reads and EC RAM, root only and off unless enabled in daemon.toml
+    async fn dump_ec_registers(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<String, zbus::fdo::Error> {
+        crate::ec_dump::check(&header, connection).await?;
+        serde_json::to_string(&crate::ec_dump::dump())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
     // Keyboard preview - apply keyboard settings immediately without saving to profile
-    async fn preview_keyboard_settings(&self, settings_json: &str) -> Result<(), zbus::fdo::Error> {
+    async fn preview_keyboard_settings(
+        &self,
+        settings_json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         let settings: KeyboardSettings = serde_json::from_str(settings_json)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
         crate::hardware_control::preview_keyboard_settings(&settings)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
 
-    async fn set_battery_settings(&self, settings_json: &str) -> Result<(), zbus::fdo::Error> {
+    async fn get_keyboard_devices(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::hardware_control::find_keyboard_backlights())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_capability_matrix(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::hardware_detection::get_capability_matrix())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_display_info(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::hardware_detection::get_display_info())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn preview_profile(&self, profile_json: &str) -> Result<String, zbus::fdo::Error> {
+        let profile: Profile = serde_json::from_str(profile_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        serde_json::to_string(&crate::hardware_control::preview_profile(&profile))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_system_profiles(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::system_profiles::get())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn promote_profile(
+        &self,
+        profile_json: &str,
+        replace: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check_admin(&header, connection).await?;
+        let profile: Profile = serde_json::from_str(profile_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        crate::system_profiles::promote(profile, replace)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// A system profile as a portable profile file
+    async fn export_profile(&self, name: &str) -> Result<String, zbus::fdo::Error> {
+        crate::system_profiles::get().profiles.iter()
+            .find(|p| p.name == name)
+            .map(tuxedo_common::profile_file::export)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("No system profile '{}'", name)))
+    }
+
+    /// Validates a profile file and adds it to the system profiles, returns the profile's name
+    async fn import_profile(
+        &self,
+        file_json: &str,
+        replace: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<String, zbus::fdo::Error> {
+        crate::write_policy::check_admin(&header, connection).await?;
+        let profile = tuxedo_common::profile_file::import(file_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let name = profile.name.clone();
+        crate::system_profiles::promote(profile, replace)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        Ok(name)
+    }
+
+    async fn remove_system_profile(
+        &self,
+        name: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check_admin(&header, connection).await?;
+        crate::system_profiles::remove(name)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    // An empty name applies no profile at boot
+    async fn set_boot_profile(
+        &self,
+        name: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check_admin(&header, connection).await?;
+        crate::system_profiles::set_boot_profile(Some(name).filter(|n| !n.is_empty()))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_power_source_profiles(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::power_source_profiles::get())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn set_power_source_profiles(
+        &self,
+        settings_json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        let settings: PowerSourceProfiles = serde_json::from_str(settings_json)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        crate::power_source_profiles::set(settings)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_app_profiles(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::app_profiles::get())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn set_app_profiles(
+        &self,
+        settings_json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        let settings: AppProfiles = serde_json::from_str(settings_json)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        crate::app_profiles::set(settings)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    // Lets clients switch to read-only before the first write is refused
+    async fn get_write_access(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> bool {
+        crate::write_policy::may_write(&header, connection).await
+    }
+
+    async fn get_last_apply_report(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::apply_timing::last())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_apply_progress(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::apply_pipeline::get_progress())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// Stops a running ApplyProfile before its next step, false if none was running
+    async fn cancel_apply(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<bool, zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        Ok(crate::apply_pipeline::cancel())
+    }
+
+    async fn get_profile_usage(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::profile_usage::get())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn reset_profile_usage(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::profile_usage::reset()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_ac_adapter_info(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::ac_adapter::get_info())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_charging_options(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::charging::get_options())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_indicator_leds(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::indicators::indicator_leds())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn panic_revert(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::hardware_control::panic_revert()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn start_dust_cleaning(
+        &self,
+        cycles: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::dust_cleaning::start(cycles)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn abort_dust_cleaning(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::dust_cleaning::abort()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_dust_cleaning_status(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::dust_cleaning::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn set_fan_min_duty(
+        &self,
+        fan_id: u32,
+        percent: u8,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::fan_min_duty::set(fan_id, percent)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn start_fan_calibration(
+        &self,
+        fan_id: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::fan_min_duty::start_calibration(fan_id)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_fan_calibration_status(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::fan_min_duty::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn start_auto_tune(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::auto_tuner::start()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn abort_auto_tune(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::auto_tuner::abort()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_auto_tune_status(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::auto_tuner::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn start_ab_test(
+        &self,
+        profile_a_json: &str,
+        profile_b_json: &str,
+        session_minutes: u32,
+        rounds: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        let a: Profile = serde_json::from_str(profile_a_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let b: Profile = serde_json::from_str(profile_b_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        let iface = connection.object_server().interface::<_, ControlInterface>("/com/tuxedo/Control").await?;
+        crate::ab_test::start(iface, a, b, session_minutes, rounds)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn abort_ab_test(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::ab_test::abort()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_ab_test_status(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::ab_test::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_network_interfaces(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::network_control::controllable_interfaces())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_feature_availability(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::hardware_detection::get_feature_availability())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_safe_mode(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::safe_mode::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn leave_safe_mode(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
+        crate::safe_mode::leave()
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    /// FanSpeedChanged and CpuTempChanged are only sampled while a client is subscribed
+    async fn subscribe_telemetry(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        let sender = header.sender()
+            .ok_or_else(|| zbus::fdo::Error::Failed("Call without sender".to_string()))?;
+        // Forwarded JSON-RPC calls come from the daemon itself, which can't receive signals
+        if connection.unique_name().is_some_and(|own| own.as_str() == sender.as_str()) {
+            return Err(zbus::fdo::Error::NotSupported("Signals are only sent over DBus".to_string()));
+        }
+        crate::telemetry::subscribe(sender.as_str());
+        Ok(())
+    }
+
+    async fn unsubscribe_telemetry(&self, #[zbus(header)] header: Header<'_>) -> Result<(), zbus::fdo::Error> {
+        if let Some(sender) = header.sender() {
+            crate::telemetry::unsubscribe(sender.as_str());
+        }
+        Ok(())
+    }
+
+    async fn get_events(&self, since: u64) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::event_log::get_events(since))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_daemon_log(&self, since: u64) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::log_buffer::get_entries(since))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_daemon_stats(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::daemon_stats::get_stats())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_ambient_light_reading(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::ambient_screen::get_reading())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn get_lid_status(&self) -> Result<String, zbus::fdo::Error> {
+        serde_json::to_string(&crate::lid_monitor::get_status())
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+
+    async fn set_battery_settings(
+        &self,
+        settings_json: &str,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> Result<(), zbus::fdo::Error> {
+        crate::write_policy::check(&header, connection).await?;
         let settings: BatterySettings = serde_json::from_str(settings_json)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
         crate::hardware_control::apply_battery_settings(&settings)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
     }
+
+    /// °C between the hottest sensor and its critical temperature, NaN if no sensor reports one
+    #[zbus(property)]
+    async fn thermal_headroom(&self) -> f64 {
+        crate::thermal_headroom::get().degrees.unwrap_or(f64::NAN)
+    }
+
+    /// Label of the sensor closest to its critical temperature
+    #[zbus(property)]
+    async fn thermal_headroom_sensor(&self) -> String {
+        crate::thermal_headroom::get().sensor
+    }
+
+    /// Name of the profile applied last, empty before the first one
+    #[zbus(property)]
+    async fn active_profile(&self) -> String {
+        crate::safe_mode::applied_profile().unwrap_or_default()
+    }
+
+    /// JSON encoded HardwareEvent
+    #[zbus(signal)]
+    pub async fn hardware_state_changed(ctxt: &SignalContext<'_>, event_json: &str) -> zbus::Result<()>;
+
+    /// JSON encoded list of FanInfo, sent when a duty or fan temperature changes
+    #[zbus(signal)]
+    pub async fn fan_speed_changed(ctxt: &SignalContext<'_>, fans_json: &str) -> zbus::Result<()>;
+
+    /// CPU package temperature in °C
+    #[zbus(signal)]
+    pub async fn cpu_temp_changed(ctxt: &SignalContext<'_>, celsius: f64) -> zbus::Result<()>;
+
+    /// Name of a profile that was applied successfully, whoever asked for it
+    #[zbus(signal)]
+    pub async fn profile_applied(ctxt: &SignalContext<'_>, name: &str) -> zbus::Result<()>;
 }
 
-pub async fn start_service(_connection: Connection) -> Result<()> {
-    let _conn = ConnectionBuilder::system()?
+/// `restore` is the profile start_control picked, announced like any other switch
+pub async fn start_service(_connection: Connection, restore: Option<Profile>) -> Result<()> {
+    let conn = ConnectionBuilder::system()?
         .name("com.tuxedo.Control")?
         .serve_at("/com/tuxedo/Control", ControlInterface)?
         .build()
         .await?;
     
+    // Signals have to come from the connection that owns the bus name
+    tokio::spawn(crate::hotkey_monitor::monitor_task(conn.clone()));
+    // Method calls arrive on this connection as well, not the one main() opened
+    tokio::spawn(crate::daemon_stats::dbus_call_counter(conn.clone()));
+    tokio::spawn(crate::thermal_headroom::monitor_task(conn.clone()));
+    tokio::spawn(crate::fan_failure::monitor_task(conn.clone()));
+    tokio::spawn(crate::telemetry::monitor_task(conn.clone()));
+    if !crate::privileges::monitor_only() {
+        tokio::spawn(crate::power_source_profiles::monitor_task(conn.clone()));
+        tokio::spawn(crate::app_profiles::monitor_task(conn.clone()));
+    }
+    
+    if let Some(profile) = restore {
+        let iface = conn.object_server().interface::<_, ControlInterface>("/com/tuxedo/Control").await?;
+        tokio::spawn(async move {
+            let name = profile.name.clone();
+            if let Err(e) = crate::apply_pipeline::apply_announced(profile, &iface).await {
+                log::warn!("Failed to restore profile '{}': {}", name, e);
+            }
+        });
+    }
+    
+    let status = crate::safe_mode::get_status();
+    if status.active {
+        let ctxt = SignalContext::new(&conn, "/com/tuxedo/Control")?;
+        crate::hotkey_monitor::emit(&ctxt, HardwareEvent::SafeMode(status)).await;
+    }
+    
+    log::info!("DBus service started");
+    
     // Keep connection alive
     std::future::pending::<()>().await;
     Ok(())
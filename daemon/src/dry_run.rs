@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+use tuxedo_common::types::{ApplyPreview, PlannedWrite, WriteKind};
+
+// apply_profile can run in preview mode: hardware writes in the apply path go through this
+// module, which records them instead while a preview is active on the current thread.
+// Module state the apply path keeps (policies, restore lists) must be left alone as well,
+// callers check is_active() for that.
+
+thread_local! {
+    static RECORDER: RefCell<Option<Vec<PlannedWrite>>> = const { RefCell::new(None) };
+}
+
+pub fn is_active() -> bool {
+    RECORDER.with(|r| r.borrow().is_some())
+}
+
+/// Run `apply` with writes recorded instead of performed
+pub fn preview(apply: impl FnOnce() -> anyhow::Result<()>) -> ApplyPreview {
+    RECORDER.with(|r| *r.borrow_mut() = Some(Vec::new()));
+    let result = apply();
+    let writes = RECORDER.with(|r| r.borrow_mut().take()).unwrap_or_default();
+
+    ApplyPreview {
+        writes,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Drop-in replacement for fs::write
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let recorded = record(|| PlannedWrite {
+        kind: WriteKind::File,
+        target: path.display().to_string(),
+        old_value: fs::read_to_string(path).ok().map(|s| s.trim().to_string()),
+        new_value: String::from_utf8_lossy(contents.as_ref()).trim().to_string(),
+    });
+
+    if recorded {
+        return Ok(());
+    }
+    let start = Instant::now();
+    let result = fs::write(path, contents);
+    crate::apply_timing::write_took(path, start.elapsed());
+    result
+}
+
+/// Returns true if the ioctl was recorded and must not be issued
+pub fn ioctl(request: libc::c_ulong, value: i32) -> bool {
+    record(|| PlannedWrite {
+        kind: WriteKind::Ioctl,
+        target: format!("/dev/tuxedo_io request {:#x}", request),
+        old_value: None,
+        new_value: value.to_string(),
+    })
+}
+
+/// Returns true if the command was recorded and must not be run
+pub fn command(program: &str, args: &[&str]) -> bool {
+    record(|| PlannedWrite {
+        kind: WriteKind::Command,
+        target: program.to_string(),
+        old_value: None,
+        new_value: args.join(" "),
+    })
+}
+
+fn record(write: impl FnOnce() -> PlannedWrite) -> bool {
+    RECORDER.with(|r| match r.borrow_mut().as_mut() {
+        Some(writes) => {
+            writes.push(write());
+            true
+        }
+        None => false,
+    })
+}
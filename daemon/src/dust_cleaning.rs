@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{DustCleaningStatus, EventKind, FanModeSource};
+use crate::tuxedo_io::TuxedoIo;
+
+// Fans are only stopped completely while every fan sensor is below this temperature
+const MAX_TEMP_FOR_STOP: u32 = 60;
+// Speed used instead of 0% when the system is too warm to stop the fans
+const MIN_SAFE_SPEED: u32 = 30;
+// Above this temperature the routine is aborted and the EC takes over again
+const ABORT_TEMP: u32 = 85;
+const PHASE_DURATION: Duration = Duration::from_secs(5);
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_CYCLES: u32 = 10;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static ABORT_REQUESTED: AtomicBool = AtomicBool::new(false);
+static STATUS: once_cell::sync::Lazy<Mutex<DustCleaningStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(DustCleaningStatus::default()));
+
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+pub fn get_status() -> DustCleaningStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+pub fn start(cycles: u32) -> Result<()> {
+    if !TuxedoIo::is_available() {
+        return Err(anyhow!("Fan control not available"));
+    }
+
+    if crate::auto_tuner::is_running() {
+        return Err(anyhow!("Auto-tuning is running"));
+    }
+
+    if crate::fan_min_duty::is_calibrating() {
+        return Err(anyhow!("Fan calibration is running"));
+    }
+
+    if crate::ab_test::is_running() {
+        return Err(anyhow!("An A/B test is running"));
+    }
+
+    if cycles == 0 || cycles > MAX_CYCLES {
+        return Err(anyhow!("Cycle count must be between 1 and {}", MAX_CYCLES));
+    }
+
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(anyhow!("Dust cleaning is already running"));
+    }
+
+    let io = match TuxedoIo::new() {
+        Ok(io) => io,
+        Err(e) => {
+            RUNNING.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    ABORT_REQUESTED.store(false, Ordering::SeqCst);
+    *STATUS.lock().unwrap() = DustCleaningStatus {
+        running: true,
+        current_cycle: 0,
+        total_cycles: cycles,
+        progress_percent: 0,
+        message: "Starting dust cleaning".to_string(),
+    };
+
+    log::info!("Starting dust cleaning routine with {} cycles", cycles);
+
+    tokio::spawn(async move {
+        let _inhibitor = crate::sleep_inhibit::acquire("Dust cleaning").await;
+        let message = match run_cleaning(&io, cycles).await {
+            Ok(()) => {
+                log::info!("Dust cleaning finished");
+                "Dust cleaning finished".to_string()
+            }
+            Err(e) => {
+                log::warn!("Dust cleaning stopped: {}", e);
+                if !ABORT_REQUESTED.load(Ordering::SeqCst) {
+                    crate::event_log::record(EventKind::FanFailsafe, format!("Dust cleaning: {}", e));
+                }
+                e.to_string()
+            }
+        };
+
+        restore_fans(&io);
+
+        {
+            let mut status = STATUS.lock().unwrap();
+            status.running = false;
+            status.message = message;
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(())
+}
+
+pub fn abort() -> Result<()> {
+    if !is_running() {
+        return Err(anyhow!("Dust cleaning is not running"));
+    }
+
+    log::info!("Dust cleaning abort requested");
+    ABORT_REQUESTED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+async fn run_cleaning(io: &TuxedoIo, cycles: u32) -> Result<()> {
+    let total_phases = cycles * 2;
+
+    for cycle in 1..=cycles {
+        let phase = (cycle - 1) * 2;
+
+        let stop_speed = match max_fan_temperature(io) {
+            Some(temp) if temp >= ABORT_TEMP => {
+                return Err(anyhow!("Aborted: temperature too high ({}°C)", temp));
+            }
+            Some(temp) if temp < MAX_TEMP_FOR_STOP => 0,
+            _ => {
+                log::info!("Dust cleaning: too warm to stop fans, using {}%", MIN_SAFE_SPEED);
+                MIN_SAFE_SPEED
+            }
+        };
+
+        update_status(cycle, phase, total_phases, "Spinning fans down");
+        set_all_fans(io, stop_speed)?;
+        hold_phase(io).await?;
+
+        update_status(cycle, phase + 1, total_phases, "Spinning fans up to 100%");
+        set_all_fans(io, 100)?;
+        hold_phase(io).await?;
+    }
+
+    update_status(cycles, total_phases, total_phases, "Dust cleaning finished");
+    Ok(())
+}
+
+async fn hold_phase(io: &TuxedoIo) -> Result<()> {
+    let mut elapsed = Duration::ZERO;
+
+    while elapsed < PHASE_DURATION {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        elapsed += CHECK_INTERVAL;
+
+        if ABORT_REQUESTED.load(Ordering::SeqCst) {
+            return Err(anyhow!("Aborted by user"));
+        }
+
+        if let Some(temp) = max_fan_temperature(io) {
+            if temp >= ABORT_TEMP {
+                return Err(anyhow!("Aborted: temperature too high ({}°C)", temp));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn update_status(cycle: u32, phase: u32, total_phases: u32, message: &str) {
+    let mut status = STATUS.lock().unwrap();
+    status.current_cycle = cycle;
+    status.progress_percent = ((phase * 100) / total_phases.max(1)) as u8;
+    status.message = message.to_string();
+}
+
+fn set_all_fans(io: &TuxedoIo, speed: u32) -> Result<()> {
+    for fan_id in 0..io.get_fan_count() {
+        io.set_fan_speed(fan_id, speed)?;
+        crate::fan_mode::record_manual(FanModeSource::DustCleaning, fan_id, speed);
+    }
+    Ok(())
+}
+
+fn max_fan_temperature(io: &TuxedoIo) -> Option<u32> {
+    (0..io.get_fan_count())
+        .filter_map(|fan_id| io.get_fan_temperature(fan_id).ok())
+        .max()
+}
+
+fn restore_fans(io: &TuxedoIo) {
+    // Custom curves are picked up again by the fan daemon on its next tick
+    let curves_active = crate::FAN_DAEMON_STATE.lock().unwrap()
+        .as_ref()
+        .map(|s| s.control_enabled)
+        .unwrap_or(false);
+
+    if !curves_active {
+        match io.set_fan_auto() {
+            Ok(()) => crate::fan_mode::record_auto(FanModeSource::DustCleaning),
+            Err(e) => log::error!("Failed to restore fan auto mode after dust cleaning: {}", e),
+        }
+    }
+}
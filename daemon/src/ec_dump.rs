@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// Raw register values for bringing up a Clevo or Uniwill model the driver doesn't know yet.
+// Off unless the administrator enables it in /etc/tuxedo-control-center/daemon.toml:
+//
+//   [developer]
+//   ec_dump = true
+//
+// It then answers DumpEcRegisters for root and adds ec_registers.json to the anomaly bundles.
+// Besides every tuxedo_io read ioctl, the EC RAM itself is included when the ec_sys module
+// is loaded (modprobe ec_sys) and debugfs is mounted.
+const EC_RAM: &str = "/sys/kernel/debug/ec/ec0/io";
+
+#[derive(Deserialize, Default)]
+struct ConfigFile {
+    #[serde(default)]
+    developer: DeveloperSettings,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct DeveloperSettings {
+    #[serde(default)]
+    ec_dump: bool,
+}
+
+#[derive(Serialize)]
+struct IoctlRead {
+    name: &'static str,
+    request: String,
+    value: Option<i32>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct EcDump {
+    interface: String,
+    fan_count: u32,
+    ioctls: Vec<IoctlRead>,
+    // "00: 12 34 ..." lines, None without ec_sys
+    ec_ram: Option<Vec<String>>,
+}
+
+/// Read on every call, so enabling it doesn't need a daemon restart
+pub fn enabled() -> bool {
+    let text = match fs::read_to_string(crate::sandbox::CONFIG_FILE) {
+        Ok(text) => text,
+        Err(_) => return false,
+    };
+    match toml::from_str::<ConfigFile>(&text) {
+        Ok(config) => config.developer.ec_dump,
+        Err(e) => {
+            log::warn!("Ignoring invalid {}: {}", crate::sandbox::CONFIG_FILE, e);
+            false
+        }
+    }
+}
+
+/// Refuses the call unless the dump is enabled and the caller is root
+pub async fn check(header: &zbus::message::Header<'_>, connection: &zbus::Connection) -> Result<(), zbus::fdo::Error> {
+    if !enabled() {
+        return Err(zbus::fdo::Error::AccessDenied(format!(
+            "EC register dump is disabled, set ec_dump = true under [developer] in {}", crate::sandbox::CONFIG_FILE)));
+    }
+    let caller = crate::write_policy::caller(header, connection).await?;
+    if caller.uid != Some(0) {
+        return Err(zbus::fdo::Error::AccessDenied("EC register dump is only available to root".to_string()));
+    }
+    Ok(())
+}
+
+pub fn dump() -> EcDump {
+    let io = if crate::tuxedo_io::TuxedoIo::is_available() {
+        crate::tuxedo_io::TuxedoIo::new()
+            .map_err(|e| log::warn!("EC dump without tuxedo_io: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    let ioctls = io.as_ref()
+        .map(|io| io.read_registers()
+            .into_iter()
+            .map(|(name, request, result)| IoctlRead {
+                name,
+                request: format!("{:#x}", request),
+                value: result.as_ref().ok().copied(),
+                error: result.err().map(|e| e.to_string()),
+            })
+            .collect())
+        .unwrap_or_default();
+
+    EcDump {
+        interface: io.as_ref().map(|io| format!("{:?}", io.get_interface())).unwrap_or_else(|| "None".to_string()),
+        fan_count: io.as_ref().map(|io| io.get_fan_count()).unwrap_or(0),
+        ioctls,
+        ec_ram: fs::read(EC_RAM).ok().map(|ram| hex_lines(&ram)),
+    }
+}
+
+fn hex_lines(bytes: &[u8]) -> Vec<String> {
+    bytes.chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let values: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{:02x}: {}", row * 16, values.join(" "))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_rows() {
+        let ram: Vec<u8> = (0..=0x12).collect();
+        let lines = hex_lines(&ram);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "00: 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f");
+        assert_eq!(lines[1], "10: 10 11 12");
+        assert!(hex_lines(&[]).is_empty());
+    }
+
+    #[test]
+    fn config_section() {
+        let config: ConfigFile = toml::from_str("[sandbox]\nfilesystem = false\n[developer]\nec_dump = true\n").unwrap();
+        assert!(config.developer.ec_dump);
+        let config: ConfigFile = toml::from_str("[sandbox]\nsyscalls = true\n").unwrap();
+        assert!(!config.developer.ec_dump);
+    }
+}
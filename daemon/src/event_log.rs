@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::{EventKind, EventLogEntry};
+
+// Append-only JSON lines, compacted to the in-memory window once it grows too large
+const LOG_DIR: &str = "/var/lib/tuxedo-control-center";
+const LOG_FILE: &str = "/var/lib/tuxedo-control-center/events.jsonl";
+const MAX_ENTRIES: usize = 5000;
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+const THROTTLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static EVENTS: once_cell::sync::Lazy<Mutex<VecDeque<EventLogEntry>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+fn load() -> VecDeque<EventLogEntry> {
+    let content = fs::read_to_string(LOG_FILE).unwrap_or_default();
+    let mut events: VecDeque<EventLogEntry> = content.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    // Logs written before sequence numbers were added number from 1
+    let mut seq = 0;
+    for event in events.iter_mut() {
+        if event.seq <= seq {
+            event.seq = seq + 1;
+        }
+        seq = event.seq;
+    }
+
+    while events.len() > MAX_ENTRIES {
+        events.pop_front();
+    }
+    events
+}
+
+pub fn record(kind: EventKind, message: impl Into<String>) {
+    let mut events = EVENTS.lock().unwrap();
+    let entry = EventLogEntry {
+        seq: events.back().map_or(1, |last| last.seq + 1),
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0),
+        kind,
+        message: message.into(),
+    };
+
+    if let Err(e) = append(&entry, &events) {
+        log::warn!("Failed to write event log: {}", e);
+    }
+
+    events.push_back(entry.clone());
+    while events.len() > MAX_ENTRIES {
+        events.pop_front();
+    }
+    drop(events);
+
+    crate::anomaly_capture::on_event(entry.kind, &entry.message);
+}
+
+fn append(entry: &EventLogEntry, events: &VecDeque<EventLogEntry>) -> std::io::Result<()> {
+    // Kept in memory only, /var/lib isn't writable without root
+    if crate::privileges::monitor_only() {
+        return Ok(());
+    }
+    fs::create_dir_all(LOG_DIR)?;
+
+    let too_large = fs::metadata(LOG_FILE).map(|m| m.len() > MAX_FILE_SIZE).unwrap_or(false);
+    if too_large {
+        let mut content = String::new();
+        for event in events.iter().chain(std::iter::once(entry)) {
+            content.push_str(&serde_json::to_string(event)?);
+            content.push('\n');
+        }
+        return fs::write(LOG_FILE, content);
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(LOG_FILE)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+}
+
+/// Events with a sequence number above `since`, oldest first
+pub fn get_events(since: u64) -> Vec<EventLogEntry> {
+    EVENTS.lock().unwrap().iter()
+        .filter(|e| e.seq > since)
+        .cloned()
+        .collect()
+}
+
+/// Events newer than the given unix timestamp, oldest first
+pub fn get_events_after(timestamp: i64) -> Vec<EventLogEntry> {
+    EVENTS.lock().unwrap().iter()
+        .filter(|e| e.timestamp > timestamp)
+        .cloned()
+        .collect()
+}
+
+/// Turns the kernel's throttle counters into events
+pub async fn throttle_monitor_task() {
+    let mut interval = tokio::time::interval(THROTTLE_POLL_INTERVAL);
+    let mut last_count = read_throttle_count();
+
+    if last_count.is_none() {
+        log::info!("CPU throttle counters not available, throttle events won't be logged");
+        return;
+    }
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("throttle_monitor");
+
+        let count = read_throttle_count();
+        if let (Some(count), Some(last)) = (count, last_count) {
+            if count > last {
+                let temp = crate::hardware_detection::get_cpu_info()
+                    .map(|cpu| format!(" at {:.0}°C", cpu.package_temp))
+                    .unwrap_or_default();
+                record(EventKind::ThermalThrottle,
+                    format!("CPU package throttled {} times{}", count - last, temp));
+            }
+        }
+        last_count = count.or(last_count);
+    }
+}
+
+// Intel only, the count is per package but repeated for every CPU in it
+fn read_throttle_count() -> Option<u64> {
+    let entries = fs::read_dir("/sys/devices/system/cpu").ok()?;
+
+    entries.flatten()
+        .filter_map(|entry| {
+            let path = entry.path().join("thermal_throttle/package_throttle_count");
+            if !Path::new(&path).exists() {
+                return None;
+            }
+            fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()
+        })
+        .max()
+}
@@ -0,0 +1,268 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tuxedo_common::types::FanControlMode;
+use crate::tuxedo_io::TuxedoIo;
+use crate::acpi_call_fan::AcpiCallFanBackend;
+
+// hwmon drivers of the TUXEDO platform, the tach of a tuxedo_io or EC method fan is on one of
+// these and not on a GPU, a USB fan hub or a pump controller that happens to have fan1_input
+const PLATFORM_HWMON: [&str; 4] = ["tuxedo", "tuxedo_io", "clevo", "uniwill"];
+const CPU_HWMON: [&str; 3] = ["coretemp", "k10temp", "zenpower"];
+// Laptop EC drivers whose pwm outputs drive the system fans. Anything else with a pwm, a
+// Super I/O chip, a USB fan hub or a pump controller, is left to its own tools.
+const FAN_HWMON: [&str; 6] = ["tuxedo", "thinkpad", "dell_smm", "asus", "hp", "gpd_fan"];
+
+// pwmN_enable and pwmN as the daemon first found them, put back by set_auto. Kept for the
+// life of the process so a backend detected again after a hotplug doesn't take the daemon's
+// own manual mode for the original.
+static ORIGINAL_PWM: once_cell::sync::Lazy<Mutex<HashMap<PathBuf, (String, String)>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Writes fan duty cycles directly, curves are evaluated by the fan daemon
+pub trait FanBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn fan_count(&self) -> u32;
+    fn set_duty(&self, fan_id: u32, percent: u32) -> Result<()>;
+    /// Current duty cycle in percent
+    fn get_duty(&self, fan_id: u32) -> Result<u32>;
+    /// Mode reported by the hardware, None if it can't be read back
+    fn get_mode(&self) -> Option<FanControlMode> {
+        None
+    }
+    /// Hand all fans back to the EC / firmware
+    fn set_auto(&self) -> Result<()>;
+    /// Temperature the fan's own sensor reports in °C
+    fn get_temperature(&self, fan_id: u32) -> Result<f32>;
+    /// Tachometer reading, None if the fan has no readable tach
+    fn get_rpm(&self, fan_id: u32) -> Option<u32> {
+        hwmon_fan_rpm(fan_id)
+    }
+}
+
+// tuxedo_io and the EC method don't report RPM, but the platform driver may register a hwmon tach
+fn hwmon_fan_rpm(fan_id: u32) -> Option<u32> {
+    crate::hardware_detection::sorted_dir_entries("/sys/class/hwmon")
+        .into_iter()
+        .filter(|hwmon| {
+            let name = fs::read_to_string(hwmon.join("name")).unwrap_or_default();
+            PLATFORM_HWMON.contains(&name.trim())
+        })
+        .find_map(|hwmon| fs::read_to_string(hwmon.join(format!("fan{}_input", fan_id + 1))).ok())
+        .and_then(|rpm| rpm.trim().parse().ok())
+}
+
+impl FanBackend for TuxedoIo {
+    fn name(&self) -> &'static str {
+        "tuxedo_io"
+    }
+
+    fn fan_count(&self) -> u32 {
+        self.get_fan_count()
+    }
+
+    fn set_duty(&self, fan_id: u32, percent: u32) -> Result<()> {
+        self.set_fan_speed(fan_id, percent)
+    }
+
+    fn get_duty(&self, fan_id: u32) -> Result<u32> {
+        self.get_fan_speed(fan_id)
+    }
+
+    fn set_auto(&self) -> Result<()> {
+        self.set_fan_auto()
+    }
+
+    fn get_temperature(&self, fan_id: u32) -> Result<f32> {
+        self.get_fan_temperature(fan_id).map(|t| t as f32)
+    }
+}
+
+struct PwmChannel {
+    hwmon: PathBuf,
+    index: u32,
+}
+
+/// Generic hwmon pwmN / pwmN_enable fallback for machines without tuxedo_io
+pub struct HwmonPwmBackend {
+    channels: Vec<PwmChannel>,
+}
+
+impl HwmonPwmBackend {
+    pub fn new() -> Result<Self> {
+        let channels = Self::find_channels();
+        if channels.is_empty() {
+            return Err(anyhow!("No controllable hwmon pwm channels found"));
+        }
+        Ok(Self { channels })
+    }
+
+    pub fn is_available() -> bool {
+        !Self::find_channels().is_empty()
+    }
+
+    fn find_channels() -> Vec<PwmChannel> {
+        let mut channels = Vec::new();
+        let mut hwmons: Vec<PathBuf> = fs::read_dir("/sys/class/hwmon")
+            .map(|rd| rd.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        hwmons.sort();
+
+        for hwmon in hwmons {
+            let name = fs::read_to_string(hwmon.join("name")).unwrap_or_default();
+            if !FAN_HWMON.contains(&name.trim()) {
+                continue;
+            }
+
+            // Channel numbers may have gaps, so collect whatever pwmN files exist
+            let mut indices: Vec<u32> = fs::read_dir(&hwmon)
+                .map(|rd| rd.flatten()
+                    .filter_map(|e| e.file_name().to_str()?.strip_prefix("pwm")?.parse().ok())
+                    .collect())
+                .unwrap_or_default();
+            indices.sort_unstable();
+
+            for index in indices {
+                let enable = hwmon.join(format!("pwm{}_enable", index));
+                if let Ok(original) = fs::read_to_string(&enable) {
+                    let pwm = fs::read_to_string(hwmon.join(format!("pwm{}", index))).unwrap_or_default();
+                    ORIGINAL_PWM.lock().unwrap().entry(enable)
+                        .or_insert_with(|| (original.trim().to_string(), pwm.trim().to_string()));
+                    channels.push(PwmChannel { hwmon: hwmon.clone(), index });
+                }
+            }
+        }
+
+        channels
+    }
+
+    fn channel(&self, fan_id: u32) -> Result<&PwmChannel> {
+        self.channels.get(fan_id as usize)
+            .ok_or_else(|| anyhow!("Invalid pwm fan ID: {}", fan_id))
+    }
+}
+
+impl FanBackend for HwmonPwmBackend {
+    fn name(&self) -> &'static str {
+        "hwmon pwm"
+    }
+
+    fn fan_count(&self) -> u32 {
+        self.channels.len() as u32
+    }
+
+    fn set_duty(&self, fan_id: u32, percent: u32) -> Result<()> {
+        let channel = self.channel(fan_id)?;
+        // pwmN_enable: 1 = manual, 2 = automatic
+        crate::dry_run::write(channel.hwmon.join(format!("pwm{}_enable", channel.index)), "1")?;
+        let value = percent.min(100) * 255 / 100;
+        crate::dry_run::write(channel.hwmon.join(format!("pwm{}", channel.index)), value.to_string())?;
+        Ok(())
+    }
+
+    fn get_duty(&self, fan_id: u32) -> Result<u32> {
+        let channel = self.channel(fan_id)?;
+        let value: u32 = fs::read_to_string(channel.hwmon.join(format!("pwm{}", channel.index)))?
+            .trim().parse()?;
+        Ok(value * 100 / 255)
+    }
+
+    fn get_mode(&self) -> Option<FanControlMode> {
+        let channel = self.channels.first()?;
+        let enable = fs::read_to_string(channel.hwmon.join(format!("pwm{}_enable", channel.index))).ok()?;
+        Some(match enable.trim() {
+            "1" => FanControlMode::Manual,
+            "0" | "2" | "3" | "4" | "5" => FanControlMode::Auto,
+            _ => FanControlMode::Unknown,
+        })
+    }
+
+    fn set_auto(&self) -> Result<()> {
+        let originals = ORIGINAL_PWM.lock().unwrap();
+        for channel in &self.channels {
+            let enable = channel.hwmon.join(format!("pwm{}_enable", channel.index));
+            match originals.get(&enable) {
+                // Firmware that ran the fan at a fixed duty gets that duty back
+                Some((mode, pwm)) if mode == "1" && !pwm.is_empty() => {
+                    crate::dry_run::write(channel.hwmon.join(format!("pwm{}", channel.index)), pwm)?;
+                    crate::dry_run::write(&enable, mode)?;
+                }
+                Some((mode, _)) if !mode.is_empty() && mode != "1" => crate::dry_run::write(&enable, mode)?,
+                // Driver automatic mode
+                _ => crate::dry_run::write(&enable, "2")?,
+            }
+        }
+        Ok(())
+    }
+
+    fn get_temperature(&self, fan_id: u32) -> Result<f32> {
+        let channel = self.channel(fan_id)?;
+
+        // Prefer the chip's own sensor, otherwise follow the CPU package
+        if let Ok(temp) = fs::read_to_string(channel.hwmon.join("temp1_input")) {
+            return Ok(temp.trim().parse::<f32>()? / 1000.0);
+        }
+
+        crate::hardware_detection::get_all_temperatures()?
+            .into_iter()
+            .filter(|s| CPU_HWMON.iter().any(|chip| s.id.starts_with(&format!("hwmon:{}", chip))))
+            .map(|s| s.temperature)
+            .reduce(f32::max)
+            .ok_or_else(|| anyhow!("No temperature sensor for pwm fan {}", fan_id))
+    }
+
+    fn get_rpm(&self, fan_id: u32) -> Option<u32> {
+        let channel = self.channel(fan_id).ok()?;
+        fs::read_to_string(channel.hwmon.join(format!("fan{}_input", channel.index))).ok()?
+            .trim().parse().ok()
+    }
+}
+
+pub fn is_available() -> bool {
+    TuxedoIo::is_available() || AcpiCallFanBackend::is_available() || HwmonPwmBackend::is_available()
+}
+
+// The fan daemon, telemetry and the D-Bus getters all ask for the backend every poll. Probing
+// opens /dev/tuxedo_io and walks hwmon, so the result is kept until a module reload or a hwmon
+// device coming or going may have changed it.
+type Detected = ((u64, u64), Option<Arc<dyn FanBackend>>);
+static DETECTED: Mutex<Option<Detected>> = Mutex::new(None);
+
+/// The same backend until the drivers or hwmon devices change, compare with Arc::ptr_eq to
+/// notice a new one
+pub fn detect() -> Option<Arc<dyn FanBackend>> {
+    let key = (crate::module_reload::generation(), crate::module_reload::hotplug_generation());
+    let mut detected = DETECTED.lock().unwrap();
+    if let Some((cached_key, ref backend)) = *detected {
+        if cached_key == key {
+            return backend.clone();
+        }
+    }
+    let backend = probe();
+    *detected = Some((key, backend.clone()));
+    backend
+}
+
+fn probe() -> Option<Arc<dyn FanBackend>> {
+    if TuxedoIo::is_available() {
+        match TuxedoIo::new() {
+            Ok(io) => return Some(Arc::new(io)),
+            Err(e) => log::warn!("Failed to open tuxedo_io for fan control: {}", e),
+        }
+    }
+
+    // Opt-in only, see acpi_call_fan
+    if AcpiCallFanBackend::is_available() {
+        match AcpiCallFanBackend::new() {
+            Ok(backend) => return Some(Arc::new(backend)),
+            Err(e) => log::warn!("acpi_call fan backend unavailable: {}", e),
+        }
+    }
+
+    match HwmonPwmBackend::new() {
+        Ok(backend) => Some(Arc::new(backend)),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+use tuxedo_common::types::{FanCurve, FanOffset, FanSettings, FanSmoothing};
+
+// Fan curves for machines without the GUI (servers, kiosks, minimal installs), read from
+// /etc/tuxedo-control-center/fancurves.toml:
+//
+//   enabled = true            # optional, false keeps the file but ignores it
+//
+//   [[fan]]
+//   id = 0                    # fan index as the fan backend counts them, from 0
+//   points = [[40, 0], [60, 30], [75, 60], [90, 100]]   # [temperature °C, speed %]
+//   sensor = "hwmon:coretemp:temp1"   # optional sensor id, default is the fan's own
+//   offset = -5               # optional shift of the whole curve in percent
+//
+//   [smoothing]               # optional, every key falls back to its default
+//   interval_ms = 1000        # temperature sampling period
+//   hysteresis = 3            # °C to cool down before a fan slows down again
+//   ramp_up = 10              # % per second, 0 = follow the curve at once
+//   ramp_down = 3
+//
+// While the file is enabled its curves replace the fan settings of every applied profile,
+// the profile's own ones come back when it is removed or disabled. The daemon reloads it on
+// SIGHUP and whenever it changes, also when the directory is only created after startup. A
+// file that fails validation is logged and ignored, the fans keep running on whatever was
+// active before.
+const CONFIG_DIR: &str = "/etc/tuxedo-control-center";
+const FILE_NAME: &str = "fancurves.toml";
+const MAX_TEMP: u8 = 100;
+// Editors save in several steps (write, rename, chmod), reload once they're done
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CurveFile {
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default, rename = "fan")]
+    fans: Vec<FanEntry>,
+    #[serde(default)]
+    smoothing: FanSmoothing,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FanEntry {
+    id: u32,
+    points: Vec<(u8, u8)>,
+    #[serde(default)]
+    sensor: Option<String>,
+    #[serde(default)]
+    offset: i8,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+struct State {
+    // Settings from the file, None when there is no enabled file
+    file: Option<FanSettings>,
+    // Fan settings of the last applied profile, restored when the file goes away
+    profile: Option<FanSettings>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { file: None, profile: None });
+
+fn path() -> String {
+    format!("{}/{}", CONFIG_DIR, FILE_NAME)
+}
+
+/// Read the file at startup, before the last profile is restored
+pub fn init() {
+    match load() {
+        Ok(Some(settings)) => {
+            log::info!("Using fan curves from {} for {} fans", path(), settings.curves.len());
+            STATE.lock().unwrap().file = Some(settings);
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Ignoring {}: {}", path(), e),
+    }
+}
+
+fn is_active() -> bool {
+    STATE.lock().unwrap().file.is_some()
+}
+
+/// The fan settings to apply for a profile: the file's while it is enabled
+pub fn settings_for(profile: &FanSettings) -> FanSettings {
+    let mut state = STATE.lock().unwrap();
+    if !crate::dry_run::is_active() {
+        state.profile = Some(profile.clone());
+    }
+    state.file.clone().unwrap_or_else(|| profile.clone())
+}
+
+/// Re-read the file and apply it, or go back to the profile's fan settings without it
+pub fn reload() {
+    let file = match load() {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("Ignoring changed {}, keeping the current fan settings: {}", path(), e);
+            return;
+        }
+    };
+
+    let fallback = {
+        let mut state = STATE.lock().unwrap();
+        if state.file == file {
+            return;
+        }
+        state.file = file.clone();
+        state.profile.clone()
+    };
+    match &file {
+        Some(_) => log::info!("Reloaded {}", path()),
+        None => log::info!("{} removed or disabled, back to the profile's fan settings", path()),
+    }
+    if crate::safe_mode::is_active() {
+        log::info!("Safe mode keeps the fans on automatic until the next profile is applied");
+        return;
+    }
+
+    // Without a profile applied yet the fans were on automatic
+    if let Err(e) = crate::hardware_control::apply_fan_settings(&fallback.unwrap_or_else(automatic)) {
+        log::warn!("Failed to apply fan settings after reloading {}: {}", path(), e);
+    }
+}
+
+/// Start the file's curves when there is no profile to restore, as on a fresh headless install
+pub fn apply_without_profile() {
+    if !is_active() {
+        return;
+    }
+    if let Err(e) = crate::hardware_control::apply_fan_settings(&automatic()) {
+        log::warn!("Failed to apply fan curves from {}: {}", path(), e);
+    }
+}
+
+fn automatic() -> FanSettings {
+    FanSettings {
+        control_enabled: false,
+        curves: Vec::new(),
+        offsets: Vec::new(),
+        smoothing: FanSmoothing::default(),
+    }
+}
+
+/// Reload on SIGHUP and when the file changes
+pub async fn watch_task() {
+    std::thread::spawn(watch_directory);
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            log::warn!("Cannot listen for SIGHUP: {}", e);
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        log::info!("SIGHUP received, reloading {}", path());
+        tokio::task::spawn_blocking(reload).await.ok();
+    }
+}
+
+fn load() -> Result<Option<FanSettings>> {
+    let text = match fs::read_to_string(path()) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let file: CurveFile = toml::from_str(&text)?;
+    if !file.enabled {
+        return Ok(None);
+    }
+    let fan_count = crate::fan_backend::detect().map(|backend| backend.fan_count());
+    validate(&file, fan_count)?;
+
+    Ok(Some(FanSettings {
+        control_enabled: true,
+        curves: file.fans.iter().map(|fan| FanCurve {
+            fan_id: fan.id,
+            points: fan.points.clone(),
+            temp_source: fan.sensor.clone(),
+        }).collect(),
+        offsets: file.fans.iter()
+            .filter(|fan| fan.offset != 0)
+            .map(|fan| FanOffset { fan_id: fan.id, percent: fan.offset })
+            .collect(),
+        smoothing: file.smoothing,
+    }))
+}
+
+fn validate(file: &CurveFile, fan_count: Option<u32>) -> Result<()> {
+    if file.fans.is_empty() {
+        return Err(anyhow!("no [[fan]] sections, set enabled = false to keep the fans on automatic"));
+    }
+    for (i, fan) in file.fans.iter().enumerate() {
+        if file.fans[..i].iter().any(|other| other.id == fan.id) {
+            return Err(anyhow!("fan {} is configured twice", fan.id));
+        }
+        if let Some(count) = fan_count.filter(|count| fan.id >= *count) {
+            return Err(anyhow!("fan {} doesn't exist, this machine has {} fans", fan.id, count));
+        }
+        if fan.points.len() < 2 {
+            return Err(anyhow!("fan {} needs at least two points", fan.id));
+        }
+        if let Some((temp, speed)) = fan.points.iter().find(|(temp, speed)| *temp > MAX_TEMP || *speed > 100) {
+            return Err(anyhow!("fan {}: point [{}, {}] is out of range (0-{} °C, 0-100 %)",
+                fan.id, temp, speed, MAX_TEMP));
+        }
+        if fan.points.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(anyhow!("fan {}: temperatures must increase from point to point", fan.id));
+        }
+        if !(-100..=100).contains(&fan.offset) {
+            return Err(anyhow!("fan {}: offset {} is out of range (-100 to 100)", fan.id, fan.offset));
+        }
+        if fan.sensor.as_deref().is_some_and(|sensor| sensor.trim().is_empty()) {
+            return Err(anyhow!("fan {}: empty sensor, leave it out to use the fan's own", fan.id));
+        }
+    }
+    Ok(())
+}
+
+// Blocking inotify loop on the config directory, the file itself is usually replaced on save.
+// While the directory doesn't exist, /etc is watched for it to appear.
+fn watch_directory() {
+    let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+    if fd < 0 {
+        log::warn!("inotify unavailable, reload {} with SIGHUP", FILE_NAME);
+        return;
+    }
+    let mut watches = Watches { config: None, parent: None };
+    if !watches.update(fd) {
+        unsafe { libc::close(fd) };
+        return;
+    }
+
+    let dir_name = CONFIG_DIR.rsplit('/').next().unwrap_or_default().as_bytes();
+    let header = std::mem::size_of::<libc::inotify_event>();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let len = unsafe { libc::read(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len()) };
+        if len < 0 {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            log::warn!("Stopped watching {}: {}", CONFIG_DIR, std::io::Error::last_os_error());
+            break;
+        }
+
+        // Each event is the fixed header followed by a NUL padded name
+        let mut ours = false;
+        let mut moved = false;
+        let mut offset = 0;
+        while offset + header <= len as usize {
+            let event = unsafe { std::ptr::read_unaligned(buffer[offset..].as_ptr() as *const libc::inotify_event) };
+            let name = buffer[offset + header..offset + header + event.len as usize]
+                .split(|&b| b == 0).next().unwrap_or_default();
+            if Some(event.wd) == watches.config {
+                ours |= name == FILE_NAME.as_bytes();
+                // The directory was removed, its watch with it
+                moved |= event.mask & libc::IN_IGNORED != 0;
+            } else if Some(event.wd) == watches.parent {
+                moved |= name == dir_name;
+            }
+            offset += header + event.len as usize;
+        }
+        if moved {
+            if !watches.update(fd) {
+                break;
+            }
+            // The file may have been written along with the directory, or gone with it
+            ours = true;
+        }
+        if ours {
+            std::thread::sleep(SETTLE_TIME);
+            reload();
+        }
+    }
+    unsafe { libc::close(fd) };
+}
+
+struct Watches {
+    config: Option<i32>,
+    parent: Option<i32>,
+}
+
+impl Watches {
+    // The config directory if it exists, else its parent. False when neither can be watched.
+    fn update(&mut self, fd: i32) -> bool {
+        let dir = std::ffi::CString::new(CONFIG_DIR).unwrap();
+        let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_MOVED_FROM | libc::IN_DELETE;
+        let wd = unsafe { libc::inotify_add_watch(fd, dir.as_ptr(), mask) };
+        if wd >= 0 {
+            self.config = Some(wd);
+            if let Some(parent) = self.parent.take() {
+                unsafe { libc::inotify_rm_watch(fd, parent) };
+            }
+            return true;
+        }
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::ENOENT) {
+            log::warn!("Not watching {} ({}), reload {} with SIGHUP", CONFIG_DIR, error, FILE_NAME);
+            return false;
+        }
+        self.config = None;
+        if self.parent.is_some() {
+            return true;
+        }
+
+        let parent_dir = Path::new(CONFIG_DIR).parent().unwrap_or(Path::new("/"));
+        let parent = std::ffi::CString::new(parent_dir.to_string_lossy().as_bytes()).unwrap();
+        let wd = unsafe { libc::inotify_add_watch(fd, parent.as_ptr(), libc::IN_CREATE | libc::IN_MOVED_TO) };
+        if wd < 0 {
+            log::warn!("Not watching {} ({}), reload {} with SIGHUP once it exists",
+                parent_dir.display(), std::io::Error::last_os_error(), FILE_NAME);
+            return false;
+        }
+        log::info!("{} doesn't exist, watching {} for it", CONFIG_DIR, parent_dir.display());
+        self.parent = Some(wd);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn file(points: Vec<(u8, u8)>) -> CurveFile {
+        CurveFile {
+            enabled: true,
+            fans: vec![FanEntry { id: 0, points, sensor: None, offset: 0 }],
+            smoothing: FanSmoothing::default(),
+        }
+    }
+
+    proptest! {
+        // Anything past the range, out of order or with a temperature twice is refused
+        #[test]
+        fn accepts_exactly_increasing_curves_in_range(points in prop::collection::vec((0u8..=110, 0u8..=110), 0..8)) {
+            let valid = points.len() >= 2
+                && points.iter().all(|(temp, speed)| *temp <= MAX_TEMP && *speed <= 100)
+                && points.windows(2).all(|pair| pair[0].0 < pair[1].0);
+            prop_assert_eq!(validate(&file(points), None).is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        assert!(validate(&file(vec![(0, 0), (100, 100)]), None).is_ok());
+        assert!(validate(&file(vec![(0, 0), (101, 100)]), None).is_err());
+        assert!(validate(&file(vec![(0, 0), (100, 101)]), None).is_err());
+        assert!(validate(&file(vec![(50, 20), (50, 80)]), None).is_err());
+        assert!(validate(&file(vec![(0, 0), (100, 100)]), Some(0)).is_err());
+    }
+}
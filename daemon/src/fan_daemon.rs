@@ -1,286 +1,279 @@
-use anyhow::{Result, anyhow};
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time;
-use tuxedo_common::types::{FanCurve, FanSettings};
-use crate::tuxedo_io;
-use crate::tuxedo_io::TuxedoIo;
-use crate::dbus_interface::ControlInterface;
+use tuxedo_common::types::{FanCurve, FanModeSource, FanSettings, FanSmoothing};
+use crate::fan_backend::FanBackend;
 
-pub struct FanCurveManager {
-    io: Option<TuxedoIo>,
-    settings: Option<FanSettings>,
-    last_update: Instant,
-    update_interval: Duration,
-    last_speeds: HashMap<u32, u8>,
+// Curves are evaluated here and written as plain duty cycles, independent of EC tables.
+// Each fan heads for the duty its curve gives, but a lower target is only taken once the
+// temperature fell `hysteresis` below the one the current target was set at, and the duty
+// moves towards it by at most ramp_up / ramp_down per second. The floors (minimum duty,
+// closed lid, a stalled sibling) are applied on top and take effect at once.
+const MIN_INTERVAL_MS: u32 = 250;
+const MAX_INTERVAL_MS: u32 = 10_000;
+
+struct FanState {
+    duty: f32,
+    target: u8,
+    target_temp: f32,
 }
 
-impl FanCurveManager {
-    pub fn new() -> Result<Self> {
-        let io = if TuxedoIo::is_available() {
-            Some(TuxedoIo::new()?)
+impl FanState {
+    fn new(target: u8, temp: f32) -> Self {
+        Self { duty: target as f32, target, target_temp: temp }
+    }
+
+    fn follow(&mut self, target: u8, temp: f32, smoothing: &FanSmoothing, elapsed: f32) -> u8 {
+        // Speeding up is never held back, slowing down waits for the temperature to drop
+        if target >= self.target || temp <= self.target_temp - smoothing.hysteresis as f32 {
+            self.target = target;
+            self.target_temp = temp;
+        }
+
+        let step = |rate: u8| if rate == 0 { f32::MAX } else { rate as f32 * elapsed };
+        let target = self.target as f32;
+        self.duty = if target > self.duty {
+            (self.duty + step(smoothing.ramp_up)).min(target)
         } else {
-            None
+            (self.duty - step(smoothing.ramp_down)).max(target)
         };
-        
-        Ok(Self {
-            io,
-            settings: None,
-            last_update: Instant::now(),
-            update_interval: Duration::from_secs(2),
-            last_speeds: HashMap::new(),
-        })
+        self.duty.round() as u8
     }
-    
-    pub fn update_settings(&mut self, settings: FanSettings) {
-        self.settings = Some(settings);
-    }
-    
-    pub fn is_enabled(&self) -> bool {
-        self.settings.as_ref().map(|s| s.control_enabled).unwrap_or(false)
-    }
-    
-    pub fn apply_curves(&mut self) -> Result<()> {
-        if !self.is_enabled() {
-            return Ok(());
+}
+
+pub async fn run(mut backend: Arc<dyn FanBackend>) {
+    log::info!("Starting fan control daemon");
+    let mut interval_ms = FanSmoothing::default().interval_ms;
+    let mut interval = time::interval(Duration::from_millis(interval_ms as u64));
+    let mut last_settings: Option<FanSettings> = None;
+    let mut sorted_curves: Vec<Vec<(u8, u8)>> = Vec::new();
+    let mut fans: HashMap<u32, FanState> = HashMap::new();
+    let mut last_tick = Instant::now();
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("fan_daemon");
+        let elapsed = last_tick.elapsed().as_secs_f32();
+        last_tick = Instant::now();
+
+        // The open device of a reloaded tuxedo_io is dead, start over with a fresh one
+        if let Some(fresh) = crate::fan_backend::detect().filter(|fresh| !Arc::ptr_eq(fresh, &backend)) {
+            log::info!("Fan control backend changed: {}", fresh.name());
+            backend = fresh;
         }
-        
-        // Rate limiting
-        if self.last_update.elapsed() < self.update_interval {
-            return Ok(());
+
+        // Dust cleaning, auto-tuning and floor calibration drive the fans directly while they
+        // run, the curves start over from their targets afterwards
+        if crate::dust_cleaning::is_running() || crate::auto_tuner::is_running() || crate::fan_min_duty::is_calibrating() {
+            fans.clear();
+            continue;
         }
-        
-        let io = self.io.as_ref().ok_or_else(|| anyhow!("TuxedoIo not available"))?;
-        let settings = self.settings.as_ref().ok_or_else(|| anyhow!("No settings configured"))?;
-        
-        for curve in &settings.curves {
-            if curve.fan_id >= io.get_fan_count() {
-                continue;
-            }
-            
-            // Get current temperature for this fan
-            let temp = match io.get_fan_temperature(curve.fan_id) {
-                Ok(t) => t as f32,
-                Err(e) => {
-                    log::warn!("Failed to read fan {} temperature: {}", curve.fan_id, e);
-                    continue;
-                }
-            };
-            
-            // Calculate speed from curve
-            let speed = self.interpolate_fan_speed(&curve.points, temp);
 
-            // Only apply if speed has changed
-            let last_speed = self.last_speeds.get(&curve.fan_id).cloned().unwrap_or(0);
-            if speed != last_speed {
-                if let Err(e) = io.set_fan_speed(curve.fan_id, speed as u32) {
-                    log::error!("Failed to set fan {} speed: {}", curve.fan_id, e);
-                } else {
-                    self.last_speeds.insert(curve.fan_id, speed);
-                    log::debug!("Fan {}: temp={}°C, new speed={}% (was {}%)", curve.fan_id, temp, speed, last_speed);
+        let settings = {
+            let state = crate::FAN_DAEMON_STATE.lock().unwrap();
+            state.clone()
+        };
+
+        if settings != last_settings {
+            if let Some(ref s) = settings {
+                sorted_curves = s.curves.iter().map(|c| sort_points(&c.points)).collect();
+
+                let period = s.smoothing.interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+                if period != interval_ms {
+                    interval_ms = period;
+                    interval = time::interval(Duration::from_millis(period as u64));
                 }
             }
+            last_settings = settings;
         }
-        
-        self.last_update = Instant::now();
-        Ok(())
-    }
-    
-    fn interpolate_fan_speed(&self, points: &[(u8, u8)], temp: f32) -> u8 {
-        if points.is_empty() {
-            return 50; // Default fallback
-        }
-        
-        if points.len() == 1 {
-            return points[0].1;
-        }
-        
-        // Sort points by temperature
-        let mut sorted_points = points.to_vec();
-        sorted_points.sort_by_key(|p| p.0);
-        
-        // Below first point
-        if temp <= sorted_points[0].0 as f32 {
-            return sorted_points[0].1;
-        }
-        
-        // Above last point
-        if temp >= sorted_points[sorted_points.len() - 1].0 as f32 {
-            return sorted_points[sorted_points.len() - 1].1;
-        }
-        
-        // Linear interpolation between points
-        for i in 0..sorted_points.len() - 1 {
-            let (temp1, speed1) = sorted_points[i];
-            let (temp2, speed2) = sorted_points[i + 1];
-            
-            if temp >= temp1 as f32 && temp <= temp2 as f32 {
-                let ratio = (temp - temp1 as f32) / (temp2 as f32 - temp1 as f32);
-                let speed = speed1 as f32 + ratio * (speed2 as f32 - speed1 as f32);
-                return speed.round() as u8;
+
+        match last_settings {
+            Some(ref fan_settings) if fan_settings.control_enabled => {
+                if let Err(e) = apply_fan_curves(backend.as_ref(), fan_settings, &sorted_curves, &mut fans, elapsed) {
+                    log::error!("Failed to apply fan curves: {}", e);
+                }
             }
+            _ => fans.clear(),
         }
-        
-        50 // Fallback
-    }
-    
-    pub fn set_auto_mode(&mut self) -> Result<()> {
-        if let Some(ref io) = self.io {
-            io.set_fan_auto()?;
-            self.settings = None;
-            log::info!("Fan curve control disabled, returned to auto mode");
-        }
-        Ok(())
     }
 }
 
-// DBus interface methods for fan curve management
-impl ControlInterface {
-    async fn set_fan_curve(&self, fan_id: u32, curve_json: &str) -> Result<(), zbus::fdo::Error> {
-        let curve: FanCurve = serde_json::from_str(curve_json)
-            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
-        
-        // Validate curve points
-        if curve.points.is_empty() {
-            return Err(zbus::fdo::Error::Failed("Curve must have at least one point".to_string()));
-        }
-        
-        if curve.points.len() > 16 {
-            return Err(zbus::fdo::Error::Failed("Curve can have at most 16 points".to_string()));
+fn apply_fan_curves(
+    backend: &dyn FanBackend,
+    settings: &FanSettings,
+    sorted_curves: &[Vec<(u8, u8)>],
+    fans: &mut HashMap<u32, FanState>,
+    elapsed: f32,
+) -> Result<()> {
+    for (i, curve) in settings.curves.iter().enumerate() {
+        if curve.fan_id >= backend.fan_count() {
+            continue;
         }
         
-        // Validate point values
-        for (temp, speed) in &curve.points {
-            if *temp > 100 {
-                return Err(zbus::fdo::Error::Failed("Temperature must be 0-100°C".to_string()));
+        let sensor = curve.temp_source.clone()
+            .unwrap_or_else(|| format!("fan{}", curve.fan_id));
+        let temp = match read_curve_temperature(backend, curve) {
+            Ok(t) => {
+                crate::sensor_health::report_success(&sensor);
+                t
             }
-            if *speed > 100 {
-                return Err(zbus::fdo::Error::Failed("Speed must be 0-100%".to_string()));
+            Err(e) => {
+                crate::sensor_health::report_failure(&sensor, e);
+                continue;
             }
+        };
+        
+        let target = calculate_fan_speed(&sorted_curves[i], temp);
+        let target = (target as i16 + settings.offset(curve.fan_id) as i16).clamp(0, 100) as u8;
+        let mut speed = fans.entry(curve.fan_id)
+            .or_insert_with(|| FanState::new(target, temp))
+            .follow(target, temp, &settings.smoothing, elapsed);
+        speed = crate::fan_min_duty::clamp(curve.fan_id, speed);
+        if let Some(floor) = crate::lid_monitor::fan_floor() {
+            speed = speed.max(floor);
+        }
+        if let Some(floor) = crate::fan_failure::sibling_floor(curve.fan_id) {
+            speed = speed.max(floor);
         }
         
-        // Store curve (would need a global state manager)
-        log::info!("Set fan curve for fan {}: {} points", fan_id, curve.points.len());
-        Ok(())
-    }
-    
-    async fn get_fan_curve(&self, fan_id: u32) -> Result<String, zbus::fdo::Error> {
-        // Retrieve stored curve (would need a global state manager)
-        // For now, return empty curve
-        let curve = FanCurve {
-            fan_id,
-            points: vec![],
-        };
-        serde_json::to_string(&curve)
-            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+        if let Err(e) = backend.set_duty(curve.fan_id, speed as u32) {
+            log::error!("Failed to set fan {} speed: {}", curve.fan_id, e);
+        } else {
+            crate::fan_mode::record_manual(FanModeSource::Daemon, curve.fan_id, speed as u32);
+            log::debug!("Fan {}: temp={}°C, curve={}%, speed={}%", curve.fan_id, temp, target, speed);
+        }
     }
     
-    async fn test_fan_curve(&self, fan_id: u32, temperature: u8) -> Result<u8, zbus::fdo::Error> {
-        // Test what speed would be applied at given temperature
-        // This helps users preview their curve
-        Ok(50) // Placeholder
+    Ok(())
+}
+
+fn read_curve_temperature(backend: &dyn FanBackend, curve: &FanCurve) -> Result<f32> {
+    match curve.temp_source {
+        Some(ref sensor_id) => crate::hardware_detection::read_temperature_sensor(sensor_id),
+        None => backend.get_temperature(curve.fan_id),
     }
 }
 
-pub struct FanDaemon {
-    settings: Option<FanSettings>,
-    running: bool,
+// Stable, of points with the same temperature the first one listed comes first
+fn sort_points(points: &[(u8, u8)]) -> Vec<(u8, u8)> {
+    let mut points = points.to_vec();
+    points.sort_by_key(|p| p.0);
+    points
 }
 
-impl FanDaemon {
-    pub fn new() -> Self {
-        Self {
-            settings: None,
-            running: false,
-        }
+fn calculate_fan_speed(sorted_points: &[(u8, u8)], temp: f32) -> u8 {
+    if sorted_points.is_empty() {
+        return 50; // Default fallback
     }
     
-    pub fn update_settings(&mut self, settings: FanSettings) {
-        self.settings = Some(settings);
+    if sorted_points.len() == 1 {
+        return sorted_points[0].1;
     }
     
-    pub async fn run(&mut self) {
-        self.running = true;
-        let mut interval = time::interval(Duration::from_secs(2));
-        
-        while self.running {
-            interval.tick().await;
-            
-            if let Some(ref settings) = self.settings {
-                if settings.control_enabled {
-                    if let Err(e) = self.apply_fan_curves(settings) {
-                        log::error!("Failed to apply fan curves: {}", e);
-                    }
-                }
-            }
-        }
+    if temp <= sorted_points[0].0 as f32 {
+        return sorted_points[0].1;
     }
     
-    pub fn stop(&mut self) {
-        self.running = false;
+    if temp >= sorted_points[sorted_points.len() - 1].0 as f32 {
+        return sorted_points[sorted_points.len() - 1].1;
     }
     
-    fn apply_fan_curves(&self, settings: &FanSettings) -> Result<()> {
-        // Use /dev/tuxedo_io instead of sysfs
-        if !TuxedoIo::is_available() {
-            return Ok(());
-        }
+    for i in 0..sorted_points.len() - 1 {
+        let (temp1, speed1) = sorted_points[i];
+        let (temp2, speed2) = sorted_points[i + 1];
         
-        let io = TuxedoIo::new()?;
-        
-        for curve in &settings.curves {
-            // Read current temperature for this fan
-            let temp = match io.get_fan_temperature(curve.fan_id) {
-                Ok(t) => t as u8,
-                Err(e) => {
-                    log::warn!("Failed to read temperature for fan {}: {}", curve.fan_id, e);
-                    continue;
-                }
-            };
-            
-            // Find appropriate speed based on curve
-            let speed = self.calculate_fan_speed(temp, &curve.points);
-            
-            // Apply speed using tuxedo_io ioctl
-            if let Err(e) = io.set_fan_speed(curve.fan_id, speed as u32) {
-                log::error!("Failed to set speed for fan {}: {}", curve.fan_id, e);
-            }
+        if temp >= temp1 as f32 && temp <= temp2 as f32 {
+            let ratio = (temp - temp1 as f32) / (temp2 as f32 - temp1 as f32);
+            let speed = speed1 as f32 + ratio * (speed2 as f32 - speed1 as f32);
+            return speed.round() as u8;
         }
-        
-        Ok(())
     }
     
-    fn calculate_fan_speed(&self, temp: u8, points: &[(u8, u8)]) -> u8 {
-        if points.is_empty() {
-            return 50; // Default
+    50 // Fallback
+}
+
+// DBus interface methods for fan curve management
+/// Checks a profile's curve before the fan loop runs it. The capabilities are only consulted
+/// when the backend answers, a missing one is reported when the fans are written.
+pub fn validate_curve(curve: &FanCurve) -> Result<()> {
+    if curve.points.is_empty() {
+        return Err(anyhow!("fan {}: the curve needs at least one point", curve.fan_id));
+    }
+    if let Ok(caps) = crate::hardware_detection::get_fan_curve_capabilities() {
+        if curve.fan_id >= caps.fan_count {
+            return Err(anyhow!("fan {} doesn't exist, this machine has {} fans", curve.fan_id, caps.fan_count));
         }
-        
-        // Find the two points to interpolate between
-        for i in 0..points.len() - 1 {
-            let (temp1, speed1) = points[i];
-            let (temp2, speed2) = points[i + 1];
-            
-            if temp >= temp1 && temp <= temp2 {
-                // Linear interpolation
-                let temp_range = temp2 - temp1;
-                if temp_range == 0 {
-                    return speed1;
-                }
-                
-                let temp_offset = temp - temp1;
-                let speed_range = speed2 as i16 - speed1 as i16;
-                let speed = speed1 as i16 + (speed_range * temp_offset as i16) / temp_range as i16;
-                
-                return speed.clamp(0, 100) as u8;
-            }
+        if let Some(max_points) = caps.max_points.filter(|max| curve.points.len() > *max as usize) {
+            return Err(anyhow!("fan {}: the curve can have at most {} points", curve.fan_id, max_points));
         }
-        
-        // If temp is below first point
-        if temp < points[0].0 {
-            return points[0].1;
+    }
+    if let Some((temp, speed)) = curve.points.iter().find(|(temp, speed)| *temp > 100 || *speed > 100) {
+        return Err(anyhow!("fan {}: point [{}, {}] is out of range (0-100 °C, 0-100 %)",
+            curve.fan_id, temp, speed));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn points() -> impl Strategy<Value = Vec<(u8, u8)>> {
+        prop::collection::vec((0u8..=100, 0u8..=100), 1..8)
+    }
+
+    proptest! {
+        // Unsorted input and duplicate temperatures included
+        #[test]
+        fn speed_stays_within_the_curve(points in points(), temp in -20.0f32..130.0) {
+            let sorted = sort_points(&points);
+            let speed = calculate_fan_speed(&sorted, temp);
+            let min = points.iter().map(|p| p.1).min().unwrap();
+            let max = points.iter().map(|p| p.1).max().unwrap();
+            prop_assert!((min..=max).contains(&speed), "{} outside {}..={} for {:?}", speed, min, max, sorted);
         }
-        
-        // If temp is above last point
-        points.last().unwrap().1
+
+        #[test]
+        fn ends_are_held_beyond_the_curve(points in points(), below in 0.0f32..=1.0, above in 0.0f32..=1.0) {
+            let sorted = sort_points(&points);
+            let (first, last) = (sorted[0], sorted[sorted.len() - 1]);
+            prop_assert_eq!(calculate_fan_speed(&sorted, first.0 as f32 - below * 50.0), first.1);
+            prop_assert_eq!(calculate_fan_speed(&sorted, last.0 as f32 + above * 50.0), last.1);
+        }
+
+        #[test]
+        fn points_are_hit_exactly(points in points(), index in any::<prop::sample::Index>()) {
+            let sorted = sort_points(&points);
+            let (temp, _) = sorted[index.index(sorted.len())];
+            let speed = calculate_fan_speed(&sorted, temp as f32);
+            prop_assert!(sorted.iter().any(|p| p.0 == temp && p.1 == speed));
+        }
+
+        #[test]
+        fn rising_curves_give_rising_speeds(
+            mut temps in prop::collection::vec(0u8..=100, 2..8),
+            mut speeds in prop::collection::vec(0u8..=100, 8),
+            a in 0.0f32..=100.0,
+            b in 0.0f32..=100.0,
+        ) {
+            temps.sort();
+            speeds.sort();
+            let sorted: Vec<(u8, u8)> = temps.iter().copied().zip(speeds).collect();
+            let (low, high) = (a.min(b), a.max(b));
+            prop_assert!(calculate_fan_speed(&sorted, low) <= calculate_fan_speed(&sorted, high));
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        let curve = [(0, 0), (100, 100)];
+        assert_eq!(calculate_fan_speed(&curve, 0.0), 0);
+        assert_eq!(calculate_fan_speed(&curve, 50.0), 50);
+        assert_eq!(calculate_fan_speed(&curve, 100.0), 100);
+        assert_eq!(calculate_fan_speed(&[(60, 40), (60, 90)], 60.0), 40);
+        assert_eq!(sort_points(&[(70, 80), (30, 10), (70, 90)]), vec![(30, 10), (70, 80), (70, 90)]);
     }
 }
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::{EventKind, FanFailure, HardwareEvent};
+use zbus::{Connection, SignalContext};
+
+// A fan that reads 0 RPM while driven at least this hard should be spinning
+const DUTY_THRESHOLD: u32 = 30;
+// Spin-up after a duty change and EC tach update lag take a few seconds
+const STALL_TIME: Duration = Duration::from_secs(20);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Duty forced on the remaining fans while one has failed
+const SIBLING_DUTY: u8 = 80;
+
+static FAILURES: once_cell::sync::Lazy<Mutex<Vec<FanFailure>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn get_failures() -> Vec<FanFailure> {
+    FAILURES.lock().unwrap().clone()
+}
+
+/// A fan has failed and the others are held up
+pub fn is_active() -> bool {
+    !FAILURES.lock().unwrap().is_empty()
+}
+
+// Minimum duty for fan_id while a sibling has failed, used by the fan curve loop
+pub fn sibling_floor(fan_id: u32) -> Option<u8> {
+    let failures = FAILURES.lock().unwrap();
+    (!failures.is_empty() && !failures.iter().any(|f| f.fan_id == fan_id)).then_some(SIBLING_DUTY)
+}
+
+fn curves_active() -> bool {
+    crate::FAN_DAEMON_STATE.lock().unwrap().as_ref().is_some_and(|s| s.control_enabled)
+}
+
+// Runs on the service connection so it can emit HardwareStateChanged
+pub async fn monitor_task(connection: Connection) {
+    let mut backend = match crate::fan_backend::detect() {
+        Some(backend) => backend,
+        None => return,
+    };
+    if (0..backend.fan_count()).all(|fan_id| backend.get_rpm(fan_id).is_none()) {
+        log::info!("No fan tachometer readable, fan failure detection disabled");
+        return;
+    }
+    let ctxt = match SignalContext::new(&connection, "/com/tuxedo/Control") {
+        Ok(ctxt) => ctxt,
+        Err(e) => {
+            log::warn!("Fan failure detection disabled: {}", e);
+            return;
+        }
+    };
+
+    let mut stalled_since: HashMap<u32, Instant> = HashMap::new();
+    let mut forced_siblings = false;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("fan_failure");
+
+        if let Some(fresh) = crate::fan_backend::detect().filter(|fresh| !Arc::ptr_eq(fresh, &backend)) {
+            backend = fresh;
+            stalled_since.clear();
+        }
+
+        // Dust cleaning and floor calibration stop the fans on purpose
+        if crate::dust_cleaning::is_running() || crate::fan_min_duty::is_calibrating() {
+            stalled_since.clear();
+            continue;
+        }
+
+        let mut failures = get_failures();
+        let before = failures.clone();
+
+        for fan_id in 0..backend.fan_count() {
+            let (duty, rpm) = match (backend.get_duty(fan_id), backend.get_rpm(fan_id)) {
+                (Ok(duty), Some(rpm)) => (duty, rpm),
+                _ => continue,
+            };
+
+            if rpm > 0 {
+                stalled_since.remove(&fan_id);
+                if failures.iter().any(|f| f.fan_id == fan_id) {
+                    log::info!("Fan {} is spinning again ({} RPM)", fan_id, rpm);
+                    failures.retain(|f| f.fan_id != fan_id);
+                }
+                continue;
+            }
+            if duty < DUTY_THRESHOLD {
+                stalled_since.remove(&fan_id);
+                continue;
+            }
+
+            let since = *stalled_since.entry(fan_id).or_insert_with(Instant::now);
+            if since.elapsed() >= STALL_TIME && !failures.iter().any(|f| f.fan_id == fan_id) {
+                let message = format!("Fan {} reads 0 RPM at {}% duty, probable fan failure", fan_id, duty);
+                log::error!("{}", message);
+                crate::event_log::record(EventKind::FanFailsafe, message);
+                failures.push(FanFailure {
+                    fan_id,
+                    duty,
+                    since: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0),
+                });
+            }
+        }
+
+        if failures != before {
+            *FAILURES.lock().unwrap() = failures.clone();
+            crate::hotkey_monitor::emit(&ctxt, HardwareEvent::FanFailure(failures.clone())).await;
+        }
+
+        // With custom curves the fan daemon applies sibling_floor, otherwise drive the siblings here
+        if curves_active() || crate::privileges::monitor_only() {
+            forced_siblings = false;
+        } else if !failures.is_empty() {
+            for fan_id in (0..backend.fan_count()).filter(|id| sibling_floor(*id).is_some()) {
+                let duty = backend.get_duty(fan_id).unwrap_or(0);
+                if duty < SIBLING_DUTY as u32 {
+                    if let Err(e) = backend.set_duty(fan_id, SIBLING_DUTY as u32) {
+                        log::warn!("Failed to raise fan {} after a fan failure: {}", fan_id, e);
+                    }
+                }
+            }
+            forced_siblings = true;
+        } else if forced_siblings {
+            forced_siblings = false;
+            if let Err(e) = backend.set_auto() {
+                log::warn!("Failed to hand the fans back to the EC: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{FanCalibrationStatus, FanMinDuty, FanModeSource};
+use crate::fan_backend::FanBackend;
+
+// Some ECs stall a fan below roughly 20% duty, it stops and may buzz or trip the firmware's
+// own fan check. The floor belongs to the machine rather than a profile, so it is kept in
+// the daemon's state directory and applies to every curve. A duty of 0 stays 0, stopping
+// the fan on purpose is fine, only the range in between is raised to the floor.
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const FLOORS_FILE: &str = "/var/lib/tuxedo-control-center/fan_min_duty.json";
+
+// Calibration walks the duty down from a speed every fan spins at until the tach reads 0
+const START_DUTY: u8 = 50;
+const STEP: u8 = 5;
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+// A stopped fan needs more to start than a spinning one needs to keep going
+const START_MARGIN: u8 = 5;
+const ABORT_TEMP: f32 = 85.0;
+
+static FLOORS: once_cell::sync::Lazy<Mutex<Vec<FanMinDuty>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+static CALIBRATING: AtomicBool = AtomicBool::new(false);
+static STATUS: once_cell::sync::Lazy<Mutex<FanCalibrationStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(FanCalibrationStatus::default()));
+
+pub fn get() -> Vec<FanMinDuty> {
+    FLOORS.lock().unwrap().clone()
+}
+
+pub fn floor(fan_id: u32) -> u8 {
+    FLOORS.lock().unwrap().iter().find(|f| f.fan_id == fan_id).map(|f| f.percent).unwrap_or(0)
+}
+
+/// Raise a computed duty to the fan's floor, 0 is left alone
+pub fn clamp(fan_id: u32, duty: u8) -> u8 {
+    if duty == 0 {
+        return 0;
+    }
+    duty.max(floor(fan_id))
+}
+
+/// Set a fan's floor by hand, 0 removes it
+pub fn set(fan_id: u32, percent: u8) -> Result<()> {
+    if percent > 100 {
+        return Err(anyhow!("Minimum duty must be between 0 and 100%"));
+    }
+    store(fan_id, percent, false)?;
+    log::info!("Fan {} minimum duty set to {}%", fan_id, percent);
+    Ok(())
+}
+
+pub fn is_calibrating() -> bool {
+    CALIBRATING.load(Ordering::SeqCst)
+}
+
+pub fn get_status() -> FanCalibrationStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+/// Measure the lowest duty the fan keeps spinning at, needs a readable tachometer
+pub fn start_calibration(fan_id: u32) -> Result<()> {
+    if crate::dust_cleaning::is_running() {
+        return Err(anyhow!("Dust cleaning is running"));
+    }
+    if crate::auto_tuner::is_running() {
+        return Err(anyhow!("Auto-tuning is running"));
+    }
+    if crate::ab_test::is_running() {
+        return Err(anyhow!("An A/B test is running"));
+    }
+
+    let backend = crate::fan_backend::detect()
+        .ok_or_else(|| anyhow!("Fan control not available"))?;
+    if fan_id >= backend.fan_count() {
+        return Err(anyhow!("No fan {}", fan_id));
+    }
+    if backend.get_rpm(fan_id).is_none() {
+        return Err(anyhow!("Fan {} has no readable tachometer, set its minimum duty by hand", fan_id));
+    }
+
+    if CALIBRATING.swap(true, Ordering::SeqCst) {
+        return Err(anyhow!("A fan is already being calibrated"));
+    }
+    *STATUS.lock().unwrap() = FanCalibrationStatus {
+        running: true,
+        fan_id: Some(fan_id),
+        progress_percent: 0,
+        message: format!("Spinning up fan {}", fan_id),
+    };
+    log::info!("Calibrating the minimum duty of fan {}", fan_id);
+
+    tokio::spawn(async move {
+        let _inhibitor = crate::sleep_inhibit::acquire("Fan calibration").await;
+        let result = run_calibration(backend.as_ref(), fan_id).await;
+        restore(backend.as_ref());
+
+        let message = match result {
+            Ok(floor) => match store(fan_id, floor, true) {
+                Ok(()) if floor == 0 => format!("Fan {} keeps spinning at {}%, no floor needed", fan_id, STEP),
+                Ok(()) => format!("Fan {} minimum duty: {}%", fan_id, floor),
+                Err(e) => format!("Failed to save the minimum duty: {}", e),
+            },
+            Err(e) => e.to_string(),
+        };
+        log::info!("Fan calibration: {}", message);
+
+        let mut status = STATUS.lock().unwrap();
+        status.running = false;
+        status.progress_percent = 100;
+        status.message = message;
+        CALIBRATING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(())
+}
+
+async fn run_calibration(backend: &dyn FanBackend, fan_id: u32) -> Result<u8> {
+    set_duty(backend, fan_id, START_DUTY)?;
+    tokio::time::sleep(SETTLE_TIME).await;
+    if backend.get_rpm(fan_id) == Some(0) {
+        return Err(anyhow!("Fan {} doesn't spin at {}%, not calibrated", fan_id, START_DUTY));
+    }
+
+    let duties: Vec<u8> = (1..START_DUTY / STEP).rev().map(|i| i * STEP).collect();
+    let mut lowest_spinning = START_DUTY;
+    for (i, duty) in duties.iter().enumerate() {
+        {
+            let mut status = STATUS.lock().unwrap();
+            status.progress_percent = ((i * 100) / duties.len()) as u8;
+            status.message = format!("Fan {} at {}%", fan_id, duty);
+        }
+
+        set_duty(backend, fan_id, *duty)?;
+        tokio::time::sleep(SETTLE_TIME).await;
+
+        if backend.get_temperature(fan_id).is_ok_and(|temp| temp >= ABORT_TEMP) {
+            return Err(anyhow!("Too warm to slow the fans down, try again when the system is idle"));
+        }
+        match backend.get_rpm(fan_id) {
+            Some(0) => return Ok((lowest_spinning + START_MARGIN).min(100)),
+            Some(_) => lowest_spinning = *duty,
+            None => return Err(anyhow!("Lost the tachometer reading of fan {}", fan_id)),
+        }
+    }
+    Ok(0)
+}
+
+fn set_duty(backend: &dyn FanBackend, fan_id: u32, duty: u8) -> Result<()> {
+    backend.set_duty(fan_id, duty as u32)?;
+    crate::fan_mode::record_manual(FanModeSource::Daemon, fan_id, duty as u32);
+    Ok(())
+}
+
+fn restore(backend: &dyn FanBackend) {
+    // Custom curves are picked up again by the fan daemon on its next tick
+    let curves_active = crate::FAN_DAEMON_STATE.lock().unwrap()
+        .as_ref()
+        .is_some_and(|s| s.control_enabled);
+    if !curves_active {
+        match backend.set_auto() {
+            Ok(()) => crate::fan_mode::record_auto(FanModeSource::Daemon),
+            Err(e) => log::error!("Failed to restore fan auto mode after calibration: {}", e),
+        }
+    }
+}
+
+fn store(fan_id: u32, percent: u8, calibrated: bool) -> Result<()> {
+    let mut floors = FLOORS.lock().unwrap();
+    let mut updated = floors.clone();
+    updated.retain(|f| f.fan_id != fan_id);
+    // A calibration that found no floor is kept, so it shows as measured
+    if percent > 0 || calibrated {
+        updated.push(FanMinDuty { fan_id, percent, calibrated });
+        updated.sort_by_key(|f| f.fan_id);
+    }
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(FLOORS_FILE, serde_json::to_string_pretty(&updated)?)?;
+    *floors = updated;
+    Ok(())
+}
+
+fn load() -> Vec<FanMinDuty> {
+    match fs::read_to_string(FLOORS_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid {}: {}", FLOORS_FILE, e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tuxedo_common::types::{FanControlMode, FanModeSource, FanModeStatus};
+use crate::fan_backend::FanBackend;
+
+// Read-back may differ slightly from the written duty due to EC rounding
+const DUTY_TOLERANCE: u32 = 5;
+
+struct LastWrite {
+    mode: FanControlMode,
+    source: FanModeSource,
+    duties: HashMap<u32, u32>,
+}
+
+static LAST_WRITE: once_cell::sync::Lazy<Mutex<Option<LastWrite>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn record_manual(source: FanModeSource, fan_id: u32, duty: u32) {
+    let mut last = LAST_WRITE.lock().unwrap();
+    match last.as_mut() {
+        Some(write) if write.mode == FanControlMode::Manual && write.source == source => {
+            write.duties.insert(fan_id, duty);
+        }
+        _ => {
+            *last = Some(LastWrite {
+                mode: FanControlMode::Manual,
+                source,
+                duties: HashMap::from([(fan_id, duty)]),
+            });
+        }
+    }
+}
+
+pub fn record_auto(source: FanModeSource) {
+    *LAST_WRITE.lock().unwrap() = Some(LastWrite {
+        mode: FanControlMode::Auto,
+        source,
+        duties: HashMap::new(),
+    });
+}
+
+pub fn get_status() -> FanModeStatus {
+    let backend = crate::fan_backend::detect();
+    let last = LAST_WRITE.lock().unwrap();
+
+    let (recorded_mode, source) = match last.as_ref() {
+        Some(write) => (write.mode, write.source),
+        None => (FanControlMode::Auto, FanModeSource::EcDefault),
+    };
+
+    let backend = match backend {
+        Some(backend) => backend,
+        None => return FanModeStatus { mode: FanControlMode::Unknown, set_by: source },
+    };
+
+    // Hardware that reports its mode wins over what we remember
+    if let Some(mode) = backend.get_mode() {
+        let set_by = if mode == recorded_mode { source } else { FanModeSource::External };
+        return FanModeStatus { mode, set_by };
+    }
+
+    // Otherwise detect foreign writes by comparing duties with our last write
+    if let Some(write) = last.as_ref().filter(|w| w.mode == FanControlMode::Manual) {
+        if duty_changed_externally(backend.as_ref(), &write.duties) {
+            return FanModeStatus { mode: FanControlMode::Unknown, set_by: FanModeSource::External };
+        }
+    }
+
+    FanModeStatus { mode: recorded_mode, set_by: source }
+}
+
+fn duty_changed_externally(backend: &dyn FanBackend, duties: &HashMap<u32, u32>) -> bool {
+    duties.iter().any(|(fan_id, written)| {
+        backend.get_duty(*fan_id)
+            .map(|current| current.abs_diff(*written) > DUTY_TOLERANCE)
+            .unwrap_or(false)
+    })
+}
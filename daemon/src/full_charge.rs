@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::battery_control::BatteryControl;
+
+// "Charge to 100% for travel": lifts the end threshold once and puts the profile's
+// thresholds back as soon as the battery is full or the charger is pulled. The override is
+// kept on disk, a daemon restart or an idle exit halfway through still restores them.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const STATE_FILE: &str = "/var/lib/tuxedo-control-center/full_charge.json";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Override {
+    started_at: i64,  // Unix seconds
+    // What to put back, the watchdog doesn't know it yet after a restart
+    thresholds: Option<(u8, u8)>,
+}
+
+static ACTIVE: once_cell::sync::Lazy<Mutex<Option<Override>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+pub fn is_active() -> bool {
+    ACTIVE.lock().unwrap().is_some()
+}
+
+pub fn started_at() -> Option<i64> {
+    ACTIVE.lock().unwrap().map(|o| o.started_at)
+}
+
+pub fn start() -> Result<()> {
+    let battery = BatteryControl::new()?;
+    if !battery.has_thresholds() {
+        return Err(anyhow!("{} has no charge thresholds to lift", battery.backend().label()));
+    }
+    if !crate::ac_adapter::get_info().online {
+        return Err(anyhow!("Connect the charger first"));
+    }
+
+    battery.set_charge_type("Standard")?;
+    if crate::dry_run::is_active() {
+        return Ok(());
+    }
+
+    set(Some(Override {
+        started_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0),
+        thresholds: crate::charge_watchdog::expected(),
+    }));
+    log::info!("Charging to 100% once, profile thresholds are restored when full or unplugged");
+    Ok(())
+}
+
+pub fn cancel() -> Result<()> {
+    let Some(active) = set(None) else {
+        return Ok(());
+    };
+    restore(active)?;
+    log::info!("Full charge override cancelled, profile thresholds restored");
+    Ok(())
+}
+
+// Puts back what apply_battery_settings last set, nothing to do if thresholds were off anyway
+fn restore(active: Override) -> Result<()> {
+    let (start, end) = match crate::charge_watchdog::expected().or(active.thresholds) {
+        Some(thresholds) => thresholds,
+        None => return Ok(()),
+    };
+    let battery = BatteryControl::new()?;
+    battery.set_charge_type("Custom")?;
+    battery.set_thresholds(start, end)
+}
+
+pub async fn monitor_task() {
+    // Not right away, the charger state comes from the adapter monitor's first poll
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + CHECK_INTERVAL, CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        if !is_active() {
+            continue;
+        }
+        let _timer = crate::daemon_stats::TickTimer::new("full_charge");
+
+        let battery = match BatteryControl::new() {
+            Ok(battery) => battery,
+            Err(_) => continue,
+        };
+        let full = battery.get_status().map(|s| s == "Full").unwrap_or(false)
+            || battery.get_capacity().map(|c| c >= 100).unwrap_or(false);
+        let unplugged = !crate::ac_adapter::get_info().online;
+        if !full && !unplugged {
+            continue;
+        }
+
+        let Some(active) = set(None) else {
+            continue;
+        };
+        match restore(active) {
+            Ok(()) => log::info!("Full charge override ended ({}), profile thresholds restored",
+                if full { "battery full" } else { "charger disconnected" }),
+            Err(e) => log::warn!("Failed to restore battery charge thresholds: {}", e),
+        }
+    }
+}
+
+// Replaces the override in memory and on disk, returns the previous one
+fn set(active: Option<Override>) -> Option<Override> {
+    let mut current = ACTIVE.lock().unwrap();
+    let previous = std::mem::replace(&mut *current, active);
+    let result = match active {
+        Some(active) => fs::create_dir_all(STATE_DIR)
+            .and_then(|_| fs::write(STATE_FILE, serde_json::to_string(&active).unwrap_or_default())),
+        None => fs::remove_file(STATE_FILE).or_else(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(e),
+        }),
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to update {}: {}", STATE_FILE, e);
+    }
+    previous
+}
+
+fn load() -> Option<Override> {
+    let active: Override = serde_json::from_str(&fs::read_to_string(STATE_FILE).ok()?).ok()?;
+    log::info!("Full charge override from before the restart still active");
+    Some(active)
+}
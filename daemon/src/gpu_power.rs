@@ -0,0 +1,374 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{DynamicPowerLimit, GpuSettings, GpuType};
+
+const CONTROL_INTERVAL: Duration = Duration::from_secs(2);
+// Raise the limit again only once the GPU is this far below the target
+const HEADROOM: f32 = 5.0;
+
+enum Backend {
+    // PCI address for nvidia-smi -i, None when nvidia-smi answered without a DRM card to match
+    Nvidia(Option<String>),
+    // hwmon directory of an amdgpu dGPU exposing power1_cap
+    Amd(PathBuf),
+}
+
+pub struct DgpuPowerControl {
+    backend: Backend,
+    pci_address: Option<String>,
+    device_path: Option<PathBuf>,
+}
+
+static DYNAMIC_SETTINGS: once_cell::sync::Lazy<Mutex<Option<DynamicPowerLimit>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+// PCI address of the dGPU picked in the active profile
+static TARGET: Mutex<Option<String>> = Mutex::new(None);
+// Fixed limit of the active profile, it replaces the controller's when dynamic mode is left
+static FIXED_LIMIT: Mutex<Option<u32>> = Mutex::new(None);
+
+impl DgpuPowerControl {
+    pub fn new() -> Result<Self> {
+        Self::find_backend(TARGET.lock().unwrap().as_deref())
+    }
+
+    pub fn is_available() -> bool {
+        Self::new().is_ok()
+    }
+
+    /// The GPU the limits apply to
+    pub fn pci_address(&self) -> Option<&str> {
+        self.pci_address.as_deref()
+    }
+
+    /// Still the dGPU the active profile picked, any one is when it picks none
+    pub fn is_target(&self) -> bool {
+        TARGET.lock().unwrap().as_deref().is_none_or(|target| self.pci_address() == Some(target))
+    }
+
+    /// Runtime-suspended, any nvidia-smi query would wake it up
+    pub fn is_suspended(&self) -> bool {
+        self.device_path.as_deref().is_some_and(runtime_suspended)
+    }
+
+    fn find_backend(target: Option<&str>) -> Result<Self> {
+        for card_path in crate::hardware_detection::drm_cards() {
+            let device_path = card_path.join("device");
+            let Some(address) = crate::hardware_detection::pci_address(&device_path) else {
+                continue;
+            };
+            if target.is_some_and(|target| target != address) {
+                continue;
+            }
+            let vendor = fs::read_to_string(device_path.join("vendor")).unwrap_or_default();
+            if crate::hardware_detection::gpu_type(&device_path, vendor.trim(), &address) != GpuType::Discrete {
+                continue;
+            }
+
+            let backend = if let Some(hwmon) = amd_power_cap(&device_path) {
+                Backend::Amd(hwmon)
+            } else if has_nvidia_driver(&device_path)
+                && (runtime_suspended(&device_path) || query_nvidia_smi(Some(&address), "power.limit").is_ok())
+            {
+                Backend::Nvidia(Some(address.clone()))
+            } else {
+                continue;
+            };
+            return Ok(Self { backend, pci_address: Some(address), device_path: Some(device_path) });
+        }
+
+        if let Some(target) = target {
+            return Err(anyhow!("dGPU {} not found or without power limit control", target));
+        }
+        if query_nvidia_smi(None, "power.limit").is_ok() {
+            return Ok(Self { backend: Backend::Nvidia(None), pci_address: None, device_path: None });
+        }
+
+        Err(anyhow!("No dGPU with power limit control found"))
+    }
+
+    /// Get supported power limit range in watts (min, max)
+    pub fn get_limits(&self) -> Result<(u32, u32)> {
+        match self.backend {
+            Backend::Nvidia(ref address) => {
+                let min = query_nvidia_smi(address.as_deref(), "power.min_limit")?;
+                let max = query_nvidia_smi(address.as_deref(), "power.max_limit")?;
+                Ok((min as u32, max as u32))
+            }
+            Backend::Amd(ref hwmon) => {
+                let min = read_microwatts(hwmon.join("power1_cap_min"))?;
+                let max = read_microwatts(hwmon.join("power1_cap_max"))?;
+                Ok((min, max))
+            }
+        }
+    }
+
+    /// Power limit currently in effect in watts
+    pub fn get_power_limit(&self) -> Result<u32> {
+        match self.backend {
+            Backend::Nvidia(ref address) => Ok(query_nvidia_smi(address.as_deref(), "power.limit")? as u32),
+            Backend::Amd(ref hwmon) => read_microwatts(hwmon.join("power1_cap")),
+        }
+    }
+
+    /// Set power limit in watts
+    pub fn set_power_limit(&self, watts: u32) -> Result<()> {
+        let (min, max) = self.get_limits()?;
+        if watts < min || watts > max {
+            return Err(anyhow!("Power limit must be between {} and {} W", min, max));
+        }
+
+        match self.backend {
+            Backend::Nvidia(ref address) => {
+                let watts = watts.to_string();
+                let mut args = vec!["-pl", &watts];
+                if let Some(address) = address {
+                    args.extend(["-i", address.as_str()]);
+                }
+                if crate::dry_run::command("nvidia-smi", &args) {
+                    return Ok(());
+                }
+                let output = crate::privilege_drop::grant(Command::new("nvidia-smi").args(&args),
+                    &[crate::privilege_drop::CAP_SYS_ADMIN])
+                    .output()?;
+                if !output.status.success() {
+                    return Err(anyhow!("nvidia-smi failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()));
+                }
+            }
+            Backend::Amd(ref hwmon) => {
+                crate::dry_run::write(hwmon.join("power1_cap"), (watts as u64 * 1_000_000).to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restore the driver's default power limit
+    pub fn reset_power_limit(&self) -> Result<()> {
+        let watts = match self.backend {
+            Backend::Nvidia(ref address) => query_nvidia_smi(address.as_deref(), "power.default_limit")? as u32,
+            Backend::Amd(ref hwmon) => read_microwatts(hwmon.join("power1_cap_default"))?,
+        };
+        self.set_power_limit(watts)
+    }
+
+    /// Get dGPU temperature in °C
+    pub fn get_temperature(&self) -> Result<f32> {
+        match self.backend {
+            Backend::Nvidia(ref address) => query_nvidia_smi(address.as_deref(), "temperature.gpu"),
+            Backend::Amd(ref hwmon) => {
+                let content = fs::read_to_string(hwmon.join("temp1_input"))?;
+                Ok(content.trim().parse::<f32>()? / 1000.0)
+            }
+        }
+    }
+}
+
+// The GPU at `address`, or the first one nvidia-smi lists
+fn query_nvidia_smi(address: Option<&str>, field: &str) -> Result<f32> {
+    let mut command = Command::new("nvidia-smi");
+    command.args([&format!("--query-gpu={}", field), "--format=csv,noheader,nounits"]);
+    if let Some(address) = address {
+        command.args(["-i", address]);
+    }
+    let output = command.output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!("nvidia-smi query for {} failed", field));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout.lines().next().unwrap_or("").trim();
+    value.parse::<f32>().map_err(|_| anyhow!("Unexpected nvidia-smi value for {}: {}", field, value))
+}
+
+fn amd_power_cap(device_path: &Path) -> Option<PathBuf> {
+    crate::hardware_detection::sorted_dir_entries(&device_path.join("hwmon").to_string_lossy())
+        .into_iter()
+        .find(|hwmon| hwmon.join("power1_cap").exists())
+}
+
+fn has_nvidia_driver(device_path: &Path) -> bool {
+    crate::hardware_detection::driver_name(device_path).as_deref() == Some("nvidia")
+}
+
+fn runtime_suspended(device_path: &Path) -> bool {
+    fs::read_to_string(device_path.join("power/runtime_status"))
+        .is_ok_and(|status| status.trim() == "suspended")
+}
+
+/// Whether the limit can be set on this GPU, without asking nvidia-smi
+pub fn supports_power_limit(device_path: &Path) -> bool {
+    amd_power_cap(device_path).is_some() || has_nvidia_driver(device_path)
+}
+
+fn read_microwatts(path: PathBuf) -> Result<u32> {
+    let content = fs::read_to_string(&path)?;
+    Ok((content.trim().parse::<u64>()? / 1_000_000) as u32)
+}
+
+pub fn dynamic_limit_active() -> bool {
+    DYNAMIC_SETTINGS.lock().unwrap().is_some()
+}
+
+pub fn apply_gpu_settings(settings: &GpuSettings) -> Result<()> {
+    if !crate::dry_run::is_active() {
+        *FIXED_LIMIT.lock().unwrap() = settings.dgpu_tdp.filter(|_| settings.dynamic_power_limit.is_none());
+        *DYNAMIC_SETTINGS.lock().unwrap() = settings.dynamic_power_limit.clone();
+        select_target(settings.dgpu_target.clone());
+    }
+
+    let control = match DgpuPowerControl::find_backend(settings.dgpu_target.as_deref()) {
+        Ok(control) => control,
+        Err(e) => {
+            log::info!("dGPU power limit control not available, skipping: {}", e);
+            return Ok(());
+        }
+    };
+
+    // In dynamic mode the controller task owns the limit
+    if settings.dynamic_power_limit.is_some() {
+        return Ok(());
+    }
+
+    if let Some(watts) = settings.dgpu_tdp {
+        control.set_power_limit(watts)?;
+        log::info!("Set dGPU power limit to {} W", watts);
+    }
+
+    Ok(())
+}
+
+// A GPU that stops being the target gets its default limit back
+fn select_target(target: Option<String>) {
+    let previous = std::mem::replace(&mut *TARGET.lock().unwrap(), target.clone());
+    if previous == target {
+        return;
+    }
+    let Ok(previous) = DgpuPowerControl::find_backend(previous.as_deref()) else {
+        return;
+    };
+    let current = DgpuPowerControl::find_backend(target.as_deref()).ok();
+    if current.is_some_and(|current| current.pci_address() == previous.pci_address()) {
+        return;
+    }
+    log::info!("dGPU power limits now apply to {}", target.as_deref().unwrap_or("the first dGPU found"));
+    if let Err(e) = previous.reset_power_limit() {
+        log::warn!("Failed to reset the power limit of the previous dGPU: {}", e);
+    }
+}
+
+// State of the dynamic limit controller, carried from one tick to the next
+#[derive(Default)]
+struct Controller {
+    control: Option<DgpuPowerControl>,
+    current_limit: Option<u32>,
+    // The limit before the controller first changed it
+    original_limit: Option<u32>,
+    last_settings: Option<DynamicPowerLimit>,
+}
+
+pub async fn dynamic_power_task() {
+    let mut controller = Controller::default();
+    let mut interval = tokio::time::interval(CONTROL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("dgpu_power_limit");
+
+        // nvidia-smi takes a while to answer, keep it off the runtime threads
+        controller = match tokio::task::spawn_blocking(move || {
+            controller.tick();
+            controller
+        }).await {
+            Ok(controller) => controller,
+            Err(e) => {
+                log::warn!("Dynamic dGPU power limit stopped: {}", e);
+                return;
+            }
+        };
+    }
+}
+
+impl Controller {
+    fn tick(&mut self) {
+        let settings = DYNAMIC_SETTINGS.lock().unwrap().clone();
+        if settings != self.last_settings {
+            // A profile without dynamic mode gets the limit back the controller found, unless
+            // it sets a fixed one of its own
+            if settings.is_none() && self.current_limit.is_some() && FIXED_LIMIT.lock().unwrap().is_none() {
+                if let Some(ref control) = self.control {
+                    let result = match self.original_limit {
+                        Some(watts) => control.set_power_limit(watts),
+                        None => control.reset_power_limit(),
+                    };
+                    match result {
+                        Ok(()) => log::info!("Dynamic dGPU power limit off, restored the previous limit"),
+                        Err(e) => log::warn!("Failed to restore the dGPU power limit: {}", e),
+                    }
+                }
+            }
+            if settings.is_none() {
+                self.original_limit = None;
+            }
+            // Start again from the maximum whenever the profile changes
+            self.current_limit = None;
+            self.last_settings = settings.clone();
+        }
+
+        let Some(settings) = settings else {
+            return;
+        };
+
+        // The target follows the profile, and a docked GPU may only show up later
+        let selected = TARGET.lock().unwrap().clone();
+        if self.control.as_ref().is_none_or(|c| selected.is_some() && c.pci_address() != selected.as_deref()) {
+            self.current_limit = None;
+            self.original_limit = None;
+            self.control = DgpuPowerControl::new().ok();
+            if let Some(ref c) = self.control {
+                log::info!("dGPU power limit controller on {}", c.pci_address().unwrap_or("the NVIDIA GPU"));
+            }
+        }
+        let Some(ref control) = self.control else {
+            return;
+        };
+
+        let temp = match control.get_temperature() {
+            Ok(t) => {
+                crate::sensor_health::report_success("dgpu");
+                t
+            }
+            Err(e) => {
+                crate::sensor_health::report_failure("dgpu", e);
+                return;
+            }
+        };
+
+        let target = settings.target_temp as f32;
+        let new_limit = match self.current_limit {
+            None => settings.max_watts,
+            Some(limit) if temp > target => limit.saturating_sub(settings.step_watts),
+            Some(limit) if temp < target - HEADROOM => limit + settings.step_watts,
+            Some(limit) => limit,
+        }
+        .clamp(settings.min_watts, settings.max_watts.max(settings.min_watts));
+
+        if Some(new_limit) != self.current_limit {
+            if self.original_limit.is_none() {
+                self.original_limit = control.get_power_limit().ok();
+            }
+            match control.set_power_limit(new_limit) {
+                Ok(()) => {
+                    log::debug!("dGPU: temp={:.1}°C, power limit {} W", temp, new_limit);
+                    self.current_limit = Some(new_limit);
+                }
+                Err(e) => log::warn!("Failed to set dGPU power limit: {}", e),
+            }
+        }
+    }
+}
@@ -1,9 +1,25 @@
 use anyhow::{anyhow, Result};
 use std::fs;
 use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tuxedo_common::types::*;
 use crate::tuxedo_io::TuxedoIo;
 
+// Vendor default TDP profiles, the EC can't report which one is active
+const DEFAULT_TDP_PROFILES: [&str; 2] = ["performance", "enthusiast"];
+const PLATFORM_PROFILE: &str = "/sys/firmware/acpi/platform_profile";
+const LEDS_PATH: &str = "/sys/class/leds";
+const SMT_CONTROL_PATH: &str = "/sys/devices/system/cpu/smt/control";
+// Taking CPUs offline under load can upset running workloads, so profile switches
+// (AC/battery, lid, app rules) may not flip SMT back and forth faster than this
+const SMT_COOLDOWN: Duration = Duration::from_secs(60);
+
+static LAST_SMT_CHANGE: once_cell::sync::Lazy<Mutex<Option<Instant>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+// Last state a profile asked for during the cooldown, written once it ends
+static PENDING_SMT: Mutex<Option<bool>> = Mutex::new(None);
+
 fn get_cpu_count() -> Result<u32> {
     let cpuinfo = fs::read_to_string("/proc/cpuinfo")?;
     let count = cpuinfo.lines()
@@ -17,7 +33,7 @@ pub fn set_cpu_governor(governor: &str) -> Result<()> {
     
     for i in 0..cpu_count {
         let path = format!("/sys/devices/system/cpu/cpu{}/cpufreq/scaling_governor", i);
-        fs::write(&path, governor)
+        crate::dry_run::write(&path, governor)
             .map_err(|e| anyhow!("Failed to set governor for CPU {}: {}", i, e))?;
     }
     
@@ -50,15 +66,15 @@ pub fn set_cpu_frequency_limits(min_freq: u64, max_freq: u64) -> Result<()> {
         // Determine order based on current vs new values
         if max_freq < current_max || min_freq > current_min {
             // Set max first
-            fs::write(&max_path, max_freq.to_string())
+            crate::dry_run::write(&max_path, max_freq.to_string())
                 .map_err(|e| anyhow!("Failed to set max frequency for CPU {}: {}", i, e))?;
-            fs::write(&min_path, min_freq.to_string())
+            crate::dry_run::write(&min_path, min_freq.to_string())
                 .map_err(|e| anyhow!("Failed to set min frequency for CPU {}: {}", i, e))?;
         } else {
             // Set min first
-            fs::write(&min_path, min_freq.to_string())
+            crate::dry_run::write(&min_path, min_freq.to_string())
                 .map_err(|e| anyhow!("Failed to set min frequency for CPU {}: {}", i, e))?;
-            fs::write(&max_path, max_freq.to_string())
+            crate::dry_run::write(&max_path, max_freq.to_string())
                 .map_err(|e| anyhow!("Failed to set max frequency for CPU {}: {}", i, e))?;
         }
     }
@@ -67,11 +83,27 @@ pub fn set_cpu_frequency_limits(min_freq: u64, max_freq: u64) -> Result<()> {
     Ok(())
 }
 
+/// A missing bound keeps its current value, moved only as far as the other bound requires
+pub fn set_cpu_frequency_bounds(min_freq: Option<u64>, max_freq: Option<u64>) -> Result<()> {
+    let current = |file: &str| fs::read_to_string(format!("/sys/devices/system/cpu/cpu0/cpufreq/{}", file))
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .ok_or_else(|| anyhow!("Failed to read {}", file));
+    
+    let (min, max) = match (min_freq, max_freq) {
+        (Some(min), Some(max)) => (min, max),
+        (Some(min), None) => (min, current("scaling_max_freq")?.max(min)),
+        (None, Some(max)) => (current("scaling_min_freq")?.min(max), max),
+        (None, None) => return Ok(()),
+    };
+    set_cpu_frequency_limits(min, max)
+}
+
 pub fn set_cpu_boost(enabled: bool) -> Result<()> {
     // AMD cpufreq boost
     let amd_path = "/sys/devices/system/cpu/cpufreq/boost";
     if Path::new(amd_path).exists() {
-        fs::write(amd_path, if enabled { "1" } else { "0" })?;
+        crate::dry_run::write(amd_path, if enabled { "1" } else { "0" })?;
         log::info!("Set AMD CPU boost to: {}", enabled);
         return Ok(());
     }
@@ -79,7 +111,7 @@ pub fn set_cpu_boost(enabled: bool) -> Result<()> {
     // Intel turbo
     let intel_path = "/sys/devices/system/cpu/intel_pstate/no_turbo";
     if Path::new(intel_path).exists() {
-        fs::write(intel_path, if enabled { "0" } else { "1" })?;
+        crate::dry_run::write(intel_path, if enabled { "0" } else { "1" })?;
         log::info!("Set Intel CPU turbo to: {}", enabled);
         return Ok(());
     }
@@ -87,25 +119,101 @@ pub fn set_cpu_boost(enabled: bool) -> Result<()> {
     // AMD P-State boost (if using amd-pstate driver)
     let amd_pstate_boost = "/sys/devices/system/cpu/amd_pstate/cpb_boost";
     if Path::new(amd_pstate_boost).exists() {
-        fs::write(amd_pstate_boost, if enabled { "1" } else { "0" })?;
+        crate::dry_run::write(amd_pstate_boost, if enabled { "1" } else { "0" })?;
         log::info!("Set AMD P-State boost to: {}", enabled);
         return Ok(());
     }
     
+    // Kernels that only expose cpufreq/policyN/boost
+    let policies = crate::hardware_detection::boost_policy_paths();
+    if !policies.is_empty() {
+        for (_, path) in &policies {
+            crate::dry_run::write(path.join("boost"), if enabled { "1" } else { "0" })?;
+        }
+        log::info!("Set CPU boost to {} on {} policies", enabled, policies.len());
+        return Ok(());
+    }
+    
     Err(anyhow!("Boost control not available"))
 }
 
+/// Per policy group boost on top of the global setting, groups missing from the map are left alone
+pub fn set_boost_groups(groups: &std::collections::BTreeMap<String, bool>) -> Result<()> {
+    let available = crate::hardware_detection::get_boost_policy_groups();
+    
+    for (id, enabled) in groups {
+        let group = match available.iter().find(|g| &g.id == id) {
+            Some(group) => group,
+            None => {
+                log::warn!("Boost policy group {} not present on this system, skipping", id);
+                continue;
+            }
+        };
+        
+        for policy in &group.policies {
+            let path = format!("/sys/devices/system/cpu/cpufreq/policy{}/boost", policy);
+            crate::dry_run::write(&path, if *enabled { "1" } else { "0" })
+                .map_err(|e| anyhow!("Failed to set boost for policy {}: {}", policy, e))?;
+        }
+        log::info!("Set boost of {} to: {}", group.label(), enabled);
+    }
+    
+    Ok(())
+}
+
 pub fn set_smt(enabled: bool) -> Result<()> {
-    let path = "/sys/devices/system/cpu/smt/control";
-    if !Path::new(path).exists() {
+    if !Path::new(SMT_CONTROL_PATH).exists() {
         return Err(anyhow!("SMT control not available"));
     }
     
-    fs::write(path, if enabled { "on" } else { "off" })?;
+    crate::dry_run::write(SMT_CONTROL_PATH, if enabled { "on" } else { "off" })?;
+    if !crate::dry_run::is_active() {
+        *LAST_SMT_CHANGE.lock().unwrap() = Some(Instant::now());
+        *PENDING_SMT.lock().unwrap() = None;
+    }
     log::info!("Set SMT to: {}", if enabled { "on" } else { "off" });
     Ok(())
 }
 
+// Profile path: skips the write if SMT is already in the requested state, and holds it back
+// until the cooldown is over if SMT was changed too recently
+fn apply_smt(enabled: bool) -> Result<()> {
+    let current = fs::read_to_string(SMT_CONTROL_PATH).unwrap_or_default();
+    if current.trim() == if enabled { "on" } else { "off" } {
+        // A switch back before the cooldown ended cancels the queued one
+        if !crate::dry_run::is_active() {
+            *PENDING_SMT.lock().unwrap() = None;
+        }
+        return Ok(());
+    }
+    
+    let remaining = LAST_SMT_CHANGE.lock().unwrap()
+        .and_then(|changed| SMT_COOLDOWN.checked_sub(changed.elapsed()))
+        .filter(|remaining| !remaining.is_zero());
+    if let Some(remaining) = remaining {
+        if crate::dry_run::is_active() {
+            return Ok(());
+        }
+        log::info!("Switching SMT {} in {} s, when the cooldown ends",
+            if enabled { "on" } else { "off" }, remaining.as_secs());
+        // Only the first queued change starts a timer, later ones replace its value
+        if PENDING_SMT.lock().unwrap().replace(enabled).is_none() {
+            std::thread::spawn(move || {
+                std::thread::sleep(remaining);
+                let pending = PENDING_SMT.lock().unwrap().take();
+                if let Some(enabled) = pending {
+                    if let Err(e) = apply_smt(enabled) {
+                        log::warn!("Failed to switch SMT after the cooldown: {}", e);
+                    }
+                }
+            });
+        }
+        return Ok(());
+    }
+    
+    set_smt(enabled)
+}
+
 pub fn set_amd_pstate_status(status: &str) -> Result<()> {
     let path = "/sys/devices/system/cpu/amd_pstate/status";
     if !Path::new(path).exists() {
@@ -116,57 +224,282 @@ pub fn set_amd_pstate_status(status: &str) -> Result<()> {
         return Err(anyhow!("Invalid AMD pstate status: {}", status));
     }
     
-    fs::write(path, status)?;
+    crate::dry_run::write(path, status)?;
     log::info!("Set AMD pstate status to: {}", status);
     Ok(())
 }
 
+pub fn set_igpu_max_frequency(max_mhz: u32) -> Result<()> {
+    let card_path = crate::hardware_detection::find_igpu_card()
+        .ok_or_else(|| anyhow!("Integrated GPU not found"))?;
+    let info = crate::hardware_detection::get_igpu_frequency_info()?;
+    let target = max_mhz.clamp(info.min_mhz, info.max_mhz);
+    
+    match info.driver.as_str() {
+        "i915" => {
+            // gt_max_freq_mhz may not go below the current minimum
+            let current_min: u32 = fs::read_to_string(card_path.join("gt_min_freq_mhz"))?
+                .trim().parse()?;
+            if target < current_min {
+                crate::dry_run::write(card_path.join("gt_min_freq_mhz"), info.min_mhz.to_string())?;
+            }
+            crate::dry_run::write(card_path.join("gt_max_freq_mhz"), target.to_string())?;
+        }
+        "amdgpu" => {
+            let device_path = card_path.join("device");
+            // Enable every DPM level at or below the cap, always keeping the lowest one
+            let levels = crate::hardware_detection::read_amdgpu_sclk_levels(&device_path);
+            let allowed: Vec<String> = levels.iter().enumerate()
+                .filter(|(i, freq)| *i == 0 || **freq <= target)
+                .map(|(i, _)| i.to_string())
+                .collect();
+            
+            crate::dry_run::write(device_path.join("power_dpm_force_performance_level"), "manual")?;
+            crate::dry_run::write(device_path.join("pp_dpm_sclk"), allowed.join(" "))?;
+        }
+        driver => return Err(anyhow!("Unsupported iGPU driver: {}", driver)),
+    }
+    
+    log::info!("Set iGPU max frequency to {} MHz", target);
+    Ok(())
+}
+
+/// Dry run of apply_profile, listing the writes it would perform
+pub fn preview_profile(profile: &Profile) -> ApplyPreview {
+    log::info!("Previewing profile: {}", profile.name);
+    crate::dry_run::preview(|| apply_profile(profile))
+}
+
 pub fn apply_profile(profile: &Profile) -> Result<()> {
     log::info!("Applying profile: {}", profile.name);
+    // Recorded before the first write, a crash halfway through counts for the crash guard
+    let preview = crate::dry_run::is_active();
+    if !preview {
+        crate::safe_mode::applying(profile);
+    }
+    if let Err(e) = crate::apply_timing::record(&profile.name, || apply_profile_steps(profile)) {
+        if !preview {
+            crate::safe_mode::apply_failed();
+        }
+        return Err(e);
+    }
     
-    // Apply CPU settings
-    if let Some(ref governor) = profile.cpu_settings.governor {
-        set_cpu_governor(governor)?;
+    // Nothing below touches hardware, a preview ends here
+    if preview {
+        return Ok(());
     }
     
-    if let Some(ref tdp_profile) = profile.cpu_settings.tdp_profile {
-        set_tdp_profile(tdp_profile)?;
+    // Remember clamshell overrides for the lid monitor
+    crate::lid_monitor::set_profile_override(profile);
+    
+    crate::safe_mode::profile_applied(profile);
+    crate::profile_usage::profile_applied(&profile.name);
+    
+    log::info!("Profile '{}' applied successfully", profile.name);
+    crate::event_log::record(EventKind::ProfileSwitch, format!("Applied profile '{}'", profile.name));
+    Ok(())
+}
+
+// Each step is timed for the apply report
+fn apply_profile_steps(profile: &Profile) -> Result<()> {
+    use crate::apply_timing::step;
+    
+    // Apply CPU settings, picking the AC or battery values
+    let cpu_settings = crate::cpu_power_source::register(&profile.cpu_settings);
+    
+    if let Some(ref governor) = cpu_settings.governor {
+        step("CPU governor", || set_cpu_governor(governor))?;
+    }
+    
+    if let Some(ref tdp_profile) = cpu_settings.tdp_profile {
+        step("TDP profile", || set_tdp_profile(tdp_profile))?;
+    }
+    
+    // After the TDP profile, which resets the limits on some boards
+    if let Some(watts) = cpu_settings.tdp {
+        step("CPU TDP", || set_cpu_tdp(watts))?;
+    }
+    
+    if let Some(ref amd_status) = cpu_settings.amd_pstate_status {
+        step("amd-pstate mode", || set_amd_pstate_status(amd_status))?;
     }
     
-    if let Some(ref amd_status) = profile.cpu_settings.amd_pstate_status {
-        set_amd_pstate_status(amd_status)?;
+    if let Some(ref epp) = cpu_settings.energy_performance_preference {
+        step("Energy performance preference", || set_energy_performance_preference(epp))?;
     }
     
-    if let Some(ref epp) = profile.cpu_settings.energy_performance_preference {
-        set_energy_performance_preference(epp)?;
+    if cpu_settings.min_frequency.is_some() || cpu_settings.max_frequency.is_some() {
+        step("CPU frequency limits", || set_cpu_frequency_bounds(cpu_settings.min_frequency, cpu_settings.max_frequency))?;
     }
     
-    if let (Some(min), Some(max)) = (profile.cpu_settings.min_frequency, profile.cpu_settings.max_frequency) {
-        set_cpu_frequency_limits(min, max)?;
+    if let Some(boost) = cpu_settings.boost {
+        step("CPU boost", || set_cpu_boost(boost))?;
     }
     
-    if let Some(boost) = profile.cpu_settings.boost {
-        set_cpu_boost(boost)?;
+    // After the global switch, which resets every policy
+    if !cpu_settings.boost_groups.is_empty() {
+        step("Boost groups", || set_boost_groups(&cpu_settings.boost_groups))?;
     }
     
-    if let Some(smt) = profile.cpu_settings.smt {
-        set_smt(smt)?;
+    if let Some(smt) = cpu_settings.smt {
+        step("SMT", || apply_smt(smt))?;
+    }
+    
+    // Apply GPU settings
+    step("GPU", || crate::gpu_power::apply_gpu_settings(&profile.gpu_settings))?;
+    
+    // Unset leaves the firmware's or the user's own limit
+    if let Some(max_mhz) = profile.gpu_settings.igpu_max_freq {
+        if crate::hardware_detection::get_igpu_frequency_info().is_ok() {
+            step("iGPU frequency", || set_igpu_max_frequency(max_mhz))?;
+        }
     }
     
     // Apply keyboard settings
-    apply_keyboard_settings(&profile.keyboard_settings)?;
+    step("Keyboard backlight", || apply_keyboard_settings(&profile.keyboard_settings))?;
+    
+    // Apply lightbar settings
+    step("Lightbar", || crate::lightbar::apply_lightbar_settings(&profile.lightbar_settings))?;
+    
+    // Apply indicator LED settings
+    step("Indicator LEDs", || crate::indicators::apply_indicator_settings(&profile.indicator_settings))?;
+    
+    // Apply input device settings
+    step("Input devices", || crate::input_power::apply_input_settings(&profile.input_settings, &profile.keyboard_settings))?;
     
     // Apply screen settings
-    apply_screen_settings(&profile.screen_settings)?;
+    step("Screen", || apply_screen_settings(&profile.screen_settings))?;
+    if !crate::dry_run::is_active() {
+        crate::ambient_screen::set_policy(&profile.screen_settings.ambient, profile.screen_settings.system_control);
+    }
+    
+    // Apply network settings
+    step("Network", || crate::network_control::apply_network_settings(&profile.network_settings))?;
+    
+    // Apply kernel VM settings
+    step("Kernel VM", || crate::vm_tuning::apply_vm_settings(&profile.vm_settings))?;
     
     // Apply fan settings - update daemon state
-    apply_fan_settings(&profile.fan_settings)?;
+    step("Fans", || apply_fan_settings(&profile.fan_settings))?;
     
-    log::info!("Profile '{}' applied successfully", profile.name);
+    // External water cooler, skipped when it isn't connected
+    step("Aquaris", || crate::aquaris::apply(profile.aquaris_settings.as_ref()))?;
     Ok(())
 }
 
+/// A reloaded tuxedo_io or tuxedo_keyboard starts from its defaults, put back the parts of
+/// the profile these drivers hold. Every step is attempted even if an earlier one fails.
+pub fn reapply_driver_settings(profile: &Profile) -> Result<()> {
+    let mut errors = Vec::new();
+    let on_ac = crate::hardware_detection::is_on_ac_power();
+    let cpu_settings = crate::cpu_power_source::effective_settings(&profile.cpu_settings, on_ac);
+
+    if let Some(ref tdp_profile) = cpu_settings.tdp_profile {
+        if let Err(e) = set_tdp_profile(tdp_profile) {
+            errors.push(format!("TDP profile: {}", e));
+        }
+    }
+    if let Some(watts) = cpu_settings.tdp {
+        if let Err(e) = set_cpu_tdp(watts) {
+            errors.push(format!("CPU TDP: {}", e));
+        }
+    }
+    if let Err(e) = apply_keyboard_settings(&profile.keyboard_settings) {
+        errors.push(format!("keyboard: {}", e));
+    }
+    if let Err(e) = apply_fan_settings(&profile.fan_settings) {
+        errors.push(format!("fans: {}", e));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(errors.join("; ")))
+    }
+}
+
+/// Escape hatch: fans back to auto, TDP and power limit overrides removed,
+/// Standard profile applied. Every step is attempted even if an earlier one fails.
+pub fn panic_revert() -> Result<()> {
+    log::warn!("Panic revert requested");
+    crate::apply_pipeline::cancel();
+    crate::event_log::record(EventKind::FanFailsafe, "Panic revert: fans on automatic, Standard profile");
+    let mut errors = Vec::new();
+
+    if crate::dust_cleaning::is_running() {
+        let _ = crate::dust_cleaning::abort();
+    }
+    if crate::auto_tuner::is_running() {
+        let _ = crate::auto_tuner::abort();
+    }
+    if crate::ab_test::is_running() {
+        let _ = crate::ab_test::abort();
+    }
+
+    if let Err(e) = apply_profile(&Profile::default()) {
+        errors.push(format!("Standard profile: {}", e));
+    }
+
+    *crate::FAN_DAEMON_STATE.lock().unwrap() = None;
+    if crate::fan_backend::is_available() {
+        if let Err(e) = set_fan_auto(0) {
+            errors.push(format!("fans: {}", e));
+        }
+    }
+
+    if let Err(e) = reset_tdp_profile() {
+        errors.push(format!("TDP profile: {}", e));
+    }
+
+    if crate::gpu_power::DgpuPowerControl::is_available() {
+        if let Err(e) = crate::gpu_power::DgpuPowerControl::new().and_then(|c| c.reset_power_limit()) {
+            errors.push(format!("dGPU power limit: {}", e));
+        }
+    }
+
+    if errors.is_empty() {
+        log::info!("Panic revert completed");
+        Ok(())
+    } else {
+        Err(anyhow!("Panic revert incomplete: {}", errors.join("; ")))
+    }
+}
+
+// The firmware's balanced profile where the device lists its profiles through ACPI, else the
+// vendor default among the profiles tuxedo_io reports
+fn reset_tdp_profile() -> Result<()> {
+    let choices = fs::read_to_string(format!("{}_choices", PLATFORM_PROFILE)).unwrap_or_default();
+    if choices.split_whitespace().any(|choice| choice == "balanced") {
+        crate::dry_run::write(PLATFORM_PROFILE, "balanced")?;
+        if !crate::dry_run::is_active() {
+            crate::hotkey_monitor::note_profile_write();
+        }
+        log::info!("Set platform profile to balanced");
+        return Ok(());
+    }
+    
+    let profiles = crate::hardware_detection::get_tdp_profiles()?;
+    match DEFAULT_TDP_PROFILES.iter().find(|p| profiles.iter().any(|a| a == *p)) {
+        Some(tdp) => set_tdp_profile(tdp),
+        None if profiles.is_empty() => Ok(()),
+        None => Err(anyhow!("No default among the TDP profiles {:?}", profiles)),
+    }
+}
+
 pub fn apply_battery_settings(settings: &BatterySettings) -> Result<()> {
+    crate::ac_adapter::set_auto_cap(settings.cap_tdp_to_adapter);
+
+    // Independent of the thresholds, these live in tuxedo-drivers rather than power_supply
+    if let Some(priority) = &settings.charging_priority {
+        crate::charging::set_priority(priority)?;
+    }
+    if let Some(profile) = &settings.charging_profile {
+        crate::charging::set_profile(profile)?;
+    }
+
+    crate::charge_watchdog::set_expected(settings.control_enabled
+        .then_some((settings.charge_start_threshold, settings.charge_end_threshold)));
+
     if !crate::battery_control::BatteryControl::is_available() {
         log::info!("Battery control not available, skipping");
         return Ok(());
@@ -174,116 +507,100 @@ pub fn apply_battery_settings(settings: &BatterySettings) -> Result<()> {
 
     let battery = crate::battery_control::BatteryControl::new()?;
 
-    if settings.control_enabled {
+    if settings.control_enabled && crate::full_charge::is_active() {
+        log::info!("Charging to 100% once, new thresholds take effect when it ends");
+    } else if settings.control_enabled && !battery.has_thresholds() {
+        log::info!("{} has no charge thresholds, skipping them", battery.backend().label());
+    } else if settings.control_enabled {
         battery.set_charge_type("Custom")?;
-        battery.set_charge_control_start_threshold(settings.charge_start_threshold)?;
-        battery.set_charge_control_end_threshold(settings.charge_end_threshold)?;
+        battery.set_thresholds(settings.charge_start_threshold, settings.charge_end_threshold)?;
+        crate::charge_watchdog::mark_thresholds_set(true);
         log::info!(
-            "Set battery thresholds: start={}, end={}",
+            "Set battery thresholds via {}: start={}, end={}",
+            battery.backend().label(),
             settings.charge_start_threshold,
             settings.charge_end_threshold
         );
-    } else {
+    } else if crate::charge_watchdog::mark_thresholds_set(false) {
+        // Only undoes our own thresholds, ones set in the BIOS or by another tool stay
         battery.set_charge_type("Standard")?;
         log::info!("Set battery charge type to Standard");
     }
 
+    if let Some(behaviour) = &settings.charge_behaviour {
+        battery.set_charge_behaviour(behaviour)?;
+        log::info!("Set battery charge behaviour to {}", behaviour);
+    }
+
     Ok(())
 }
 
-fn apply_keyboard_settings(settings: &KeyboardSettings) -> Result<()> {
+pub fn apply_keyboard_settings(settings: &KeyboardSettings) -> Result<()> {
     if !settings.control_enabled {
         log::info!("Keyboard control disabled, skipping");
         return Ok(());
     }
     
-    let base_path = find_keyboard_backlight_path()
-        .ok_or_else(|| anyhow!("Keyboard backlight not found"))?;
-    
-    use tuxedo_common::types::KeyboardMode;
-    match &settings.mode {
-        KeyboardMode::SingleColor { r, g, b, brightness } => {
-            log::info!("Applying keyboard: RGB({}, {}, {}) brightness {}%", r, g, b, brightness);
-            
-            let color_path = format!("{}/multi_intensity", base_path);
-            if Path::new(&color_path).exists() {
-                let color_str = format!("{} {} {}", r, g, b);
-                log::info!("Writing to {}: {}", color_path, color_str);
-                fs::write(&color_path, color_str)?;
-            } else {
-                log::warn!("multi_intensity not found at {}", color_path);
-            }
-            
-            let brightness_path = format!("{}/brightness", base_path);
-            if Path::new(&brightness_path).exists() {
-                let max_brightness_path = format!("{}/max_brightness", base_path);
-                let max_brightness: u32 = if let Ok(max_str) = fs::read_to_string(&max_brightness_path) {
-                    max_str.trim().parse().unwrap_or(255)
-                } else {
-                    255
-                };
-                
-                let actual_brightness = ((*brightness as u32) * max_brightness) / 100;
-                
-                log::info!("Writing to {}: {} ({}% of {} max)", 
-                    brightness_path, actual_brightness, brightness, max_brightness);
-                
-                fs::write(&brightness_path, actual_brightness.to_string())?;
-            } else {
-                log::warn!("brightness not found at {}", brightness_path);
-            }
-            
-            log::info!("✅ Keyboard backlight applied successfully");
-        }
-        _ => {
-            if let Ok(kbd) = RgbKeyboardControl::new() {
-                kbd.set_mode(&settings.mode)?;
-                log::info!("✅ Keyboard effect mode applied successfully");
-            } else {
-                log::warn!("RGB keyboard control not available for effect modes");
-            }
-        }
-    }
-    
+    write_keyboard_settings(settings)?;
+    log::info!("✅ Keyboard backlight applied successfully");
     Ok(())
 }
 
 pub fn preview_keyboard_settings(settings: &KeyboardSettings) -> Result<()> {
-    let base_path = find_keyboard_backlight_path()
-        .ok_or_else(|| anyhow!("Keyboard backlight not found"))?;
-    
-    use tuxedo_common::types::KeyboardMode;
-    match &settings.mode {
-        KeyboardMode::SingleColor { r, g, b, brightness } => {
-            let color_path = format!("{}/multi_intensity", base_path);
-            if Path::new(&color_path).exists() {
-                let color_str = format!("{} {} {}", r, g, b);
-                fs::write(&color_path, color_str)?;
-            }
-            
-            let brightness_path = format!("{}/brightness", base_path);
-            if Path::new(&brightness_path).exists() {
-                let max_brightness_path = format!("{}/max_brightness", base_path);
-                let max_brightness: u32 = if let Ok(max_str) = fs::read_to_string(&max_brightness_path) {
-                    max_str.trim().parse().unwrap_or(255)
-                } else {
-                    255
-                };
-                
-                let actual_brightness = ((*brightness as u32) * max_brightness) / 100;
-                fs::write(&brightness_path, actual_brightness.to_string())?;
-            }
+    write_keyboard_settings(settings)
+}
+
+fn write_keyboard_settings(settings: &KeyboardSettings) -> Result<()> {
+    let devices = find_keyboard_backlights();
+    if devices.is_empty() {
+        return Err(anyhow!("Keyboard backlight not found"));
+    }
+    
+    for device in &devices {
+        // Devices without their own entry follow the main settings
+        let mode = settings.devices.iter()
+            .find(|d| d.device == device.name)
+            .map(|d| &d.mode)
+            .unwrap_or(&settings.mode);
+        if device.name == crate::uniwill_keyboard::DEVICE_NAME {
+            crate::uniwill_keyboard::apply_mode(mode)?;
+            continue;
+        }
+        
+        let base_path = format!("{}/{}", LEDS_PATH, device.name);
+        
+        if !device.rgb {
+            // White-only backlight, colors and effects don't apply
+            log::info!("Applying {}: brightness {}%", device.name, mode.brightness());
+            write_led_brightness(&base_path, mode.brightness())?;
+            continue;
         }
-        _ => {
-            if let Ok(kbd) = RgbKeyboardControl::new() {
-                kbd.set_mode(&settings.mode)?;
+        
+        use tuxedo_common::types::KeyboardMode;
+        match mode {
+            KeyboardMode::SingleColor { r, g, b, brightness } => {
+                log::info!("Applying {}: RGB({}, {}, {}) brightness {}%", device.name, r, g, b, brightness);
+                
+                crate::dry_run::write(format!("{}/multi_intensity", base_path), format!("{} {} {}", r, g, b))?;
+                write_led_brightness(&base_path, *brightness)?;
             }
+            _ => RgbKeyboardControl::with_path(base_path).set_mode(mode)?,
         }
     }
     
     Ok(())
 }
 
+fn write_led_brightness(base_path: &str, percent: u8) -> Result<()> {
+    let max_brightness: u32 = fs::read_to_string(format!("{}/max_brightness", base_path))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(255);
+    let actual_brightness = ((percent.min(100) as u32) * max_brightness) / 100;
+    crate::dry_run::write(format!("{}/brightness", base_path), actual_brightness.to_string())?;
+    Ok(())
+}
+
 fn apply_screen_settings(settings: &ScreenSettings) -> Result<()> {
     if settings.system_control {
         log::info!("Using system screen brightness control");
@@ -312,13 +629,13 @@ fn apply_screen_settings(settings: &ScreenSettings) -> Result<()> {
             // Write to actual_brightness first (this is writable)
             let actual_path = format!("{}/actual_brightness", base_path);
             if Path::new(&actual_path).exists() {
-                if let Err(e) = fs::write(&actual_path, actual_brightness.to_string()) {
+                if let Err(e) = crate::dry_run::write(&actual_path, actual_brightness.to_string()) {
                     log::warn!("Could not write to actual_brightness: {}", e);
                 }
             }
             
             // Then write to brightness
-            match fs::write(&brightness_path, actual_brightness.to_string()) {
+            match crate::dry_run::write(&brightness_path, actual_brightness.to_string()) {
                 Ok(_) => {
                     log::info!("Set screen brightness to {}% at {}", settings.brightness, base_path);
                     return Ok(());
@@ -344,6 +661,9 @@ pub fn set_tdp_profile(profile_name: &str) -> Result<()> {
     
     if let Some(profile_id) = profiles.iter().position(|p| p == profile_name) {
         io.set_performance_profile(profile_id as u32)?;
+        if !crate::dry_run::is_active() {
+            crate::hotkey_monitor::note_profile_write();
+        }
         log::info!("Set TDP profile to: {} (id: {})", profile_name, profile_id);
         Ok(())
     } else {
@@ -351,42 +671,66 @@ pub fn set_tdp_profile(profile_name: &str) -> Result<()> {
     }
 }
 
+/// Sustained CPU power limit, clamped to the range the EC accepts
+pub fn set_cpu_tdp(watts: u32) -> Result<()> {
+    let limits = crate::hardware_detection::get_tdp_limits()?;
+    let clamped = watts.clamp(limits.min_watts, limits.max_watts);
+    if clamped != watts {
+        log::warn!("TDP {} W outside {}-{} W, using {} W", watts, limits.min_watts, limits.max_watts, clamped);
+    }
+    
+    let io = TuxedoIo::new()?;
+    io.set_tdp(0, clamped as i32)?;
+    log::info!("Set CPU TDP to {} W", clamped);
+    Ok(())
+}
+
 pub fn set_fan_speed(fan_id: u32, speed_percent: u32) -> Result<()> {
     if !TuxedoIo::is_available() {
         return Err(anyhow!("Fan control not available"));
     }
     
-    let speed = speed_percent.min(100);
+    let speed = crate::fan_min_duty::clamp(fan_id, speed_percent.min(100) as u8) as u32;
     log::info!("DBus request: set fan {} to {}%", fan_id, speed);
     let io = TuxedoIo::new()?;
     io.set_fan_speed(fan_id, speed)?;
+    crate::fan_mode::record_manual(FanModeSource::Daemon, fan_id, speed);
     
     log::info!("Set fan {} to {}%", fan_id, speed);
     Ok(())
 }
 
 pub fn set_fan_auto(fan_id: u32) -> Result<()> {
-    if !TuxedoIo::is_available() {
-        return Err(anyhow!("Fan control not available"));
+    let backend = crate::fan_backend::detect()
+        .ok_or_else(|| anyhow!("Fan control not available"))?;
+    backend.set_auto()?;
+    if !crate::dry_run::is_active() {
+        crate::fan_mode::record_auto(FanModeSource::Daemon);
     }
     
-    let io = TuxedoIo::new()?;
-    io.set_fan_auto()?;
-    
     log::info!("Set all fans to auto mode");
     Ok(())
 }
 
-fn apply_fan_settings(settings: &FanSettings) -> Result<()> {
-    if !TuxedoIo::is_available() {
-        log::info!("Fan control not available (/dev/tuxedo_io not present)");
+pub fn apply_fan_settings(settings: &FanSettings) -> Result<()> {
+    if !crate::fan_backend::is_available() {
+        log::info!("Fan control not available (no tuxedo_io or hwmon pwm)");
         return Ok(());
     }
     
+    // fancurves.toml overrides the profile's fans on headless installs
+    let settings = &crate::fan_curve_file::settings_for(settings);
+    
     log::info!("Applying fan settings: enabled={}", settings.control_enabled);
     
+    if settings.control_enabled {
+        for curve in &settings.curves {
+            crate::fan_daemon::validate_curve(curve)?;
+        }
+    }
+    
     // Update the global fan daemon state
-    {
+    if !crate::dry_run::is_active() {
         let mut state = crate::FAN_DAEMON_STATE.lock().unwrap();
         if settings.control_enabled {
             *state = Some(settings.clone());
@@ -426,24 +770,67 @@ pub fn get_webcam_state() -> Result<bool> {
     io.get_webcam_state()
 }
 
-fn find_keyboard_backlight_path() -> Option<String> {
-    let possible_paths = vec![
-        "/sys/class/leds/rgb:kbd_backlight",
-        "/sys/class/leds/tuxedo::kbd_backlight",
-        "/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight",
-        "/sys/class/leds/asus::kbd_backlight",
-    ];
-    
-    for path in possible_paths {
-        let brightness_path = format!("{}/brightness", path);
-        if Path::new(&brightness_path).exists() {
-            log::info!("Found keyboard backlight at: {}", path);
-            return Some(path.to_string());
+pub fn turn_off_keyboard_backlight() -> Result<()> {
+    let devices = find_keyboard_backlights();
+    if devices.is_empty() {
+        return Err(anyhow!("Keyboard backlight not found"));
+    }
+    for device in devices {
+        if device.name == crate::uniwill_keyboard::DEVICE_NAME {
+            crate::uniwill_keyboard::turn_off()?;
+            continue;
+        }
+        crate::dry_run::write(format!("{}/{}/brightness", LEDS_PATH, device.name), "0")?;
+    }
+    Ok(())
+}
+
+/// All keyboard backlight LEDs, built-in keyboards first
+pub fn find_keyboard_backlights() -> Vec<KeyboardDevice> {
+    let mut devices: Vec<KeyboardDevice> = fs::read_dir(LEDS_PATH)
+        .map(|rd| rd.flatten().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.contains("kbd_backlight") || !entry.path().join("brightness").exists() {
+                return None;
+            }
+            // The class entry links to the real device, USB keyboards sit below a usb bus
+            let external = fs::canonicalize(entry.path())
+                .map(|p| p.to_string_lossy().contains("/usb"))
+                .unwrap_or(false);
+            let rgb = entry.path().join("multi_intensity").exists();
+            Some(KeyboardDevice { name, external, rgb })
+        })
+        .collect();
+    
+    devices.sort_by(|a, b| a.external.cmp(&b.external).then_with(|| a.name.cmp(&b.name)));
+    
+    // Older drivers on Uniwill boards don't register an LED class device
+    if !devices.iter().any(|d| !d.external) && crate::uniwill_keyboard::legacy_available() {
+        devices.insert(0, KeyboardDevice {
+            name: crate::uniwill_keyboard::DEVICE_NAME.to_string(),
+            external: false,
+            rgb: true,
+        });
+    }
+    
+    devices
+}
+
+pub fn find_keyboard_backlight_path() -> Option<String> {
+    // LED class devices only, the legacy Uniwill interface has no brightness file
+    match find_keyboard_backlights().iter().find(|d| d.name != crate::uniwill_keyboard::DEVICE_NAME) {
+        Some(device) => {
+            log::info!("Found keyboard backlight at: {}/{}", LEDS_PATH, device.name);
+            Some(format!("{}/{}", LEDS_PATH, device.name))
+        }
+        None => {
+            log::warn!("No keyboard backlight found");
+            None
         }
     }
-    
-    log::warn!("No keyboard backlight found");
-    None
 }
 
 pub fn set_energy_performance_preference(epp: &str) -> Result<()> {
@@ -458,7 +845,7 @@ pub fn set_energy_performance_preference(epp: &str) -> Result<()> {
     for i in 0..cpu_count {
         let path = format!("/sys/devices/system/cpu/cpu{}/cpufreq/energy_performance_preference", i);
         if Path::new(&path).exists() {
-            fs::write(&path, epp)
+            crate::dry_run::write(&path, epp)
                 .map_err(|e| anyhow!("Failed to set EPP for CPU {}: {}", i, e))?;
         }
     }
@@ -473,32 +860,8 @@ pub struct RgbKeyboardControl {
 }
 
 impl RgbKeyboardControl {
-    pub fn new() -> Result<Self> {
-        let base_path = Self::find_keyboard_backlight_path()?;
-        Ok(Self { base_path })
-    }
-    
-    pub fn is_available() -> bool {
-        Self::find_keyboard_backlight_path().is_ok()
-    }
-    
-    fn find_keyboard_backlight_path() -> Result<String> {
-        let possible_paths = vec![
-            "/sys/class/leds/rgb:kbd_backlight",
-            "/sys/class/leds/tuxedo::kbd_backlight",
-            "/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight",
-            "/sys/class/leds/asus::kbd_backlight",
-        ];
-        
-        for path in possible_paths {
-            let brightness_path = format!("{}/brightness", path);
-            if Path::new(&brightness_path).exists() {
-                log::info!("Found keyboard backlight at: {}", path);
-                return Ok(path.to_string());
-            }
-        }
-        
-        Err(anyhow!("No RGB keyboard backlight found"))
+    pub fn with_path(base_path: String) -> Self {
+        Self { base_path }
     }
     
     pub fn set_color(&self, red: u8, green: u8, blue: u8) -> Result<()> {
@@ -508,7 +871,7 @@ impl RgbKeyboardControl {
         }
         
         let color_str = format!("{} {} {}", red, green, blue);
-        fs::write(&color_path, color_str)?;
+        crate::dry_run::write(&color_path, color_str)?;
         
         log::info!("Set keyboard RGB color: ({}, {}, {})", red, green, blue);
         Ok(())
@@ -525,7 +888,7 @@ impl RgbKeyboardControl {
         };
         
         let actual_brightness = ((brightness as u32) * max_brightness) / 100;
-        fs::write(&brightness_path, actual_brightness.to_string())?;
+        crate::dry_run::write(&brightness_path, actual_brightness.to_string())?;
         
         log::info!("Set keyboard brightness to {}%", brightness);
         Ok(())
@@ -558,7 +921,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Breathe { r, g, b, brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "breathing")?;
+                    crate::dry_run::write(&mode_path, "breathing")?;
                 }
                 self.set_color(*r, *g, *b)?;
                 self.set_brightness(*brightness)?;
@@ -567,7 +930,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Wave { brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "wave")?;
+                    crate::dry_run::write(&mode_path, "wave")?;
                     self.set_brightness(*brightness)?;
                     log::info!("Set wave mode with speed {}", speed);
                 } else {
@@ -577,7 +940,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Cycle { brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "cycle")?;
+                    crate::dry_run::write(&mode_path, "cycle")?;
                     self.set_brightness(*brightness)?;
                     log::info!("Set cycle mode with speed {}", speed);
                 } else {
@@ -587,7 +950,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Dance { brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "dance")?;
+                    crate::dry_run::write(&mode_path, "dance")?;
                     self.set_brightness(*brightness)?;
                     log::info!("Set dance mode with speed {}", speed);
                 } else {
@@ -597,7 +960,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Flash { r, g, b, brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "flash")?;
+                    crate::dry_run::write(&mode_path, "flash")?;
                 }
                 self.set_color(*r, *g, *b)?;
                 self.set_brightness(*brightness)?;
@@ -606,7 +969,7 @@ impl RgbKeyboardControl {
             KeyboardMode::RandomColor { brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "random")?;
+                    crate::dry_run::write(&mode_path, "random")?;
                     self.set_brightness(*brightness)?;
                     log::info!("Set random color mode with speed {}", speed);
                 } else {
@@ -616,7 +979,7 @@ impl RgbKeyboardControl {
             KeyboardMode::Tempo { brightness, speed } => {
                 let mode_path = format!("{}/mode", self.base_path);
                 if Path::new(&mode_path).exists() {
-                    fs::write(&mode_path, "tempo")?;
+                    crate::dry_run::write(&mode_path, "tempo")?;
                     self.set_brightness(*brightness)?;
                     log::info!("Set tempo mode with speed {}", speed);
                 } else {
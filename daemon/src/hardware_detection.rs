@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::sync::Mutex;
 use crate::tuxedo_io::TuxedoIo;
@@ -10,6 +10,15 @@ use tuxedo_common::types::*;
 
 // Thread-safe storage for previous CPU stats
 static PREVIOUS_CPU_STATS: Mutex<Option<HashMap<u32, CpuStats>>> = Mutex::new(None);
+// Sectors read/written per block device at the previous storage poll
+static PREVIOUS_IO_STATS: Mutex<Option<HashMap<String, IoStats>>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+struct IoStats {
+    sampled_at: std::time::Instant,
+    sectors_read: u64,
+    sectors_written: u64,
+}
 
 #[derive(Debug, Clone)]
 struct CpuStats {
@@ -198,7 +207,7 @@ fn get_core_temp(cpu: u32) -> Result<f32> {
     Err(anyhow!("Core temperature not found"))
 }
 
-fn get_package_temp() -> Result<f32> {
+pub fn get_package_temp() -> Result<f32> {
     for entry in fs::read_dir("/sys/class/hwmon")? {
         let entry = entry?;
         let name_path = entry.path().join("name");
@@ -396,7 +405,8 @@ fn detect_cpu_capabilities() -> CpuCapabilities {
     
     CpuCapabilities {
         has_boost: Path::new("/sys/devices/system/cpu/cpufreq/boost").exists() ||
-                   Path::new("/sys/devices/system/cpu/intel_pstate/no_turbo").exists(),
+                   Path::new("/sys/devices/system/cpu/intel_pstate/no_turbo").exists() ||
+                   !boost_policy_paths().is_empty(),
         
         has_cpuinfo_max_freq: Path::new(&format!("{}/cpuinfo_max_freq", base_path)).exists(),
         
@@ -420,9 +430,121 @@ fn detect_cpu_capabilities() -> CpuCapabilities {
             Path::new(&format!("{}/scaling_available_governors", base_path)).exists(),
         
         has_amd_pstate: Path::new("/sys/devices/system/cpu/amd_pstate/status").exists(),
+        
+        has_per_policy_boost: !boost_policy_paths().is_empty(),
     }
 }
 
+/// cpufreq policies that have their own boost file, by policy number
+pub fn boost_policy_paths() -> Vec<(u32, PathBuf)> {
+    let mut policies: Vec<(u32, PathBuf)> = sorted_dir_entries("/sys/devices/system/cpu/cpufreq")
+        .into_iter()
+        .filter(|path| path.join("boost").exists())
+        .filter_map(|path| {
+            let number = path.file_name()?.to_str()?.strip_prefix("policy")?.parse().ok()?;
+            Some((number, path))
+        })
+        .collect();
+    policies.sort_by_key(|(number, _)| *number);
+    policies
+}
+
+/// cpufreq stats/time_in_state of all policies, only drivers with a frequency table keep them
+fn read_time_in_state() -> Vec<FrequencyResidency> {
+    let mut totals: std::collections::BTreeMap<u64, u64> = std::collections::BTreeMap::new();
+    
+    for policy in sorted_dir_entries("/sys/devices/system/cpu/cpufreq") {
+        let content = match fs::read_to_string(policy.join("stats/time_in_state")) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        
+        // "<frequency in kHz> <time in 10 ms units>" per line
+        for line in content.lines() {
+            let mut fields = line.split_whitespace().map(|f| f.parse::<u64>());
+            if let (Some(Ok(freq)), Some(Ok(time))) = (fields.next(), fields.next()) {
+                *totals.entry(freq).or_default() += time * 10;
+            }
+        }
+    }
+    
+    totals.into_iter()
+        .map(|(freq_khz, time_ms)| FrequencyResidency { freq_khz, time_ms })
+        .collect()
+}
+
+/// Per-policy boost state, grouped by core type or cluster
+pub fn get_boost_policy_groups() -> Vec<BoostPolicyGroup> {
+    let mut groups: Vec<BoostPolicyGroup> = Vec::new();
+    
+    for (policy, path) in boost_policy_paths() {
+        let enabled = match fs::read_to_string(path.join("boost")) {
+            Ok(boost) => boost.trim() == "1",
+            Err(_) => continue,
+        };
+        // acpi-cpufreq lowers cpuinfo_max_freq while boost is off, amd_pstate_max_freq stays put
+        let max_freq = ["amd_pstate_max_freq", "cpuinfo_max_freq"].iter()
+            .find_map(|file| fs::read_to_string(path.join(file)).ok()?.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        let id = policy_group_id(&path);
+        
+        match groups.iter_mut().find(|g| g.id == id) {
+            Some(group) => {
+                group.policies.push(policy);
+                group.max_freq = group.max_freq.max(max_freq);
+                if group.enabled != Some(enabled) {
+                    group.enabled = None;
+                }
+            }
+            None => groups.push(BoostPolicyGroup {
+                id,
+                max_freq,
+                policies: vec![policy],
+                enabled: Some(enabled),
+            }),
+        }
+    }
+    
+    groups.sort_by_key(|group| std::cmp::Reverse(group.max_freq));
+    groups
+}
+
+// The key a profile stores, it must survive a different policy numbering and preferred-core
+// frequency ranking: the hybrid core type ("cpu_core", "cpu_atom") the perf PMUs list the CPU
+// under, else its cluster if that's more than one core (big.LITTLE), else one group for all
+fn policy_group_id(policy_path: &Path) -> String {
+    let cpu = match fs::read_to_string(policy_path.join("related_cpus")).ok()
+        .and_then(|cpus| cpus.split_whitespace().next()?.parse::<u32>().ok()) {
+        Some(cpu) => cpu,
+        None => return "cpu".to_string(),
+    };
+    
+    for core_type in ["cpu_core", "cpu_atom"] {
+        let cpus = fs::read_to_string(format!("/sys/devices/{}/cpus", core_type)).unwrap_or_default();
+        if cpu_list_contains(cpus.trim(), cpu) {
+            return core_type.to_string();
+        }
+    }
+    
+    let topology = PathBuf::from(format!("/sys/devices/system/cpu/cpu{}/topology", cpu));
+    let read = |file: &str| fs::read_to_string(topology.join(file)).ok().map(|s| s.trim().to_string());
+    if let (Some(cluster_id), Some(cluster), Some(core)) =
+        (read("cluster_id"), read("cluster_cpus_list"), read("core_cpus_list")) {
+        if cluster_id != "-1" && cluster != core {
+            return format!("cluster{}", cluster_id);
+        }
+    }
+    "cpu".to_string()
+}
+
+// "0-3,8,10-11"
+fn cpu_list_contains(list: &str, cpu: u32) -> bool {
+    list.split(',').any(|range| match range.split_once('-') {
+        Some((first, last)) => matches!((first.parse::<u32>(), last.parse::<u32>()), (Ok(first), Ok(last)) if (first..=last).contains(&cpu)),
+        None => range.parse() == Ok(cpu),
+    })
+}
+
 fn read_governor() -> Result<String> {
     let path = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor";
     
@@ -455,6 +577,12 @@ fn is_boost_enabled() -> Result<bool> {
         return Ok(no_turbo.trim() == "0");
     }
     
+    // Only per-policy files, enabled if every policy boosts
+    let groups = get_boost_policy_groups();
+    if !groups.is_empty() {
+        return Ok(groups.iter().all(|g| g.enabled == Some(true)));
+    }
+    
     Ok(false)
 }
 
@@ -584,47 +712,53 @@ pub fn get_current_tdp_profile() -> Result<String> {
 }
 
 pub fn get_fan_speeds() -> Result<Vec<(u32, u32)>> {
-    if !TuxedoIo::is_available() {
-        return Ok(vec![]);
-    }
-    
-    let io = TuxedoIo::new()?;
-    let mut fans = Vec::new();
+    let backend = match crate::fan_backend::detect() {
+        Some(backend) => backend,
+        None => return Ok(vec![]),
+    };
     
-    for fan_id in 0..io.get_fan_count() {
-        match io.get_fan_speed(fan_id) {
-            Ok(speed) => {
-                if speed > 0 {
-                    fans.push((fan_id, speed));
-                }
-            }
-            Err(_) => break,
+    // A fan that fails to read doesn't hide the ones after it
+    Ok((0..backend.fan_count())
+        .filter_map(|fan_id| backend.get_duty(fan_id).ok().map(|speed| (fan_id, speed)))
+        .filter(|(_, speed)| *speed > 0)
+        .collect())
+}
+
+pub fn get_fan_curve_capabilities() -> Result<FanCurveCapabilities> {
+    let backend = crate::fan_backend::detect()
+        .ok_or_else(|| anyhow!("Fan control not available"))?;
+    
+    // Some drivers expose a fixed curve table as pwmN_auto_pointM_{temp,pwm}
+    let mut table_points: Option<u32> = None;
+    for hwmon_path in sorted_dir_entries("/sys/class/hwmon") {
+        let points = (1..=32)
+            .take_while(|n| hwmon_path.join(format!("pwm1_auto_point{}_temp", n)).exists())
+            .count() as u32;
+        if points > 0 {
+            table_points = Some(table_points.unwrap_or(0).max(points));
         }
     }
     
-    Ok(fans)
+    // Curves are evaluated by the fan daemon and written as plain duty cycles,
+    // so the table size does not limit the number of points
+    Ok(FanCurveCapabilities {
+        max_points: None,
+        hardware_table_points: table_points,
+        fan_count: backend.fan_count(),
+        min_duty: crate::fan_min_duty::get(),
+    })
 }
 
 pub fn get_fan_temperatures() -> Result<Vec<(u32, u32)>> {
-    if !TuxedoIo::is_available() {
-        return Ok(vec![]);
-    }
-    
-    let io = TuxedoIo::new()?;
-    let mut temps = Vec::new();
-    
-    for fan_id in 0..io.get_fan_count() {
-        match io.get_fan_temperature(fan_id) {
-            Ok(temp) => {
-                if temp > 0 {
-                    temps.push((fan_id, temp));
-                }
-            }
-            Err(_) => break,
-        }
-    }
+    let backend = match crate::fan_backend::detect() {
+        Some(backend) => backend,
+        None => return Ok(vec![]),
+    };
     
-    Ok(temps)
+    Ok((0..backend.fan_count())
+        .filter_map(|fan_id| backend.get_temperature(fan_id).ok().map(|temp| (fan_id, temp as u32)))
+        .filter(|(_, temp)| *temp > 0)
+        .collect())
 }
 
 pub fn get_tdp_info() -> Result<(i32, i32, i32)> {
@@ -642,6 +776,30 @@ pub fn get_tdp_info() -> Result<(i32, i32, i32)> {
     Ok((current, min, max))
 }
 
+pub fn get_tdp_limits() -> Result<TdpLimits> {
+    let (current, min, max) = get_tdp_info()?;
+    let (min, max) = (min.max(0) as u32, max.max(0) as u32);
+    if max <= min {
+        return Err(anyhow!("EC reports no usable TDP range ({}-{} W)", min, max));
+    }
+    
+    // Spread over the EC range, so the same names mean the same thing relative to the chassis
+    let presets = [("Silent", 1), ("Balanced", 2), ("Performance", 3), ("Maximum", 4)]
+        .iter()
+        .map(|(name, quarter)| TdpPreset {
+            name: name.to_string(),
+            watts: min + (max - min) * quarter / 4,
+        })
+        .collect();
+    
+    Ok(TdpLimits {
+        min_watts: min,
+        max_watts: max,
+        current_watts: current.max(0) as u32,
+        presets,
+    })
+}
+
 pub fn get_cpu_info() -> Result<CpuInfo> {
     let name = get_cpu_name()?;
     let core_count = get_cpu_count()?;
@@ -701,6 +859,12 @@ pub fn get_cpu_info() -> Result<CpuInfo> {
         false
     };
     
+    let boost_policies = if capabilities.has_per_policy_boost {
+        get_boost_policy_groups()
+    } else {
+        vec![]
+    };
+    
     let smt_enabled = if capabilities.has_smt {
         is_smt_enabled().unwrap_or(true)
     } else {
@@ -763,6 +927,8 @@ pub fn get_cpu_info() -> Result<CpuInfo> {
         governor,
         available_governors,
         boost_enabled,
+        boost_policies,
+        time_in_state: read_time_in_state(),
         smt_enabled,
         scaling_driver,
         amd_pstate_status,
@@ -800,68 +966,259 @@ pub fn get_system_info() -> Result<SystemInfo> {
         product_name,
         manufacturer,
         bios_version,
+        microcode: get_microcode_version(),
+        vulnerabilities: get_cpu_vulnerabilities(),
+        memory_modules: get_memory_modules(),
     })
 }
 
+/// Connected displays, described by their EDID
+pub fn get_display_info() -> Vec<DisplayInfo> {
+    sorted_dir_entries("/sys/class/drm")
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            // Connectors are named card<N>-<type>-<index>, e.g. card1-eDP-1
+            let connector = match name.split_once('-') {
+                Some((card, connector)) if card.starts_with("card") => connector.to_string(),
+                _ => return None,
+            };
+            
+            let status = fs::read_to_string(path.join("status")).ok()?;
+            if status.trim() != "connected" {
+                return None;
+            }
+            
+            let edid = fs::read(path.join("edid")).ok()?;
+            let internal = ["eDP", "LVDS", "DSI"].iter().any(|c| connector.starts_with(c));
+            parse_edid(&edid, connector, internal)
+        })
+        .collect()
+}
+
+fn parse_edid(edid: &[u8], connector: String, internal: bool) -> Option<DisplayInfo> {
+    const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+    if edid.len() < 128 || edid[..8] != HEADER {
+        return None;
+    }
+    
+    // Three 5-bit letters, 'A' = 1
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let manufacturer: String = [10, 5, 0]
+        .iter()
+        .map(|shift| (b'A' - 1 + ((id >> shift) & 0x1F) as u8) as char)
+        .collect();
+    
+    let mut info = DisplayInfo {
+        connector,
+        internal,
+        manufacturer,
+        model: None,
+        native_resolution: None,
+        refresh_rates: Vec::new(),
+        max_brightness_nits: None,
+    };
+    
+    for descriptor in edid[54..126].chunks(18) {
+        let pixel_clock = u16::from_le_bytes([descriptor[0], descriptor[1]]) as u64 * 10_000;
+        if pixel_clock > 0 {
+            // Detailed timing, the first one is the preferred (native) mode
+            let h_active = descriptor[2] as u64 | ((descriptor[4] as u64 & 0xF0) << 4);
+            let h_blank = descriptor[3] as u64 | ((descriptor[4] as u64 & 0x0F) << 8);
+            let v_active = descriptor[5] as u64 | ((descriptor[7] as u64 & 0xF0) << 4);
+            let v_blank = descriptor[6] as u64 | ((descriptor[7] as u64 & 0x0F) << 8);
+            let total = (h_active + h_blank) * (v_active + v_blank);
+            
+            if info.native_resolution.is_none() {
+                info.native_resolution = Some((h_active as u32, v_active as u32));
+            }
+            if let Some(rate) = (pixel_clock + total / 2).checked_div(total) {
+                info.refresh_rates.push(rate as u32);
+            }
+            continue;
+        }
+        
+        let text = || {
+            let text: String = descriptor[5..].iter()
+                .take_while(|b| **b != 0x0A)
+                .map(|b| *b as char)
+                .collect();
+            Some(text.trim().to_string()).filter(|t| !t.is_empty())
+        };
+        match descriptor[3] {
+            // Monitor name
+            0xFC => info.model = text().or(info.model.take()),
+            // Laptop panels usually put the part number into an unspecified text descriptor
+            0xFE if info.model.is_none() => info.model = text(),
+            _ => {}
+        }
+    }
+    
+    // CTA-861 extension block, HDR static metadata holds the desired max luminance
+    if edid.len() >= 256 && edid[128] == 0x02 {
+        let end = (128 + edid[130] as usize).min(255);
+        let mut offset = 132;
+        while offset < end {
+            let tag = edid[offset] >> 5;
+            let len = (edid[offset] & 0x1F) as usize;
+            let data = &edid[(offset + 1).min(end)..(offset + 1 + len).min(end)];
+            if tag == 7 && data.len() >= 4 && data[0] == 0x06 && data[3] > 0 {
+                let nits = 50.0 * 2f64.powf(data[3] as f64 / 32.0);
+                info.max_brightness_nits = Some(nits.round() as u32);
+            }
+            offset += 1 + len;
+        }
+    }
+    
+    info.refresh_rates.sort_unstable_by(|a, b| b.cmp(a));
+    info.refresh_rates.dedup();
+    Some(info)
+}
+
+// Raw SMBIOS structures are only readable by root
+fn get_memory_modules() -> Vec<MemoryModule> {
+    sorted_dir_entries("/sys/firmware/dmi/entries")
+        .into_iter()
+        .filter(|path| path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("17-")))
+        .filter_map(|path| fs::read(path.join("raw")).ok())
+        .filter_map(|raw| parse_memory_device(&raw))
+        .collect()
+}
+
+fn parse_memory_device(raw: &[u8]) -> Option<MemoryModule> {
+    let length = *raw.get(1)? as usize;
+    if raw[0] != 17 || length < 0x1B || raw.len() < length {
+        return None;
+    }
+    
+    let word = |offset: usize| (offset + 2 <= length)
+        .then(|| u16::from_le_bytes([raw[offset], raw[offset + 1]]));
+    let dword = |offset: usize| (offset + 4 <= length)
+        .then(|| u32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]]));
+    // Strings follow the formatted area, referenced by 1-based index
+    let strings: Vec<String> = raw[length..]
+        .split(|b| *b == 0)
+        .take_while(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).trim().to_string())
+        .collect();
+    let string = |offset: usize| raw.get(offset)
+        .and_then(|index| (*index as usize).checked_sub(1))
+        .and_then(|index| strings.get(index).cloned())
+        .filter(|s| !s.is_empty() && s != "Unknown" && s != "Not Specified");
+    
+    let size_mb = match word(0x0C)? {
+        0 | 0xFFFF => return None,  // Empty slot / unknown
+        0x7FFF => dword(0x1C)? as u64 & 0x7FFF_FFFF,
+        size if size & 0x8000 != 0 => (size & 0x7FFF) as u64 / 1024,  // Given in KB
+        size => size as u64,
+    };
+    
+    let memory_type = match raw[0x12] {
+        0x12 => Some("DDR"),
+        0x13 => Some("DDR2"),
+        0x18 => Some("DDR3"),
+        0x1A => Some("DDR4"),
+        0x1B => Some("LPDDR"),
+        0x1C => Some("LPDDR2"),
+        0x1D => Some("LPDDR3"),
+        0x1E => Some("LPDDR4"),
+        0x22 => Some("DDR5"),
+        0x23 => Some("LPDDR5"),
+        _ => None,
+    };
+    
+    // 0xFFFF means the real value is in the 32-bit extended field (SMBIOS 3.3+)
+    let speed = |offset: usize, extended: usize| match word(offset) {
+        Some(0) | None => None,
+        Some(0xFFFF) => dword(extended),
+        Some(speed) => Some(speed as u32),
+    };
+    
+    let slot = [string(0x11), string(0x10)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" / ");
+    
+    Some(MemoryModule {
+        slot,
+        size_mb,
+        memory_type: memory_type.map(str::to_string),
+        speed_mts: speed(0x15, 0x54),
+        configured_speed_mts: speed(0x20, 0x58),
+        manufacturer: string(0x17),
+        part_number: string(0x1A),
+    })
+}
+
+fn get_microcode_version() -> Option<String> {
+    if let Ok(version) = fs::read_to_string("/sys/devices/system/cpu/cpu0/microcode/version") {
+        return Some(version.trim().to_string());
+    }
+    
+    // Format: "microcode\t: 0xf4"
+    fs::read_to_string("/proc/cpuinfo").ok()?
+        .lines()
+        .find(|line| line.starts_with("microcode"))
+        .and_then(|line| line.split(':').nth(1))
+        .map(|version| version.trim().to_string())
+}
+
+fn get_cpu_vulnerabilities() -> Vec<CpuVulnerability> {
+    sorted_dir_entries("/sys/devices/system/cpu/vulnerabilities")
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let status = fs::read_to_string(&path).ok()?.trim().to_string();
+            Some(CpuVulnerability { name, status })
+        })
+        .collect()
+}
+
 pub fn get_gpu_info() -> Result<Vec<GpuInfo>> {
     let mut gpus = Vec::new();
     
-    for i in 0..4 {
-        let card_path = format!("/sys/class/drm/card{}", i);
-        if !Path::new(&card_path).exists() {
+    for card_path in drm_cards() {
+        let device_path = card_path.join("device");
+        let Ok(vendor) = fs::read_to_string(device_path.join("vendor")) else {
             continue;
-        }
+        };
+        let vendor = vendor.trim();
+        let id = pci_address(&device_path).unwrap_or_default();
+        let index = card_path.file_name()
+            .map(|name| name.to_string_lossy().trim_start_matches("card").to_string())
+            .unwrap_or_default();
+        let name = crate::pci_ids::gpu_name(&device_path).unwrap_or_else(|| match vendor {
+            "0x1002" => format!("AMD GPU {}", index),
+            "0x10de" => format!("NVIDIA GPU {}", index),
+            "0x8086" => format!("Intel GPU {}", index),
+            _ => format!("GPU {}", index),
+        });
         
-        let device_path = format!("{}/device", card_path);
-        let vendor_path = format!("{}/vendor", device_path);
+        let status = fs::read_to_string(device_path.join("power/runtime_status"))
+            .unwrap_or_else(|_| "unknown".to_string())
+            .trim()
+            .to_string();
         
-        if let Ok(vendor) = fs::read_to_string(&vendor_path) {
-            let vendor = vendor.trim();
-            let name = match vendor {
-                "0x1002" => format!("AMD GPU {}", i),
-                "0x10de" => format!("NVIDIA GPU {}", i),
-                "0x8086" => format!("Intel GPU {}", i),
-                _ => format!("GPU {}", i),
-            };
-            
-            let gpu_type = if i == 0 {
-                GpuType::Integrated
-            } else {
-                GpuType::Discrete
-            };
-            
-            let status_path = format!("{}/power/runtime_status", device_path);
-            let status = fs::read_to_string(&status_path)
-                .unwrap_or_else(|_| "unknown".to_string())
-                .trim()
-                .to_string();
-            
-            // Read frequency
-            let frequency = read_gpu_frequency(&device_path);
-            
-            // Read temperature
-            let temperature = read_gpu_temperature(&device_path);
-            
-            // Read load
-            let load = read_gpu_load(&device_path);
-            
-            // Read power
-            let power = read_gpu_power(&device_path);
-            
-            // Read voltage (optional)
-            let voltage = read_gpu_voltage(&device_path);
-            
-            gpus.push(GpuInfo {
-                name,
-                gpu_type,
-                status,
-                frequency,
-                temperature,
-                load,
-                power,
-                voltage,
-            });
-        }
+        // Reading the DPM tables or hwmon wakes a runtime suspended dGPU, it would never sleep
+        let awake = status != "suspended";
+        let device = device_path.to_string_lossy();
+        gpus.push(GpuInfo {
+            gpu_type: gpu_type(&device_path, vendor, &id),
+            driver: driver_name(&device_path).unwrap_or_default(),
+            external: fs::read_to_string(device_path.join("removable"))
+                .is_ok_and(|s| s.trim() == "removable"),
+            power_limit_control: crate::gpu_power::supports_power_limit(&device_path),
+            id,
+            name,
+            status,
+            frequency: awake.then(|| read_gpu_frequency(&device)).flatten(),
+            memory_frequency: awake.then(|| read_gpu_memory_frequency(&device)).flatten(),
+            temperature: awake.then(|| read_gpu_temperature(&device)).flatten(),
+            load: awake.then(|| read_gpu_load(&device)).flatten(),
+            power: awake.then(|| read_gpu_power(&device)).flatten(),
+            voltage: awake.then(|| read_gpu_voltage(&device)).flatten(),
+        });
     }
     
     if gpus.is_empty() {
@@ -871,110 +1228,167 @@ pub fn get_gpu_info() -> Result<Vec<GpuInfo>> {
     Ok(gpus)
 }
 
-fn read_gpu_frequency(device_path: &str) -> Option<u64> {
-    // AMD
-    if let Ok(freq_str) = fs::read_to_string(format!("{}/pp_dpm_sclk", device_path)) {
-        for line in freq_str.lines() {
-            if line.contains('*') {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    if let Ok(freq) = parts[1].trim_end_matches("Mhz").parse::<u64>() {
-                        return Some(freq);
-                    }
-                }
-            }
+// card0, card1, ... without the connectors (card0-eDP-1) and render nodes
+pub fn drm_cards() -> Vec<PathBuf> {
+    sorted_dir_entries("/sys/class/drm")
+        .into_iter()
+        .filter(|path| path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .is_some_and(|name| name.starts_with("card") && !name.contains('-')))
+        .collect()
+}
+
+// "0000:01:00.0", the device link of a DRM card points into the PCI tree
+pub fn pci_address(device_path: &Path) -> Option<String> {
+    let device = fs::canonicalize(device_path).ok()?;
+    Some(device.file_name()?.to_string_lossy().to_string())
+}
+
+pub fn driver_name(device_path: &Path) -> Option<String> {
+    let driver = fs::read_link(device_path.join("driver")).ok()?;
+    Some(driver.file_name()?.to_string_lossy().to_string())
+}
+
+// The card index says nothing once a dock or a second dGPU is involved, nor does boot_vga
+// with the MUX switched to the dGPU, so the vendor decides where it can
+pub fn gpu_type(device_path: &Path, vendor: &str, pci_address: &str) -> GpuType {
+    match vendor {
+        // No integrated NVIDIA graphics on x86
+        "0x10de" => GpuType::Discrete,
+        // Intel's iGPU is always 00:02.0, Arc cards sit behind a root port
+        "0x8086" if pci_address.ends_with("00:02.0") => GpuType::Integrated,
+        "0x8086" => GpuType::Discrete,
+        // amdgpu drives APUs and dGPUs alike, the APU is the boot VGA device
+        _ => {
+            let boot_vga = fs::read_to_string(device_path.join("boot_vga"))
+                .is_ok_and(|s| s.trim() == "1");
+            if boot_vga { GpuType::Integrated } else { GpuType::Discrete }
         }
     }
-    
-    // Intel
-    if let Ok(freq_str) = fs::read_to_string(format!("{}/gt_cur_freq_mhz", device_path)) {
-        if let Ok(freq) = freq_str.trim().parse::<u64>() {
-            return Some(freq);
+}
+
+// The integrated GPU is the boot VGA device on hybrid laptops
+pub fn find_igpu_card() -> Option<PathBuf> {
+    for card_path in sorted_dir_entries("/sys/class/drm") {
+        let name = card_path.file_name()?.to_string_lossy().to_string();
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+        
+        let boot_vga = fs::read_to_string(card_path.join("device/boot_vga"))
+            .map(|s| s.trim() == "1")
+            .unwrap_or(false);
+        if boot_vga {
+            return Some(card_path);
         }
     }
-    
     None
 }
 
-fn read_gpu_temperature(device_path: &str) -> Option<f32> {
-    // Check hwmon
-    let hwmon_path = format!("{}/hwmon", device_path);
-    if let Ok(entries) = fs::read_dir(&hwmon_path) {
-        for entry in entries.flatten() {
-            let temp_input = entry.path().join("temp1_input");
-            if let Ok(temp_str) = fs::read_to_string(&temp_input) {
-                if let Ok(temp) = temp_str.trim().parse::<f32>() {
-                    return Some(temp / 1000.0);
-                }
-            }
-        }
+// Frequencies of the amdgpu shader clock DPM levels, e.g. "1: 1100Mhz *"
+pub fn read_amdgpu_sclk_levels(device_path: &Path) -> Vec<u32> {
+    fs::read_to_string(device_path.join("pp_dpm_sclk"))
+        .map(|content| content.lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .filter_map(|freq| freq.trim_end_matches("Mhz").trim_end_matches("MHz").parse().ok())
+            .collect())
+        .unwrap_or_default()
+}
+
+pub fn get_igpu_frequency_info() -> Result<IgpuFrequencyInfo> {
+    let card_path = find_igpu_card().ok_or_else(|| anyhow!("Integrated GPU not found"))?;
+    
+    // i915 exposes the GT frequency range on the card itself
+    if card_path.join("gt_max_freq_mhz").exists() {
+        let read_mhz = |file: &str| -> Result<u32> {
+            Ok(fs::read_to_string(card_path.join(file))?.trim().parse()?)
+        };
+        return Ok(IgpuFrequencyInfo {
+            driver: "i915".to_string(),
+            min_mhz: read_mhz("gt_RPn_freq_mhz")?,
+            max_mhz: read_mhz("gt_RP0_freq_mhz")?,
+            current_max_mhz: read_mhz("gt_max_freq_mhz")?,
+        });
     }
     
-    // AMD specific
-    if let Ok(temp_str) = fs::read_to_string(format!("{}/gpu_busy_percent", device_path)) {
-        if let Ok(temp) = temp_str.trim().parse::<f32>() {
-            return Some(temp);
-        }
+    let levels = read_amdgpu_sclk_levels(&card_path.join("device"));
+    if let (Some(&min), Some(&max)) = (levels.iter().min(), levels.iter().max()) {
+        // The applied cap can't be read back from pp_dpm_sclk, report the full range
+        return Ok(IgpuFrequencyInfo {
+            driver: "amdgpu".to_string(),
+            min_mhz: min,
+            max_mhz: max,
+            current_max_mhz: max,
+        });
     }
     
-    None
+    Err(anyhow!("iGPU frequency control not supported"))
 }
 
-fn read_gpu_load(device_path: &str) -> Option<f32> {
+// The active DPM level is marked, e.g. "1: 1800Mhz *"
+fn amdgpu_active_level(device_path: &str, file: &str) -> Option<u64> {
+    fs::read_to_string(format!("{}/{}", device_path, file)).ok()?
+        .lines()
+        .find(|line| line.trim_end().ends_with('*'))?
+        .split_whitespace()
+        .nth(1)?
+        .trim_end_matches("Mhz")
+        .trim_end_matches("MHz")
+        .parse()
+        .ok()
+}
+
+// amdgpu registers a single hwmon per card, values are in milli or micro units
+fn gpu_hwmon_value(device_path: &str, file: &str) -> Option<f32> {
+    sorted_dir_entries(&format!("{}/hwmon", device_path))
+        .into_iter()
+        .find_map(|hwmon| fs::read_to_string(hwmon.join(file)).ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn read_gpu_frequency(device_path: &str) -> Option<u64> {
     // AMD
-    if let Ok(load_str) = fs::read_to_string(format!("{}/gpu_busy_percent", device_path)) {
-        if let Ok(load) = load_str.trim().parse::<f32>() {
-            return Some(load);
-        }
+    if let Some(mhz) = amdgpu_active_level(device_path, "pp_dpm_sclk") {
+        return Some(mhz);
     }
     
     // Intel
-    if let Ok(load_str) = fs::read_to_string(format!("{}/gt_RP0_freq_mhz", device_path)) {
-        // Intel doesn't directly expose load, would need calculation
+    if let Ok(freq_str) = fs::read_to_string(format!("{}/gt_cur_freq_mhz", device_path)) {
+        if let Ok(freq) = freq_str.trim().parse::<u64>() {
+            return Some(freq);
+        }
     }
     
     None
 }
 
+// APUs share system memory and often report a single fixed level
+fn read_gpu_memory_frequency(device_path: &str) -> Option<u64> {
+    amdgpu_active_level(device_path, "pp_dpm_mclk")
+}
+
+// temp1 is the edge sensor on amdgpu, junction and memory follow as temp2 and temp3
+fn read_gpu_temperature(device_path: &str) -> Option<f32> {
+    gpu_hwmon_value(device_path, "temp1_input").map(|millidegrees| millidegrees / 1000.0)
+}
+
+fn read_gpu_load(device_path: &str) -> Option<f32> {
+    // AMD, Intel doesn't expose a load
+    fs::read_to_string(format!("{}/gpu_busy_percent", device_path)).ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+// power1_average on dGPUs and older APUs, newer APUs only have power1_input
 fn read_gpu_power(device_path: &str) -> Option<f32> {
-    let hwmon_path = format!("{}/hwmon", device_path);
-    if let Ok(entries) = fs::read_dir(&hwmon_path) {
-        for entry in entries.flatten() {
-            // Try power1_average first
-            let power_avg = entry.path().join("power1_average");
-            if let Ok(power_str) = fs::read_to_string(&power_avg) {
-                if let Ok(microwatts) = power_str.trim().parse::<f32>() {
-                    return Some(microwatts / 1_000_000.0);
-                }
-            }
-            
-            // Try power1_input
-            let power_input = entry.path().join("power1_input");
-            if let Ok(power_str) = fs::read_to_string(&power_input) {
-                if let Ok(microwatts) = power_str.trim().parse::<f32>() {
-                    return Some(microwatts / 1_000_000.0);
-                }
-            }
-        }
-    }
-    
-    None
+    gpu_hwmon_value(device_path, "power1_average")
+        .or_else(|| gpu_hwmon_value(device_path, "power1_input"))
+        .map(|microwatts| microwatts / 1_000_000.0)
 }
 
 fn read_gpu_voltage(device_path: &str) -> Option<f32> {
-    let hwmon_path = format!("{}/hwmon", device_path);
-    if let Ok(entries) = fs::read_dir(&hwmon_path) {
-        for entry in entries.flatten() {
-            let voltage_input = entry.path().join("in0_input");
-            if let Ok(volt_str) = fs::read_to_string(&voltage_input) {
-                if let Ok(millivolts) = volt_str.trim().parse::<f32>() {
-                    return Some(millivolts / 1000.0);
-                }
-            }
-        }
-    }
-    
-    None
+    gpu_hwmon_value(device_path, "in0_input").map(|millivolts| millivolts / 1000.0)
 }
 
 // WiFi information detection
@@ -1032,6 +1446,7 @@ pub fn get_wifi_info() -> Result<Vec<WiFiInfo>> {
         // Read channel and rates from iwconfig or iw
         let (channel, channel_width) = read_wifi_channel(&interface);
         let (tx_rate, rx_rate) = read_wifi_rates(&interface);
+        let power_save = read_wifi_power_save(&interface);
         
         wifi_devices.push(WiFiInfo {
             interface,
@@ -1042,6 +1457,7 @@ pub fn get_wifi_info() -> Result<Vec<WiFiInfo>> {
             channel_width,
             tx_rate,
             rx_rate,
+            power_save,
         });
     }
     
@@ -1072,6 +1488,26 @@ fn read_wifi_signal(interface: &str) -> Option<i32> {
     None
 }
 
+pub fn read_wifi_power_save(interface: &str) -> Option<bool> {
+    let output = std::process::Command::new("iw")
+        .args(["dev", interface, "get", "power_save"])
+        .output()
+        .ok()?;
+    
+    if !output.status.success() {
+        return None;
+    }
+    
+    // Format: "Power save: on"
+    let info = String::from_utf8_lossy(&output.stdout);
+    let state = info.trim().rsplit(':').next()?.trim();
+    match state {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
 fn read_wifi_channel(interface: &str) -> (Option<u32>, Option<u32>) {
     // Try to use iw command first (more modern)
     if let Ok(output) = std::process::Command::new("iw")
@@ -1165,27 +1601,308 @@ fn read_wifi_rates(interface: &str) -> (Option<f64>, Option<f64>) {
     (None, None)
 }
 
-pub fn get_battery_info() -> Result<BatteryInfo> {
-    let base = if Path::new("/sys/class/power_supply/BAT0").exists() {
-        "/sys/class/power_supply/BAT0"
+// Distinguishes a read-only /sys (containers) from missing permissions
+fn check_writable(path: &str) -> std::result::Result<(), String> {
+    let c_path = std::ffi::CString::new(path).map_err(|e| e.to_string())?;
+    // Effective ids and capabilities, access() would check for the user alone after a privilege drop
+    if unsafe { libc::faccessat(libc::AT_FDCWD, c_path.as_ptr(), libc::W_OK, libc::AT_EACCESS) } == 0 {
+        return Ok(());
+    }
+    
+    match std::io::Error::last_os_error().raw_os_error() {
+        Some(libc::EROFS) => Err(format!("{} is on a read-only filesystem (running in a container?)", path)),
+        Some(libc::ENOENT) => Err(format!("{} not found", path)),
+        _ => Err(format!("No write permission for {}", path)),
+    }
+}
+
+fn feature_status(feature: Feature, result: std::result::Result<(), String>) -> FeatureStatus {
+    FeatureStatus {
+        feature,
+        available: result.is_ok(),
+        reason: result.err(),
+    }
+}
+
+fn capability(name: &str, interface: &str, result: std::result::Result<(), String>) -> Capability {
+    Capability {
+        name: name.to_string(),
+        supported: result.is_ok(),
+        interface: interface.to_string(),
+        reason: result.err(),
+    }
+}
+
+// First of several alternative sysfs files that exists, checked for write access
+fn sysfs_capability(name: &str, paths: &[&str]) -> Capability {
+    match paths.iter().find(|path| Path::new(path).exists()) {
+        Some(path) => capability(name, path, check_writable(path)),
+        None => capability(name, &paths.join(" | "), Err("Not present".to_string())),
+    }
+}
+
+/// Every control the app knows about and the interface it uses, for debugging missing sections
+pub fn get_capability_matrix() -> Vec<Capability> {
+    const TUXEDO_IO_IOCTL: &str = "/dev/tuxedo_io (ioctl)";
+    let tuxedo_io = || {
+        if TuxedoIo::is_available() {
+            check_writable("/dev/tuxedo_io")
+        } else {
+            Err("tuxedo_io module not loaded".to_string())
+        }
+    };
+    
+    let fan_control = match crate::fan_backend::detect() {
+        Some(backend) => {
+            let interface = match backend.name() {
+                "tuxedo_io" => TUXEDO_IO_IOCTL,
+                "hwmon pwm" => "/sys/class/hwmon/hwmon*/pwm*",
+                _ => "/proc/acpi/call",
+            };
+            capability(&format!("Fan control ({}, {} fans)", backend.name(), backend.fan_count()), interface, Ok(()))
+        }
+        None => capability("Fan control", "/dev/tuxedo_io | /sys/class/hwmon/hwmon*/pwm*",
+            Err("No fan interface found".to_string())),
+    };
+    
+    let keyboards = crate::hardware_control::find_keyboard_backlights();
+    let keyboard = match keyboards.first() {
+        Some(device) if device.name == crate::uniwill_keyboard::DEVICE_NAME => {
+            let path = crate::uniwill_keyboard::color_path();
+            capability("Keyboard backlight (Uniwill, legacy driver)", path, check_writable(path))
+        }
+        Some(device) if crate::uniwill_keyboard::led_devices().contains(&device.name) => {
+            let path = format!("/sys/class/leds/{}/brightness", device.name);
+            capability("Keyboard backlight (Uniwill)", &path, check_writable(&path))
+        }
+        Some(device) => {
+            let path = format!("/sys/class/leds/{}/brightness", device.name);
+            capability("Keyboard backlight", &path, check_writable(&path))
+        }
+        None => capability("Keyboard backlight", "/sys/class/leds/*kbd_backlight*", Err("Not present".to_string())),
+    };
+    let keyboard_rgb = match keyboards.iter().find(|device| device.rgb && device.name != crate::uniwill_keyboard::DEVICE_NAME) {
+        Some(device) => {
+            let path = format!("/sys/class/leds/{}/multi_intensity", device.name);
+            capability("Keyboard RGB", &path, check_writable(&path))
+        }
+        None => capability("Keyboard RGB", "/sys/class/leds/*kbd_backlight*/multi_intensity",
+            Err("Single color or no keyboard backlight".to_string())),
+    };
+    
+    let batteries = ["/sys/class/power_supply/BAT0", "/sys/class/power_supply/BAT1"];
+    let battery_files = |file: &str| batteries.iter().map(|bat| format!("{}/{}", bat, file)).collect::<Vec<_>>();
+    let battery_capability = |name: &str, file: &str| {
+        let paths = battery_files(file);
+        sysfs_capability(name, &paths.iter().map(String::as_str).collect::<Vec<_>>())
+    };
+    
+    let dgpu = capability("dGPU power limit", "nvidia-smi | /sys/class/drm/card*/device/hwmon/hwmon*/power1_cap",
+        crate::gpu_power::DgpuPowerControl::new().map(|_| ()).map_err(|e| e.to_string()));
+    
+    let igpu = match get_igpu_frequency_info() {
+        Ok(info) if info.driver == "i915" => capability("iGPU frequency (i915)", "/sys/class/drm/card*/gt_max_freq_mhz", Ok(())),
+        Ok(_) => capability("iGPU frequency (amdgpu)", "/sys/class/drm/card*/device/pp_dpm_sclk", Ok(())),
+        Err(e) => capability("iGPU frequency", "/sys/class/drm/card*", Err(e.to_string())),
+    };
+    
+    let sensors = get_all_temperatures().map(|s| s.len()).unwrap_or(0);
+    let temperatures = capability(&format!("Temperature sensors ({} found)", sensors), "/sys/class/hwmon/hwmon*/temp*_input",
+        if sensors > 0 { Ok(()) } else { Err("No temperature sensors found".to_string()) });
+    
+    let indicator_count = crate::indicators::indicator_leds().len();
+    let indicators = capability(&format!("Indicator LEDs ({} found)", indicator_count), "/sys/class/leds/*::{mute,micmute,power,charging}",
+        if indicator_count > 0 { Ok(()) } else { Err("Not present".to_string()) });
+    
+    let intrusion = sorted_dir_entries("/sys/class/hwmon")
+        .into_iter()
+        .map(|hwmon| hwmon.join("intrusion0_alarm"))
+        .find(|path| path.exists());
+    let intrusion = match intrusion {
+        Some(path) => capability("Chassis intrusion detection", &path.to_string_lossy(), Ok(())),
+        None => capability("Chassis intrusion detection", "/sys/class/hwmon/hwmon*/intrusion0_alarm",
+            Err("Not exposed by this machine".to_string())),
+    };
+    
+    let mut capabilities = vec![
+        fan_control,
+        capability("TDP profiles", TUXEDO_IO_IOCTL, tuxedo_io()),
+        sysfs_capability("Platform profile", &["/sys/firmware/acpi/platform_profile"]),
+        sysfs_capability("CPU frequency limits", &["/sys/devices/system/cpu/cpu0/cpufreq/scaling_max_freq"]),
+        sysfs_capability("CPU boost", &[
+            "/sys/devices/system/cpu/cpufreq/boost",
+            "/sys/devices/system/cpu/intel_pstate/no_turbo",
+            "/sys/devices/system/cpu/amd_pstate/cpb_boost",
+            "/sys/devices/system/cpu/cpufreq/policy0/boost",
+        ]),
+        sysfs_capability("SMT", &["/sys/devices/system/cpu/smt/control"]),
+        sysfs_capability("Energy performance preference",
+            &["/sys/devices/system/cpu/cpu0/cpufreq/energy_performance_preference"]),
+        sysfs_capability("AMD P-State mode", &["/sys/devices/system/cpu/amd_pstate/status"]),
+        capability("Webcam switch", TUXEDO_IO_IOCTL, tuxedo_io()),
+        keyboard,
+        keyboard_rgb,
+        battery_capability("Charge type (flexicharger)", "charge_type"),
+        battery_capability("Charge start threshold", "charge_control_start_threshold"),
+        battery_capability("Charge end threshold", "charge_control_end_threshold"),
+        battery_capability("Charge behaviour", "charge_behaviour"),
+        sysfs_capability("Charging priority (USB-C)", &[crate::charging::priority_path()]),
+        sysfs_capability("Charging profile", &[crate::charging::profile_path()]),
+        sysfs_capability("Screen brightness", &[
+            "/sys/class/backlight/intel_backlight/brightness",
+            "/sys/class/backlight/amdgpu_bl0/brightness",
+            "/sys/class/backlight/amdgpu_bl1/brightness",
+            "/sys/class/backlight/acpi_video0/brightness",
+        ]),
+        dgpu,
+        igpu,
+        indicators,
+        temperatures,
+        intrusion,
+    ];
+    
+    if crate::lightbar::is_available() {
+        let path = crate::lightbar::brightness_path();
+        capabilities.push(capability("Lightbar", &path, check_writable(&path)));
+    }
+    
+    capabilities
+}
+
+/// Which features the daemon can actually control in this environment
+pub fn get_feature_availability() -> Vec<FeatureStatus> {
+    let tuxedo_io = || {
+        if TuxedoIo::is_available() {
+            check_writable("/dev/tuxedo_io")
+        } else {
+            Err("/dev/tuxedo_io not found, is tuxedo-drivers installed?".to_string())
+        }
+    };
+    
+    let fan_control = if TuxedoIo::is_available() {
+        tuxedo_io()
+    } else if crate::fan_backend::is_available() {
+        Ok(())
+    } else {
+        Err("No tuxedo_io or hwmon pwm fan interface found".to_string())
+    };
+    
+    let battery = crate::battery_control::BatteryControl::new()
+        .map_err(|_| "Battery charge control not supported".to_string())
+        .and_then(|battery| check_writable(&battery.control_path().to_string_lossy()));
+    
+    let keyboard = match crate::hardware_control::find_keyboard_backlight_path() {
+        Some(path) => check_writable(&format!("{}/brightness", path)),
+        None if crate::uniwill_keyboard::legacy_available() => check_writable(crate::uniwill_keyboard::color_path()),
+        None => Err("Keyboard backlight not found".to_string()),
+    };
+    
+    let gpu = if crate::gpu_power::DgpuPowerControl::is_available() {
+        Ok(())
+    } else {
+        Err("No dGPU with power limit control found".to_string())
+    };
+    
+    let mut features = vec![
+        feature_status(Feature::FanControl, fan_control),
+        feature_status(Feature::TdpProfiles, tuxedo_io()),
+        feature_status(Feature::CpuFrequency,
+            check_writable("/sys/devices/system/cpu/cpu0/cpufreq/scaling_max_freq")),
+        feature_status(Feature::KeyboardBacklight, keyboard),
+        feature_status(Feature::BatteryCharging, battery),
+        feature_status(Feature::GpuPowerLimit, gpu),
+        feature_status(Feature::Webcam, tuxedo_io()),
+    ];
+    
+    // Only reported on chassis that have one
+    if crate::lightbar::is_available() {
+        features.push(feature_status(Feature::LightBar,
+            check_writable(&crate::lightbar::brightness_path())));
+    }
+    if crate::ambient_light::is_available() {
+        features.push(feature_status(Feature::AmbientLight, Ok(())));
+    }
+    
+    features
+}
+
+fn battery_base() -> Result<&'static str> {
+    if Path::new("/sys/class/power_supply/BAT0").exists() {
+        Ok("/sys/class/power_supply/BAT0")
     } else if Path::new("/sys/class/power_supply/BAT1").exists() {
-        "/sys/class/power_supply/BAT1"
+        Ok("/sys/class/power_supply/BAT1")
     } else {
-        return Err(anyhow!("No battery found"));
+        Err(anyhow!("No battery found"))
+    }
+}
+
+pub fn get_battery_info() -> Result<BatteryInfo> {
+    let base = battery_base()?;
+    let status = read_sysfs_string(&format!("{}/status", base)).unwrap_or_else(|_| "Unknown".to_string());
+    let voltage_mv = read_sysfs_u64(&format!("{}/voltage_now", base))? / 1000;
+    let current_ua = read_sysfs_i64(&format!("{}/current_now", base)).ok();
+
+    // Most drivers report current_now unsigned, some negative while discharging: take the sign from status
+    let sign = match status.as_str() {
+        "Charging" => 1,
+        "Discharging" => -1,
+        _ => current_ua.map(i64::signum).unwrap_or(0),
+    };
+    // Batteries reporting energy have power_now and no current_now
+    let power_uw = read_sysfs_u64(&format!("{}/power_now", base)).ok()
+        .unwrap_or_else(|| current_ua.unwrap_or(0).unsigned_abs() * voltage_mv / 1000);
+    let current_ma = match current_ua {
+        Some(current) => current.abs() / 1000,
+        None if voltage_mv > 0 => (power_uw / voltage_mv) as i64,
+        None => 0,
     };
 
     Ok(BatteryInfo {
-        voltage_mv: read_sysfs_u64(&format!("{}/voltage_now", base))? / 1000,
-        current_ma: read_sysfs_i64(&format!("{}/current_now", base))? / 1000,
+        voltage_mv,
+        current_ma: sign * current_ma,
         charge_percent: read_sysfs_u64(&format!("{}/capacity", base))?,
         capacity_mah: read_sysfs_u64(&format!("{}/charge_full", base))? / 1000,
         manufacturer: read_sysfs_string(&format!("{}/manufacturer", base))?,
         model: read_sysfs_string(&format!("{}/model_name", base))?,
         charge_start_threshold: read_sysfs_u64(&format!("{}/charge_control_start_threshold", base)).ok().map(|v| v as u8),
         charge_end_threshold: read_sysfs_u64(&format!("{}/charge_control_end_threshold", base)).ok().map(|v| v as u8),
+        session: crate::power_session::get_report(),
+        // power_supply reports temp in tenths of a degree
+        temperature: read_sysfs_i64(&format!("{}/temp", base)).ok().map(|t| t as f32 / 10.0),
+        full_charge_since: crate::full_charge::started_at(),
+        // USB-C supplies count as AC, the adapter monitor only sees them after its first poll
+        on_ac_power: is_on_ac_power() || crate::ac_adapter::get_info().online,
+        power_draw_w: sign as f64 * power_uw as f64 / 1_000_000.0,
+        status,
     })
 }
 
+/// Remaining battery energy in µWh
+pub fn read_battery_energy() -> Result<u64> {
+    let base = battery_base()?;
+    
+    if let Ok(energy) = read_sysfs_u64(&format!("{}/energy_now", base)) {
+        return Ok(energy);
+    }
+    
+    // Batteries reporting charge instead of energy: µAh * µV
+    let charge = read_sysfs_u64(&format!("{}/charge_now", base))?;
+    let voltage = read_sysfs_u64(&format!("{}/voltage_now", base))?;
+    Ok(charge * voltage / 1_000_000)
+}
+
+pub fn is_on_ac_power() -> bool {
+    for supply in sorted_dir_entries("/sys/class/power_supply") {
+        let is_mains = fs::read_to_string(supply.join("type"))
+            .map(|t| t.trim() == "Mains")
+            .unwrap_or(false);
+        if is_mains && fs::read_to_string(supply.join("online")).map(|o| o.trim() == "1").unwrap_or(false) {
+            return true;
+        }
+    }
+    false
+}
+
 pub fn get_mount_info() -> Result<Vec<MountInfo>> {
     let sys = System::new();
     let mut mounts_info = Vec::new();
@@ -1210,20 +1927,82 @@ pub fn get_mount_info() -> Result<Vec<MountInfo>> {
     Ok(mounts_info)
 }
 
+// Optional attributes are probed all the time, only count real failures
+fn read_sysfs<P: AsRef<Path>>(path: P) -> std::io::Result<String> {
+    fs::read_to_string(path).inspect_err(|e| {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            crate::daemon_stats::record_sysfs_error();
+        }
+    })
+}
+
 fn read_sysfs_u64(path: &str) -> Result<u64> {
-    Ok(fs::read_to_string(path)?.trim().parse()?)
+    Ok(read_sysfs(path)?.trim().parse()?)
 }
 
 fn read_sysfs_i64(path: &str) -> Result<i64> {
-    Ok(fs::read_to_string(path)?.trim().parse()?)
+    Ok(read_sysfs(path)?.trim().parse()?)
 }
 
 fn read_sysfs_string(path: &str) -> Result<String> {
-    Ok(fs::read_to_string(path)?.trim().to_string())
+    Ok(read_sysfs(path)?.trim().to_string())
+}
+
+pub fn read_millidegrees(path: &Path) -> Option<f32> {
+    read_sysfs(path)
+        .ok()
+        .and_then(|s| s.trim().parse::<f32>().ok())
+        .map(|t| t / 1000.0)
+}
+
+pub fn sorted_dir_entries(path: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<_> = fs::read_dir(path)
+        .map(|rd| rd.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+// Collects the sensors of every provider into one list
+pub fn get_all_temperatures() -> Result<Vec<TemperatureSensor>> {
+    Ok(crate::sensor_providers::all_sensors())
+}
+
+pub fn read_temperature_sensor(id: &str) -> Result<f32> {
+    crate::sensor_providers::read(id)
+        .ok_or_else(|| anyhow!("Temperature sensor '{}' not found", id))
+}
+
+fn read_io_stats(device_path: &Path) -> Option<IoStats> {
+    // Fields 3 and 7 of /sys/block/*/stat, always counted in 512 byte sectors
+    let stat = fs::read_to_string(device_path.join("stat")).ok()?;
+    let fields: Vec<u64> = stat.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+    
+    Some(IoStats {
+        sampled_at: std::time::Instant::now(),
+        sectors_read: *fields.get(2)?,
+        sectors_written: *fields.get(6)?,
+    })
+}
+
+fn io_rates(prev: &IoStats, current: &IoStats) -> (f64, f64) {
+    let secs = current.sampled_at.duration_since(prev.sampled_at).as_secs_f64();
+    if secs <= 0.0 {
+        return (0.0, 0.0);
+    }
+    
+    let mb_s = |sectors: u64| sectors as f64 * 512.0 / 1_000_000.0 / secs;
+    (
+        mb_s(current.sectors_read.saturating_sub(prev.sectors_read)),
+        mb_s(current.sectors_written.saturating_sub(prev.sectors_written)),
+    )
 }
 
 pub fn get_storage_device_info() -> Result<Vec<StorageDevice>> {
     let mut storage_devices = Vec::new();
+    let mut previous_io_lock = PREVIOUS_IO_STATS.lock().unwrap();
+    let previous_io = previous_io_lock.take().unwrap_or_default();
+    let mut current_io = HashMap::new();
 
     for entry in std::fs::read_dir("/sys/block")? {
         let entry = entry?;
@@ -1263,13 +2042,30 @@ pub fn get_storage_device_info() -> Result<Vec<StorageDevice>> {
             }
         }
 
+        let (read_mb_s, write_mb_s) = match read_io_stats(&path) {
+            Some(current) => {
+                let rates = previous_io.get(&dev_name).map(|prev| io_rates(prev, &current));
+                current_io.insert(dev_name.clone(), current);
+                rates.unzip()
+            }
+            None => (None, None),
+        };
+
+        // SATA drives only have a hwmon sensor with the drivetemp module loaded
+        let smart = crate::smart::read_smart(&dev_name);
+        let temperature = temperature.or_else(|| smart.as_ref().and_then(|s| s.temperature));
+
         storage_devices.push(StorageDevice {
             device: format!("/dev/{}", dev_name),
             model,
             size_gb,
             temperature,
+            read_mb_s,
+            write_mb_s,
+            smart,
         });
     }
 
+    *previous_io_lock = Some(current_io);
     Ok(storage_devices)
 }
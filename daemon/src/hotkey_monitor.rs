@@ -0,0 +1,109 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{FanModeSource, HardwareEvent};
+use zbus::{Connection, SignalContext};
+use crate::dbus_interface::ControlInterface;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const PLATFORM_PROFILE: &str = "/sys/firmware/acpi/platform_profile";
+// The EC takes a moment to reflect a TDP profile the daemon set in platform_profile
+const OWN_WRITE_WINDOW: Duration = Duration::from_secs(3);
+// power-profiles-daemon, under its current and its old bus name
+const PPD_SERVICES: [(&str, &str, &str); 2] = [
+    ("org.freedesktop.UPower.PowerProfiles", "/org/freedesktop/UPower/PowerProfiles", "org.freedesktop.UPower.PowerProfiles"),
+    ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles", "net.hadess.PowerProfiles"),
+];
+
+static LAST_OWN_WRITE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// The daemon changed the EC profile, the platform_profile change that follows isn't a hotkey
+pub fn note_profile_write() {
+    *LAST_OWN_WRITE.lock().unwrap() = Some(Instant::now());
+}
+
+/// Fn hotkeys are handled by the EC without telling anyone, so poll the state they change
+pub async fn monitor_task(connection: Connection) {
+    let ctxt = match SignalContext::new(&connection, "/com/tuxedo/Control") {
+        Ok(ctxt) => ctxt,
+        Err(e) => {
+            log::warn!("Hotkey monitor disabled: {}", e);
+            return;
+        }
+    };
+
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last_profile = read_platform_profile();
+    let mut last_fan_mode = crate::fan_mode::get_status();
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("hotkey_monitor");
+
+        let profile = read_platform_profile();
+        if profile != last_profile {
+            if let Some(ref profile) = profile {
+                let own_write = LAST_OWN_WRITE.lock().unwrap()
+                    .is_some_and(|written| written.elapsed() < OWN_WRITE_WINDOW);
+                if own_write {
+                    log::debug!("Performance profile changed to {} by the daemon", profile);
+                } else if ppd_profile(&connection).await.as_deref() == Some(ppd_name(profile)) {
+                    // ppd wrote it, or follows the hotkey: either way the desktop's power menu
+                    // already shows it
+                    log::debug!("Performance profile changed to {}, power-profiles-daemon agrees", profile);
+                } else {
+                    log::info!("Performance profile changed to {} outside the daemon", profile);
+                    emit(&ctxt, HardwareEvent::PerformanceProfile(profile.clone())).await;
+                }
+            }
+            last_profile = profile;
+        }
+
+        let fan_mode = crate::fan_mode::get_status();
+        if fan_mode != last_fan_mode && fan_mode.set_by == FanModeSource::External {
+            log::info!("Fan mode changed to {:?} outside the daemon", fan_mode.mode);
+            emit(&ctxt, HardwareEvent::FanMode(fan_mode.mode)).await;
+        }
+        last_fan_mode = fan_mode;
+    }
+}
+
+pub async fn emit(ctxt: &SignalContext<'_>, event: HardwareEvent) {
+    let json = match serde_json::to_string(&event) {
+        Ok(json) => json,
+        Err(e) => {
+            log::warn!("Failed to serialize hardware event: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = ControlInterface::hardware_state_changed(ctxt, &json).await {
+        log::warn!("Failed to emit HardwareStateChanged: {}", e);
+    }
+}
+
+// ActiveProfile of power-profiles-daemon, None when it isn't running
+async fn ppd_profile(connection: &Connection) -> Option<String> {
+    for (service, path, interface) in PPD_SERVICES {
+        let Ok(proxy) = zbus::Proxy::new(connection, service, path, interface).await else {
+            continue;
+        };
+        if let Ok(profile) = proxy.get_property::<String>("ActiveProfile").await {
+            return Some(profile);
+        }
+    }
+    None
+}
+
+// ppd's name for a platform_profile choice
+fn ppd_name(platform_profile: &str) -> &'static str {
+    match platform_profile {
+        "low-power" | "quiet" | "cool" => "power-saver",
+        "performance" => "performance",
+        _ => "balanced",
+    }
+}
+
+fn read_platform_profile() -> Option<String> {
+    fs::read_to_string(PLATFORM_PROFILE).ok().map(|s| s.trim().to_string())
+}
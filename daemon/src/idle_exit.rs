@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+// With D-Bus activation the daemon doesn't have to run all the time: the bus starts it
+// on the first method call and it may exit again once nobody has called it for a while.
+// Anything that keeps working in the background after a call returns pins it in memory.
+
+pub const FLAG: &str = "--idle-exit";
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Idle timeout from `--idle-exit <seconds>`, None keeps the daemon running
+pub fn timeout_from_args() -> Option<Duration> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = args.iter()
+        .position(|arg| arg == FLAG)
+        .and_then(|i| args.get(i + 1))?;
+
+    match value.parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => {
+            log::warn!("Ignoring invalid {} value '{}'", FLAG, value);
+            None
+        }
+    }
+}
+
+/// Why the daemon has to stay up, None if it may exit
+fn active_work() -> Option<&'static str> {
+    if crate::FAN_DAEMON_STATE.lock().unwrap().is_some() {
+        return Some("custom fan curves");
+    }
+    if crate::dust_cleaning::is_running() {
+        return Some("dust cleaning");
+    }
+    if crate::auto_tuner::is_running() {
+        return Some("auto tuning");
+    }
+    if crate::ab_test::is_running() {
+        return Some("A/B test");
+    }
+    if crate::gpu_power::dynamic_limit_active() {
+        return Some("dynamic dGPU power limit");
+    }
+    if crate::cpu_power_source::has_battery_overrides() {
+        return Some("CPU battery overrides");
+    }
+    if crate::input_power::battery_backlight_active() {
+        return Some("keyboard backlight off on battery");
+    }
+    if crate::input_power::ambient_backlight_active() {
+        return Some("keyboard backlight following ambient light");
+    }
+    if crate::ambient_screen::is_active() {
+        return Some("screen brightness following ambient light");
+    }
+    if crate::ac_adapter::auto_cap_enabled() {
+        return Some("charger power limit cap");
+    }
+    if crate::charge_watchdog::is_active() {
+        return Some("charge threshold watchdog");
+    }
+    if crate::full_charge::is_active() {
+        return Some("full charge override");
+    }
+    if crate::fan_failure::is_active() {
+        return Some("fan failure");
+    }
+    if crate::power_source_profiles::is_enabled() {
+        return Some("AC/battery profile switching");
+    }
+    if crate::app_profiles::is_enabled() {
+        return Some("application profile switching");
+    }
+    None
+}
+
+/// Resolves once the daemon has been idle for `timeout` with nothing running in the background
+pub async fn wait(timeout: Duration) {
+    log::info!("Exiting after {} s without DBus calls", timeout.as_secs());
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if crate::daemon_stats::idle_for() < timeout {
+            continue;
+        }
+        match active_work() {
+            Some(reason) => log::debug!("Idle, but staying up for {}", reason),
+            None => {
+                log::info!("No DBus calls for {} s, exiting until activated again", timeout.as_secs());
+                return;
+            }
+        }
+    }
+}
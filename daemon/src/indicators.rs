@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tuxedo_common::types::IndicatorSettings;
+
+const LEDS_PATH: &str = "/sys/class/leds";
+// LED functions (the part after the last ':') that are status indicators
+const INDICATOR_FUNCTIONS: [&str; 4] = ["mute", "micmute", "power", "charging"];
+
+// Power LED breathing during suspend is run by the EC on its own, none of the current drivers
+// expose it, so only on and off are offered here.
+
+// Trigger and brightness each LED had before a profile turned it off
+#[derive(Clone)]
+struct OriginalState {
+    trigger: String,
+    brightness: String,
+}
+
+static ORIGINAL_STATES: once_cell::sync::Lazy<Mutex<HashMap<String, OriginalState>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// EC / platform indicator LEDs, keyboard backlights and lightbars are handled elsewhere
+pub fn indicator_leds() -> Vec<String> {
+    let entries = match fs::read_dir(LEDS_PATH) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut leds: Vec<String> = entries.flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let function = name.rsplit(':').next().unwrap_or("");
+            INDICATOR_FUNCTIONS.contains(&function) && !name.starts_with("input")
+        })
+        .collect();
+    leds.sort();
+    leds
+}
+
+pub fn apply_indicator_settings(settings: &IndicatorSettings) -> Result<()> {
+    let mut shared = ORIGINAL_STATES.lock().unwrap();
+    // A preview works on a copy, the real restore list has to survive it
+    let mut preview_copy = shared.clone();
+    let originals = if crate::dry_run::is_active() { &mut preview_copy } else { &mut *shared };
+    let leds = indicator_leds();
+
+    // Hand LEDs the profile no longer disables back to their trigger and brightness
+    let restore: Vec<String> = originals.keys()
+        .filter(|led| !settings.disabled_leds.contains(led))
+        .cloned()
+        .collect();
+    for led in restore {
+        let Some(original) = originals.remove(&led) else {
+            continue;
+        };
+        match restore_led(&led, &original) {
+            Ok(()) => log::info!("Restored LED {} to trigger {}", led, original.trigger),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    for led in &settings.disabled_leds {
+        if !leds.contains(led) {
+            log::warn!("Indicator LED {} not found", led);
+            continue;
+        }
+
+        if !originals.contains_key(led) {
+            originals.insert(led.clone(), OriginalState {
+                trigger: current_trigger(led)?,
+                brightness: read_led(led, "brightness")?,
+            });
+        }
+
+        write_led(led, "trigger", "none")?;
+        write_led(led, "brightness", "0")?;
+        log::info!("Turned off indicator LED {}", led);
+    }
+
+    Ok(())
+}
+
+// A trigger sets the brightness itself, without one the LED keeps what is written
+fn restore_led(led: &str, original: &OriginalState) -> Result<()> {
+    write_led(led, "trigger", &original.trigger)?;
+    if original.trigger == "none" {
+        write_led(led, "brightness", &original.brightness)?;
+    }
+    Ok(())
+}
+
+// The trigger file lists all triggers with the active one in brackets
+fn current_trigger(led: &str) -> Result<String> {
+    let triggers = read_led(led, "trigger")?;
+    triggers.split_whitespace()
+        .find_map(|t| t.strip_prefix('[').and_then(|t| t.strip_suffix(']')))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("No active trigger for LED {}", led))
+}
+
+fn read_led(led: &str, attribute: &str) -> Result<String> {
+    fs::read_to_string(format!("{}/{}/{}", LEDS_PATH, led, attribute))
+        .map(|value| value.trim().to_string())
+        .map_err(|e| anyhow!("Failed to read {} of LED {}: {}", attribute, led, e))
+}
+
+fn write_led(led: &str, attribute: &str, value: &str) -> Result<()> {
+    crate::dry_run::write(format!("{}/{}/{}", LEDS_PATH, led, attribute), value)
+        .map_err(|e| anyhow!("Failed to write {} of LED {}: {}", attribute, led, e))
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{InputSettings, KeyboardSettings};
+
+const MOUSEPOLL_PATH: &str = "/sys/module/usbhid/parameters/mousepoll";
+const USB_HID_CLASS: &str = "03";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+struct BacklightPolicy {
+    off_on_battery: bool,
+    keyboard: KeyboardSettings,
+    backlight_off: bool,
+    ambient_lux: crate::ambient_light::Smoothed,
+    // Brightness percent last applied for the ambient light, None until the first reading
+    ambient_percent: Option<u8>,
+}
+
+// Ambient changes smaller than this aren't worth rewriting the backlight for
+const AMBIENT_STEP: u8 = 10;
+
+impl BacklightPolicy {
+    fn follows_ambient_light(&self) -> bool {
+        self.keyboard.control_enabled && self.keyboard.ambient.enabled && crate::ambient_light::is_available()
+    }
+
+    fn keyboard_settings(&self) -> KeyboardSettings {
+        match self.ambient_percent {
+            Some(percent) if self.follows_ambient_light() => self.keyboard.scaled(percent),
+            _ => self.keyboard.clone(),
+        }
+    }
+}
+
+static BACKLIGHT_POLICY: once_cell::sync::Lazy<Mutex<Option<BacklightPolicy>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn battery_backlight_active() -> bool {
+    BACKLIGHT_POLICY.lock().unwrap().as_ref().is_some_and(|p| p.off_on_battery)
+}
+
+pub fn ambient_backlight_active() -> bool {
+    BACKLIGHT_POLICY.lock().unwrap().as_ref().is_some_and(|p| p.follows_ambient_light())
+}
+
+pub fn apply_input_settings(settings: &InputSettings, keyboard: &KeyboardSettings) -> Result<()> {
+    if let Some(interval) = settings.mouse_poll_interval {
+        if Path::new(MOUSEPOLL_PATH).exists() {
+            // Only affects devices bound after the change, i.e. re-plugged mice
+            crate::dry_run::write(MOUSEPOLL_PATH, interval.to_string())?;
+            log::info!("Set usbhid mouse poll interval to {} ms", interval);
+        } else {
+            log::info!("usbhid mousepoll parameter not available, skipping");
+        }
+    }
+
+    if let Some(enabled) = settings.usb_hid_autosuspend {
+        set_usb_hid_autosuspend(enabled)?;
+    }
+
+    if !crate::dry_run::is_active() {
+        *BACKLIGHT_POLICY.lock().unwrap() = Some(BacklightPolicy {
+            off_on_battery: settings.keyboard_backlight_off_on_battery,
+            keyboard: keyboard.clone(),
+            // apply_profile has just restored the keyboard settings
+            backlight_off: false,
+            ambient_lux: Default::default(),
+            ambient_percent: None,
+        });
+    }
+
+    Ok(())
+}
+
+fn set_usb_hid_autosuspend(enabled: bool) -> Result<()> {
+    let entries = match fs::read_dir("/sys/bus/usb/devices") {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    let control = if enabled { "auto" } else { "on" };
+    let mut count = 0;
+
+    for entry in entries.flatten() {
+        let device_path = entry.path();
+        if !device_path.join("power/control").exists() || !has_hid_interface(&device_path) {
+            continue;
+        }
+
+        if let Err(e) = crate::dry_run::write(device_path.join("power/control"), control) {
+            log::warn!("Failed to set autosuspend for {:?}: {}", device_path, e);
+        } else {
+            count += 1;
+        }
+    }
+
+    log::info!("Set USB autosuspend to '{}' for {} HID devices", control, count);
+    Ok(())
+}
+
+// Interfaces are subdirectories named <device>:<config>.<interface>
+fn has_hid_interface(device_path: &Path) -> bool {
+    fs::read_dir(device_path)
+        .map(|entries| entries.flatten().any(|entry| {
+            entry.file_name().to_string_lossy().contains(':')
+                && fs::read_to_string(entry.path().join("bInterfaceClass"))
+                    .map(|class| class.trim() == USB_HID_CLASS)
+                    .unwrap_or(false)
+        }))
+        .unwrap_or(false)
+}
+
+pub async fn battery_backlight_task() {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("keyboard_backlight");
+
+        let on_ac = crate::hardware_detection::is_on_ac_power();
+        let mut policy = BACKLIGHT_POLICY.lock().unwrap();
+        let policy = match policy.as_mut() {
+            Some(policy) => policy,
+            None => continue,
+        };
+
+        let should_be_off = policy.off_on_battery && !on_ac;
+        if should_be_off != policy.backlight_off {
+            let result = if should_be_off {
+                log::info!("On battery, turning keyboard backlight off");
+                crate::hardware_control::turn_off_keyboard_backlight()
+            } else {
+                log::info!("Restoring keyboard backlight");
+                crate::hardware_control::apply_keyboard_settings(&policy.keyboard_settings())
+            };
+
+            match result {
+                Ok(()) => policy.backlight_off = should_be_off,
+                Err(e) => log::warn!("Failed to update keyboard backlight: {}", e),
+            }
+            continue;
+        }
+
+        if policy.backlight_off || !policy.follows_ambient_light() {
+            continue;
+        }
+        if let Some(lux) = crate::ambient_light::read_lux() {
+            follow_ambient_light(policy, lux);
+        }
+    }
+}
+
+fn follow_ambient_light(policy: &mut BacklightPolicy, lux: f64) {
+    let lux = policy.ambient_lux.update(lux);
+    let ambient = &policy.keyboard.ambient;
+    let brightness = 1.0 - crate::ambient_light::position(lux, ambient.dark_lux, ambient.bright_lux);
+    let percent = (brightness * 100.0).round() as u8;
+
+    // Always follow into fully off and fully on, otherwise only in steps
+    let changed = match policy.ambient_percent {
+        Some(last) => last != percent
+            && (last.abs_diff(percent) >= AMBIENT_STEP || percent == 0 || percent == 100),
+        None => true,
+    };
+    if !changed {
+        return;
+    }
+
+    log::debug!("Ambient light {:.0} lx, keyboard backlight at {}%", lux, percent);
+    match crate::hardware_control::apply_keyboard_settings(&policy.keyboard.scaled(percent)) {
+        Ok(()) => policy.ambient_percent = Some(percent),
+        Err(e) => log::warn!("Failed to adjust keyboard backlight to ambient light: {}", e),
+    }
+}
@@ -0,0 +1,381 @@
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use serde_json::{json, Value as Json};
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use zbus::zvariant::{Structure, StructureBuilder, Value};
+use zbus::Connection;
+
+// Line-delimited JSON-RPC 2.0 on a Unix socket, for scripts and status bars where DBus is
+// awkward. Every request is forwarded to com.tuxedo.Control, so both surfaces stay identical.
+// Forwarded calls carry the uid of the socket peer (SO_PEERCRED): the write policy and the
+// root-only methods judge that process, not the daemon. The socket permissions only decide
+// who may connect at all.
+//
+//   echo '{"jsonrpc":"2.0","id":1,"method":"get_cpu_info"}' | socat - UNIX:/run/tuxedo-control-center/control.sock
+
+pub const FLAG: &str = "--json-rpc";
+// Members of this group may use the socket as well, otherwise it is root only
+pub const GROUP_FLAG: &str = "--json-rpc-group";
+const SOCKET_PATH: &str = "/run/tuxedo-control-center/control.sock";
+const SERVICE: &str = "com.tuxedo.Control";
+const OBJECT_PATH: &str = "/com/tuxedo/Control";
+const INTERFACE: &str = "com.tuxedo.Control";
+
+// The DBus default, a method that takes longer won't answer at all
+const CALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(25);
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+fn enabled_from_args() -> bool {
+    std::env::args().any(|arg| arg == FLAG)
+}
+
+fn group_from_args() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == GROUP_FLAG)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+struct Method {
+    name: String,
+    // (name, signature) of the in arguments, in call order
+    args: Vec<(String, String)>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// Bind the socket if --json-rpc is given. Called while the daemon is still root, so the
+/// socket can be handed to --json-rpc-group before privilege_drop gives up CAP_CHOWN.
+pub fn listen_from_args() -> Option<std::os::unix::net::UnixListener> {
+    if !enabled_from_args() {
+        return None;
+    }
+    match bind() {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            log::warn!("JSON-RPC socket disabled: {}", e);
+            None
+        }
+    }
+}
+
+pub async fn serve(listener: std::os::unix::net::UnixListener, connection: Connection) {
+    let listener = match listener.set_nonblocking(true).and_then(|_| UnixListener::from_std(listener)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("JSON-RPC socket disabled: {}", e);
+            return;
+        }
+    };
+    log::info!("JSON-RPC socket listening on {}", SOCKET_PATH);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_client(stream, connection.clone()));
+            }
+            Err(e) => log::warn!("JSON-RPC accept failed: {}", e),
+        }
+    }
+}
+
+// Bound under a temporary name and moved into place once the permissions are set,
+// so there is no moment where anyone could connect to it
+fn bind() -> Result<std::os::unix::net::UnixListener> {
+    let path = Path::new(SOCKET_PATH);
+    let dir = path.parent().ok_or_else(|| anyhow!("Invalid socket path"))?;
+    fs::create_dir_all(dir)?;
+    let staging = dir.join(".control.sock.new");
+    let _ = fs::remove_file(&staging);
+
+    let listener = std::os::unix::net::UnixListener::bind(&staging)?;
+    let mode = match group_from_args() {
+        Some(group) => {
+            let gid = lookup_group(&group)?;
+            let c_path = CString::new(staging.to_string_lossy().as_bytes())?;
+            if unsafe { libc::chown(c_path.as_ptr(), 0, gid) } != 0 {
+                return Err(anyhow!("Failed to hand the socket to group {}: {}", group, std::io::Error::last_os_error()));
+            }
+            0o660
+        }
+        None => 0o600,
+    };
+    fs::set_permissions(&staging, fs::Permissions::from_mode(mode))?;
+    fs::rename(&staging, path)?;
+    Ok(listener)
+}
+
+pub fn lookup_group(name: &str) -> Result<libc::gid_t> {
+    let c_name = CString::new(name)?;
+    let group = unsafe { libc::getgrnam(c_name.as_ptr()) };
+    if group.is_null() {
+        return Err(anyhow!("Unknown group '{}'", name));
+    }
+    Ok(unsafe { (*group).gr_gid })
+}
+
+async fn handle_client(stream: UnixStream, connection: Connection) {
+    // The write policy applies to whoever is on the other end, not to the daemon forwarding
+    let caller = match stream.peer_cred() {
+        Ok(cred) => crate::write_policy::Caller {
+            uid: Some(cred.uid()),
+            pid: cred.pid().and_then(|pid| u32::try_from(pid).ok()),
+            groups: None,
+        },
+        Err(e) => {
+            log::warn!("JSON-RPC client without credentials, closing: {}", e);
+            return;
+        }
+    };
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut methods: Option<Vec<Method>> = None;
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = handle_line(&line, &connection, &caller, &mut methods).await;
+        let mut out = response.to_string();
+        out.push('\n');
+        if writer.write_all(out.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn handle_line(line: &str, connection: &Connection, caller: &crate::write_policy::Caller,
+    methods: &mut Option<Vec<Method>>) -> Json {
+    let request: Json = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return error_response(Json::Null, RpcError::new(PARSE_ERROR, e.to_string())),
+    };
+    let id = request.get("id").cloned().unwrap_or(Json::Null);
+    let method = match request.get("method").and_then(Json::as_str) {
+        Some(method) => method,
+        None => return error_response(id, RpcError::new(INVALID_REQUEST, "Missing method")),
+    };
+
+    // Introspected once per client, the interface doesn't change while the daemon runs
+    if methods.is_none() {
+        match introspect(connection).await {
+            Ok(list) => *methods = Some(list),
+            Err(e) => return error_response(id, RpcError::new(SERVER_ERROR, e.to_string())),
+        }
+    }
+    let methods = methods.as_deref().unwrap_or_default();
+
+    let result = if method == "rpc.methods" {
+        Ok(Json::Array(methods.iter()
+            .map(|m| json!({
+                "name": m.name,
+                "params": m.args.iter().map(|(name, sig)| json!({ "name": name, "type": sig })).collect::<Vec<_>>(),
+            }))
+            .collect()))
+    } else {
+        call(connection, caller, methods, method, request.get("params")).await
+    };
+
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, e),
+    }
+}
+
+fn error_response(id: Json, error: RpcError) -> Json {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": error.code, "message": error.message } })
+}
+
+// get_cpu_info and GetCpuInfo both name the DBus method GetCpuInfo
+fn member_name(method: &str) -> String {
+    method.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+async fn call(connection: &Connection, caller: &crate::write_policy::Caller, methods: &[Method], method: &str,
+    params: Option<&Json>) -> Result<Json, RpcError> {
+    let name = member_name(method);
+    let method = methods.iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| RpcError::new(METHOD_NOT_FOUND, format!("Unknown method '{}'", method)))?;
+
+    // Positional or by argument name
+    let values: Vec<&Json> = match params {
+        None | Some(Json::Null) => Vec::new(),
+        Some(Json::Array(values)) => values.iter().collect(),
+        Some(Json::Object(map)) => method.args.iter()
+            .map(|(arg, _)| map.get(arg).ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing param '{}'", arg))))
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(RpcError::new(INVALID_PARAMS, "params must be an array or object")),
+    };
+    if values.len() != method.args.len() {
+        return Err(RpcError::new(INVALID_PARAMS,
+            format!("{} takes {} params, got {}", method.name, method.args.len(), values.len())));
+    }
+
+    let message = zbus::Message::method(OBJECT_PATH, method.name.as_str())
+        .and_then(|builder| builder.destination(SERVICE))
+        .and_then(|builder| builder.interface(INTERFACE));
+    let message = if values.is_empty() {
+        message.and_then(|builder| builder.build(&()))
+    } else {
+        let mut body = StructureBuilder::new();
+        for ((arg, signature), value) in method.args.iter().zip(values) {
+            let value = to_value(value, signature)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Param '{}' must be of DBus type '{}'", arg, signature)))?;
+            body = body.append_field(value);
+        }
+        message.and_then(|builder| builder.build(&body.build()))
+    };
+    let message = message.map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?;
+
+    let serial = message.primary_header().serial_num().get();
+    crate::write_policy::forwarding(serial, caller.clone());
+    let reply = tokio::time::timeout(CALL_TIMEOUT, send_and_wait(connection, &message)).await
+        .unwrap_or_else(|_| Err(zbus::Error::Failure(format!("{} timed out", method.name))));
+    crate::write_policy::forwarded(serial);
+    let reply = reply.map_err(|e| match e {
+        zbus::Error::MethodError(_, Some(message), _) => RpcError::new(SERVER_ERROR, message),
+        e => RpcError::new(SERVER_ERROR, e.to_string()),
+    })?;
+
+    let body = reply.body();
+    if body.signature().is_none_or(|s| s.is_empty()) {
+        return Ok(Json::Null);
+    }
+    let reply: Structure = body.deserialize().map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?;
+    let mut fields: Vec<Json> = reply.fields().iter().map(to_json).collect();
+    Ok(if fields.len() == 1 { fields.remove(0) } else { Json::Array(fields) })
+}
+
+// Sent as a prepared message rather than with call_method, the serial has to be known
+// beforehand so the write policy can find the caller it was sent for
+async fn send_and_wait(connection: &Connection, message: &zbus::Message) -> zbus::Result<zbus::Message> {
+    let serial = message.primary_header().serial_num();
+    // Subscribed before sending, the reply can't slip past
+    let mut incoming = zbus::MessageStream::from(connection);
+    connection.send(message).await?;
+
+    while let Some(reply) = incoming.next().await {
+        let reply = reply?;
+        if reply.header().reply_serial() != Some(serial) {
+            continue;
+        }
+        return match reply.message_type() {
+            zbus::message::Type::Error => Err(zbus::Error::from(reply)),
+            _ => Ok(reply),
+        };
+    }
+    Err(zbus::Error::Failure("Connection closed".to_string()))
+}
+
+fn to_value(value: &Json, signature: &str) -> Option<Value<'static>> {
+    Some(match signature {
+        "s" => Value::from(value.as_str()?.to_string()),
+        "b" => Value::from(value.as_bool()?),
+        "y" => Value::from(u8::try_from(value.as_u64()?).ok()?),
+        "q" => Value::from(u16::try_from(value.as_u64()?).ok()?),
+        "u" => Value::from(u32::try_from(value.as_u64()?).ok()?),
+        "t" => Value::from(value.as_u64()?),
+        "n" => Value::from(i16::try_from(value.as_i64()?).ok()?),
+        "i" => Value::from(i32::try_from(value.as_i64()?).ok()?),
+        "x" => Value::from(value.as_i64()?),
+        "d" => Value::from(value.as_f64()?),
+        _ => return None,
+    })
+}
+
+fn to_json(value: &Value) -> Json {
+    match value {
+        Value::U8(v) => json!(v),
+        Value::Bool(v) => json!(v),
+        Value::I16(v) => json!(v),
+        Value::U16(v) => json!(v),
+        Value::I32(v) => json!(v),
+        Value::U32(v) => json!(v),
+        Value::I64(v) => json!(v),
+        Value::U64(v) => json!(v),
+        Value::F64(v) => json!(v),
+        // Most methods return their data as a JSON document in a string
+        Value::Str(s) => match serde_json::from_str::<Json>(s.as_str()) {
+            Ok(parsed) if parsed.is_object() || parsed.is_array() => parsed,
+            _ => json!(s.as_str()),
+        },
+        Value::Array(array) => Json::Array(array.iter().map(to_json).collect()),
+        Value::Structure(structure) => Json::Array(structure.fields().iter().map(to_json).collect()),
+        Value::Value(inner) => to_json(inner),
+        other => json!(other.to_string()),
+    }
+}
+
+// The method list comes from the service's own introspection data, so new DBus methods
+// show up here without touching this module
+async fn introspect(connection: &Connection) -> Result<Vec<Method>> {
+    let reply = connection.call_method(
+        Some(SERVICE), OBJECT_PATH, Some("org.freedesktop.DBus.Introspectable"), "Introspect", &(),
+    ).await?;
+    let xml: String = reply.body().deserialize()?;
+    Ok(parse_methods(&xml))
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+fn parse_methods(xml: &str) -> Vec<Method> {
+    let interface_start = match xml.find(&format!("<interface name=\"{}\">", INTERFACE)) {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    let interface = &xml[interface_start..];
+    let interface = &interface[..interface.find("</interface>").unwrap_or(interface.len())];
+
+    let mut methods: Vec<Method> = Vec::new();
+    // Signals have arg tags too, only those between <method> and </method> count
+    let mut in_method = false;
+    for tag in interface.split('<').map(str::trim) {
+        if tag.starts_with("method ") {
+            if let Some(name) = attribute(tag, "name") {
+                methods.push(Method { name: name.to_string(), args: Vec::new() });
+                in_method = !tag.ends_with("/>");
+            }
+        } else if tag.starts_with("/method") {
+            in_method = false;
+        } else if in_method && tag.starts_with("arg ") && attribute(tag, "direction") != Some("out") {
+            if let (Some(method), Some(signature)) = (methods.last_mut(), attribute(tag, "type")) {
+                let name = attribute(tag, "name").unwrap_or("").to_string();
+                method.args.push((name, signature.to_string()));
+            }
+        }
+    }
+    methods
+}
@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{ClamshellOverride, LidStatus, Profile};
+use zbus::zvariant::OwnedValue;
+use zbus::Connection;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Connector types that belong to the built-in panel
+const INTERNAL_CONNECTORS: [&str; 3] = ["eDP", "LVDS", "DSI"];
+
+struct ProfileOverride {
+    clamshell: ClamshellOverride,
+    // The profile's own TDP profile, restored when the lid is opened again
+    base_tdp_profile: Option<String>,
+}
+
+static STATUS: once_cell::sync::Lazy<Mutex<LidStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(LidStatus::default()));
+static ACTIVE_OVERRIDE: once_cell::sync::Lazy<Mutex<Option<ProfileOverride>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn get_status() -> LidStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+// Called from apply_profile so the monitor knows what to apply on the next transition
+pub fn set_profile_override(profile: &Profile) {
+    *ACTIVE_OVERRIDE.lock().unwrap() = Some(ProfileOverride {
+        clamshell: profile.clamshell_override.clone(),
+        base_tdp_profile: profile.cpu_settings.tdp_profile.clone(),
+    });
+
+    let mut status = STATUS.lock().unwrap();
+    status.override_active = false;
+    if status.clamshell {
+        // Profile applied while already in clamshell mode
+        status.override_active = apply_override(true);
+    }
+}
+
+// Minimum fan speed enforced on custom fan curves, if a clamshell override is active
+pub fn fan_floor() -> Option<u8> {
+    if !STATUS.lock().unwrap().override_active {
+        return None;
+    }
+
+    ACTIVE_OVERRIDE.lock().unwrap()
+        .as_ref()
+        .map(|o| o.clamshell.fan_min_speed)
+        .filter(|floor| *floor > 0)
+}
+
+pub async fn monitor_task(connection: Connection) {
+    log::info!("Starting lid/dock monitor");
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("lid_monitor");
+
+        // logind does not emit change signals for LidClosed/Docked, so poll instead
+        let lid_closed = match logind_bool_property(&connection, "LidClosed").await {
+            Ok(closed) => closed,
+            Err(_) => acpi_lid_closed().unwrap_or(false),
+        };
+        let docked = logind_bool_property(&connection, "Docked").await.unwrap_or(false);
+        let external_display = external_display_connected();
+        let clamshell = lid_closed && (docked || external_display);
+
+        let mut status = STATUS.lock().unwrap();
+        if status.lid_closed != lid_closed || status.docked != docked {
+            log::info!("Lid {}, {}", if lid_closed { "closed" } else { "open" },
+                if docked { "docked" } else { "undocked" });
+        }
+
+        if status.clamshell != clamshell {
+            log::info!("Clamshell mode {}", if clamshell { "entered" } else { "left" });
+            status.override_active = apply_override(clamshell);
+        }
+
+        status.lid_closed = lid_closed;
+        status.docked = docked;
+        status.external_display = external_display;
+        status.clamshell = clamshell;
+    }
+}
+
+// Returns whether the override is active afterwards
+fn apply_override(clamshell: bool) -> bool {
+    let active = ACTIVE_OVERRIDE.lock().unwrap();
+    let profile_override = match active.as_ref() {
+        Some(o) if o.clamshell.enabled => o,
+        _ => return false,
+    };
+
+    let tdp_profile = if clamshell {
+        profile_override.clamshell.tdp_profile.as_ref()
+    } else {
+        profile_override.base_tdp_profile.as_ref()
+    };
+
+    // Only touch the TDP profile if the override actually changes it
+    if profile_override.clamshell.tdp_profile.is_some() {
+        if let Some(tdp) = tdp_profile {
+            if let Err(e) = crate::hardware_control::set_tdp_profile(tdp) {
+                log::warn!("Failed to apply clamshell TDP profile: {}", e);
+            }
+        }
+    }
+
+    clamshell
+}
+
+async fn logind_bool_property(connection: &Connection, name: &str) -> Result<bool> {
+    let proxy = zbus::Proxy::new(
+        connection,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.DBus.Properties",
+    ).await?;
+
+    let value: OwnedValue = proxy
+        .call("Get", &("org.freedesktop.login1.Manager", name))
+        .await?;
+    bool::try_from(value).map_err(|e| anyhow!("Invalid {} value: {}", name, e))
+}
+
+fn acpi_lid_closed() -> Option<bool> {
+    let entries = fs::read_dir("/proc/acpi/button/lid").ok()?;
+
+    for entry in entries.flatten() {
+        if let Ok(state) = fs::read_to_string(entry.path().join("state")) {
+            // Format: "state:      open"
+            return Some(state.contains("closed"));
+        }
+    }
+
+    None
+}
+
+fn external_display_connected() -> bool {
+    let entries = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Connectors are named card<N>-<type>-<index>, e.g. card1-HDMI-A-1
+        let connector = match name.split_once('-') {
+            Some((card, connector)) if card.starts_with("card") => connector.to_string(),
+            _ => return false,
+        };
+
+        if INTERNAL_CONNECTORS.iter().any(|c| connector.starts_with(c)) {
+            return false;
+        }
+
+        fs::read_to_string(entry.path().join("status"))
+            .map(|s| s.trim() == "connected")
+            .unwrap_or(false)
+    })
+}
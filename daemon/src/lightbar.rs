@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+use tuxedo_common::types::LightBarSettings;
+
+// tuxedo-drivers expose the lightbar as one LED per color channel plus an animation switch
+const CHANNELS: [&str; 3] = [
+    "/sys/class/leds/lightbar_rgb:1:status",
+    "/sys/class/leds/lightbar_rgb:2:status",
+    "/sys/class/leds/lightbar_rgb:3:status",
+];
+const ANIMATION: &str = "/sys/class/leds/lightbar_animation::status";
+
+pub fn is_available() -> bool {
+    CHANNELS.iter().all(|channel| Path::new(channel).join("brightness").exists())
+}
+
+pub fn brightness_path() -> String {
+    format!("{}/brightness", CHANNELS[0])
+}
+
+pub fn apply_lightbar_settings(settings: &LightBarSettings) -> Result<()> {
+    if !settings.control_enabled {
+        return Ok(());
+    }
+
+    if !is_available() {
+        log::info!("Lightbar not available, skipping");
+        return Ok(());
+    }
+
+    let animation = settings.on && settings.animation;
+    if Path::new(ANIMATION).exists() {
+        write_led(ANIMATION, if animation { 255 } else { 0 })?;
+    }
+
+    if animation {
+        log::info!("Set lightbar to animation");
+        return Ok(());
+    }
+
+    let color = if settings.on {
+        [settings.r, settings.g, settings.b]
+    } else {
+        [0, 0, 0]
+    };
+    for (channel, value) in CHANNELS.iter().zip(color) {
+        write_led(channel, value)?;
+    }
+
+    log::info!("Set lightbar to RGB({}, {}, {})", color[0], color[1], color[2]);
+    Ok(())
+}
+
+// value is 0-255, scaled to the LED's own range
+fn write_led(led: &str, value: u8) -> Result<()> {
+    let max: u32 = fs::read_to_string(format!("{}/max_brightness", led))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(255);
+
+    crate::dry_run::write(format!("{}/brightness", led), (value as u32 * max / 255).to_string())
+        .map_err(|e| anyhow!("Failed to write {}: {}", led, e))
+}
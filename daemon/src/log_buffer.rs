@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::{Level, Log, Metadata, Record};
+use tuxedo_common::types::{DaemonLog, LogEntry, LogLevel};
+
+// Recent log records kept in memory for the GUI's log viewer, so it doesn't need
+// journal access or a journalctl process. RUST_LOG still controls stderr/journal output.
+const MAX_ENTRIES: usize = 2000;
+// Our own debug output is useful in the viewer, other crates (zbus) only when something breaks
+const OWN_TARGET: &str = "tuxedo_daemon";
+const OWN_LEVEL: Level = Level::Debug;
+const FOREIGN_LEVEL: Level = Level::Warn;
+
+struct Buffer {
+    started: i64,
+    next_seq: u64,
+    entries: VecDeque<LogEntry>,
+}
+
+static BUFFER: once_cell::sync::Lazy<Mutex<Buffer>> = once_cell::sync::Lazy::new(|| {
+    Mutex::new(Buffer {
+        started: now_ms() / 1000,
+        next_seq: 1,
+        entries: VecDeque::with_capacity(MAX_ENTRIES),
+    })
+});
+
+struct BufferLogger {
+    stderr: env_logger::Logger,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+fn captured(metadata: &Metadata) -> bool {
+    if metadata.target().starts_with(OWN_TARGET) {
+        metadata.level() <= OWN_LEVEL
+    } else {
+        metadata.level() <= FOREIGN_LEVEL
+    }
+}
+
+fn to_level(level: Level) -> LogLevel {
+    match level {
+        Level::Error => LogLevel::Error,
+        Level::Warn => LogLevel::Warn,
+        Level::Info => LogLevel::Info,
+        Level::Debug => LogLevel::Debug,
+        Level::Trace => LogLevel::Trace,
+    }
+}
+
+impl Log for BufferLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || captured(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+
+        if !captured(record.metadata()) {
+            return;
+        }
+
+        let mut buffer = BUFFER.lock().unwrap();
+        let entry = LogEntry {
+            seq: buffer.next_seq,
+            timestamp_ms: now_ms(),
+            level: to_level(record.level()),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        buffer.next_seq += 1;
+        buffer.entries.push_back(entry);
+        while buffer.entries.len() > MAX_ENTRIES {
+            buffer.entries.pop_front();
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Replaces env_logger::init(), logs to stderr as before and into the ring buffer
+pub fn init() {
+    let stderr = env_logger::Builder::from_default_env().build();
+    let max_level = stderr.filter().max(OWN_LEVEL.to_level_filter());
+
+    if log::set_boxed_logger(Box::new(BufferLogger { stderr })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Records with a sequence number above `since`, oldest first
+pub fn get_entries(since: u64) -> DaemonLog {
+    let buffer = BUFFER.lock().unwrap();
+    DaemonLog {
+        started: buffer.started,
+        entries: buffer.entries.iter()
+            .filter(|e| e.seq > since)
+            .cloned()
+            .collect(),
+    }
+}
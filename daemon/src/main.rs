@@ -4,6 +4,61 @@ mod hardware_control;
 mod hardware_detection;
 mod tuxedo_io;
 mod battery_control;
+mod dust_cleaning;
+mod lid_monitor;
+mod gpu_power;
+mod input_power;
+mod fan_backend;
+mod fan_mode;
+mod network_control;
+mod power_session;
+mod smart;
+mod vm_tuning;
+mod cpu_power_source;
+mod daemon_stats;
+mod sensor_health;
+mod lightbar;
+mod indicators;
+mod hotkey_monitor;
+mod auto_tuner;
+mod event_log;
+mod acpi_call_fan;
+mod safe_mode;
+mod uniwill_keyboard;
+mod charging;
+mod ac_adapter;
+mod dry_run;
+mod idle_exit;
+mod log_buffer;
+mod charge_watchdog;
+mod full_charge;
+mod thermal_headroom;
+mod fan_failure;
+mod anomaly_capture;
+mod sensor_providers;
+mod json_rpc;
+mod ambient_light;
+mod ambient_screen;
+mod apply_timing;
+mod write_policy;
+mod system_profiles;
+mod fan_curve_file;
+mod fan_min_duty;
+mod module_reload;
+mod pci_ids;
+mod apply_pipeline;
+mod telemetry;
+mod privileges;
+mod power_source_profiles;
+mod app_profiles;
+mod privilege_drop;
+mod sandbox;
+mod ec_dump;
+mod profile_usage;
+mod ab_test;
+mod sleep_inhibit;
+mod metrics;
+mod aquaris;
 
 use anyhow::Result;
 use tokio::signal;
@@ -14,19 +69,119 @@ use tuxedo_common::types::FanSettings;
 pub static FAN_DAEMON_STATE: once_cell::sync::Lazy<Arc<Mutex<Option<FanSettings>>>> = 
     once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
+fn main() -> Result<()> {
+    log_buffer::init();
+
+    // Bound while still root, the socket may belong to --json-rpc-group
+    let json_rpc_listener = json_rpc::listen_from_args();
+
+    // Before the runtime starts its worker threads, they inherit whatever is left
+    if let Some(user) = privilege_drop::user_from_args() {
+        tuxedo_io::TuxedoIo::open_early();
+        if let Err(e) = privilege_drop::apply(&user) {
+            log::error!("Cannot drop privileges to {}: {}", user, e);
+            std::process::exit(1);
+        }
+    }
+    sandbox::apply();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(json_rpc_listener))
+}
+
+async fn run(json_rpc_listener: Option<std::os::unix::net::UnixListener>) -> Result<()> {
+    // CLI escape hatch, talks to the running daemon and doesn't need root
+    if std::env::args().any(|arg| arg == "--panic-revert") {
+        return panic_revert_cli().await;
+    }
+    if let Some(arg) = std::env::args().find(|arg| arg == "--charge-full" || arg == "--charge-full-cancel") {
+        return full_charge_cli(arg == "--charge-full").await;
+    }
+
     log::info!("Starting TUXEDO Control Center Daemon");
 
-    // Check if running as root
-    if unsafe { libc::geteuid() } != 0 {
-        eprintln!("Error: Daemon must run as root");
-        std::process::exit(1);
+    // Without root, or the capabilities root normally has, only monitoring works
+    privileges::init();
+    let restore = if privileges::monitor_only() {
+        None
+    } else {
+        start_control().await
+    };
+
+    // Log thermal throttling
+    tokio::spawn(async move {
+        event_log::throttle_monitor_task().await;
+    });
+
+    // Keep recent sensor readings for the bundles written when a failsafe triggers
+    tokio::spawn(async move {
+        anomaly_capture::sampler_task().await;
+    });
+
+    // Warn about underpowered chargers and cap the CPU limit if a profile asks for it
+    tokio::spawn(async move {
+        ac_adapter::monitor_task().await;
+    });
+
+    // Track energy used since the last unplug
+    tokio::spawn(async move {
+        power_session::session_task().await;
+    });
+
+    // Start DBus service
+    write_policy::init();
+    let connection = zbus::Connection::system().await?;
+
+    // Watch lid and dock state for clamshell overrides
+    let lid_connection = connection.clone();
+    tokio::spawn(async move {
+        lid_monitor::monitor_task(lid_connection).await;
+    });
+
+    // Follow the ambient light sensor with the screen brightness
+    tokio::spawn(ambient_screen::monitor_task());
+
+    // Optional JSON-RPC socket mirroring the DBus API, forwarded over this connection
+    if let Some(listener) = json_rpc_listener {
+        tokio::spawn(json_rpc::serve(listener, connection.clone()));
+    }
+
+    // Optional Prometheus endpoint with the GetDaemonStats numbers
+    if let Some(address) = metrics::address_from_args() {
+        tokio::spawn(metrics::serve(address));
     }
 
+    // The service runs until a shutdown signal arrives, systemd stops it with SIGTERM
+    let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate())?;
+    let idle_timeout = idle_exit::timeout_from_args();
+    let idle = async {
+        match idle_timeout {
+            Some(timeout) => idle_exit::wait(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = dbus_interface::start_service(connection.clone(), restore) => result?,
+        result = signal::ctrl_c() => result?,
+        _ = sigterm.recv() => {}
+        _ = idle => {}
+    }
+    log::info!("Shutting down daemon");
+    if !privileges::monitor_only() {
+        safe_mode::clean_shutdown();
+        profile_usage::flush();
+    }
+
+    Ok(())
+}
+
+// Hardware setup and background control loops, all of which write. Returns the profile to
+// restore, applied once the DBus service is up so clients hear about it.
+async fn start_control() -> Option<tuxedo_common::types::Profile> {
     // Initialize hardware interfaces
-    let tuxedo_io = if tuxedo_io::TuxedoIo::is_available() {
+    if tuxedo_io::TuxedoIo::is_available() {
         match tuxedo_io::TuxedoIo::new() {
             Ok(io) => {
                 let interface = match io.get_interface() {
@@ -36,134 +191,131 @@ async fn main() -> Result<()> {
                 };
                 log::info!("Detected hardware interface: {}", interface);
                 log::info!("Number of fans: {}", io.get_fan_count());
-                Some(io)
             }
             Err(e) => {
                 log::warn!("Failed to initialize tuxedo_io: {}", e);
-                None
             }
         }
     } else {
         log::warn!("/dev/tuxedo_io not available - some features will be disabled");
-        None
-    };
+    }
 
     // Check battery charge control
-    if battery_control::BatteryControl::is_available() {
-        log::info!("Battery charge control (flexicharger) is available");
-    } else {
-        log::info!("Battery charge control not available");
+    match battery_control::BatteryControl::new() {
+        Ok(battery) => log::info!("Battery charge control backend: {}", battery.backend().label()),
+        Err(_) => log::info!("Battery charge control not available"),
     }
 
     // Start fan daemon in background
-    if let Some(io) = tuxedo_io {
-        let fan_io = Arc::new(io);
+    if let Some(backend) = fan_backend::detect() {
+        log::info!("Fan control backend: {} ({} fans)", backend.name(), backend.fan_count());
         tokio::spawn(async move {
-            fan_daemon_task(fan_io).await;
+            fan_daemon::run(backend).await;
         });
     }
 
-    // Start DBus service
-    let connection = zbus::Connection::system().await?;
-    let _service = dbus_interface::start_service(connection.clone()).await?;
+    // Start dGPU power limit controller (idle unless a profile enables dynamic mode), it
+    // looks for the GPU itself since a docked one may appear later
+    tokio::spawn(async move {
+        gpu_power::dynamic_power_task().await;
+    });
 
-    log::info!("DBus service started");
+    // Turn the keyboard backlight off on battery or dim it with ambient light if the profile asks for it
+    tokio::spawn(async move {
+        input_power::battery_backlight_task().await;
+    });
 
-    // Wait for shutdown signal
-    signal::ctrl_c().await?;
-    log::info!("Shutting down daemon");
+    // Switch CPU settings between AC and battery values
+    tokio::spawn(async move {
+        cpu_power_source::power_source_task().await;
+    });
 
-    Ok(())
-}
+    // Re-apply charge thresholds the firmware resets
+    tokio::spawn(async move {
+        charge_watchdog::watchdog_task().await;
+    });
 
-async fn fan_daemon_task(io: Arc<tuxedo_io::TuxedoIo>) {
-    log::info!("Starting fan control daemon");
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(2));
-    let mut last_settings: Option<FanSettings> = None;
-    let mut sorted_curves: Vec<Vec<(u8, u8)>> = Vec::new();
-
-    loop {
-        interval.tick().await;
-
-        let settings = {
-            let state = FAN_DAEMON_STATE.lock().unwrap();
-            state.clone()
-        };
-
-        if settings != last_settings {
-            if let Some(ref s) = settings {
-                sorted_curves = s.curves.iter().map(|c| {
-                    let mut points = c.points.clone();
-                    points.sort_by_key(|p| p.0);
-                    points
-                }).collect();
-            }
-            last_settings = settings;
-        }
+    // Restore the thresholds after a one-off charge to 100%
+    tokio::spawn(async move {
+        full_charge::monitor_task().await;
+    });
 
-        if let Some(ref fan_settings) = last_settings {
-            if fan_settings.control_enabled {
-                if let Err(e) = apply_fan_curves(&io, fan_settings, &sorted_curves) {
-                    log::error!("Failed to apply fan curves: {}", e);
+    // Count switches, time and temperatures per profile for the usage page
+    tokio::spawn(profile_usage::sampler_task());
+
+    // Re-apply driver-held settings when a DKMS upgrade reloads the TUXEDO modules
+    module_reload::start();
+
+    // Fan curves from /etc for installs without the GUI, reloaded on SIGHUP or when edited
+    fan_curve_file::init();
+    tokio::spawn(fan_curve_file::watch_task());
+
+    // Restore the last applied profile, unless it keeps crashing the daemon.
+    // On the first start after boot the administrator's boot profile goes first.
+    let restore = safe_mode::startup();
+    let restore = if safe_mode::is_active() {
+        None
+    } else {
+        system_profiles::boot_profile_due().or(restore)
+    };
+    match restore {
+        Some(profile) => return Some(profile),
+        None if safe_mode::is_active() => {
+            if fan_backend::is_available() {
+                if let Err(e) = hardware_control::set_fan_auto(0) {
+                    log::warn!("Failed to set fans to auto in safe mode: {}", e);
                 }
             }
         }
+        None => fan_curve_file::apply_without_profile(),
     }
+    None
 }
 
-fn apply_fan_curves(io: &tuxedo_io::TuxedoIo, settings: &FanSettings, sorted_curves: &[Vec<(u8, u8)>]) -> Result<()> {
-    for (i, curve) in settings.curves.iter().enumerate() {
-        if curve.fan_id >= io.get_fan_count() {
-            continue;
+async fn panic_revert_cli() -> Result<()> {
+    let connection = zbus::Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    match proxy.call::<_, _, ()>("PanicRevert", &()).await {
+        Ok(()) => {
+            println!("Reverted to Standard profile, fans on automatic");
+            Ok(())
         }
-        
-        let temp = match io.get_fan_temperature(curve.fan_id) {
-            Ok(t) => t as f32,
-            Err(e) => {
-                log::warn!("Failed to read fan {} temperature: {}", curve.fan_id, e);
-                continue;
-            }
-        };
-        
-        let speed = calculate_fan_speed(&sorted_curves[i], temp);
-        
-        if let Err(e) = io.set_fan_speed(curve.fan_id, speed as u32) {
-            log::error!("Failed to set fan {} speed: {}", curve.fan_id, e);
-        } else {
-            log::debug!("Fan {}: temp={}°C, speed={}%", curve.fan_id, temp, speed);
+        Err(e) => {
+            eprintln!("Panic revert failed: {}", e);
+            std::process::exit(1);
         }
     }
-    
-    Ok(())
 }
 
-fn calculate_fan_speed(sorted_points: &[(u8, u8)], temp: f32) -> u8 {
-    if sorted_points.is_empty() {
-        return 50; // Default fallback
-    }
-    
-    if sorted_points.len() == 1 {
-        return sorted_points[0].1;
-    }
-    
-    if temp <= sorted_points[0].0 as f32 {
-        return sorted_points[0].1;
-    }
-    
-    if temp >= sorted_points[sorted_points.len() - 1].0 as f32 {
-        return sorted_points[sorted_points.len() - 1].1;
-    }
-    
-    for i in 0..sorted_points.len() - 1 {
-        let (temp1, speed1) = sorted_points[i];
-        let (temp2, speed2) = sorted_points[i + 1];
-        
-        if temp >= temp1 as f32 && temp <= temp2 as f32 {
-            let ratio = (temp - temp1 as f32) / (temp2 as f32 - temp1 as f32);
-            let speed = speed1 as f32 + ratio * (speed2 as f32 - speed1 as f32);
-            return speed.round() as u8;
+async fn full_charge_cli(start: bool) -> Result<()> {
+    let connection = zbus::Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let method = if start { "StartFullCharge" } else { "CancelFullCharge" };
+    match proxy.call::<_, _, ()>(method, &()).await {
+        Ok(()) if start => {
+            println!("Charging to 100% once, thresholds are restored when full or unplugged");
+            Ok(())
+        }
+        Ok(()) => {
+            println!("Full charge cancelled, thresholds restored");
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("Full charge override failed: {}", e);
+            std::process::exit(1);
         }
     }
-    
-    50 // Fallback
 }
+
@@ -0,0 +1,124 @@
+use std::fmt::Write as _;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tuxedo_common::types::DaemonStats;
+
+// GetDaemonStats in the Prometheus text format, for scraping next to node_exporter:
+//
+//   tuxedo-daemon --metrics 127.0.0.1:9853
+//   curl http://127.0.0.1:9853/metrics
+//
+// Off unless the flag is given. The packaged unit only allows AF_UNIX and AF_NETLINK sockets,
+// a drop-in adding AF_INET (and AF_INET6) to RestrictAddressFamilies is needed as well.
+
+pub const FLAG: &str = "--metrics";
+// A scrape request is a single GET, anything longer isn't one
+const MAX_REQUEST: usize = 8192;
+
+pub fn address_from_args() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == FLAG)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+pub async fn serve(address: String) {
+    let listener = match TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("Metrics endpoint disabled, cannot listen on {}: {}", address, e);
+            return;
+        }
+    };
+    log::info!("Metrics endpoint listening on http://{}/metrics", address);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_client(stream));
+            }
+            Err(e) => log::warn!("Metrics accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_client(mut stream: TcpStream) {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buffer[..n]),
+        }
+    }
+
+    let request_line = String::from_utf8_lossy(&request);
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = render(&crate::daemon_stats::get_stats());
+            format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(), body)
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    };
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+fn render(stats: &DaemonStats) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP tuxedo_daemon_uptime_seconds Time since the DBus service started");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_uptime_seconds gauge");
+    let _ = writeln!(out, "tuxedo_daemon_uptime_seconds {}", stats.uptime_secs);
+    let _ = writeln!(out, "# HELP tuxedo_daemon_sysfs_read_errors_total Failed sysfs reads, missing files not counted");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_sysfs_read_errors_total counter");
+    let _ = writeln!(out, "tuxedo_daemon_sysfs_read_errors_total {}", stats.sysfs_read_errors);
+
+    let _ = writeln!(out, "# HELP tuxedo_daemon_task_ticks_total Loop iterations of a background task");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_task_ticks_total counter");
+    for task in &stats.tasks {
+        let _ = writeln!(out, "tuxedo_daemon_task_ticks_total{{task=\"{}\"}} {}", escape(&task.name), task.ticks);
+    }
+    let _ = writeln!(out, "# HELP tuxedo_daemon_task_tick_seconds Duration of a loop iteration, last, average and maximum");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_task_tick_seconds gauge");
+    for task in &stats.tasks {
+        for (stat, millis) in [("last", task.last_ms), ("avg", task.avg_ms), ("max", task.max_ms)] {
+            let _ = writeln!(out, "tuxedo_daemon_task_tick_seconds{{task=\"{}\",stat=\"{}\"}} {}",
+                escape(&task.name), stat, millis / 1000.0);
+        }
+    }
+
+    let _ = writeln!(out, "# HELP tuxedo_daemon_dbus_calls_total Method calls on com.tuxedo.Control");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_dbus_calls_total counter");
+    for call in &stats.dbus_calls {
+        let _ = writeln!(out, "tuxedo_daemon_dbus_calls_total{{method=\"{}\"}} {}", escape(&call.method), call.count);
+    }
+
+    let _ = writeln!(out, "# HELP tuxedo_daemon_sensor_failures_total Failed reads of a sensor");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_sensor_failures_total counter");
+    for sensor in &stats.sensor_failures {
+        let _ = writeln!(out, "tuxedo_daemon_sensor_failures_total{{sensor=\"{}\"}} {}",
+            escape(&sensor.sensor), sensor.total_failures);
+    }
+
+    let _ = writeln!(out, "# HELP tuxedo_daemon_charge_threshold_resets_total Charge thresholds the firmware reset");
+    let _ = writeln!(out, "# TYPE tuxedo_daemon_charge_threshold_resets_total counter");
+    let _ = writeln!(out, "tuxedo_daemon_charge_threshold_resets_total {}", stats.charge_watchdog.resets);
+    out
+}
+
+// Label values are quoted, with backslash, quote and newline escaped
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape("hwmon\\temp\"1\"\n"), "hwmon\\\\temp\\\"1\\\"\\n");
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tuxedo_common::types::EventKind;
+
+// A DKMS upgrade reloads the TUXEDO modules and they come back with their defaults: fans on
+// the EC table, keyboard backlight at the firmware colour, TDP profile reset. The kernel
+// announces module and device additions on the uevent netlink socket, the same messages udev
+// processes, and the driver-held parts of the active profile are re-applied then, after any
+// apply that is running.
+const WATCHED_MODULES: [&str; 5] = ["tuxedo_io", "tuxedo_keyboard", "clevo_acpi", "clevo_wmi", "uniwill_wmi"];
+const WATCHED_DEVICES: [&str; 1] = ["tuxedo_io"];
+// One reload sends a burst of events (modules, platform devices, LEDs), re-apply once after it
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+// Kernel uevents, udev's own rebroadcast uses group 2
+const KERNEL_GROUP: u32 = 1;
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static HOTPLUG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped on every reload, holders of an open device handle reopen it when this changes
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Bumped when a hwmon device is added or removed, a USB fan controller or a docked GPU
+pub fn hotplug_generation() -> u64 {
+    HOTPLUG_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Called from the runtime, the re-apply goes through its apply pipeline
+pub fn start() {
+    let runtime = Handle::current();
+    std::thread::spawn(move || watch(runtime));
+}
+
+fn watch(runtime: Handle) {
+    let fd = unsafe {
+        libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_KOBJECT_UEVENT)
+    };
+    if fd < 0 {
+        log::warn!("Module reload detection disabled: {}", std::io::Error::last_os_error());
+        return;
+    }
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = KERNEL_GROUP;
+    let bound = unsafe {
+        libc::bind(fd, &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t)
+    };
+    if bound < 0 {
+        log::warn!("Module reload detection disabled: {}", std::io::Error::last_os_error());
+        unsafe { libc::close(fd) };
+        return;
+    }
+
+    let mut buffer = [0u8; 8192];
+    loop {
+        let reloaded = match receive(fd, &mut buffer, 0) {
+            Ok(message) => {
+                if is_hwmon_change(message) {
+                    HOTPLUG_GENERATION.fetch_add(1, Ordering::SeqCst);
+                }
+                reloaded_driver(message)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            // The socket buffer overflowed and events were dropped, later ones still arrive
+            Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                log::debug!("Missed uevents: {}", e);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Module reload detection stopped: {}", e);
+                break;
+            }
+        };
+        let Some(driver) = reloaded else {
+            continue;
+        };
+
+        std::thread::sleep(SETTLE_TIME);
+        while receive(fd, &mut buffer, libc::MSG_DONTWAIT).is_ok() {}
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+        log::info!("{} was reloaded", driver);
+        reapply(&runtime, &driver);
+    }
+    unsafe { libc::close(fd) };
+}
+
+fn receive(fd: i32, buffer: &mut [u8], flags: i32) -> std::io::Result<&[u8]> {
+    let len = unsafe { libc::recv(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), flags) };
+    if len < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(&buffer[..len as usize])
+}
+
+fn is_hwmon_change(message: &[u8]) -> bool {
+    let fields: Vec<&[u8]> = message.split(|&b| b == 0).collect();
+    fields.contains(&&b"SUBSYSTEM=hwmon"[..])
+        && (fields.contains(&&b"ACTION=add"[..]) || fields.contains(&&b"ACTION=remove"[..]))
+}
+
+// "add@/module/tuxedo_io\0ACTION=add\0DEVPATH=/module/tuxedo_io\0SUBSYSTEM=module\0..."
+fn reloaded_driver(message: &[u8]) -> Option<String> {
+    let fields: Vec<&str> = message.split(|&b| b == 0)
+        .filter_map(|field| std::str::from_utf8(field).ok())
+        .collect();
+    let value = |key: &str| fields.iter().find_map(|field| field.strip_prefix(key)?.strip_prefix('='));
+    if value("ACTION") != Some("add") {
+        return None;
+    }
+
+    match value("SUBSYSTEM")? {
+        "module" => {
+            let name = value("DEVPATH")?.strip_prefix("/module/")?;
+            WATCHED_MODULES.contains(&name).then(|| format!("Module {}", name))
+        }
+        "misc" => {
+            let name = value("DEVNAME")?;
+            WATCHED_DEVICES.contains(&name).then(|| format!("/dev/{}", name))
+        }
+        _ => None,
+    }
+}
+
+fn reapply(runtime: &Handle, driver: &str) {
+    if crate::safe_mode::is_active() {
+        log::info!("Safe mode is active, not re-applying a profile");
+        return;
+    }
+    let Some((name, result)) = runtime.block_on(crate::apply_pipeline::reapply_driver_settings()) else {
+        return;
+    };
+
+    match result {
+        Ok(()) => {
+            log::info!("Re-applied fan, keyboard and TDP settings of '{}'", name);
+            crate::event_log::record(EventKind::ProfileSwitch,
+                format!("{} was reloaded, re-applied '{}'", driver, name));
+        }
+        Err(e) => log::warn!("Failed to re-apply '{}' after a module reload: {}", name, e),
+    }
+}
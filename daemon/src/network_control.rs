@@ -0,0 +1,175 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::process::Command;
+use std::sync::Mutex;
+use tuxedo_common::types::{NetworkSettings, RadioType};
+
+// What the previous profile turned off, so it can be turned back on
+#[derive(Default, Clone)]
+struct DisabledState {
+    interfaces: Vec<String>,
+    radios: Vec<RadioType>,
+}
+
+static DISABLED: once_cell::sync::Lazy<Mutex<DisabledState>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(DisabledState::default()));
+
+pub fn apply_network_settings(settings: &NetworkSettings) -> Result<()> {
+    apply_interface_state(settings);
+    apply_radio_state(settings);
+
+    if let Some(enabled) = settings.wifi_power_save {
+        for interface in wireless_interfaces() {
+            // A missing iw binary or unsupported driver shouldn't fail the whole profile
+            match set_wifi_power_save(&interface, enabled) {
+                Ok(()) => log::info!("Set WiFi power save on {} to {}", interface,
+                    if enabled { "on" } else { "off" }),
+                Err(e) => log::warn!("Failed to set WiFi power save on {}: {}", interface, e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_interface_state(settings: &NetworkSettings) {
+    let mut shared = DISABLED.lock().unwrap();
+    // A preview works on a copy, the real restore list has to survive it
+    let mut preview_copy = shared.clone();
+    let disabled = if crate::dry_run::is_active() { &mut preview_copy } else { &mut *shared };
+    let controllable = controllable_interfaces();
+
+    for interface in &disabled.interfaces {
+        if !settings.disabled_interfaces.contains(interface) {
+            match set_interface_up(interface, true) {
+                Ok(()) => log::info!("Brought network interface {} back up", interface),
+                Err(e) => log::warn!("Failed to bring {} up: {}", interface, e),
+            }
+        }
+    }
+
+    disabled.interfaces.clear();
+    for interface in &settings.disabled_interfaces {
+        // Profiles are user supplied, never touch loopback, bridges, VPNs and the like
+        if !controllable.contains(interface) {
+            log::warn!("Refusing to disable {}: not a physical network interface", interface);
+            continue;
+        }
+
+        match set_interface_up(interface, false) {
+            Ok(()) => {
+                log::info!("Brought network interface {} down", interface);
+                disabled.interfaces.push(interface.clone());
+            }
+            Err(e) => log::warn!("Failed to bring {} down: {}", interface, e),
+        }
+    }
+}
+
+fn apply_radio_state(settings: &NetworkSettings) {
+    let mut shared = DISABLED.lock().unwrap();
+    let mut preview_copy = shared.clone();
+    let disabled = if crate::dry_run::is_active() { &mut preview_copy } else { &mut *shared };
+
+    for radio in &disabled.radios {
+        if !settings.blocked_radios.contains(radio) {
+            if let Err(e) = set_rfkill_block(*radio, false) {
+                log::warn!("Failed to unblock {:?}: {}", radio, e);
+            }
+        }
+    }
+
+    disabled.radios.clear();
+    for radio in &settings.blocked_radios {
+        match set_rfkill_block(*radio, true) {
+            Ok(()) => disabled.radios.push(*radio),
+            Err(e) => log::warn!("Failed to block {:?}: {}", radio, e),
+        }
+    }
+}
+
+/// Physical ethernet and WiFi interfaces that profiles may bring down
+pub fn controllable_interfaces() -> Vec<String> {
+    let entries = match fs::read_dir("/sys/class/net") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut interfaces: Vec<String> = entries.flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            // Virtual interfaces have no backing device, type 1 is ARPHRD_ETHER (also WiFi)
+            path.join("device").exists()
+                && fs::read_to_string(path.join("type")).map(|t| t.trim() == "1").unwrap_or(false)
+        })
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    interfaces.sort();
+    interfaces
+}
+
+fn set_interface_up(interface: &str, up: bool) -> Result<()> {
+    if crate::dry_run::command("ip", &["link", "set", interface, if up { "up" } else { "down" }]) {
+        return Ok(());
+    }
+    let output = crate::privilege_drop::grant(Command::new("ip")
+        .args(["link", "set", interface, if up { "up" } else { "down" }]), &[crate::privilege_drop::CAP_NET_ADMIN])
+        .output()
+        .map_err(|e| anyhow!("Failed to run ip: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!("ip failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(())
+}
+
+fn set_rfkill_block(radio: RadioType, blocked: bool) -> Result<()> {
+    let mut found = false;
+
+    for entry in fs::read_dir("/sys/class/rfkill")?.flatten() {
+        let path = entry.path();
+        let kind = fs::read_to_string(path.join("type")).unwrap_or_default();
+        if kind.trim() != radio.rfkill_type() {
+            continue;
+        }
+
+        crate::dry_run::write(path.join("soft"), if blocked { "1" } else { "0" })?;
+        found = true;
+    }
+
+    if !found && blocked {
+        return Err(anyhow!("No {} rfkill switch found", radio.rfkill_type()));
+    }
+
+    log::info!("{} {:?} radios", if blocked { "Blocked" } else { "Unblocked" }, radio);
+    Ok(())
+}
+
+pub fn set_wifi_power_save(interface: &str, enabled: bool) -> Result<()> {
+    if crate::dry_run::command("iw", &["dev", interface, "set", "power_save", if enabled { "on" } else { "off" }]) {
+        return Ok(());
+    }
+    let output = crate::privilege_drop::grant(Command::new("iw")
+        .args(["dev", interface, "set", "power_save", if enabled { "on" } else { "off" }]), &[crate::privilege_drop::CAP_NET_ADMIN])
+        .output()
+        .map_err(|e| anyhow!("Failed to run iw: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!("iw failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(())
+}
+
+fn wireless_interfaces() -> Vec<String> {
+    let entries = match fs::read_dir("/sys/class/net") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries.flatten()
+        .filter(|entry| entry.path().join("wireless").exists())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect()
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+
+// GPU names as they are sold, "AMD Radeon 780M" rather than "AMD GPU 0". Sources in order:
+//   - the NVIDIA driver's information file for the GPU, the same name nvidia-smi prints
+//   - libdrm's amdgpu.ids, APUs sharing a device id differ by revision (780M vs 760M)
+//   - the pci.ids database of hwdata or pciutils, its [bracketed] names are the product ones
+//   - the built-in table below, for minimal installs without either file
+//   - the code name from pci.ids ("Phoenix1"), which is at least specific
+// The files are a megabyte or more, names are looked up once per device.
+const AMDGPU_IDS: &str = "/usr/share/libdrm/amdgpu.ids";
+const PCI_IDS: [&str; 3] = ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids", "/usr/share/pci.ids"];
+const NVIDIA_GPUS: &str = "/proc/driver/nvidia/gpus";
+
+const VENDOR_AMD: u16 = 0x1002;
+const VENDOR_NVIDIA: u16 = 0x10de;
+const VENDOR_INTEL: u16 = 0x8086;
+
+// GPUs in current TUXEDO and Clevo laptops: (vendor, device, name)
+const BUILT_IN: &[(u16, u16, &str)] = &[
+    (VENDOR_INTEL, 0x9a49, "Intel Iris Xe Graphics"),
+    (VENDOR_INTEL, 0x46a6, "Intel Iris Xe Graphics"),
+    (VENDOR_INTEL, 0xa7a0, "Intel Iris Xe Graphics"),
+    (VENDOR_INTEL, 0x7d55, "Intel Arc Graphics"),
+    (VENDOR_INTEL, 0x64a0, "Intel Arc Graphics 130V / 140V"),
+    (VENDOR_AMD, 0x1638, "AMD Radeon Graphics (Cezanne)"),
+    (VENDOR_AMD, 0x1681, "AMD Radeon 680M"),
+    (VENDOR_AMD, 0x15bf, "AMD Radeon 760M / 780M"),
+    (VENDOR_AMD, 0x150e, "AMD Radeon 880M / 890M"),
+    (VENDOR_NVIDIA, 0x2520, "NVIDIA GeForce RTX 3060 Laptop GPU"),
+    (VENDOR_NVIDIA, 0x2860, "NVIDIA GeForce RTX 4070 Laptop GPU"),
+    (VENDOR_NVIDIA, 0x28e0, "NVIDIA GeForce RTX 4060 Laptop GPU"),
+    (VENDOR_NVIDIA, 0x28e1, "NVIDIA GeForce RTX 4050 Laptop GPU"),
+];
+
+// Keyed by PCI address and ids, a dock may bring a different GPU at the same address
+type CacheKey = (String, u16, u16);
+static CACHE: once_cell::sync::Lazy<Mutex<HashMap<CacheKey, Option<String>>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Product name of the PCI device behind `device_path`, None if no source knows it
+pub fn gpu_name(device_path: &Path) -> Option<String> {
+    let address = crate::hardware_detection::pci_address(device_path)?;
+    let vendor = read_hex(&device_path.join("vendor"))?;
+    let device = read_hex(&device_path.join("device"))?;
+    let key = (address, vendor, device);
+
+    if let Some(name) = CACHE.lock().unwrap().get(&key) {
+        return name.clone();
+    }
+    let revision = read_hex(&device_path.join("revision")).unwrap_or(0);
+    let name = lookup(&key.0, vendor, device, revision);
+    if let Some(ref name) = name {
+        log::debug!("GPU {} ({:04x}:{:04x}) is {}", key.0, vendor, device, name);
+    }
+    CACHE.lock().unwrap().insert(key, name.clone());
+    name
+}
+
+fn lookup(address: &str, vendor: u16, device: u16, revision: u16) -> Option<String> {
+    if vendor == VENDOR_NVIDIA {
+        if let Some(name) = nvidia_driver_name(address) {
+            return Some(name);
+        }
+    }
+    if vendor == VENDOR_AMD {
+        if let Some(name) = amdgpu_ids_name(device, revision) {
+            return Some(name);
+        }
+    }
+
+    let pci_ids = pci_ids_name(vendor, device);
+    if let Some((name, true)) = pci_ids {
+        return Some(name);
+    }
+    BUILT_IN.iter()
+        .find(|(v, d, _)| *v == vendor && *d == device)
+        .map(|(_, _, name)| name.to_string())
+        .or(pci_ids.map(|(name, _)| name))
+}
+
+// "Model: 		 NVIDIA GeForce RTX 4070 Laptop GPU", readable without waking the GPU
+fn nvidia_driver_name(address: &str) -> Option<String> {
+    let information = fs::read_to_string(format!("{}/{}/information", NVIDIA_GPUS, address)).ok()?;
+    information.lines()
+        .find_map(|line| line.strip_prefix("Model:"))
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty())
+}
+
+// "15BF,	C4,	AMD Radeon 780M", an entry with revision 00 covers the whole device id
+fn amdgpu_ids_name(device: u16, revision: u16) -> Option<String> {
+    let file = File::open(AMDGPU_IDS).ok()?;
+    let mut any_revision = None;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let mut fields = line.splitn(3, ',').map(str::trim);
+        let (Some(id), Some(rev), Some(name)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if u16::from_str_radix(id, 16).ok() != Some(device) {
+            continue;
+        }
+        match u16::from_str_radix(rev, 16) {
+            Ok(rev) if rev == revision => return Some(name.to_string()),
+            Ok(0) => any_revision = Some(name.to_string()),
+            _ => {}
+        }
+    }
+    any_revision
+}
+
+// "10de  NVIDIA Corporation" followed by "\t2860  AD106M [GeForce RTX 4070 Max-Q / Mobile]".
+// Returns the name and whether it is a product name rather than a code name.
+fn pci_ids_name(vendor: u16, device: u16) -> Option<(String, bool)> {
+    let file = PCI_IDS.iter().find_map(|path| File::open(path).ok())?;
+    let mut vendor_name = None;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if !line.starts_with('\t') {
+            // The device classes follow the vendors
+            if vendor_name.is_some() || line.starts_with("C ") {
+                return None;
+            }
+            let Some((id, name)) = line.split_once("  ") else {
+                continue;
+            };
+            if u16::from_str_radix(id, 16).ok() == Some(vendor) {
+                vendor_name = Some(name.trim().to_string());
+            }
+            continue;
+        }
+        let Some(ref vendor_name) = vendor_name else {
+            continue;
+        };
+        // Subsystem entries are indented twice
+        if line.starts_with("\t\t") {
+            continue;
+        }
+        let Some((id, name)) = line[1..].split_once("  ") else {
+            continue;
+        };
+        if u16::from_str_radix(id, 16).ok() != Some(device) {
+            continue;
+        }
+
+        let product = name.find('[')
+            .and_then(|start| name[start + 1..].find(']').map(|len| &name[start + 1..start + 1 + len]));
+        let prefix = short_vendor(vendor).unwrap_or(vendor_name);
+        let (name, is_product) = match product {
+            Some(product) => (product, true),
+            None => (name.trim(), false),
+        };
+        return Some(if name.starts_with(prefix) {
+            (name.to_string(), is_product)
+        } else {
+            (format!("{} {}", prefix, name), is_product)
+        });
+    }
+    None
+}
+
+// pci.ids spells vendors out in full ("Advanced Micro Devices, Inc. [AMD/ATI]")
+fn short_vendor(vendor: u16) -> Option<&'static str> {
+    match vendor {
+        VENDOR_AMD => Some("AMD"),
+        VENDOR_NVIDIA => Some("NVIDIA"),
+        VENDOR_INTEL => Some("Intel"),
+        _ => None,
+    }
+}
+
+// "0x10de"
+fn read_hex(path: &Path) -> Option<u16> {
+    let content = fs::read_to_string(path).ok()?;
+    u16::from_str_radix(content.trim().trim_start_matches("0x"), 16).ok()
+}
@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::SessionPowerReport;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+struct Session {
+    started: Instant,
+    last_energy: u64,  // µWh
+    used: u64,         // µWh
+}
+
+static SESSION: once_cell::sync::Lazy<Mutex<Option<Session>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+pub fn get_report() -> Option<SessionPowerReport> {
+    let session = SESSION.lock().unwrap();
+    let session = session.as_ref()?;
+
+    let duration = session.started.elapsed();
+    let energy_used_wh = session.used as f64 / 1_000_000.0;
+    let hours = duration.as_secs_f64() / 3600.0;
+
+    Some(SessionPowerReport {
+        duration_secs: duration.as_secs(),
+        energy_used_wh,
+        average_draw_w: if hours > 0.0 { energy_used_wh / hours } else { 0.0 },
+    })
+}
+
+pub async fn session_task() {
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("power_session");
+
+        let mut session = SESSION.lock().unwrap();
+
+        // Plugging in ends the session, the next unplug starts from zero
+        if crate::hardware_detection::is_on_ac_power() {
+            if session.take().is_some() {
+                log::info!("AC connected, battery session reset");
+            }
+            continue;
+        }
+
+        let energy = match crate::hardware_detection::read_battery_energy() {
+            Ok(energy) => energy,
+            Err(_) => continue,
+        };
+
+        match session.as_mut() {
+            Some(s) => {
+                // Sum the drops only, the gauge occasionally jumps up while recalibrating
+                s.used += s.last_energy.saturating_sub(energy);
+                s.last_energy = energy;
+            }
+            None => {
+                log::info!("Running on battery, starting power session");
+                *session = Some(Session { started: Instant::now(), last_energy: energy, used: 0 });
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+use tuxedo_common::types::{EventKind, PowerSourceProfiles};
+use zbus::Connection;
+use crate::dbus_interface::ControlInterface;
+
+// Switches between an "on AC" and an "on battery" profile when the charger is plugged in or
+// out. The GUI sends copies of both profiles, so switching also works before anybody logs
+// in and with the GUI closed. Only changes of the power source switch, a profile picked by
+// hand stays until the next one.
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const SETTINGS_FILE: &str = "/var/lib/tuxedo-control-center/power_source_profiles.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static SETTINGS: once_cell::sync::Lazy<Mutex<PowerSourceProfiles>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+pub fn get() -> PowerSourceProfiles {
+    SETTINGS.lock().unwrap().clone()
+}
+
+/// Switching is configured, the daemon has to stay up to notice the charger
+pub fn is_enabled() -> bool {
+    let settings = SETTINGS.lock().unwrap();
+    settings.enabled && (settings.on_ac.is_some() || settings.on_battery.is_some())
+}
+
+pub fn set(settings: PowerSourceProfiles) -> Result<()> {
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(SETTINGS_FILE, serde_json::to_string_pretty(&settings)?)?;
+    log::info!("Power source profiles: {} on AC, {} on battery{}",
+        name_or_none(settings.on_ac.as_ref()), name_or_none(settings.on_battery.as_ref()),
+        if settings.enabled { "" } else { " (disabled)" });
+    *SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
+
+fn name_or_none(profile: Option<&tuxedo_common::types::Profile>) -> String {
+    profile.map(|p| format!("'{}'", p.name)).unwrap_or_else(|| "nothing".to_string())
+}
+
+fn load() -> PowerSourceProfiles {
+    match fs::read_to_string(SETTINGS_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid {}: {}", SETTINGS_FILE, e);
+            PowerSourceProfiles::default()
+        }),
+        Err(_) => PowerSourceProfiles::default(),
+    }
+}
+
+// Runs on the service connection, ActiveProfile and ProfileApplied come from the bus name owner
+pub async fn monitor_task(connection: Connection) {
+    let iface = match connection.object_server().interface::<_, ControlInterface>("/com/tuxedo/Control").await {
+        Ok(iface) => iface,
+        Err(e) => {
+            log::warn!("Power source profile switching disabled: {}", e);
+            return;
+        }
+    };
+    let mut on_ac = crate::hardware_detection::is_on_ac_power();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("power_source_profiles");
+
+        let now_on_ac = crate::hardware_detection::is_on_ac_power();
+        if now_on_ac == on_ac {
+            continue;
+        }
+        on_ac = now_on_ac;
+
+        let settings = get();
+        let profile = if on_ac { settings.on_ac } else { settings.on_battery };
+        let Some(profile) = profile.filter(|_| settings.enabled) else {
+            continue;
+        };
+        if crate::safe_mode::is_active() {
+            log::info!("Safe mode is active, not switching to '{}'", profile.name);
+            continue;
+        }
+        if crate::ab_test::is_running() {
+            log::info!("A/B test is running, not switching to '{}'", profile.name);
+            continue;
+        }
+        if crate::safe_mode::applied_profile().as_deref() == Some(profile.name.as_str()) {
+            continue;
+        }
+
+        let name = profile.name.clone();
+        let source = if on_ac { "AC" } else { "battery" };
+        log::info!("Power source changed to {}, switching to '{}'", source, name);
+        match crate::apply_pipeline::apply_announced(profile, &iface).await {
+            Ok(()) => crate::event_log::record(EventKind::ProfileSwitch, format!("On {}, switched to '{}'", source, name)),
+            Err(e) => log::warn!("Failed to switch to '{}': {}", name, e),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use std::ffi::CString;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// With --drop-privileges <user> the daemon gives up root before anything else runs. What it
+// needs root for is set up first: /dev/tuxedo_io is opened (the ioctls need no capability, only
+// the open file), the JSON-RPC socket is bound and handed to --json-rpc-group, and the root
+// helper for /proc/sys/vm is forked, that file system checks for euid 0 on every write. Nothing
+// in the daemon reads /dev/cpu/*/msr, so those aren't opened. Afterwards it keeps
+//   CAP_DAC_OVERRIDE  sysfs files owned by root (cpufreq, hwmon pwm, backlight, leds,
+//                     power_supply thresholds), /proc/acpi/call, /dev/input/event* for hotkeys,
+//                     reopening /dev/tuxedo_io after a module reload, and the state under
+//                     /var/lib, /var/log and /run/tuxedo-control-center, which stay owned by root
+//   CAP_NET_ADMIN     the rfkill switches in sysfs
+// as effective capabilities. CAP_SYS_ADMIN and CAP_SYS_RAWIO stay permitted but not effective,
+// only to be passed on by grant() to the helper programs that need them: nvidia-smi setting a
+// power limit, and smartctl. No capability is raised as ambient for every child, ip, iw and
+// liquidctl get just what grant() names for them. Everything else is dropped from the bounding
+// set. Owning com.tuxedo.Control needs the user in the D-Bus policy, the packaged one allows
+// "tuxedo-daemon".
+pub const FLAG: &str = "--drop-privileges";
+
+pub const CAP_DAC_OVERRIDE: u32 = 1;
+pub const CAP_NET_ADMIN: u32 = 12;
+pub const CAP_SYS_RAWIO: u32 = 17;
+pub const CAP_SYS_ADMIN: u32 = 21;
+const EFFECTIVE: [u32; 2] = [CAP_DAC_OVERRIDE, CAP_NET_ADMIN];
+const KEPT: [u32; 4] = [CAP_DAC_OVERRIDE, CAP_NET_ADMIN, CAP_SYS_RAWIO, CAP_SYS_ADMIN];
+
+static DROPPED: AtomicBool = AtomicBool::new(false);
+
+const CAP_LAST_CAP: &str = "/proc/sys/kernel/cap_last_cap";
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// The user to switch to, from `--drop-privileges <user>`
+pub fn user_from_args() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == FLAG)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+/// Switch to `user` keeping KEPT. Capabilities belong to threads, so this has to run
+/// before the first thread is started, threads inherit them from their creator.
+pub fn apply(user: &str) -> Result<()> {
+    if unsafe { libc::geteuid() } != 0 {
+        return Err(anyhow!("Not running as root, nothing to drop"));
+    }
+    let (uid, gid) = lookup_user(user)?;
+    crate::vm_tuning::start_helper()?;
+
+    let last_cap: u32 = std::fs::read_to_string(CAP_LAST_CAP)
+        .ok()
+        .and_then(|last| last.trim().parse().ok())
+        .unwrap_or(40);
+    for cap in (0..=last_cap).filter(|cap| !KEPT.contains(cap)) {
+        if unsafe { libc::prctl(libc::PR_CAPBSET_DROP, cap as libc::c_ulong, 0, 0, 0) } != 0 {
+            let error = std::io::Error::last_os_error();
+            // Past the last capability this kernel knows
+            if error.raw_os_error() == Some(libc::EINVAL) {
+                break;
+            }
+            return Err(anyhow!("Failed to drop capability {} from the bounding set: {}", cap, error));
+        }
+    }
+
+    // Without this setuid clears the permitted set along with the root user
+    if unsafe { libc::prctl(libc::PR_SET_KEEPCAPS, 1, 0, 0, 0) } != 0 {
+        return Err(anyhow!("Failed to keep capabilities: {}", std::io::Error::last_os_error()));
+    }
+    let switched = unsafe {
+        libc::setgroups(0, std::ptr::null()) == 0 && libc::setgid(gid) == 0 && libc::setuid(uid) == 0
+    };
+    if !switched {
+        return Err(anyhow!("Failed to switch to user {}: {}", user, std::io::Error::last_os_error()));
+    }
+
+    let mask = |caps: &[u32]| caps.iter().fold(0u64, |mask, cap| mask | 1 << cap);
+    let (kept, effective) = (mask(&KEPT), mask(&EFFECTIVE));
+    let mut header = CapHeader { version: LINUX_CAPABILITY_VERSION_3, pid: 0 };
+    // Inheritable too, grant() can only raise ambient capabilities that are in both sets
+    let data = [0, 32].map(|shift| CapData {
+        effective: (effective >> shift) as u32,
+        permitted: (kept >> shift) as u32,
+        inheritable: (kept >> shift) as u32,
+    });
+    if unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) } != 0 {
+        return Err(anyhow!("Failed to set capabilities: {}", std::io::Error::last_os_error()));
+    }
+    unsafe { libc::prctl(libc::PR_SET_KEEPCAPS, 0, 0, 0, 0) };
+    DROPPED.store(true, Ordering::SeqCst);
+
+    log::info!("Running as {} (uid {}) with CAP_DAC_OVERRIDE and CAP_NET_ADMIN", user, uid);
+    Ok(())
+}
+
+/// Pass `caps` on to the program `command` runs, as ambient capabilities of that child only.
+/// Without a privilege drop the daemon is root and so are its helpers, nothing to do then.
+pub fn grant<'a>(command: &'a mut Command, caps: &'static [u32]) -> &'a mut Command {
+    if !DROPPED.load(Ordering::SeqCst) {
+        return command;
+    }
+    // Runs in the forked child right before exec, prctl is safe to call there
+    unsafe {
+        command.pre_exec(move || {
+            for &cap in caps {
+                let raised = libc::prctl(libc::PR_CAP_AMBIENT, libc::PR_CAP_AMBIENT_RAISE as libc::c_ulong,
+                    cap as libc::c_ulong, 0, 0);
+                if raised != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Empty effective, permitted and inheritable sets for the calling thread
+pub fn clear_capabilities() -> Result<()> {
+    let mut header = CapHeader { version: LINUX_CAPABILITY_VERSION_3, pid: 0 };
+    let data = [0u32, 0].map(|bits| CapData { effective: bits, permitted: bits, inheritable: bits });
+    if unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) } != 0 {
+        return Err(anyhow!("Failed to clear capabilities: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+fn lookup_user(name: &str) -> Result<(libc::uid_t, libc::gid_t)> {
+    let c_name = CString::new(name)?;
+    let user = unsafe { libc::getpwnam(c_name.as_ptr()) };
+    if user.is_null() {
+        return Err(anyhow!("Unknown user '{}'", name));
+    }
+    Ok(unsafe { ((*user).pw_uid, (*user).pw_gid) })
+}
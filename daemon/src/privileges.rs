@@ -0,0 +1,45 @@
+use once_cell::sync::Lazy;
+
+// Without root the daemon still reads sensors, frequencies and fan speeds, which is enough
+// for development and for users who only want monitoring. Hardware writes, the fan curve
+// loop, profile restore and the state under /var/lib are left out then, and every write
+// method is refused. Root in a container or a unit with a reduced capability set counts as
+// unprivileged too, what matters is whether sysfs and /dev/tuxedo_io writes can work.
+// Owning com.tuxedo.Control on the system bus needs a D-Bus policy for the user; for
+// development, point DBUS_SYSTEM_BUS_ADDRESS of the daemon and the GUI at the session bus.
+pub const FLAG: &str = "--monitor-only";
+
+// Writing sysfs files owned by root and opening /dev/tuxedo_io, its ioctls need no capability
+const CAP_DAC_OVERRIDE: u32 = 1;
+
+static MONITOR_ONLY: Lazy<bool> = Lazy::new(|| {
+    if std::env::args().any(|arg| arg == FLAG) {
+        log::info!("Started with {}, monitoring only", FLAG);
+        return true;
+    }
+    if has_capability(CAP_DAC_OVERRIDE) {
+        return false;
+    }
+    log::warn!("Running without CAP_DAC_OVERRIDE (uid {}), monitoring only: no fan control, no profiles, settings are read-only",
+        unsafe { libc::geteuid() });
+    true
+});
+
+/// Decide once at startup, so the reason is logged before anything is skipped
+pub fn init() {
+    Lazy::force(&MONITOR_ONLY);
+}
+
+/// True when the daemon may only read hardware state
+pub fn monitor_only() -> bool {
+    *MONITOR_ONLY
+}
+
+// "CapEff:	000001ffffffffff" in /proc/self/status
+fn has_capability(cap: u32) -> bool {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    status.lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+        .is_some_and(|caps| caps & (1 << cap) != 0)
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::{ProfileUsage, ProfileUsageEntry};
+
+// Which profiles are actually used: switches, time applied and the CPU temperature while
+// applied, per profile name. Local only, nothing of it leaves the machine. Written every few
+// samples and on shutdown rather than every minute, the counters aren't worth the disk wear.
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const STATE_FILE: &str = "/var/lib/tuxedo-control-center/profile_usage.json";
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+const SAVE_EVERY: u32 = 10;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Counters {
+    switches: u32,
+    active_secs: u64,
+    // Temperature times seconds, and the seconds a temperature was read for
+    temp_secs: f64,
+    temp_covered_secs: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Usage {
+    since: i64,
+    profiles: BTreeMap<String, Counters>,
+    // Re-applying the same profile (after a module reload, a resume) isn't a switch
+    last_applied: Option<String>,
+}
+
+static USAGE: once_cell::sync::Lazy<Mutex<Usage>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+pub fn get() -> ProfileUsage {
+    let usage = USAGE.lock().unwrap();
+    ProfileUsage {
+        since: usage.since,
+        profiles: usage.profiles.iter()
+            .map(|(name, counters)| ProfileUsageEntry {
+                name: name.clone(),
+                switches: counters.switches,
+                active_secs: counters.active_secs,
+                average_cpu_temp: (counters.temp_covered_secs > 0)
+                    .then(|| (counters.temp_secs / counters.temp_covered_secs as f64) as f32),
+            })
+            .collect(),
+    }
+}
+
+/// Called by apply_profile
+pub fn profile_applied(name: &str) {
+    let mut usage = USAGE.lock().unwrap();
+    if usage.last_applied.as_deref() == Some(name) {
+        return;
+    }
+    usage.last_applied = Some(name.to_string());
+    usage.profiles.entry(name.to_string()).or_default().switches += 1;
+    save(&usage);
+}
+
+pub fn reset() -> Result<()> {
+    let mut usage = USAGE.lock().unwrap();
+    let last_applied = usage.last_applied.take();
+    *usage = Usage { since: now(), last_applied, ..Default::default() };
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(STATE_FILE, serde_json::to_string(&*usage)?)?;
+    log::info!("Profile usage statistics reset");
+    Ok(())
+}
+
+/// Write what the last samples added, called on shutdown
+pub fn flush() {
+    save(&USAGE.lock().unwrap());
+}
+
+pub async fn sampler_task() {
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    // The first tick is immediate, there is nothing to count yet
+    interval.tick().await;
+    let mut unsaved = 0;
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("profile_usage");
+
+        let Some(name) = crate::safe_mode::applied_profile() else {
+            continue;
+        };
+        let temp = crate::hardware_detection::get_package_temp().ok();
+
+        let mut usage = USAGE.lock().unwrap();
+        let counters = usage.profiles.entry(name).or_default();
+        counters.active_secs += SAMPLE_INTERVAL.as_secs();
+        if let Some(temp) = temp {
+            counters.temp_secs += temp as f64 * SAMPLE_INTERVAL.as_secs_f64();
+            counters.temp_covered_secs += SAMPLE_INTERVAL.as_secs();
+        }
+
+        unsaved += 1;
+        if unsaved >= SAVE_EVERY {
+            unsaved = 0;
+            save(&usage);
+        }
+    }
+}
+
+fn load() -> Usage {
+    fs::read_to_string(STATE_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| Usage { since: now(), ..Default::default() })
+}
+
+fn save(usage: &Usage) {
+    let result = fs::create_dir_all(STATE_DIR)
+        .and_then(|_| fs::write(STATE_FILE, serde_json::to_string(usage).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("Failed to write {}: {}", STATE_FILE, e);
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tuxedo_common::types::{EventKind, Profile, SafeModeStatus};
+
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const STATE_FILE: &str = "/var/lib/tuxedo-control-center/crash_guard.json";
+// A crash this soon after applying a profile is blamed on the profile
+const CRASH_WINDOW_SECS: i64 = 120;
+// Consecutive crashes before the profile is no longer restored
+const CRASH_LIMIT: u32 = 3;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CrashGuard {
+    // Last applied profile, restored when the daemon starts
+    profile: Option<Profile>,
+    // Written before the hardware is touched and cleared once the apply returns, a crash
+    // halfway through an apply leaves it set
+    #[serde(default)]
+    applying: Option<Profile>,
+    // When the last apply started
+    applied_at: i64,
+    // Still set on startup if the previous run didn't shut down cleanly
+    running: bool,
+    crash_count: u32,
+}
+
+static GUARD: once_cell::sync::Lazy<Mutex<CrashGuard>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(CrashGuard::default()));
+static STATUS: once_cell::sync::Lazy<Mutex<SafeModeStatus>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(SafeModeStatus::default()));
+
+pub fn get_status() -> SafeModeStatus {
+    STATUS.lock().unwrap().clone()
+}
+
+pub fn is_active() -> bool {
+    STATUS.lock().unwrap().active
+}
+
+/// Name of the profile applied last, also across restarts
+pub fn applied_profile() -> Option<String> {
+    GUARD.lock().unwrap().profile.as_ref().map(|p| p.name.clone())
+}
+
+/// The whole profile, for re-applying parts of it
+pub fn last_applied() -> Option<Profile> {
+    GUARD.lock().unwrap().profile.clone()
+}
+
+/// Called once on startup, returns the profile to restore unless the daemon has to start in safe mode
+pub fn startup() -> Option<Profile> {
+    let mut guard = load();
+
+    let crashed = guard.running
+        && (guard.profile.is_some() || guard.applying.is_some())
+        && now() - guard.applied_at < CRASH_WINDOW_SECS;
+    guard.crash_count = if crashed { guard.crash_count + 1 } else { 0 };
+    guard.running = true;
+
+    // A profile that never finished applying is the one to blame, the last complete one is restored
+    let blamed = guard.applying.take().or_else(|| guard.profile.clone());
+    let profile_name = blamed.as_ref().map(|p| p.name.clone());
+    let active = guard.crash_count >= CRASH_LIMIT;
+    if active {
+        log::error!("Daemon crashed {} times right after applying profile {:?}, starting in safe mode",
+            guard.crash_count, profile_name);
+        crate::event_log::record(EventKind::FanFailsafe, format!(
+            "Safe mode: crashed {} times after applying '{}', fans on automatic",
+            guard.crash_count, profile_name.clone().unwrap_or_default()));
+    } else if crashed {
+        log::warn!("Previous run crashed shortly after applying a profile ({} of {})",
+            guard.crash_count, CRASH_LIMIT);
+    }
+
+    *STATUS.lock().unwrap() = SafeModeStatus {
+        active,
+        profile: profile_name,
+        crash_count: guard.crash_count,
+    };
+
+    let restore = if active { None } else { guard.profile.clone() };
+    save(&guard);
+    *GUARD.lock().unwrap() = guard;
+    restore
+}
+
+/// Called by apply_profile before the first write, saved right away so it survives a crash
+pub fn applying(profile: &Profile) {
+    let mut guard = GUARD.lock().unwrap();
+    guard.applying = Some(profile.clone());
+    guard.applied_at = now();
+    save(&guard);
+}
+
+/// Called by apply_profile when the apply returned an error, the previous profile stays
+pub fn apply_failed() {
+    let mut guard = GUARD.lock().unwrap();
+    guard.applying = None;
+    save(&guard);
+}
+
+/// Called by apply_profile, an explicit apply also ends safe mode
+pub fn profile_applied(profile: &Profile) {
+    let mut guard = GUARD.lock().unwrap();
+    guard.profile = Some(profile.clone());
+    guard.applying = None;
+
+    let mut status = STATUS.lock().unwrap();
+    if status.active {
+        log::info!("Profile '{}' applied, leaving safe mode", profile.name);
+        status.active = false;
+        guard.crash_count = 0;
+    }
+
+    save(&guard);
+}
+
+pub fn leave() -> Result<()> {
+    let mut guard = GUARD.lock().unwrap();
+    guard.crash_count = 0;
+    STATUS.lock().unwrap().active = false;
+
+    log::info!("Safe mode left on request");
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(STATE_FILE, serde_json::to_string(&*guard)?)?;
+    Ok(())
+}
+
+pub fn clean_shutdown() {
+    let mut guard = GUARD.lock().unwrap();
+    guard.running = false;
+    save(&guard);
+}
+
+fn load() -> CrashGuard {
+    fs::read_to_string(STATE_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(guard: &CrashGuard) {
+    let result = fs::create_dir_all(STATE_DIR)
+        .and_then(|_| fs::write(STATE_FILE, serde_json::to_string(guard).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("Failed to write {}: {}", STATE_FILE, e);
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::ffi::CString;
+use std::fs;
+
+// Hardening on top of the privilege drop, configured in /etc/tuxedo-control-center/daemon.toml:
+//
+//   [sandbox]
+//   filesystem = true         # Landlock, false when chasing a "Permission denied"
+//   syscalls = true           # seccomp filter, false when chasing an "Operation not permitted"
+//
+// The filesystem layer leaves the daemon, and the helper programs it runs, read access to the
+// system directories and write access only to sysfs, /dev, the few procfs files it tunes and
+// its own state directories. The syscall layer refuses with EPERM what a hardware daemon has
+// no business doing: loading modules or kernels, mounting, tracing other processes, eBPF,
+// namespaces, keyrings and setting the clock. Both need to be set up before the first thread
+// starts, threads inherit them. A kernel without Landlock or an architecture without a
+// syscall table here just runs without that layer.
+pub const CONFIG_FILE: &str = "/etc/tuxedo-control-center/daemon.toml";
+
+// Readable, and executable for the helpers
+const READ_PATHS: [&str; 8] = ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc", "/run", "/proc"];
+const WRITE_PATHS: [&str; 7] = [
+    "/sys",
+    "/dev",
+    "/proc/sys/vm",
+    "/proc/acpi",
+    "/var/lib/tuxedo-control-center",
+    "/var/log/tuxedo-control-center",
+    "/run/tuxedo-control-center",
+];
+// Created before the rules are added, a rule needs the directory to exist
+const STATE_DIRS: [&str; 3] = [
+    "/var/lib/tuxedo-control-center",
+    "/var/log/tuxedo-control-center",
+    "/run/tuxedo-control-center",
+];
+
+#[derive(Deserialize, Default)]
+struct ConfigFile {
+    #[serde(default)]
+    sandbox: SandboxSettings,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SandboxSettings {
+    #[serde(default = "default_enabled")]
+    filesystem: bool,
+    #[serde(default = "default_enabled")]
+    syscalls: bool,
+}
+
+impl Default for SandboxSettings {
+    fn default() -> Self {
+        Self { filesystem: true, syscalls: true }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn load() -> Result<SandboxSettings> {
+    let text = match fs::read_to_string(CONFIG_FILE) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SandboxSettings::default()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(toml::from_str::<ConfigFile>(&text)?.sandbox)
+}
+
+/// Restrict this thread and everything it starts. Failures are logged, the daemon runs
+/// unsandboxed rather than not at all.
+pub fn apply() {
+    let settings = load().unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid {}: {}", CONFIG_FILE, e);
+        SandboxSettings::default()
+    });
+    if !settings.filesystem && !settings.syscalls {
+        log::info!("Sandbox disabled in {}", CONFIG_FILE);
+        return;
+    }
+
+    // Required for unprivileged Landlock and seccomp, and keeps setuid helpers from gaining more
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        log::warn!("Sandbox disabled, no_new_privs failed: {}", std::io::Error::last_os_error());
+        return;
+    }
+    if settings.filesystem {
+        match restrict_filesystem() {
+            Ok(abi) => log::info!("Filesystem access restricted (Landlock ABI {})", abi),
+            Err(e) => log::warn!("Filesystem access not restricted: {}", e),
+        }
+    }
+    if settings.syscalls {
+        match filter_syscalls() {
+            Ok(count) => log::info!("{} syscalls blocked", count),
+            Err(e) => log::warn!("Syscalls not filtered: {}", e),
+        }
+    }
+}
+
+// Landlock
+
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1;
+const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+const ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+const ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+const ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+const ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+const ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+// Everything of ABI 1, character and block devices, FIFOs and symlinks are never allowed
+const ACCESS_FS_ABI_1: u64 = (1 << 13) - 1;
+const ACCESS_FS_REFER: u64 = 1 << 13;
+const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+
+const ACCESS_READ: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+const ACCESS_WRITE: u64 = ACCESS_READ | ACCESS_FS_WRITE_FILE | ACCESS_FS_REMOVE_DIR | ACCESS_FS_REMOVE_FILE
+    | ACCESS_FS_MAKE_DIR | ACCESS_FS_MAKE_REG | ACCESS_FS_MAKE_SOCK | ACCESS_FS_TRUNCATE;
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+fn restrict_filesystem() -> Result<i64> {
+    let abi = unsafe {
+        libc::syscall(libc::SYS_landlock_create_ruleset, std::ptr::null::<RulesetAttr>(), 0, LANDLOCK_CREATE_RULESET_VERSION)
+    };
+    if abi < 1 {
+        return Err(anyhow!("Landlock unavailable: {}", std::io::Error::last_os_error()));
+    }
+    // Newer rights are only known to newer kernels, renames between directories (REFER) stay denied
+    let mut handled = ACCESS_FS_ABI_1;
+    if abi >= 2 {
+        handled |= ACCESS_FS_REFER;
+    }
+    if abi >= 3 {
+        handled |= ACCESS_FS_TRUNCATE;
+    }
+
+    for dir in STATE_DIRS {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let attr = RulesetAttr { handled_access_fs: handled };
+    let ruleset = unsafe {
+        libc::syscall(libc::SYS_landlock_create_ruleset, &attr, std::mem::size_of::<RulesetAttr>(), 0)
+    };
+    if ruleset < 0 {
+        return Err(anyhow!("Failed to create the ruleset: {}", std::io::Error::last_os_error()));
+    }
+    let ruleset = ruleset as libc::c_int;
+
+    let rules = READ_PATHS.iter().map(|path| (path, ACCESS_READ))
+        .chain(WRITE_PATHS.iter().map(|path| (path, ACCESS_WRITE)));
+    let mut result = Ok(());
+    for (path, access) in rules {
+        if let Err(e) = add_rule(ruleset, path, access & handled) {
+            result = Err(e);
+            break;
+        }
+    }
+    if result.is_ok() && unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) } != 0 {
+        result = Err(anyhow!("Failed to enforce the ruleset: {}", std::io::Error::last_os_error()));
+    }
+    unsafe { libc::close(ruleset) };
+    result.map(|()| abi)
+}
+
+// Missing paths are skipped, /lib64 or /proc/acpi don't exist everywhere
+fn add_rule(ruleset: libc::c_int, path: &str, access: u64) -> Result<()> {
+    let c_path = CString::new(path)?;
+    let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+    if fd < 0 {
+        log::debug!("Sandbox: skipping {}: {}", path, std::io::Error::last_os_error());
+        return Ok(());
+    }
+    let rule = PathBeneathAttr { allowed_access: access, parent_fd: fd };
+    let added = unsafe {
+        libc::syscall(libc::SYS_landlock_add_rule, ruleset, LANDLOCK_RULE_PATH_BENEATH, &rule, 0)
+    };
+    let error = std::io::Error::last_os_error();
+    unsafe { libc::close(fd) };
+    if added != 0 {
+        return Err(anyhow!("Failed to allow {}: {}", path, error));
+    }
+    Ok(())
+}
+
+// seccomp
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xC000_003E;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xC000_00B7;
+// x32 syscalls share the x86_64 audit arch, they are told apart by this bit in the number
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+fn blocked_syscalls() -> Vec<libc::c_long> {
+    let mut blocked = vec![
+        libc::SYS_init_module, libc::SYS_finit_module, libc::SYS_delete_module,
+        libc::SYS_kexec_load, libc::SYS_kexec_file_load, libc::SYS_reboot,
+        libc::SYS_mount, libc::SYS_umount2, libc::SYS_pivot_root, libc::SYS_chroot,
+        libc::SYS_fsopen, libc::SYS_fsmount, libc::SYS_move_mount, libc::SYS_open_tree,
+        libc::SYS_swapon, libc::SYS_swapoff, libc::SYS_acct, libc::SYS_quotactl,
+        libc::SYS_ptrace, libc::SYS_process_vm_readv, libc::SYS_process_vm_writev,
+        libc::SYS_bpf, libc::SYS_perf_event_open, libc::SYS_userfaultfd,
+        libc::SYS_setns, libc::SYS_unshare,
+        libc::SYS_keyctl, libc::SYS_add_key, libc::SYS_request_key,
+        libc::SYS_open_by_handle_at, libc::SYS_name_to_handle_at, libc::SYS_fanotify_init,
+        libc::SYS_settimeofday, libc::SYS_clock_settime, libc::SYS_clock_adjtime, libc::SYS_adjtimex,
+    ];
+    #[cfg(target_arch = "x86_64")]
+    blocked.extend([libc::SYS_iopl, libc::SYS_ioperm, libc::SYS_uselib]);
+    blocked
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+fn filter_syscalls() -> Result<usize> {
+    const ARCH_OFFSET: u32 = 4;  // seccomp_data.arch, the syscall number is at 0
+    let stmt = |code: u32, k: u32| libc::sock_filter { code: code as u16, jt: 0, jf: 0, k };
+    let jump = |code: u32, k: u32, jt: u8, jf: u8| libc::sock_filter { code: code as u16, jt, jf, k };
+    let deny = stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ERRNO | libc::EPERM as u32);
+
+    let blocked = blocked_syscalls();
+    let mut program = vec![
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, ARCH_OFFSET),
+        jump(libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K, AUDIT_ARCH, 1, 0),
+        stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_KILL_PROCESS),
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 0),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    program.extend([jump(libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K, X32_SYSCALL_BIT, 0, 1), deny]);
+    for nr in &blocked {
+        program.extend([jump(libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K, *nr as u32, 0, 1), deny]);
+    }
+    program.push(stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW));
+
+    let fprog = libc::sock_fprog { len: program.len() as u16, filter: program.as_mut_ptr() };
+    if unsafe { libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &fprog) } != 0 {
+        return Err(anyhow!("Failed to install the filter: {}", std::io::Error::last_os_error()));
+    }
+    Ok(blocked.len())
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn filter_syscalls() -> Result<usize> {
+    Err(anyhow!("No syscall table for this architecture"))
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{EventKind, SensorFailureStats};
+
+// A failing sensor is logged once, then at most this often while it keeps failing
+const LOG_INTERVAL: Duration = Duration::from_secs(300);
+// Good reads halve the failure count once per period without a failure, so a sensor that
+// fails every few reads stays reported as failing instead of logging a new failure each time
+const DECAY_PERIOD: Duration = Duration::from_secs(30);
+
+struct SensorState {
+    total_failures: u64,
+    consecutive_failures: u64,
+    suppressed: u64,
+    last_error: String,
+    last_logged: Instant,
+    last_decay: Instant,  // Last failure or halving
+}
+
+static SENSORS: once_cell::sync::Lazy<Mutex<HashMap<String, SensorState>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn report_failure(sensor: &str, error: impl Display) {
+    let mut sensors = SENSORS.lock().unwrap();
+    let error = error.to_string();
+
+    let state = match sensors.get_mut(sensor) {
+        Some(state) => state,
+        None => {
+            log::warn!("sensor={} read failed: {}", sensor, error);
+            crate::event_log::record(EventKind::SensorFailure, format!("{}: {}", sensor, error));
+            sensors.insert(sensor.to_string(), SensorState {
+                total_failures: 1,
+                consecutive_failures: 1,
+                suppressed: 0,
+                last_error: error,
+                last_logged: Instant::now(),
+                last_decay: Instant::now(),
+            });
+            return;
+        }
+    };
+
+    state.total_failures += 1;
+    state.consecutive_failures += 1;
+    state.last_decay = Instant::now();
+
+    if state.consecutive_failures == 1 {
+        crate::event_log::record(EventKind::SensorFailure, format!("{}: {}", sensor, error));
+    }
+    if state.consecutive_failures == 1 || state.last_logged.elapsed() >= LOG_INTERVAL {
+        log::warn!("sensor={} read failed: {} (failures={}, suppressed={})",
+            sensor, error, state.consecutive_failures, state.suppressed);
+        state.last_logged = Instant::now();
+        state.suppressed = 0;
+    } else {
+        state.suppressed += 1;
+    }
+    state.last_error = error;
+}
+
+pub fn report_success(sensor: &str) {
+    let mut sensors = SENSORS.lock().unwrap();
+    if let Some(state) = sensors.get_mut(sensor) {
+        if state.consecutive_failures == 0 {
+            return;
+        }
+        let halvings = state.last_decay.elapsed().as_secs() / DECAY_PERIOD.as_secs();
+        if halvings == 0 {
+            return;
+        }
+        state.consecutive_failures = state.consecutive_failures.checked_shr(halvings as u32).unwrap_or(0);
+        state.last_decay = Instant::now();
+        if state.consecutive_failures == 0 {
+            log::info!("sensor={} recovered", sensor);
+            state.suppressed = 0;
+        }
+    }
+}
+
+pub fn failure_stats() -> Vec<SensorFailureStats> {
+    let mut stats: Vec<SensorFailureStats> = SENSORS.lock().unwrap().iter()
+        .map(|(sensor, state)| SensorFailureStats {
+            sensor: sensor.clone(),
+            total_failures: state.total_failures,
+            consecutive_failures: state.consecutive_failures,
+            last_error: state.last_error.clone(),
+        })
+        .collect();
+    stats.sort_by_key(|sensor| std::cmp::Reverse(sensor.total_failures));
+    stats
+}
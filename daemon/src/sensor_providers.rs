@@ -0,0 +1,232 @@
+use std::fs;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::TemperatureSensor;
+use crate::hardware_detection::{read_millidegrees, sorted_dir_entries};
+
+// Temperature sources usable in fan curves, alerts and the statistics page. Sensor ids are
+// prefixed with the provider id so a single sensor can be read without asking every provider.
+pub trait SensorProvider: Send + Sync {
+    /// Prefix of the ids this provider hands out, e.g. "hwmon"
+    fn id(&self) -> &'static str;
+    fn sensors(&self) -> Vec<TemperatureSensor>;
+    /// Current temperature of one of this provider's sensors in °C
+    fn read(&self, sensor_id: &str) -> Option<f32> {
+        self.sensors().into_iter().find(|s| s.id == sensor_id).map(|s| s.temperature)
+    }
+}
+
+static PROVIDERS: once_cell::sync::Lazy<Vec<Box<dyn SensorProvider>>> = once_cell::sync::Lazy::new(|| {
+    let mut providers: Vec<Box<dyn SensorProvider>> = vec![
+        Box::new(HwmonProvider),
+        Box::new(ThermalZoneProvider),
+    ];
+    if LiquidctlProvider::is_available() {
+        log::info!("liquidctl found, its devices are available as temperature sources");
+        providers.push(Box::new(LiquidctlProvider::default()));
+    }
+    providers
+});
+
+pub fn all_sensors() -> Vec<TemperatureSensor> {
+    PROVIDERS.iter().flat_map(|p| p.sensors()).collect()
+}
+
+pub fn read(sensor_id: &str) -> Option<f32> {
+    let prefix = sensor_id.split(':').next()?;
+    PROVIDERS.iter()
+        .find(|p| p.id() == prefix)
+        .and_then(|p| p.read(sensor_id))
+}
+
+/// Every hwmon temperature channel, including USB thermometers and coolers with a kernel driver
+pub struct HwmonProvider;
+
+impl SensorProvider for HwmonProvider {
+    fn id(&self) -> &'static str {
+        "hwmon"
+    }
+
+    fn sensors(&self) -> Vec<TemperatureSensor> {
+        let mut sensors: Vec<TemperatureSensor> = Vec::new();
+
+        for hwmon_path in sorted_dir_entries("/sys/class/hwmon") {
+            let chip = match fs::read_to_string(hwmon_path.join("name")) {
+                Ok(name) => name.trim().to_string(),
+                Err(_) => continue,
+            };
+            // Chips behind USB are external devices, not part of the laptop
+            let source = match fs::canonicalize(hwmon_path.join("device")) {
+                Ok(device) if device.to_string_lossy().contains("/usb") => "hwmon (USB)",
+                _ => "hwmon",
+            };
+
+            let mut channels: Vec<u32> = fs::read_dir(&hwmon_path)
+                .map(|rd| rd.flatten()
+                    .filter_map(|f| {
+                        let name = f.file_name().to_string_lossy().to_string();
+                        name.strip_prefix("temp")
+                            .and_then(|rest| rest.strip_suffix("_input"))
+                            .and_then(|n| n.parse().ok())
+                    })
+                    .collect())
+                .unwrap_or_default();
+            channels.sort_unstable();
+
+            for channel in channels {
+                let temperature = match read_millidegrees(&hwmon_path.join(format!("temp{}_input", channel))) {
+                    Some(t) => t,
+                    None => continue,
+                };
+
+                let label = fs::read_to_string(hwmon_path.join(format!("temp{}_label", channel)))
+                    .map(|l| l.trim().to_string())
+                    .unwrap_or_else(|_| format!("temp{}", channel));
+
+                let mut id = format!("hwmon:{}:temp{}", chip, channel);
+                // Several chips may share a name (e.g. multiple NVMe drives)
+                let mut suffix = 2;
+                while sensors.iter().any(|s| s.id == id) {
+                    id = format!("hwmon:{}#{}:temp{}", chip, suffix, channel);
+                    suffix += 1;
+                }
+
+                sensors.push(TemperatureSensor {
+                    id,
+                    label: format!("{} {}", chip, label),
+                    source: source.to_string(),
+                    temperature,
+                    critical: read_millidegrees(&hwmon_path.join(format!("temp{}_crit", channel))),
+                });
+            }
+        }
+
+        sensors
+    }
+}
+
+pub struct ThermalZoneProvider;
+
+impl SensorProvider for ThermalZoneProvider {
+    fn id(&self) -> &'static str {
+        "thermal"
+    }
+
+    fn sensors(&self) -> Vec<TemperatureSensor> {
+        let mut sensors = Vec::new();
+
+        for zone_path in sorted_dir_entries("/sys/class/thermal") {
+            let zone = match zone_path.file_name() {
+                Some(name) if name.to_string_lossy().starts_with("thermal_zone") => {
+                    name.to_string_lossy().to_string()
+                }
+                _ => continue,
+            };
+
+            let temperature = match read_millidegrees(&zone_path.join("temp")) {
+                Some(t) => t,
+                None => continue,
+            };
+
+            let zone_type = fs::read_to_string(zone_path.join("type"))
+                .map(|t| t.trim().to_string())
+                .unwrap_or_else(|_| zone.clone());
+
+            // The critical trip point, if the zone defines one
+            let mut critical = None;
+            for trip in 0..16 {
+                match fs::read_to_string(zone_path.join(format!("trip_point_{}_type", trip))) {
+                    Ok(trip_type) if trip_type.trim() == "critical" => {
+                        critical = read_millidegrees(&zone_path.join(format!("trip_point_{}_temp", trip)));
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+
+            sensors.push(TemperatureSensor {
+                id: format!("thermal:{}", zone),
+                label: format!("{} ({})", zone_type, zone),
+                source: "thermal_zone".to_string(),
+                temperature,
+                critical,
+            });
+        }
+
+        sensors
+    }
+}
+
+// liquidctl is a subprocess per query, the fan loop asks every second
+const LIQUIDCTL_CACHE: Duration = Duration::from_secs(2);
+
+/// USB coolers without a kernel hwmon driver, through `liquidctl status --json`
+#[derive(Default)]
+pub struct LiquidctlProvider {
+    cache: Mutex<Option<(Instant, Vec<TemperatureSensor>)>>,
+}
+
+impl LiquidctlProvider {
+    pub fn is_available() -> bool {
+        std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join("liquidctl").exists()))
+            .unwrap_or(false)
+    }
+
+    fn query() -> Vec<TemperatureSensor> {
+        // The USB and hidraw nodes of the cooler belong to root
+        let output = match crate::privilege_drop::grant(Command::new("liquidctl").args(["status", "--json"]),
+            &[crate::privilege_drop::CAP_DAC_OVERRIDE]).output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                log::debug!("liquidctl status failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+                return Vec::new();
+            }
+            Err(e) => {
+                log::debug!("Failed to run liquidctl: {}", e);
+                return Vec::new();
+            }
+        };
+
+        // [{"description": "NZXT Kraken X53", "status": [{"key": "Liquid temperature", "value": 31.2, "unit": "°C"}]}]
+        let devices: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+        devices.iter()
+            .flat_map(|device| {
+                let description = device["description"].as_str().unwrap_or("liquidctl device").to_string();
+                device["status"].as_array().cloned().unwrap_or_default()
+                    .into_iter()
+                    .filter(|item| item["unit"].as_str() == Some("°C"))
+                    .filter_map(move |item| {
+                        let key = item["key"].as_str()?;
+                        Some(TemperatureSensor {
+                            id: format!("liquidctl:{}:{}", description, key),
+                            label: format!("{} {}", description, key),
+                            source: "liquidctl".to_string(),
+                            temperature: item["value"].as_f64()? as f32,
+                            critical: None,
+                        })
+                    })
+            })
+            .collect()
+    }
+}
+
+impl SensorProvider for LiquidctlProvider {
+    fn id(&self) -> &'static str {
+        "liquidctl"
+    }
+
+    fn sensors(&self) -> Vec<TemperatureSensor> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((queried_at, sensors)) = cache.as_ref() {
+            if queried_at.elapsed() < LIQUIDCTL_CACHE {
+                return sensors.clone();
+            }
+        }
+        let sensors = Self::query();
+        *cache = Some((Instant::now(), sensors.clone()));
+        sensors
+    }
+}
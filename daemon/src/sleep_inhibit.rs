@@ -0,0 +1,49 @@
+use anyhow::Result;
+use zbus::zvariant::OwnedFd;
+
+// Dust cleaning, fan calibration, auto-tuning and the A/B test hold a logind "sleep" inhibitor
+// while they run, so an idle suspend doesn't freeze them with the fans at a fixed duty or cut a
+// measurement in half. logind keeps the lock as long as the file descriptor it hands out is open,
+// dropping the Inhibitor releases it, on completion and abort alike. Closing the lid still
+// suspends with logind's default LidSwitchIgnoreInhibited=yes. Without root (--drop-privileges)
+// polkit may refuse the lock, the routine then runs without it.
+pub struct Inhibitor {
+    _fd: OwnedFd,
+    what: &'static str,
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        log::debug!("Released sleep inhibitor ({})", self.what);
+    }
+}
+
+/// None when logind isn't there or refuses, the routine runs anyway
+pub async fn acquire(what: &'static str) -> Option<Inhibitor> {
+    match inhibit(what).await {
+        Ok(fd) => {
+            log::debug!("Holding sleep inhibitor ({})", what);
+            Some(Inhibitor { _fd: fd, what })
+        }
+        Err(e) => {
+            log::warn!("Failed to inhibit sleep ({}): {}", what, e);
+            None
+        }
+    }
+}
+
+async fn inhibit(what: &str) -> Result<OwnedFd> {
+    let connection = zbus::Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    ).await?;
+
+    let why = format!("{} is running", what);
+    let fd: OwnedFd = proxy
+        .call("Inhibit", &("sleep", "TUXEDO Control Center", why.as_str(), "block"))
+        .await?;
+    Ok(fd)
+}
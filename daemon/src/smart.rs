@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::SmartInfo;
+
+// smartctl takes a while, the statistics page polls every second
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const REALLOCATED_SECTOR_COUNT: u64 = 5;
+// smartctl's exit status for a SATA drive it left spun down because of -n standby. The default
+// is 2, the same as a device that failed to open, and a drive that answers would need nearly
+// every failure bit set to end up here
+const STANDBY_EXIT: i32 = 123;
+
+static SMARTCTL_AVAILABLE: once_cell::sync::Lazy<bool> = once_cell::sync::Lazy::new(|| {
+    Command::new("smartctl")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+});
+
+// When the drive was last asked, and what it answered
+type CacheEntry = (Instant, Option<SmartInfo>);
+
+static CACHE: once_cell::sync::Lazy<Mutex<HashMap<String, CacheEntry>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// SMART data for NVMe and SATA drives, None if smartctl is missing or the drive is unsupported
+pub fn read_smart(dev_name: &str) -> Option<SmartInfo> {
+    if !*SMARTCTL_AVAILABLE || !is_smart_capable(dev_name) {
+        return None;
+    }
+
+    let previous = match CACHE.lock().unwrap().get(dev_name) {
+        Some((read_at, info)) if read_at.elapsed() < CACHE_TTL => return info.clone(),
+        Some((_, info)) => info.clone(),
+        None => None,
+    };
+
+    // Without the lock, a slow drive doesn't hold up the queries of the others
+    let info = match query_smartctl(dev_name) {
+        Query::Info(info) => info,
+        // Spun down, keep what it reported while awake and look again later
+        Query::Standby => previous,
+    };
+    CACHE.lock().unwrap().insert(dev_name.to_string(), (Instant::now(), info.clone()));
+    info
+}
+
+enum Query {
+    Info(Option<SmartInfo>),
+    Standby,
+}
+
+fn is_smart_capable(dev_name: &str) -> bool {
+    if dev_name.starts_with("nvme") {
+        return true;
+    }
+
+    // USB bridges and card readers show up as sd* too, only ATA disks speak SMART reliably
+    dev_name.starts_with("sd")
+        && fs::read_to_string(format!("/sys/block/{}/device/vendor", dev_name))
+            .map(|v| v.trim() == "ATA")
+            .unwrap_or(false)
+}
+
+fn query_smartctl(dev_name: &str) -> Query {
+    let mut command = Command::new("smartctl");
+    command.args(["--json", "-H", "-A"]);
+    // Only ATA drives have a standby check, NVMe manages its power states itself
+    let standby = format!("standby,{}", STANDBY_EXIT);
+    if dev_name.starts_with("sd") {
+        command.args(["-n", &standby]);
+    }
+    command.arg(format!("/dev/{}", dev_name));
+
+    // Opening the device node, ATA pass-through and NVMe admin commands
+    let output = match crate::privilege_drop::grant(&mut command,
+        &[crate::privilege_drop::CAP_DAC_OVERRIDE, crate::privilege_drop::CAP_SYS_RAWIO, crate::privilege_drop::CAP_SYS_ADMIN])
+        .output() {
+        Ok(output) => output,
+        Err(_) => return Query::Info(None),
+    };
+    if output.status.code() == Some(STANDBY_EXIT) {
+        return Query::Standby;
+    }
+    Query::Info(parse_smartctl(&output.stdout))
+}
+
+fn parse_smartctl(stdout: &[u8]) -> Option<SmartInfo> {
+    // Exit status is a bitmask that is non-zero for failing drives, so only the JSON counts
+    let json: serde_json::Value = serde_json::from_slice(stdout).ok()?;
+    let passed = json["smart_status"]["passed"].as_bool()?;
+
+    let reallocated_sectors = json["ata_smart_attributes"]["table"]
+        .as_array()
+        .and_then(|table| {
+            table.iter().find(|attr| attr["id"].as_u64() == Some(REALLOCATED_SECTOR_COUNT))
+        })
+        .and_then(|attr| attr["raw"]["value"].as_u64());
+
+    Some(SmartInfo {
+        passed,
+        temperature: json["temperature"]["current"].as_f64().map(|t| t as f32),
+        power_on_hours: json["power_on_time"]["hours"].as_u64(),
+        reallocated_sectors,
+        percentage_used: json["nvme_smart_health_information_log"]["percentage_used"]
+            .as_u64()
+            .map(|p| p as u8),
+    })
+}
@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::sync::Mutex;
+use tuxedo_common::types::{EventKind, Profile, SystemProfiles};
+
+// System profiles belong to the daemon rather than a user's GUI configuration: they are
+// managed by the administrator (by hand in this file, or by promoting a user profile over
+// DBus) and one of them can be applied at boot, before anybody logs in.
+//
+// Precedence: a system profile wins over a user profile of the same name. The GUI applies
+// the system one when switching by name, and the boot profile goes before restoring the
+// profile applied last.
+const STATE_DIR: &str = "/var/lib/tuxedo-control-center";
+const PROFILES_FILE: &str = "/var/lib/tuxedo-control-center/system_profiles.json";
+// Remembers the boot the boot profile was applied in, so daemon restarts don't reapply it
+const BOOT_ID_FILE: &str = "/var/lib/tuxedo-control-center/boot_id";
+const KERNEL_BOOT_ID: &str = "/proc/sys/kernel/random/boot_id";
+
+static PROFILES: once_cell::sync::Lazy<Mutex<SystemProfiles>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load()));
+
+pub fn get() -> SystemProfiles {
+    PROFILES.lock().unwrap().clone()
+}
+
+/// Copies a profile into system scope, an existing one of that name is only replaced on request
+pub fn promote(profile: Profile, replace: bool) -> Result<()> {
+    if profile.name.trim().is_empty() {
+        return Err(anyhow!("Profile has no name"));
+    }
+    let mut profiles = PROFILES.lock().unwrap();
+    let mut updated = profiles.clone();
+    match updated.profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(_) if !replace => return Err(anyhow!("System profile '{}' already exists", profile.name)),
+        Some(existing) => *existing = profile.clone(),
+        None => updated.profiles.push(profile.clone()),
+    }
+    save(&updated)?;
+    *profiles = updated;
+
+    log::info!("Profile '{}' promoted to system scope", profile.name);
+    crate::event_log::record(EventKind::ProfileSwitch, format!("Profile '{}' is now a system profile", profile.name));
+    Ok(())
+}
+
+pub fn remove(name: &str) -> Result<()> {
+    let mut profiles = PROFILES.lock().unwrap();
+    if !profiles.profiles.iter().any(|p| p.name == name) {
+        return Err(anyhow!("No system profile '{}'", name));
+    }
+    let mut updated = profiles.clone();
+    updated.profiles.retain(|p| p.name != name);
+    if updated.boot_profile.as_deref() == Some(name) {
+        updated.boot_profile = None;
+    }
+    save(&updated)?;
+    *profiles = updated;
+    log::info!("System profile '{}' removed", name);
+    Ok(())
+}
+
+/// None applies no profile at boot, the last applied one is restored as before
+pub fn set_boot_profile(name: Option<&str>) -> Result<()> {
+    let mut profiles = PROFILES.lock().unwrap();
+    if let Some(name) = name {
+        if !profiles.profiles.iter().any(|p| p.name == name) {
+            return Err(anyhow!("No system profile '{}'", name));
+        }
+    }
+    let mut updated = profiles.clone();
+    updated.boot_profile = name.map(str::to_string);
+    save(&updated)?;
+    *profiles = updated;
+    Ok(())
+}
+
+/// The boot profile, on the daemon's first start in this boot only
+pub fn boot_profile_due() -> Option<Profile> {
+    let boot_id = fs::read_to_string(KERNEL_BOOT_ID).ok()?.trim().to_string();
+    if fs::read_to_string(BOOT_ID_FILE).is_ok_and(|last| last.trim() == boot_id) {
+        return None;
+    }
+    if let Err(e) = fs::create_dir_all(STATE_DIR).and_then(|_| fs::write(BOOT_ID_FILE, &boot_id)) {
+        log::warn!("Failed to write {}: {}", BOOT_ID_FILE, e);
+    }
+
+    let profiles = PROFILES.lock().unwrap();
+    let name = profiles.boot_profile.as_ref()?;
+    let profile = profiles.profiles.iter().find(|p| &p.name == name).cloned();
+    if profile.is_none() {
+        log::warn!("Boot profile '{}' is not a system profile", name);
+    }
+    profile
+}
+
+fn load() -> SystemProfiles {
+    match fs::read_to_string(PROFILES_FILE) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid {}: {}", PROFILES_FILE, e);
+            SystemProfiles::default()
+        }),
+        Err(_) => SystemProfiles::default(),
+    }
+}
+
+fn save(profiles: &SystemProfiles) -> Result<()> {
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(PROFILES_FILE, serde_json::to_string_pretty(profiles)?)?;
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use futures_util::StreamExt;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tuxedo_common::types::FanInfo;
+use zbus::{Connection, SignalContext};
+use crate::dbus_interface::ControlInterface;
+use crate::fan_backend::FanBackend;
+
+// Fan speeds and the CPU temperature as signals, so clients can subscribe instead of calling
+// GetFanInfo and GetCpuInfo every second. Values are sampled at the rate the GUI used to poll
+// and only changes are signalled. Sampling only runs while a client has called
+// SubscribeTelemetry, a client leaving the bus counts as unsubscribing.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Package sensors report in millidegrees and jitter by a few tenths
+const MIN_TEMP_CHANGE: f64 = 0.5;
+
+// Unique bus names of the subscribed clients
+static SUBSCRIBERS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+static SUBSCRIBED: once_cell::sync::Lazy<Notify> = once_cell::sync::Lazy::new(Notify::new);
+
+pub fn subscribe(client: &str) {
+    if SUBSCRIBERS.lock().unwrap().insert(client.to_string()) {
+        log::debug!("{} subscribed to telemetry", client);
+        SUBSCRIBED.notify_one();
+    }
+}
+
+pub fn unsubscribe(client: &str) {
+    if SUBSCRIBERS.lock().unwrap().remove(client) {
+        log::debug!("{} unsubscribed from telemetry", client);
+    }
+}
+
+fn has_subscribers() -> bool {
+    !SUBSCRIBERS.lock().unwrap().is_empty()
+}
+
+// Clients that exit or crash never unsubscribe themselves
+async fn watch_clients(connection: Connection) -> zbus::Result<()> {
+    let proxy = zbus::fdo::DBusProxy::new(&connection).await?;
+    let mut changes = proxy.receive_name_owner_changed().await?;
+    while let Some(change) = changes.next().await {
+        let args = change.args()?;
+        if args.new_owner().is_none() {
+            unsubscribe(args.name().as_str());
+        }
+    }
+    Ok(())
+}
+
+/// One entry per fan the backend reports, however many that is
+pub fn fan_info(backend: &dyn FanBackend) -> Vec<FanInfo> {
+    (0..backend.fan_count())
+        .map(|fan_id| FanInfo {
+            id: fan_id,
+            name: format!("Fan {}", fan_id),
+            rpm_or_percent: backend.get_duty(fan_id).unwrap_or(0),
+            temperature: backend.get_temperature(fan_id).ok(),
+            is_rpm: false,  // Currently returning percentage
+        })
+        .collect()
+}
+
+// Duty and whole degrees, sensor noise below that isn't worth a signal
+fn fan_key(fans: &[FanInfo]) -> Vec<(u32, Option<i32>)> {
+    fans.iter()
+        .map(|fan| (fan.rpm_or_percent, fan.temperature.map(|t| t.round() as i32)))
+        .collect()
+}
+
+// Runs on the service connection, signals have to come from the bus name owner
+pub async fn monitor_task(connection: Connection) {
+    let ctxt = match SignalContext::new(&connection, "/com/tuxedo/Control") {
+        Ok(ctxt) => ctxt,
+        Err(e) => {
+            log::warn!("Telemetry signals disabled: {}", e);
+            return;
+        }
+    };
+
+    let watch_connection = connection.clone();
+    tokio::spawn(async move {
+        if let Err(e) = watch_clients(watch_connection).await {
+            log::warn!("Not watching telemetry clients, subscriptions won't expire: {}", e);
+        }
+    });
+
+    let mut last_fans = None;
+    let mut last_temp: Option<f64> = None;
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        if !has_subscribers() {
+            // A new subscriber gets the current values, changed or not
+            last_fans = None;
+            last_temp = None;
+            SUBSCRIBED.notified().await;
+            interval.reset_immediately();
+        }
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("telemetry");
+
+        if let Some(ref backend) = crate::fan_backend::detect() {
+            let fans = fan_info(backend.as_ref());
+            let key = fan_key(&fans);
+            if last_fans.as_ref() != Some(&key) {
+                last_fans = Some(key);
+                match serde_json::to_string(&fans) {
+                    Ok(json) => {
+                        if let Err(e) = ControlInterface::fan_speed_changed(&ctxt, &json).await {
+                            log::debug!("Failed to signal fan speeds: {}", e);
+                        }
+                    }
+                    Err(e) => log::debug!("Failed to serialize fan speeds: {}", e),
+                }
+            }
+        }
+
+        if let Ok(temp) = crate::hardware_detection::get_package_temp() {
+            let temp = temp as f64;
+            if last_temp.is_none_or(|last| (last - temp).abs() >= MIN_TEMP_CHANGE) {
+                last_temp = Some(temp);
+                if let Err(e) = ControlInterface::cpu_temp_changed(&ctxt, temp).await {
+                    log::debug!("Failed to signal CPU temperature: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use zbus::Connection;
+use crate::dbus_interface::ControlInterface;
+
+// Distance of the hottest sensor to its critical temperature, as a quick health figure
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Only signal changes of at least this much, temperatures jitter by tenths of a degree
+const MIN_CHANGE: f64 = 1.0;
+// Some chips report nonsense critical values (0, 255, ...)
+const PLAUSIBLE_CRITICAL: std::ops::RangeInclusive<f32> = 50.0..=150.0;
+
+#[derive(Clone, Default)]
+pub struct Headroom {
+    pub degrees: Option<f64>,
+    pub sensor: String,
+}
+
+static CURRENT: once_cell::sync::Lazy<Mutex<Headroom>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Headroom::default()));
+
+pub fn get() -> Headroom {
+    CURRENT.lock().unwrap().clone()
+}
+
+fn measure() -> Headroom {
+    let sensors = crate::hardware_detection::get_all_temperatures().unwrap_or_default();
+    sensors.iter()
+        .filter_map(|s| {
+            let critical = s.critical.filter(|c| PLAUSIBLE_CRITICAL.contains(c))?;
+            Some(((critical - s.temperature) as f64, &s.label))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(degrees, sensor)| Headroom { degrees: Some(degrees), sensor: sensor.clone() })
+        .unwrap_or_default()
+}
+
+// Runs on the service connection, property change signals have to come from the bus name owner
+pub async fn monitor_task(connection: Connection) {
+    let iface = match connection.object_server().interface::<_, ControlInterface>("/com/tuxedo/Control").await {
+        Ok(iface) => iface,
+        Err(e) => {
+            log::warn!("Thermal headroom disabled: {}", e);
+            return;
+        }
+    };
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let _timer = crate::daemon_stats::TickTimer::new("thermal_headroom");
+
+        let headroom = measure();
+        let changed = {
+            let mut current = CURRENT.lock().unwrap();
+            let changed = match (current.degrees, headroom.degrees) {
+                (Some(old), Some(new)) => (old - new).abs() >= MIN_CHANGE || current.sensor != headroom.sensor,
+                (old, new) => old.is_some() != new.is_some(),
+            };
+            if changed {
+                *current = headroom;
+            }
+            changed
+        };
+
+        if changed {
+            let ctxt = iface.signal_context();
+            let iface = iface.get().await;
+            if let Err(e) = iface.thermal_headroom_changed(ctxt).await {
+                log::debug!("Failed to signal thermal headroom change: {}", e);
+            }
+            let _ = iface.thermal_headroom_sensor_changed(ctxt).await;
+        }
+    }
+}
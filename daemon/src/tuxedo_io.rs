@@ -1,16 +1,25 @@
 use anyhow::{anyhow, Result};
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::os::unix::io::AsRawFd;
+use std::sync::Mutex;
 use nix::errno::Errno;
 use nix::libc;
 
 const TUXEDO_IO_DEVICE: &str = "/dev/tuxedo_io";
+
+// Opened once, before privilege_drop gives up root, and shared by every TuxedoIo. A module
+// reload replaces the device, the handle is reopened then, tagged with the reload generation.
+static DEVICE: Mutex<Option<(u64, File)>> = Mutex::new(None);
 const IOCTL_MAGIC: u8 = 0xEC;
 const MAGIC_READ_CL: u8 = IOCTL_MAGIC + 1;
 const MAGIC_WRITE_CL: u8 = IOCTL_MAGIC + 2;
 const MAGIC_READ_UW: u8 = IOCTL_MAGIC + 3;
 const MAGIC_WRITE_UW: u8 = IOCTL_MAGIC + 4;
 
+// The Clevo write ioctl packs one duty byte per fan into a single i32
+const CLEVO_MAX_FANS: u32 = 3;
+const UNIWILL_MAX_FANS: u32 = 2;
+
 // Hardware check ioctls
 // nix::ioctl_read!(ioctl_cl_hw_interface_id, MAGIC_READ_CL, 0x00, [u8; 30]);
 // nix::ioctl_read!(ioctl_hwcheck_cl, IOCTL_MAGIC, 0x05, i32);
@@ -53,6 +62,45 @@ const MAGIC_WRITE_UW: u8 = IOCTL_MAGIC + 4;
 // nix::ioctl_write_ptr!(ioctl_uw_tdp2_w, MAGIC_WRITE_UW, 0x17, i32);
 // nix::ioctl_write_ptr!(ioctl_uw_perf_prof, MAGIC_WRITE_UW, 0x18, i32);
 
+/// One fan as the Clevo fan info ioctls (0x10-0x12, one per fan) report it:
+///
+///   bits  0-7   duty cycle, 0-255
+///   bits  8-15  temp1, not filled in by the ECs seen so far
+///   bits 16-23  temp2, the fan's sensor in °C; 0 or 1 when there is no such fan
+///   bits 24-31  unused
+///
+/// There is no tachometer field, RPM only comes from a hwmon tach where the platform driver
+/// registers one (FanBackend::get_rpm).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClevoFanInfo {
+    pub raw_duty: u8,
+    pub temp1: u8,
+    pub temp2: u8,
+}
+
+impl ClevoFanInfo {
+    pub fn from_raw(raw: i32) -> Self {
+        Self {
+            raw_duty: (raw & 0xFF) as u8,
+            temp1: ((raw >> 8) & 0xFF) as u8,
+            temp2: ((raw >> 16) & 0xFF) as u8,
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.temp2 > 1
+    }
+
+    pub fn duty_percent(&self) -> u32 {
+        TuxedoIo::clevo_raw_to_percent(self.raw_duty)
+    }
+
+    /// temp2, it is the reliable one on Clevo
+    pub fn temperature(&self) -> Option<u32> {
+        self.exists().then_some(self.temp2 as u32)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HardwareInterface {
     Clevo,
@@ -101,6 +149,9 @@ impl TuxedoIo {
     }
     
     fn ioctl_write_i32(fd: i32, request: libc::c_ulong, data: i32) -> Result<()> {
+        if crate::dry_run::ioctl(request, data) {
+            return Ok(());
+        }
         let res = unsafe { libc::ioctl(fd, request, &data as *const i32) };
         Errno::result(res)
             .map_err(|e| anyhow!("ioctl write failed (req={:#x}): {}", request, e))?;
@@ -108,17 +159,43 @@ impl TuxedoIo {
     }
     
     fn ioctl_write_only(fd: i32, request: libc::c_ulong, arg: i32) -> Result<()> {
+        if crate::dry_run::ioctl(request, arg) {
+            return Ok(());
+        }
         let res = unsafe { libc::ioctl(fd, request, arg) };
         Errno::result(res)
             .map_err(|e| anyhow!("ioctl write failed (req={:#x}): {}", request, e))?;
         Ok(())
     }
     
-    pub fn new() -> Result<Self> {
+    /// Open the device while the daemon is still root
+    pub fn open_early() {
+        if Self::is_available() {
+            if let Err(e) = Self::open_device() {
+                log::warn!("Failed to open {}: {}", TUXEDO_IO_DEVICE, e);
+            }
+        }
+    }
+
+    fn open_device() -> Result<File> {
+        let generation = crate::module_reload::generation();
+        let mut shared = DEVICE.lock().unwrap();
+        if let Some((opened_in, ref device)) = *shared {
+            if opened_in == generation {
+                return Ok(device.try_clone()?);
+            }
+        }
+
         let device = OpenOptions::new()
             .read(true)
             .write(true)
             .open(TUXEDO_IO_DEVICE)?;
+        *shared = Some((generation, device.try_clone()?));
+        Ok(device)
+    }
+
+    pub fn new() -> Result<Self> {
+        let device = Self::open_device()?;
 
         let interface = Self::detect_interface(&device)?;
         let fan_count = Self::detect_fan_count(&device, interface)?;
@@ -154,6 +231,19 @@ impl TuxedoIo {
         (((p * 255) + 50) / 100) as u8
     }
 
+    fn read_clevo_fan_info(fd: i32, fan_id: u32) -> Result<ClevoFanInfo> {
+        if fan_id >= CLEVO_MAX_FANS {
+            return Err(anyhow!("Invalid Clevo fan ID: {}", fan_id));
+        }
+        let request = Self::ior(MAGIC_READ_CL, 0x10 + fan_id as u8, Self::PTR_SIZE);
+        Self::ioctl_read_i32(fd, request).map(ClevoFanInfo::from_raw)
+    }
+
+    // Fan 0 in the lowest byte, the top byte stays clear
+    fn clevo_pack_duties(raw: [u8; CLEVO_MAX_FANS as usize]) -> i32 {
+        (raw[0] as i32) | ((raw[1] as i32) << 8) | ((raw[2] as i32) << 16)
+    }
+
     fn detect_interface(device: &std::fs::File) -> Result<HardwareInterface> {
         let fd = device.as_raw_fd();
 
@@ -199,19 +289,10 @@ impl TuxedoIo {
         match interface {
             HardwareInterface::Clevo => {
                 let mut count = 0;
-                for fan_id in 0..3u32 {
-                    let seq = 0x10 + fan_id as u8;
-                    let request = Self::ior(MAGIC_READ_CL, seq, Self::PTR_SIZE);
-                    
-                    if let Ok(raw) = Self::ioctl_read_i32(fd, request) {
-                        // Use temp2 field (bits 16-23) to check if fan exists
-                        let temp2 = ((raw >> 16) & 0xFF) as u32;
-                        if temp2 <= 1 {
-                            break;
-                        }
-                        count += 1;
-                    } else {
-                        break;
+                for fan_id in 0..CLEVO_MAX_FANS {
+                    match Self::read_clevo_fan_info(fd, fan_id) {
+                        Ok(info) if info.exists() => count += 1,
+                        _ => break,
                     }
                 }
                 Ok(count)
@@ -235,20 +316,10 @@ impl TuxedoIo {
         let fd = self.device.as_raw_fd();
 
         match self.interface {
-            HardwareInterface::Clevo => {
-                if fan_id >= 3 {
-                    return Err(anyhow!("Invalid Clevo fan ID: {}", fan_id));
-                }
-                
-                let seq = 0x10 + fan_id as u8;
-                let request = Self::ior(MAGIC_READ_CL, seq, Self::PTR_SIZE);
-                let raw = Self::ioctl_read_i32(fd, request)?;
-
-                Ok(Self::clevo_raw_to_percent((raw & 0xFF) as u8))
-            }
+            HardwareInterface::Clevo => Ok(Self::read_clevo_fan_info(fd, fan_id)?.duty_percent()),
 
             HardwareInterface::Uniwill => {
-                if fan_id >= 2 {
+                if fan_id >= UNIWILL_MAX_FANS {
                     return Err(anyhow!("Invalid Uniwill fan ID: {}", fan_id));
                 }
                 
@@ -276,26 +347,21 @@ impl TuxedoIo {
                 Self::ioctl_write_i32(fd, auto_request, manual_val)?;
                 
                 // Step 2: Read current speeds for all fans
-                let mut current_raw = [0u8; 3];
-                for i in 0..self.fan_count.min(3) {
-                    let seq = 0x10 + i as u8;
-                    let request = Self::ior(MAGIC_READ_CL, seq, Self::PTR_SIZE);
-                    
-                    if let Ok(raw) = Self::ioctl_read_i32(fd, request) {
-                        current_raw[i as usize] = (raw & 0xFF) as u8;
+                let mut current_raw = [0u8; CLEVO_MAX_FANS as usize];
+                for i in 0..self.fan_count.min(CLEVO_MAX_FANS) {
+                    if let Ok(info) = Self::read_clevo_fan_info(fd, i) {
+                        current_raw[i as usize] = info.raw_duty;
                     }
                 }
 
                 // Step 3: Update the requested fan speed
-                if fan_id >= 3 {
+                if fan_id >= CLEVO_MAX_FANS {
                     return Err(anyhow!("Invalid Clevo fan ID: {}", fan_id));
                 }
                 current_raw[fan_id as usize] = Self::clevo_percent_to_raw(speed_percent);
 
                 // Step 4: Pack all fan speeds into a single i32
-                let packed = (current_raw[0] as i32)
-                    | ((current_raw[1] as i32) << 8)
-                    | ((current_raw[2] as i32) << 16);
+                let packed = Self::clevo_pack_duties(current_raw);
 
                 log::debug!(
                     "Setting Clevo fan {} to {}% (raw: {:#04x}), packed: {:#08x}",
@@ -365,25 +431,12 @@ impl TuxedoIo {
         let fd = self.device.as_raw_fd();
 
         match self.interface {
-            HardwareInterface::Clevo => {
-                if fan_id >= 3 {
-                    return Err(anyhow!("Invalid Clevo fan ID: {}", fan_id));
-                }
-                
-                let seq = 0x10 + fan_id as u8;
-                let request = Self::ior(MAGIC_READ_CL, seq, Self::PTR_SIZE);
-                let raw = Self::ioctl_read_i32(fd, request)?;
-
-                // Use temp2 field (bits 16-23) - more reliable on Clevo
-                let temp2 = ((raw >> 16) & 0xFF) as u32;
-                if temp2 <= 1 {
-                    return Err(anyhow!("Fan {} not available", fan_id));
-                }
-                Ok(temp2)
-            }
+            HardwareInterface::Clevo => Self::read_clevo_fan_info(fd, fan_id)?
+                .temperature()
+                .ok_or_else(|| anyhow!("Fan {} not available", fan_id)),
 
             HardwareInterface::Uniwill => {
-                if fan_id >= 2 {
+                if fan_id >= UNIWILL_MAX_FANS {
                     return Err(anyhow!("Invalid Uniwill fan ID: {}", fan_id));
                 }
                 
@@ -583,4 +636,119 @@ impl TuxedoIo {
         let request = Self::iow(MAGIC_WRITE_CL, 0x12, Self::PTR_SIZE);
         Self::ioctl_write_i32(fd, request, value)
     }
+
+    /// Every read ioctl of this interface with its raw result, for the developer EC dump.
+    /// Nothing is decoded, a new model may well use the bits differently.
+    pub fn read_registers(&self) -> Vec<(&'static str, libc::c_ulong, Result<i32>)> {
+        let fd = self.device.as_raw_fd();
+        let mut reads: Vec<(&'static str, u8, u8)> = vec![
+            ("hwcheck_cl", IOCTL_MAGIC, 0x05),
+            ("hwcheck_uw", IOCTL_MAGIC, 0x06),
+        ];
+        match self.interface {
+            HardwareInterface::Clevo => reads.extend([
+                ("cl_faninfo1", MAGIC_READ_CL, 0x10),
+                ("cl_faninfo2", MAGIC_READ_CL, 0x11),
+                ("cl_faninfo3", MAGIC_READ_CL, 0x12),
+                ("cl_webcam_sw", MAGIC_READ_CL, 0x13),
+            ]),
+            HardwareInterface::Uniwill => reads.extend([
+                ("uw_fanspeed", MAGIC_READ_UW, 0x10),
+                ("uw_fanspeed2", MAGIC_READ_UW, 0x11),
+                ("uw_fan_temp", MAGIC_READ_UW, 0x12),
+                ("uw_fan_temp2", MAGIC_READ_UW, 0x13),
+                ("uw_tdp0", MAGIC_READ_UW, 0x18),
+                ("uw_tdp1", MAGIC_READ_UW, 0x19),
+                ("uw_tdp2", MAGIC_READ_UW, 0x1a),
+                ("uw_tdp0_min", MAGIC_READ_UW, 0x1b),
+                ("uw_tdp1_min", MAGIC_READ_UW, 0x1c),
+                ("uw_tdp2_min", MAGIC_READ_UW, 0x1d),
+                ("uw_tdp0_max", MAGIC_READ_UW, 0x1e),
+                ("uw_tdp1_max", MAGIC_READ_UW, 0x1f),
+                ("uw_tdp2_max", MAGIC_READ_UW, 0x20),
+                ("uw_profs_available", MAGIC_READ_UW, 0x21),
+            ]),
+            HardwareInterface::None => {}
+        }
+
+        reads.into_iter()
+            .map(|(name, type_, nr)| {
+                let request = Self::ior(type_, nr, Self::PTR_SIZE);
+                (name, request, Self::ioctl_read_i32(fd, request))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn packed_duties_unpack_per_fan(raw in any::<[u8; 3]>()) {
+            let packed = TuxedoIo::clevo_pack_duties(raw);
+            prop_assert_eq!(packed >> 24, 0);
+            for (fan, duty) in raw.iter().enumerate() {
+                prop_assert_eq!(((packed >> (8 * fan)) & 0xFF) as u8, *duty);
+            }
+        }
+
+        #[test]
+        fn fan_info_fields_come_from_their_bytes(raw in any::<i32>()) {
+            let info = ClevoFanInfo::from_raw(raw);
+            prop_assert_eq!(info.raw_duty as i32, raw & 0xFF);
+            prop_assert_eq!(info.temp1 as i32, (raw >> 8) & 0xFF);
+            prop_assert_eq!(info.temp2 as i32, (raw >> 16) & 0xFF);
+            prop_assert!(info.duty_percent() <= 100);
+            prop_assert_eq!(info.temperature().is_some(), info.exists());
+        }
+
+        #[test]
+        fn percent_survives_raw_round_trip(percent in 0u32..=100) {
+            let raw = TuxedoIo::clevo_percent_to_raw(percent);
+            prop_assert_eq!(TuxedoIo::clevo_raw_to_percent(raw), percent);
+        }
+
+        #[test]
+        fn percent_to_raw_is_monotonic_and_capped(a in any::<u32>(), b in any::<u32>()) {
+            let (low, high) = (a.min(b), a.max(b));
+            prop_assert!(TuxedoIo::clevo_percent_to_raw(low) <= TuxedoIo::clevo_percent_to_raw(high));
+            prop_assert!(TuxedoIo::clevo_raw_to_percent(TuxedoIo::clevo_percent_to_raw(high)) <= 100);
+        }
+    }
+
+    #[test]
+    fn fan_info() {
+        // 45 °C, temp1 empty, full duty
+        let info = ClevoFanInfo::from_raw(0x002D_00FF);
+        assert_eq!(info, ClevoFanInfo { raw_duty: 0xFF, temp1: 0, temp2: 45 });
+        assert_eq!(info.duty_percent(), 100);
+        assert_eq!(info.temperature(), Some(45));
+
+        let stopped = ClevoFanInfo::from_raw(0x0028_0000);
+        assert_eq!(stopped.duty_percent(), 0);
+        assert_eq!(stopped.temperature(), Some(40));
+
+        // Missing fans report temp2 0 or 1, whatever the other bytes say
+        for raw in [0x0000_0000, 0x0001_0080, 0x0100_3380, !0x00FF_0000] {
+            let info = ClevoFanInfo::from_raw(raw);
+            assert!(!info.exists(), "{:#x}", raw);
+            assert_eq!(info.temperature(), None);
+        }
+
+        // The top byte is ignored, also when it makes the value negative
+        assert_eq!(ClevoFanInfo::from_raw(0xFF32_1480_u32 as i32), ClevoFanInfo { raw_duty: 0x80, temp1: 0x14, temp2: 0x32 });
+        assert_eq!(ClevoFanInfo::from_raw(0x0032_0080).duty_percent(), 50);
+    }
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(TuxedoIo::clevo_percent_to_raw(0), 0);
+        assert_eq!(TuxedoIo::clevo_percent_to_raw(100), 0xFF);
+        assert_eq!(TuxedoIo::clevo_percent_to_raw(u32::MAX), 0xFF);
+        assert_eq!(TuxedoIo::clevo_raw_to_percent(0xFF), 100);
+        assert_eq!(TuxedoIo::clevo_pack_duties([0xFF; 3]), 0x00FF_FFFF);
+    }
 }
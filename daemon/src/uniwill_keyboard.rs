@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+use tuxedo_common::types::KeyboardMode;
+
+// Current tuxedo-drivers (tuxedo_keyboard with uniwill_wmi) and the mainline uniwill-wmi driver
+// register the backlight as LED class devices, white:kbd_backlight or one multicolor
+// rgb:kbd_backlight per zone, which the generic LED code drives. Uniwill boards running the
+// older tuxedo-keyboard (3.x) driver have none, their single-zone backlight takes one of eight
+// named colors through a platform attribute. That attribute is only used as a fallback.
+
+/// Name reported in KeyboardDevice for the legacy interface
+pub const DEVICE_NAME: &str = "tuxedo_keyboard (Uniwill)";
+const LEDS_PATH: &str = "/sys/class/leds";
+// Found in the device path of the LEDs the current drivers register
+const DRIVER_DEVICES: [&str; 3] = ["tuxedo_keyboard", "uniwill-wmi", "uniwill_wmi"];
+const COLOR_STRING: &str = "/sys/devices/platform/tuxedo_keyboard/uw_kbd_bl_color/color_string";
+
+const COLORS: [(&str, (u8, u8, u8)); 8] = [
+    ("BLACK", (0, 0, 0)),
+    ("RED", (255, 0, 0)),
+    ("GREEN", (0, 255, 0)),
+    ("BLUE", (0, 0, 255)),
+    ("YELLOW", (255, 255, 0)),
+    ("MAGENTA", (255, 0, 255)),
+    ("CYAN", (0, 255, 255)),
+    ("WHITE", (255, 255, 255)),
+];
+
+/// Keyboard backlight LEDs of the current drivers
+pub fn led_devices() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(LEDS_PATH) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries.flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().contains("kbd_backlight"))
+        .filter(|entry| fs::canonicalize(entry.path())
+            .is_ok_and(|path| DRIVER_DEVICES.iter().any(|device| path.to_string_lossy().contains(device))))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The legacy attribute, only when the current drivers registered no LED
+pub fn legacy_available() -> bool {
+    Path::new(COLOR_STRING).exists() && led_devices().is_empty()
+}
+
+pub fn color_path() -> &'static str {
+    COLOR_STRING
+}
+
+pub fn apply_mode(mode: &KeyboardMode) -> Result<()> {
+    if !legacy_available() {
+        return Err(anyhow!("Legacy Uniwill keyboard backlight not found"));
+    }
+
+    let (r, g, b) = match mode {
+        KeyboardMode::SingleColor { r, g, b, .. }
+        | KeyboardMode::Breathe { r, g, b, .. }
+        | KeyboardMode::Flash { r, g, b, .. } => (*r, *g, *b),
+        // No effects in this interface, keep the keyboard lit
+        _ => (255, 255, 255),
+    };
+
+    // Brightness can't be set separately, off is the only other level
+    let name = if mode.brightness() == 0 { "BLACK" } else { nearest_color(r, g, b) };
+    crate::dry_run::write(COLOR_STRING, name)?;
+    log::info!("Set Uniwill keyboard color to {}", name);
+    Ok(())
+}
+
+pub fn turn_off() -> Result<()> {
+    crate::dry_run::write(COLOR_STRING, "BLACK")?;
+    Ok(())
+}
+
+fn nearest_color(r: u8, g: u8, b: u8) -> &'static str {
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        [(r, cr), (g, cg), (b, cb)]
+            .iter()
+            .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+            .sum::<i32>()
+    };
+
+    // Black is reserved for off
+    COLORS[1..].iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(name, _)| *name)
+        .unwrap_or("WHITE")
+}
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::os::unix::net::UnixStream;
+use std::sync::Mutex;
+use tuxedo_common::types::VmSettings;
+
+const SWAPPINESS: &str = "/proc/sys/vm/swappiness";
+const DIRTY_WRITEBACK: &str = "/proc/sys/vm/dirty_writeback_centisecs";
+const KNOBS: [&str; 2] = [SWAPPINESS, DIRTY_WRITEBACK];
+
+// Deliberately narrower than what the kernel accepts
+const SWAPPINESS_RANGE: RangeInclusive<u32> = 0..=100;
+const DIRTY_WRITEBACK_RANGE: RangeInclusive<u32> = 100..=6000;
+
+// Values found before the first profile touched a knob, restored when a profile leaves it unset
+static ORIGINAL_VALUES: once_cell::sync::Lazy<Mutex<HashMap<&'static str, String>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+// The kernel lets only euid 0 write /proc/sys, capabilities don't count, and it checks on every
+// write rather than on open, so a file kept open from before the privilege drop doesn't help.
+// With --drop-privileges the writes go to a helper forked while still root instead. It gives
+// up every capability, never executes anything and only writes the two knobs above.
+static HELPER: Mutex<Option<UnixStream>> = Mutex::new(None);
+
+/// Fork the helper, called by privilege_drop::apply before switching users
+pub fn start_helper() -> Result<()> {
+    let (daemon_end, helper_end) = UnixStream::pair()?;
+    match unsafe { libc::fork() } {
+        -1 => Err(anyhow!("Failed to start the VM settings helper: {}", std::io::Error::last_os_error())),
+        0 => {
+            drop(daemon_end);
+            serve_helper(helper_end);
+            unsafe { libc::_exit(0) }
+        }
+        _ => {
+            *HELPER.lock().unwrap() = Some(daemon_end);
+            Ok(())
+        }
+    }
+}
+
+fn serve_helper(stream: UnixStream) {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM);
+        libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0);
+    }
+    if let Err(e) = crate::privilege_drop::clear_capabilities() {
+        log::warn!("VM settings helper keeps its capabilities: {}", e);
+    }
+
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    // "<knob> <value>" per line, answered with "ok" or the error
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        let reply = match line.split_once(' ') {
+            Some((path, value)) if KNOBS.contains(&path) && value.parse::<u32>().is_ok() => {
+                match fs::write(path, value) {
+                    Ok(()) => "ok".to_string(),
+                    Err(e) => e.to_string(),
+                }
+            }
+            _ => format!("Refused '{}'", line),
+        };
+        if writeln!(writer, "{}", reply).is_err() {
+            return;
+        }
+    }
+}
+
+fn write_knob(path: &'static str, value: &str) -> Result<()> {
+    let mut helper = HELPER.lock().unwrap();
+    let stream = match helper.as_mut() {
+        Some(stream) if !crate::dry_run::is_active() => stream,
+        _ => return Ok(crate::dry_run::write(path, value)?),
+    };
+
+    writeln!(stream, "{} {}", path, value)?;
+    let mut reply = String::new();
+    BufReader::new(&*stream).read_line(&mut reply)?;
+    match reply.trim() {
+        "ok" => Ok(()),
+        "" => Err(anyhow!("VM settings helper exited")),
+        error => Err(anyhow!("Failed to write {}: {}", path, error)),
+    }
+}
+
+pub fn apply_vm_settings(settings: &VmSettings) -> Result<()> {
+    apply_knob(SWAPPINESS, settings.swappiness.map(u32::from), SWAPPINESS_RANGE)?;
+    apply_knob(DIRTY_WRITEBACK, settings.dirty_writeback_centisecs, DIRTY_WRITEBACK_RANGE)?;
+    Ok(())
+}
+
+fn apply_knob(path: &'static str, value: Option<u32>, range: RangeInclusive<u32>) -> Result<()> {
+    let mut shared = ORIGINAL_VALUES.lock().unwrap();
+    // A preview works on a copy, the real restore list has to survive it
+    let mut preview_copy = shared.clone();
+    let originals = if crate::dry_run::is_active() { &mut preview_copy } else { &mut *shared };
+
+    match value {
+        Some(value) => {
+            if !range.contains(&value) {
+                return Err(anyhow!("{} must be between {} and {}", path, range.start(), range.end()));
+            }
+
+            if !originals.contains_key(path) {
+                originals.insert(path, fs::read_to_string(path)?.trim().to_string());
+            }
+
+            write_knob(path, &value.to_string())?;
+            log::info!("Set {} to {}", path, value);
+        }
+        None => {
+            if let Some(original) = originals.remove(path) {
+                write_knob(path, &original)?;
+                log::info!("Restored {} to {}", path, original);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use zbus::message::Header;
+use zbus::Connection;
+
+// Read-only policy for shared machines and demo setups: with --read-only-users every DBus
+// method that changes hardware state is refused unless the caller is root or, with
+// --write-group, a member of that group. Reading sensors and settings stays open to everyone.
+// Calls json_rpc forwards arrive from the daemon's own connection, the policy applies to the
+// socket peer it registered for them, not to the daemon. System profiles additionally need
+// root or an admin group, with or without the policy.
+pub const FLAG: &str = "--read-only-users";
+pub const GROUP_FLAG: &str = "--write-group";
+// Administrators in the default polkit rules of Debian/Ubuntu and Fedora/Arch
+const ADMIN_GROUPS: [&str; 3] = ["sudo", "wheel", "admin"];
+
+struct Policy {
+    write_gid: Option<u32>,
+}
+
+/// Who sent a call, from the bus or from the JSON-RPC socket
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub uid: Option<u32>,
+    pub pid: Option<u32>,
+    // None when unknown, the groups are read for pid then
+    pub groups: Option<Vec<u32>>,
+}
+
+// Forwarded JSON-RPC calls by message serial
+static FORWARDED: Lazy<Mutex<HashMap<u32, Caller>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Called by json_rpc before it sends the call with this serial on the daemon's own connection
+pub fn forwarding(serial: u32, caller: Caller) {
+    FORWARDED.lock().unwrap().insert(serial, caller);
+}
+
+/// Called by json_rpc once the reply arrived
+pub fn forwarded(serial: u32) {
+    FORWARDED.lock().unwrap().remove(&serial);
+}
+
+static POLICY: Lazy<Option<Policy>> = Lazy::new(|| {
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|arg| arg == FLAG) {
+        return None;
+    }
+    let group = args.iter()
+        .position(|arg| arg == GROUP_FLAG)
+        .and_then(|i| args.get(i + 1));
+    let write_gid = group.and_then(|group| match crate::json_rpc::lookup_group(group) {
+        Ok(gid) => Some(gid),
+        Err(e) => {
+            log::warn!("{}, only root may change settings", e);
+            None
+        }
+    });
+    log::info!("Read-only policy active, writes allowed for root{}",
+        group.filter(|_| write_gid.is_some()).map(|g| format!(" and group {}", g)).unwrap_or_default());
+    Some(Policy { write_gid })
+});
+
+/// Log the policy at startup instead of on the first call
+pub fn init() {
+    Lazy::force(&POLICY);
+}
+
+/// Whether the sender of this call may change hardware state
+pub async fn may_write(header: &Header<'_>, connection: &Connection) -> bool {
+    if crate::privileges::monitor_only() {
+        return false;
+    }
+    let policy = match POLICY.as_ref() {
+        Some(policy) => policy,
+        None => return true,
+    };
+    let caller = match caller(header, connection).await {
+        Ok(caller) => caller,
+        Err(e) => {
+            log::warn!("Cannot identify caller, refusing write: {}", e);
+            return false;
+        }
+    };
+
+    if caller.uid == Some(0) {
+        return true;
+    }
+    match policy.write_gid {
+        Some(gid) => in_group(&caller, gid),
+        None => false,
+    }
+}
+
+fn in_group(caller: &Caller, gid: u32) -> bool {
+    match &caller.groups {
+        Some(groups) => groups.contains(&gid),
+        // Older dbus-daemons don't report groups, the caller's process does
+        None => caller.pid.is_some_and(|pid| process_in_group(pid, gid)),
+    }
+}
+
+/// The process that sent this call: what the bus daemon knows about the sender, or the
+/// JSON-RPC socket peer for a call json_rpc forwarded
+pub async fn caller(header: &Header<'_>, connection: &Connection) -> Result<Caller, zbus::fdo::Error> {
+    let sender = header.sender()
+        .ok_or_else(|| zbus::fdo::Error::Failed("Call without sender".to_string()))?
+        .to_owned();
+
+    if connection.unique_name().is_some_and(|own| own.as_str() == sender.as_str()) {
+        let serial = header.primary().serial_num().get();
+        return FORWARDED.lock().unwrap().get(&serial).cloned()
+            .ok_or_else(|| zbus::fdo::Error::AccessDenied("Call from the daemon itself, not forwarded for anyone".to_string()));
+    }
+
+    let credentials = zbus::fdo::DBusProxy::new(connection).await?
+        .get_connection_credentials(sender.into()).await?;
+    Ok(Caller {
+        uid: credentials.unix_user_id(),
+        pid: credentials.process_id(),
+        groups: credentials.unix_group_ids().map(|groups| groups.to_vec()),
+    })
+}
+
+/// Refuses the call for callers the policy doesn't allow to write
+pub async fn check(header: &Header<'_>, connection: &Connection) -> Result<(), zbus::fdo::Error> {
+    if may_write(header, connection).await {
+        Ok(())
+    } else if crate::privileges::monitor_only() {
+        Err(zbus::fdo::Error::AccessDenied(
+            "The daemon runs without root privileges, monitoring only".to_string()))
+    } else {
+        Err(zbus::fdo::Error::AccessDenied(
+            "Settings are read-only for this user on this machine".to_string()))
+    }
+}
+
+/// System profiles are the administrator's: on top of the write policy, the caller has to be
+/// root or in an admin group
+pub async fn check_admin(header: &Header<'_>, connection: &Connection) -> Result<(), zbus::fdo::Error> {
+    check(header, connection).await?;
+    let caller = caller(header, connection).await?;
+    let admin = caller.uid == Some(0) || ADMIN_GROUPS.iter()
+        .filter_map(|group| crate::json_rpc::lookup_group(group).ok())
+        .any(|gid| in_group(&caller, gid));
+    if admin {
+        Ok(())
+    } else {
+        Err(zbus::fdo::Error::AccessDenied(format!(
+            "System profiles can only be changed by root or members of {}", ADMIN_GROUPS.join(", "))))
+    }
+}
+
+fn process_in_group(pid: u32, gid: u32) -> bool {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+    status.lines()
+        .filter(|line| line.starts_with("Groups:") || line.starts_with("Gid:"))
+        .flat_map(|line| line.split_whitespace().skip(1))
+        .any(|id| id.parse() == Ok(gid))
+}
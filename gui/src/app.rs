@@ -1,19 +1,40 @@
 use egui::{Context, CentralPanel, TopBottomPanel};
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
 use tuxedo_common::types::*;
 
-use crate::dbus_client::DbusClient;
+use crate::dbus_client::{DbusClient, Telemetry};
+use crate::history::{History, HistorySample};
+use crate::app_monitor::SteamGame;
+use crate::config_lint::{self, ConfigIssue};
+use crate::error_hints::ErrorHint;
 use crate::theme::TuxedoTheme;
-use crate::pages::{statistics, profiles, tuning, settings};
-use crate::keyboard_shortcuts::KeyboardShortcuts;
+use crate::pages::{statistics, profiles, tuning, settings, events, capabilities, whats_new, usage};
+use crate::keyboard_shortcuts::{KeyboardShortcuts, SectionNav};
+
+/// Starts the GUI in monitoring-only mode, like `read_only` in the configuration
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+// Sliders and checkboxes ask for a save on every change, the file is written at most this often
+const CONFIG_SAVE_INTERVAL: Duration = Duration::from_secs(3);
+
+// The daemon keeps fewer, this only bounds a GUI left open on the log for days
+const MAX_DAEMON_LOG_ENTRIES: usize = 5000;
+
+// Seconds between GetCpuInfo calls while the daemon signals the CPU temperature
+const CPU_POLL_TICKS: u32 = 3;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Page {
     Statistics,
     Profiles,
     Tuning,
+    Events,
+    Capabilities,
     Settings,
+    WhatsNew,
+    Usage,
 }
 
 pub struct AppState {
@@ -29,19 +50,110 @@ pub struct AppState {
     pub fan_info: Vec<FanInfo>,
     pub storage_device_info: Vec<StorageDevice>,
     pub mount_info: Vec<MountInfo>,
+    pub temperature_sensors: Vec<TemperatureSensor>,
+    pub temperature_ranges: HashMap<String, (f32, f32)>,  // Min/max seen this session, by sensor id
     pub available_start_thresholds: Vec<u8>,
     pub available_end_thresholds: Vec<u8>,
+    pub dust_cleaning_status: Option<DustCleaningStatus>,
+    pub auto_tune_status: Option<AutoTuneStatus>,
+    pub ab_test_status: Option<AbTestStatus>,
+    pub fan_calibration_status: Option<FanCalibrationStatus>,
+    pub available_tdp_profiles: Vec<String>,
+    pub tdp_limits: Option<TdpLimits>,
+    pub network_interfaces: Vec<String>,
+    pub keyboard_devices: Vec<KeyboardDevice>,
+    pub indicator_leds: Vec<String>,
+    pub aquaris: AquarisStatus,
+    pub display_info: Vec<DisplayInfo>,
+    pub charging_options: ChargingOptions,
+    pub charge_control: ChargeControlInfo,
+    pub thermal_headroom: Option<(f64, String)>,  // °C below critical, limiting sensor
+    pub ac_adapter: Option<AcAdapterInfo>,
+    pub can_cap_tdp: bool,
+    pub igpu_frequency_info: Option<IgpuFrequencyInfo>,
+    pub fan_curve_capabilities: Option<FanCurveCapabilities>,
+    pub lid_status: Option<LidStatus>,
+    pub fan_mode: Option<FanModeStatus>,
+    pub feature_status: Vec<FeatureStatus>,
+    pub daemon_unavailable: Option<String>,
+    pub safe_mode: Option<SafeModeStatus>,
+    pub fan_failures: Vec<FanFailure>,
+    pub battery_temp_alarmed: bool,  // Notified, rearmed once the battery cooled down
+    pub config_issues: Vec<ConfigIssue>,
+    pub events: Vec<EventLogEntry>,
+    pub daemon_log: Vec<LogEntry>,
+    pub daemon_log_started: Option<i64>,
+    pub capabilities: Vec<Capability>,
+    pub history: History,
     
     // UI state
     pub current_page: Page,
+    pub sections: SectionNav,
     pub status_message: Option<StatusMessage>,
+    pub dust_cleaning_cycles: u32,
+    pub temperature_filter: String,
+    pub residency_baseline: Option<(Instant, Vec<FrequencyResidency>)>,
+    pub event_filter: String,
+    pub event_kind_filter: Option<EventKind>,
+    pub search_query: String,
+    pub focus_search: bool,
+    pub show_daemon_log: bool,
+    pub log_level_filter: LogLevel,
+    pub log_follow: bool,
     
     // Profile editing
     pub editing_profile_index: Option<usize>,
     pub editing_profile_name: Option<String>,
+    pub import_profile_path: String,
+    pub ab_test_a: String,
+    pub ab_test_b: String,
+    pub ab_test_minutes: u32,
+    pub ab_test_rounds: u32,
     
     // Async state
     pub pending_battery_update: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
+    pub panic_revert_requested: bool,
+    pub pending_panic_revert: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
+    pub full_charge_requested: Option<bool>,  // true = start, false = cancel
+    pub pending_full_charge: Option<(bool, oneshot::Receiver<Result<(), anyhow::Error>>)>,
+    pub pending_events: Option<oneshot::Receiver<Result<Vec<EventLogEntry>, anyhow::Error>>>,
+    pub events_polled_at: Option<Instant>,
+    pub ambient_reading: Option<AmbientLightReading>,
+    pub pending_ambient_reading: Option<oneshot::Receiver<Result<AmbientLightReading, anyhow::Error>>>,
+    pub ambient_polled_at: Option<Instant>,
+    pub game_running: bool,
+    pub running_steam_apps: Vec<u32>,
+    pub steam_games: Option<Vec<SteamGame>>,   // Read from the Steam libraries when the settings need them
+    pub auto_switched_profile: Option<String>,
+    pub auto_switch_previous_profile: Option<String>,  // Profile to restore once the game ends
+    pub pending_daemon_log: Option<oneshot::Receiver<Result<DaemonLog, anyhow::Error>>>,
+    pub daemon_log_polled_at: Option<Instant>,
+    pub capabilities_requested: bool,
+    pub capabilities_error: Option<String>,
+    pub pending_capabilities: Option<oneshot::Receiver<Result<Vec<Capability>, anyhow::Error>>>,
+    pub profile_usage: Option<ProfileUsage>,
+    pub profile_usage_requested: bool,
+    pub pending_profile_usage: Option<oneshot::Receiver<Result<ProfileUsage, anyhow::Error>>>,
+    pub apply_preview: Option<ApplyPreview>,
+    pub pending_smt_change: Option<bool>,
+    pub pending_preview: Option<oneshot::Receiver<Result<ApplyPreview, anyhow::Error>>>,
+    pub pending_apply: Option<oneshot::Receiver<Result<Option<ApplyReport>, anyhow::Error>>>,
+    pub apply_report: Option<ApplyReport>,
+    pub apply_progress: Option<ApplyProgress>,
+    pub show_apply_report: bool,
+    pub read_only: bool,  // From the configuration, the command line or the daemon's policy
+    pub whats_new_since: Option<String>,  // Version seen before this upgrade, "" if unknown
+    pub config_dirty_since: Option<Instant>,
+    saved_config: Option<String>,  // As last read or written, unchanged configs aren't written again
+    pub system_profiles: Option<SystemProfiles>,  // None until the daemon answered
+    pub pending_system_profiles: Option<oneshot::Receiver<Result<SystemProfiles, anyhow::Error>>>,
+    pub pending_min_duty_change: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
+    pub pending_fan_capabilities: Option<oneshot::Receiver<Result<FanCurveCapabilities, anyhow::Error>>>,
+    pub pending_system_change: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
+    power_source_synced: Option<String>,  // As last sent to the daemon, JSON
+    pending_power_source_change: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
+    app_profiles_synced: Option<String>,
+    pending_app_profiles_change: Option<oneshot::Receiver<Result<(), anyhow::Error>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +161,7 @@ pub struct StatusMessage {
     pub text: String,
     pub is_error: bool,
     pub shown_at: Instant,
+    pub hint: Option<ErrorHint>,
 }
 
 impl AppState {
@@ -63,36 +176,216 @@ impl AppState {
             fan_info: Vec::new(),
             storage_device_info: Vec::new(),
             mount_info: Vec::new(),
+            temperature_sensors: Vec::new(),
+            temperature_ranges: HashMap::new(),
             available_start_thresholds: Vec::new(),
             available_end_thresholds: Vec::new(),
+            dust_cleaning_status: None,
+            auto_tune_status: None,
+            ab_test_status: None,
+            fan_calibration_status: None,
+            available_tdp_profiles: Vec::new(),
+            tdp_limits: None,
+            network_interfaces: Vec::new(),
+            keyboard_devices: Vec::new(),
+            indicator_leds: Vec::new(),
+            aquaris: AquarisStatus::default(),
+            display_info: Vec::new(),
+            charging_options: ChargingOptions::default(),
+            charge_control: ChargeControlInfo::default(),
+            thermal_headroom: None,
+            ac_adapter: None,
+            can_cap_tdp: false,
+            igpu_frequency_info: None,
+            fan_curve_capabilities: None,
+            lid_status: None,
+            fan_mode: None,
+            feature_status: Vec::new(),
+            daemon_unavailable: None,
+            safe_mode: None,
+            fan_failures: Vec::new(),
+            battery_temp_alarmed: false,
+            config_issues: Vec::new(),
+            events: Vec::new(),
+            daemon_log: Vec::new(),
+            daemon_log_started: None,
+            capabilities: Vec::new(),
+            history: History::load(),
             current_page: Page::Statistics,
+            sections: SectionNav::default(),
             status_message: None,
+            dust_cleaning_cycles: 3,
+            temperature_filter: String::new(),
+            residency_baseline: None,
+            event_filter: String::new(),
+            event_kind_filter: None,
+            search_query: String::new(),
+            focus_search: false,
+            show_daemon_log: false,
+            log_level_filter: LogLevel::Info,
+            log_follow: true,
             editing_profile_index: None,
             editing_profile_name: None,
+            import_profile_path: String::new(),
+            ab_test_a: String::new(),
+            ab_test_b: String::new(),
+            ab_test_minutes: 10,
+            ab_test_rounds: 2,
             pending_battery_update: None,
+            panic_revert_requested: false,
+            pending_panic_revert: None,
+            full_charge_requested: None,
+            pending_full_charge: None,
+            pending_events: None,
+            events_polled_at: None,
+            ambient_reading: None,
+            pending_ambient_reading: None,
+            ambient_polled_at: None,
+            game_running: false,
+            running_steam_apps: Vec::new(),
+            steam_games: None,
+            auto_switched_profile: None,
+            auto_switch_previous_profile: None,
+            pending_daemon_log: None,
+            daemon_log_polled_at: None,
+            capabilities_requested: false,
+            capabilities_error: None,
+            pending_capabilities: None,
+            profile_usage: None,
+            profile_usage_requested: false,
+            pending_profile_usage: None,
+            apply_preview: None,
+            pending_smt_change: None,
+            pending_preview: None,
+            pending_apply: None,
+            apply_report: None,
+            apply_progress: None,
+            show_apply_report: false,
+            read_only: false,
+            whats_new_since: None,
+            config_dirty_since: None,
+            saved_config: None,
+            system_profiles: None,
+            pending_system_profiles: None,
+            pending_min_duty_change: None,
+            pending_fan_capabilities: None,
+            pending_system_change: None,
+            power_source_synced: None,
+            pending_power_source_change: None,
+            app_profiles_synced: None,
+            pending_app_profiles_change: None,
         }
     }
     
 pub fn load_config(&mut self) {
     if let Ok(config) = load_config_from_disk() {
+        self.saved_config = serde_json::to_string_pretty(&config).ok();
         self.config = config;
+        self.config_issues = config_lint::lint(&self.config, None);
     }
 }
     
+    /// Marks the configuration as changed, it is written by flush_config a little later
     pub fn save_config(&mut self) -> anyhow::Result<()> {
-        save_config_to_disk(&self.config)?;
-        self.show_message("Configuration saved", false);
+        self.config_dirty_since.get_or_insert_with(Instant::now);
         Ok(())
     }
     
+    /// Writes pending changes, returns false if there was nothing to write
+    pub fn flush_config(&mut self) -> anyhow::Result<bool> {
+        if self.config_dirty_since.take().is_none() {
+            return Ok(false);
+        }
+        let json = serde_json::to_string_pretty(&self.config)?;
+        if self.saved_config.as_ref() == Some(&json) {
+            return Ok(false);
+        }
+        write_config_json(&json)?;
+        self.saved_config = Some(json);
+        Ok(true)
+    }
+    
+    /// The "Save now" button
+    pub fn save_config_now(&mut self) {
+        self.config_dirty_since.get_or_insert_with(Instant::now);
+        match self.flush_config() {
+            Ok(_) => self.show_message("Configuration saved", false),
+            Err(e) => self.show_message(format!("Failed to save configuration: {}", e), true),
+        }
+    }
+    
     pub fn show_message(&mut self, text: impl Into<String>, is_error: bool) {
+        let text = text.into();
+        let hint = if is_error { crate::error_hints::hint_for(&text) } else { None };
         self.status_message = Some(StatusMessage {
-            text: text.into(),
+            text,
             is_error,
             shown_at: Instant::now(),
+            hint,
         });
     }
     
+    /// Why a feature can't be used right now, None if the daemon reports it as available
+    pub fn feature_unavailable(&self, feature: Feature) -> Option<String> {
+        if let Some(ref message) = self.daemon_unavailable {
+            return Some(message.clone());
+        }
+        
+        self.feature_status.iter()
+            .find(|s| s.feature == feature && !s.available)
+            .map(|s| s.reason.clone().unwrap_or_else(|| "Not supported on this system".to_string()))
+    }
+    
+    /// A profile by name, system profiles take precedence over user profiles of the same name
+    pub fn find_profile(&self, name: &str) -> Option<&Profile> {
+        self.system_profile(name)
+            .or_else(|| self.config.profiles.iter().find(|p| p.name == name))
+    }
+    
+    pub fn system_profile(&self, name: &str) -> Option<&Profile> {
+        self.system_profiles.as_ref()?.profiles.iter().find(|p| p.name == name)
+    }
+    
+    /// Copies of the AC and battery profiles for the daemon, system profiles take precedence
+    pub fn power_source_profiles(&self) -> PowerSourceProfiles {
+        let settings = &self.config.auto_switch;
+        let find = |name: &str| Some(name).filter(|n| !n.is_empty()).and_then(|n| self.find_profile(n)).cloned();
+        PowerSourceProfiles {
+            enabled: settings.power_source_enabled,
+            on_ac: find(&settings.ac_profile),
+            on_battery: find(&settings.battery_profile),
+        }
+    }
+    
+    /// Application rules for the daemon with one copy of each profile they name
+    pub fn app_profiles(&self) -> AppProfiles {
+        let mut settings = AppProfiles::default();
+        for rule in self.config.auto_switch.app_rules.iter().filter(|rule| !rule.process.trim().is_empty()) {
+            let Some(profile) = self.find_profile(&rule.profile) else {
+                continue;
+            };
+            if !settings.profiles.iter().any(|p| p.name == profile.name) {
+                settings.profiles.push(profile.clone());
+            }
+            settings.rules.push(AppProfileRule {
+                process: rule.process.trim().to_string(),
+                profile: rule.profile.clone(),
+            });
+        }
+        settings
+    }
+    
+    /// User profiles, then system profiles without a user profile of the same name
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.config.profiles.iter().map(|p| p.name.clone()).collect();
+        for profile in self.system_profiles.iter().flat_map(|s| &s.profiles) {
+            if !names.contains(&profile.name) {
+                names.push(profile.name.clone());
+            }
+        }
+        names
+    }
+    
     pub fn current_profile(&self) -> Option<&Profile> {
         self.config.profiles.iter()
             .find(|p| p.name == self.config.current_profile)
@@ -108,6 +401,17 @@ pub fn load_config(&mut self) {
         self.config.profiles.iter()
             .position(|p| p.name == self.config.current_profile)
     }
+    
+    fn record_temperature(&mut self, id: &str, temp: f32) {
+        let range = self.temperature_ranges.entry(id.to_string()).or_insert((temp, temp));
+        range.0 = range.0.min(temp);
+        range.1 = range.1.max(temp);
+    }
+}
+
+/// Key in temperature_ranges for a fan's own sensor, curves without a temp_source follow it
+pub fn fan_sensor_id(fan_id: u32) -> String {
+    format!("fan:{}", fan_id)
 }
 
 pub struct TuxedoApp {
@@ -128,11 +432,42 @@ pub enum HardwareUpdate {
     CpuInfo(CpuInfo),
     GpuInfo(Vec<GpuInfo>),
     BatteryInfo(BatteryInfo),
+    AcAdapterInfo(AcAdapterInfo),
     WifiInfo(Vec<WiFiInfo>),
     FanInfo(Vec<FanInfo>),
     StorageDeviceInfo(Vec<StorageDevice>),
     MountInfo(Vec<MountInfo>),
     AvailableThresholds(Vec<u8>, Vec<u8>),
+    DustCleaningStatus(DustCleaningStatus),
+    AutoTuneStatus(AutoTuneStatus),
+    AbTestStatus(AbTestStatus),
+    FanCalibrationStatus(FanCalibrationStatus),
+    Temperatures(Vec<TemperatureSensor>),
+    TdpProfiles(Vec<String>),
+    NetworkInterfaces(Vec<String>),
+    KeyboardDevices(Vec<KeyboardDevice>),
+    IndicatorLeds(Vec<String>),
+    Aquaris(AquarisStatus),
+    SafeMode(SafeModeStatus),
+    DisplayInfo(Vec<DisplayInfo>),
+    ChargingOptions(ChargingOptions),
+    ChargeControl(ChargeControlInfo),
+    TdpLimits(TdpLimits),
+    HardwareEvent(HardwareEvent),
+    ThermalHeadroom(Option<f64>, String),
+    CpuTemp(f32),
+    ProfileApplied(String),
+    IgpuFrequencyInfo(IgpuFrequencyInfo),
+    FanCurveCapabilities(FanCurveCapabilities),
+    LidStatus(LidStatus),
+    FanMode(FanModeStatus),
+    FeatureAvailability(Vec<FeatureStatus>),
+    WriteAccess(bool),
+    SystemProfiles(SystemProfiles),
+    GameMode(bool),
+    SteamApps(Vec<u32>),
+    DaemonUnavailable(String),
+    Tray(crate::system_tray::TrayEvent),
     Error(String),
 }
 
@@ -140,6 +475,17 @@ impl TuxedoApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let mut state = AppState::new();
         state.load_config();
+        state.read_only = state.config.read_only || std::env::args().any(|arg| arg == READ_ONLY_FLAG);
+        
+        // The release notes open once after an upgrade, a fresh install has nothing new to show
+        if state.config.last_seen_version.as_deref() != Some(whats_new::VERSION) && !state.read_only {
+            if state.saved_config.is_some() {
+                state.whats_new_since = Some(state.config.last_seen_version.clone().unwrap_or_default());
+                state.current_page = Page::WhatsNew;
+            }
+            state.config.last_seen_version = Some(whats_new::VERSION.to_string());
+            let _ = state.save_config();
+        }
         
         // Create DBus client
         let dbus_client = match DbusClient::new() {
@@ -159,9 +505,47 @@ impl TuxedoApp {
         
         // Setup background polling
         let (hw_update_tx, hw_update_rx) = mpsc::unbounded_channel();
+        tokio::spawn(crate::gamemode::watch(hw_update_tx.clone()));
+        crate::app_monitor::set_enabled(state.config.app_monitoring_enabled);
+        tokio::spawn(crate::app_monitor::watch(hw_update_tx.clone()));
+        if !state.read_only {
+            let names = state.config.profiles.iter().map(|p| p.name.clone()).collect();
+            crate::system_tray::spawn(names, hw_update_tx.clone(), cc.egui_ctx.clone());
+        }
         if let Some(ref client) = dbus_client {
             start_background_polling(client.clone(), hw_update_tx.clone(), &state.config);
 
+            // OSD feedback for hotkeys handled by the EC
+            let mut events = client.hardware_events();
+            let tx_clone = hw_update_tx.clone();
+            tokio::spawn(async move {
+                while let Some(event) = events.recv().await {
+                    let _ = tx_clone.send(HardwareUpdate::HardwareEvent(event));
+                }
+            });
+
+            // Fan speeds and the CPU temperature as they change, polled less once these arrive
+            let mut telemetry = client.telemetry();
+            let tx_clone = hw_update_tx.clone();
+            tokio::spawn(async move {
+                while let Some(telemetry) = telemetry.recv().await {
+                    let update = match telemetry {
+                        Telemetry::FanSpeed(fans) => HardwareUpdate::FanInfo(fans),
+                        Telemetry::CpuTemp(celsius) => HardwareUpdate::CpuTemp(celsius),
+                        Telemetry::ProfileApplied(name) => HardwareUpdate::ProfileApplied(name),
+                    };
+                    let _ = tx_clone.send(update);
+                }
+            });
+
+            let mut headroom = client.thermal_headroom();
+            let tx_clone = hw_update_tx.clone();
+            tokio::spawn(async move {
+                while let Some((degrees, sensor)) = headroom.recv().await {
+                    let _ = tx_clone.send(HardwareUpdate::ThermalHeadroom(degrees, sensor));
+                }
+            });
+
             // Initial system info load
             let client_clone = client.clone();
             let tx_clone = hw_update_tx.clone();
@@ -171,6 +555,71 @@ impl TuxedoApp {
                 }
             });
 
+            // Fetch static capabilities (TDP profiles, iGPU range, fan curve limits, network interfaces)
+            let client_clone = client.clone();
+            let tx_clone = hw_update_tx.clone();
+            tokio::spawn(async move {
+                // Everything privileged goes through the daemon, so find out up front what it can do
+                match client_clone.get_feature_availability().await {
+                    Ok(Ok(status)) => {
+                        let _ = tx_clone.send(HardwareUpdate::FeatureAvailability(status));
+                    }
+                    Ok(Err(e)) => {
+                        let _ = tx_clone.send(HardwareUpdate::DaemonUnavailable(daemon_unavailable_message(&e.to_string())));
+                    }
+                    Err(_) => {
+                        let _ = tx_clone.send(HardwareUpdate::DaemonUnavailable(daemon_unavailable_message("no system bus")));
+                    }
+                }
+                // Older daemons have no write policy
+                if let Ok(Ok(allowed)) = client_clone.get_write_access().await {
+                    let _ = tx_clone.send(HardwareUpdate::WriteAccess(allowed));
+                }
+                if let Ok(Ok(profiles)) = client_clone.get_system_profiles().await {
+                    let _ = tx_clone.send(HardwareUpdate::SystemProfiles(profiles));
+                }
+                if let Ok(Ok(profiles)) = client_clone.get_tdp_profiles().await {
+                    let _ = tx_clone.send(HardwareUpdate::TdpProfiles(profiles));
+                }
+                if let Ok(Ok(info)) = client_clone.get_igpu_frequency_info().await {
+                    let _ = tx_clone.send(HardwareUpdate::IgpuFrequencyInfo(info));
+                }
+                if let Ok(Ok(caps)) = client_clone.get_fan_curve_capabilities().await {
+                    let _ = tx_clone.send(HardwareUpdate::FanCurveCapabilities(caps));
+                }
+                if let Ok(Ok(interfaces)) = client_clone.get_network_interfaces().await {
+                    let _ = tx_clone.send(HardwareUpdate::NetworkInterfaces(interfaces));
+                }
+                if let Ok(Ok(devices)) = client_clone.get_keyboard_devices().await {
+                    let _ = tx_clone.send(HardwareUpdate::KeyboardDevices(devices));
+                }
+                if let Ok(Ok(leds)) = client_clone.get_indicator_leds().await {
+                    let _ = tx_clone.send(HardwareUpdate::IndicatorLeds(leds));
+                }
+                if let Ok(Ok(status)) = client_clone.get_aquaris_status().await {
+                    let _ = tx_clone.send(HardwareUpdate::Aquaris(status));
+                }
+                if let Ok(Ok(displays)) = client_clone.get_display_info().await {
+                    let _ = tx_clone.send(HardwareUpdate::DisplayInfo(displays));
+                }
+                if let Ok(Ok(options)) = client_clone.get_charging_options().await {
+                    let _ = tx_clone.send(HardwareUpdate::ChargingOptions(options));
+                }
+                if let Ok(Ok(info)) = client_clone.get_charge_control_info().await {
+                    let _ = tx_clone.send(HardwareUpdate::ChargeControl(info));
+                }
+                if let Ok(Ok(limits)) = client_clone.get_tdp_limits().await {
+                    let _ = tx_clone.send(HardwareUpdate::TdpLimits(limits));
+                }
+                // The SafeMode signal is sent before the GUI may have been running
+                if let Ok(Ok(status)) = client_clone.get_safe_mode().await {
+                    let _ = tx_clone.send(HardwareUpdate::SafeMode(status));
+                }
+                if let Ok(Ok(failures)) = client_clone.get_fan_failures().await {
+                    let _ = tx_clone.send(HardwareUpdate::HardwareEvent(HardwareEvent::FanFailure(failures)));
+                }
+            });
+
             // Fetch available thresholds
             let client_clone = client.clone();
             tokio::spawn(async move {
@@ -199,7 +648,22 @@ impl TuxedoApp {
         }
     }
     
-    fn handle_hardware_updates(&mut self) {
+    // One notification per hot spell, the battery has to cool 3 °C below the limit to rearm it
+    fn check_battery_temperature(&mut self, temperature: Option<f32>) {
+        let (Some(limit), Some(temperature)) = (self.state.config.battery_temp_alarm, temperature) else {
+            self.state.battery_temp_alarmed = false;
+            return;
+        };
+        if temperature >= limit as f32 && !self.state.battery_temp_alarmed {
+            self.state.battery_temp_alarmed = true;
+            crate::desktop_integration::notify("Battery temperature",
+                format!("The battery is at {:.0}°C, above the {}°C alarm", temperature, limit));
+        } else if temperature < limit as f32 - 3.0 {
+            self.state.battery_temp_alarmed = false;
+        }
+    }
+    
+    fn handle_hardware_updates(&mut self, ctx: &Context) {
         // Process all pending updates (non-blocking)
         while let Ok(update) = self.hw_update_rx.try_recv() {
             match update {
@@ -208,17 +672,30 @@ impl TuxedoApp {
                 }
                 HardwareUpdate::CpuInfo(info) => {
                     self.state.cpu_info = Some(info);
+                    if let Some(sample) = HistorySample::from_state(&self.state) {
+                        self.state.history.record(sample, &self.state.config.history_settings);
+                    }
                 }
                 HardwareUpdate::GpuInfo(info) => {
                     self.state.gpu_info = info;
                 }
                 HardwareUpdate::BatteryInfo(info) => {
+                    self.check_battery_temperature(info.temperature);
                     self.state.battery_info = Some(info);
                 }
+                HardwareUpdate::AcAdapterInfo(info) => {
+                    self.state.can_cap_tdp = info.can_set_limit;
+                    self.state.ac_adapter = Some(info).filter(|a| a.online);
+                }
                 HardwareUpdate::WifiInfo(info) => {
                     self.state.wifi_info = info;
                 }
                 HardwareUpdate::FanInfo(info) => {
+                    for fan in &info {
+                        if let Some(temp) = fan.temperature {
+                            self.state.record_temperature(&fan_sensor_id(fan.id), temp);
+                        }
+                    }
                     self.state.fan_info = info;
                 }
                 HardwareUpdate::StorageDeviceInfo(info) => {
@@ -231,6 +708,159 @@ impl TuxedoApp {
                     self.state.available_start_thresholds = start;
                     self.state.available_end_thresholds = end;
                 }
+                HardwareUpdate::DustCleaningStatus(status) => {
+                    self.state.dust_cleaning_status = Some(status);
+                }
+                HardwareUpdate::AutoTuneStatus(status) => {
+                    self.state.auto_tune_status = Some(status);
+                }
+                HardwareUpdate::AbTestStatus(status) => {
+                    self.state.ab_test_status = Some(status);
+                }
+                HardwareUpdate::FanCalibrationStatus(status) => {
+                    // A finished calibration changed the floors the curve editors show
+                    let finished = self.state.fan_calibration_status.as_ref().is_some_and(|s| s.running)
+                        && !status.running;
+                    if finished {
+                        if let Some(ref client) = self.dbus_client {
+                            self.state.pending_fan_capabilities = Some(client.get_fan_curve_capabilities());
+                        }
+                    }
+                    self.state.fan_calibration_status = Some(status);
+                }
+                HardwareUpdate::Temperatures(sensors) => {
+                    for sensor in &sensors {
+                        self.state.record_temperature(&sensor.id, sensor.temperature);
+                    }
+                    self.state.temperature_sensors = sensors;
+                }
+                HardwareUpdate::NetworkInterfaces(interfaces) => {
+                    self.state.network_interfaces = interfaces;
+                }
+                HardwareUpdate::KeyboardDevices(devices) => {
+                    self.state.keyboard_devices = devices;
+                }
+                HardwareUpdate::IndicatorLeds(leds) => {
+                    self.state.indicator_leds = leds;
+                }
+                HardwareUpdate::Aquaris(status) => {
+                    self.state.aquaris = status;
+                }
+                HardwareUpdate::DisplayInfo(displays) => {
+                    self.state.display_info = displays;
+                }
+                HardwareUpdate::ChargingOptions(options) => {
+                    self.state.charging_options = options;
+                }
+                HardwareUpdate::ChargeControl(info) => {
+                    self.state.charge_control = info;
+                }
+                HardwareUpdate::TdpLimits(limits) => {
+                    self.state.tdp_limits = Some(limits);
+                }
+                HardwareUpdate::SafeMode(status) => {
+                    self.state.safe_mode = Some(status).filter(|s| s.active);
+                }
+                HardwareUpdate::ThermalHeadroom(degrees, sensor) => {
+                    self.state.thermal_headroom = degrees.map(|d| (d, sensor));
+                }
+                HardwareUpdate::CpuTemp(celsius) => {
+                    if let Some(ref mut cpu) = self.state.cpu_info {
+                        cpu.package_temp = celsius;
+                    }
+                    if let Some(sample) = HistorySample::from_state(&self.state) {
+                        self.state.history.record(sample, &self.state.config.history_settings);
+                    }
+                }
+                HardwareUpdate::ProfileApplied(name) => {
+                    // Applied by tuxedoctl, the boot profile or another session
+                    if name != self.state.config.current_profile && self.state.find_profile(&name).is_some() {
+                        self.state.config.current_profile = name.clone();
+                        self.state.show_message(format!("Profile '{}' was applied", name), false);
+                    }
+                }
+                HardwareUpdate::HardwareEvent(event) => {
+                    let text = match event {
+                        HardwareEvent::PerformanceProfile(profile) => format!("Performance profile: {}", profile),
+                        HardwareEvent::FanMode(mode) => {
+                            // The next poll would catch it, but keep the UI in step right away
+                            self.state.fan_mode = Some(FanModeStatus { mode, set_by: FanModeSource::External });
+                            format!("Fan mode: {:?}", mode)
+                        }
+                        HardwareEvent::SafeMode(status) => {
+                            self.state.safe_mode = Some(status).filter(|s| s.active);
+                            "Daemon started in safe mode".to_string()
+                        }
+                        // Shown next to the Save button on the Tuning page
+                        HardwareEvent::ApplyProgress(progress) => {
+                            self.state.apply_progress = Some(progress);
+                            continue;
+                        }
+                        HardwareEvent::FanFailure(failures) => {
+                            let new = failures.iter()
+                                .filter(|f| !self.state.fan_failures.iter().any(|old| old.fan_id == f.fan_id))
+                                .map(|f| f.fan_id.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            self.state.fan_failures = failures;
+                            // The banner carries it from here, only the first report gets a notification
+                            if new.is_empty() {
+                                continue;
+                            }
+                            crate::desktop_integration::notify("Fan failure",
+                                format!("Fan {} is not spinning, check the fan", new));
+                            format!("Probable failure of fan {}", new)
+                        }
+                    };
+                    self.state.show_message(text, false);
+                }
+                HardwareUpdate::TdpProfiles(profiles) => {
+                    self.state.available_tdp_profiles = profiles;
+                }
+                HardwareUpdate::IgpuFrequencyInfo(info) => {
+                    self.state.igpu_frequency_info = Some(info);
+                }
+                HardwareUpdate::FanCurveCapabilities(caps) => {
+                    self.state.fan_curve_capabilities = Some(caps);
+                }
+                HardwareUpdate::LidStatus(status) => {
+                    self.state.lid_status = Some(status);
+                }
+                HardwareUpdate::FanMode(status) => {
+                    self.state.fan_mode = Some(status);
+                }
+                HardwareUpdate::FeatureAvailability(status) => {
+                    self.state.feature_status = status;
+                    self.state.daemon_unavailable = None;
+                }
+                HardwareUpdate::WriteAccess(allowed) => {
+                    if !allowed && !self.state.read_only {
+                        log::info!("The daemon refuses changes from this user, monitoring only");
+                        self.state.read_only = true;
+                    }
+                }
+                HardwareUpdate::GameMode(running) => {
+                    self.state.game_running = running;
+                    self.update_auto_switch();
+                }
+                HardwareUpdate::SteamApps(apps) => {
+                    self.state.running_steam_apps = apps;
+                    self.update_auto_switch();
+                }
+                HardwareUpdate::SystemProfiles(profiles) => {
+                    self.state.system_profiles = Some(profiles);
+                    self.state.config_issues = config_lint::lint(&self.state.config, self.state.system_profiles.as_ref());
+                    self.sync_daemon_profiles();
+                }
+                HardwareUpdate::DaemonUnavailable(message) => {
+                    self.state.daemon_unavailable = Some(message);
+                    // No system profiles to wait for, references to them are really missing
+                    if self.state.system_profiles.is_none() {
+                        self.state.system_profiles = Some(SystemProfiles::default());
+                        self.state.config_issues = config_lint::lint(&self.state.config, self.state.system_profiles.as_ref());
+                    }
+                }
+                HardwareUpdate::Tray(event) => self.handle_tray_event(ctx, event),
                 HardwareUpdate::Error(err) => {
                     log::error!("Hardware update error: {}", err);
                 }
@@ -252,6 +882,571 @@ impl TuxedoApp {
                 }
             }
         }
+        
+        if std::mem::take(&mut self.state.panic_revert_requested) {
+            if let Some(ref client) = self.dbus_client {
+                self.state.pending_panic_revert = Some(client.panic_revert());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_panic_revert.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {
+                    if let Some(standard) = self.state.config.profiles.iter().find(|p| p.is_default) {
+                        self.state.config.current_profile = standard.name.clone();
+                    }
+                    self.state.show_message("Reverted to Standard profile, fans on automatic", false);
+                    crate::desktop_integration::notify("Panic revert", "Standard profile applied, fans on automatic");
+                }
+                Ok(Err(e)) => {
+                    self.state.show_message(format!("Panic revert failed: {}", e), true);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_panic_revert = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.state.show_message("Panic revert channel closed", true);
+                }
+            }
+        }
+        
+        if let Some(start) = self.state.full_charge_requested.take() {
+            if let Some(ref client) = self.dbus_client {
+                let rx = if start { client.start_full_charge() } else { client.cancel_full_charge() };
+                self.state.pending_full_charge = Some((start, rx));
+            }
+        }
+        
+        if let Some((start, mut rx)) = self.state.pending_full_charge.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {
+                    // Shown right away, the next battery poll confirms it
+                    if let Some(info) = self.state.battery_info.as_mut() {
+                        info.full_charge_since = start.then(|| chrono::Utc::now().timestamp());
+                    }
+                    self.state.show_message(if start {
+                        "Charging to 100% once, thresholds return when full or unplugged"
+                    } else {
+                        "Charge thresholds restored"
+                    }, false);
+                }
+                Ok(Err(e)) => {
+                    self.state.show_message(format!("Full charge override failed: {}", e), true);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_full_charge = Some((start, rx));
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // The capability matrix probes a lot of sysfs, only fetch it when the page asks for it
+        if std::mem::take(&mut self.state.capabilities_requested) && self.state.pending_capabilities.is_none() {
+            if let Some(ref client) = self.dbus_client {
+                self.state.pending_capabilities = Some(client.get_capability_matrix());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_capabilities.take() {
+            match rx.try_recv() {
+                Ok(Ok(capabilities)) => {
+                    self.state.capabilities = capabilities;
+                    self.state.capabilities_error = None;
+                }
+                Ok(Err(e)) => self.state.capabilities_error = Some(e.to_string()),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_capabilities = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Profile usage changes by the minute, fetched when its page is opened or refreshed
+        if std::mem::take(&mut self.state.profile_usage_requested) && self.state.pending_profile_usage.is_none() {
+            if let Some(ref client) = self.dbus_client {
+                self.state.pending_profile_usage = Some(client.get_profile_usage());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_profile_usage.take() {
+            match rx.try_recv() {
+                Ok(Ok(usage)) => self.state.profile_usage = Some(usage),
+                Ok(Err(e)) => self.state.show_message(format!("Failed to read profile usage: {}", e), true),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_profile_usage = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_preview.take() {
+            match rx.try_recv() {
+                Ok(Ok(preview)) => self.state.apply_preview = Some(preview),
+                Ok(Err(e)) => self.state.show_message(format!("Preview failed: {}", e), true),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_preview = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Saving on the Tuning page applies too, slow writes open the report by themselves
+        if let Some(mut rx) = self.state.pending_apply.take() {
+            match rx.try_recv() {
+                Ok(Ok(report)) => {
+                    self.state.show_apply_report |= report.as_ref().is_some_and(|r| !r.slow_writes.is_empty());
+                    self.state.apply_report = report;
+                }
+                Ok(Err(e)) => {
+                    if let Some(failed) = e.downcast_ref::<crate::dbus_client::ApplyFailed>() {
+                        self.state.apply_report = Some(failed.0.clone());
+                        self.state.show_apply_report = true;
+                    }
+                    self.state.show_message(format!("Applying the profile failed: {}", e), true);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_apply = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_power_source_change.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    // Sent again with the next change
+                    self.state.power_source_synced = None;
+                    self.state.show_message(format!("Failed to set the AC and battery profiles: {}", e), true);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_power_source_change = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_app_profiles_change.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    self.state.app_profiles_synced = None;
+                    self.state.show_message(format!("Failed to set the application profiles: {}", e), true);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_app_profiles_change = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Changes to system profiles are followed by a fresh list from the daemon
+        if let Some(mut rx) = self.state.pending_system_change.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {
+                    if let Some(ref client) = self.dbus_client {
+                        self.state.pending_system_profiles = Some(client.get_system_profiles());
+                    }
+                }
+                Ok(Err(e)) => self.state.show_message(format!("Changing system profiles failed: {}", e), true),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_system_change = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_system_profiles.take() {
+            match rx.try_recv() {
+                Ok(Ok(profiles)) => {
+                    self.state.system_profiles = Some(profiles);
+                    self.sync_daemon_profiles();
+                }
+                Ok(Err(e)) => log::warn!("Failed to read system profiles: {}", e),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_system_profiles = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Fan floors are part of the capabilities, re-read them after a change
+        if let Some(mut rx) = self.state.pending_min_duty_change.take() {
+            match rx.try_recv() {
+                Ok(Ok(())) => {
+                    if let Some(ref client) = self.dbus_client {
+                        self.state.pending_fan_capabilities = Some(client.get_fan_curve_capabilities());
+                    }
+                }
+                Ok(Err(e)) => self.state.show_message(format!("Setting the minimum fan duty failed: {}", e), true),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_min_duty_change = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_fan_capabilities.take() {
+            match rx.try_recv() {
+                Ok(Ok(caps)) => self.state.fan_curve_capabilities = Some(caps),
+                Ok(Err(e)) => log::warn!("Failed to read fan capabilities: {}", e),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_fan_capabilities = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // The event log is only fetched while it is shown, new entries only
+        let events_due = self.state.events_polled_at
+            .map_or(true, |t| t.elapsed() >= Duration::from_secs(5));
+        if self.state.current_page == Page::Events && self.state.pending_events.is_none() && events_due {
+            if let Some(ref client) = self.dbus_client {
+                let since = self.state.events.last().map(|e| e.seq).unwrap_or(0);
+                self.state.pending_events = Some(client.get_events(since));
+                self.state.events_polled_at = Some(Instant::now());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_events.take() {
+            match rx.try_recv() {
+                Ok(Ok(events)) => {
+                    // A reply can overlap the previous one, keep each event once
+                    let last = self.state.events.last().map_or(0, |e| e.seq);
+                    self.state.events.extend(events.into_iter().filter(|e| e.seq > last));
+                }
+                Ok(Err(e)) => log::warn!("Failed to fetch events: {}", e),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_events = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Live ambient light for calibrating the screen brightness curve on the Tuning page
+        let ambient_due = self.state.ambient_polled_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs(1));
+        let has_als = self.state.feature_status.iter().any(|s| s.feature == Feature::AmbientLight);
+        if self.state.current_page == Page::Tuning && has_als
+            && self.state.pending_ambient_reading.is_none() && ambient_due {
+            if let Some(ref client) = self.dbus_client {
+                self.state.pending_ambient_reading = Some(client.get_ambient_light_reading());
+                self.state.ambient_polled_at = Some(Instant::now());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_ambient_reading.take() {
+            match rx.try_recv() {
+                Ok(Ok(reading)) => self.state.ambient_reading = Some(reading),
+                Ok(Err(e)) => log::debug!("Failed to read ambient light: {}", e),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_ambient_reading = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+        
+        // Same for the daemon log, polled faster so follow mode feels live
+        let log_due = self.state.daemon_log_polled_at
+            .map_or(true, |t| t.elapsed() >= Duration::from_secs(1));
+        if self.state.current_page == Page::Events && self.state.show_daemon_log
+            && self.state.pending_daemon_log.is_none() && log_due {
+            if let Some(ref client) = self.dbus_client {
+                let since = self.state.daemon_log.last().map(|e| e.seq).unwrap_or(0);
+                self.state.pending_daemon_log = Some(client.get_daemon_log(since));
+                self.state.daemon_log_polled_at = Some(Instant::now());
+            }
+        }
+        
+        if let Some(mut rx) = self.state.pending_daemon_log.take() {
+            match rx.try_recv() {
+                Ok(Ok(log)) => {
+                    if self.state.daemon_log_started != Some(log.started) {
+                        // Sequence numbers restart with the daemon, fetch everything again
+                        self.state.daemon_log.clear();
+                        self.state.daemon_log_started = Some(log.started);
+                        self.state.daemon_log_polled_at = None;
+                    } else {
+                        self.state.daemon_log.extend(log.entries);
+                        let excess = self.state.daemon_log.len().saturating_sub(MAX_DAEMON_LOG_ENTRIES);
+                        self.state.daemon_log.drain(..excess);
+                    }
+                }
+                Ok(Err(e)) => log::warn!("Failed to fetch daemon log: {}", e),
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.state.pending_daemon_log = Some(rx);
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+        }
+    }
+    
+    fn draw_thermal_headroom(&self, ui: &mut egui::Ui) {
+        let Some((degrees, sensor)) = &self.state.thermal_headroom else {
+            return;
+        };
+        let color = if *degrees < 10.0 {
+            egui::Color32::from_rgb(255, 80, 80)
+        } else if *degrees < 20.0 {
+            egui::Color32::from_rgb(255, 180, 0)
+        } else {
+            egui::Color32::from_rgb(100, 200, 100)
+        };
+        ui.colored_label(color, format!("🌡 {:.0}°C", degrees))
+            .on_hover_text(format!("Thermal headroom: {:.0}°C below critical on {}", degrees, sensor));
+    }
+    
+    fn draw_search(&mut self, ui: &mut egui::Ui) {
+        let response = ui.add(egui::TextEdit::singleline(&mut self.state.search_query)
+            .hint_text("🔍 Search (Ctrl+F)")
+            .desired_width(160.0));
+        if std::mem::take(&mut self.state.focus_search) {
+            response.request_focus();
+        }
+        
+        // Typing filters the page sections, picking a result jumps to it
+        self.state.sections.set_filter(crate::search::matching_sections(&self.state.search_query));
+        
+        let hits = crate::search::search(&self.state.search_query, 8);
+        let mut picked = None;
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            picked = hits.first();
+        }
+        
+        let popup_id = ui.make_persistent_id("search_results");
+        if response.has_focus() && !hits.is_empty() {
+            ui.memory_mut(|mem| mem.open_popup(popup_id));
+        }
+        egui::popup_below_widget(ui, popup_id, &response, egui::PopupCloseBehavior::CloseOnClickOutside, |ui| {
+            ui.set_min_width(280.0);
+            for hit in &hits {
+                if ui.selectable_label(false, &hit.label).clicked() {
+                    picked = Some(hit);
+                }
+            }
+        });
+        
+        if let Some(hit) = picked {
+            self.state.current_page = hit.page;
+            self.state.sections.jump_to(hit.section);
+            self.state.search_query.clear();
+            self.state.sections.set_filter(None);
+            ui.memory_mut(|mem| mem.close_popup());
+        }
+    }
+    
+    fn draw_config_issues(&mut self, ctx: &Context) {
+        let mut fix: Option<usize> = None;
+        let mut fix_all = false;
+        let mut ignore = false;
+        
+        egui::Window::new("⚠ Configuration problems")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("The configuration file has problems that may make profiles behave unexpectedly:");
+                ui.add_space(8.0);
+                
+                egui::Grid::new("config_issues_grid")
+                    .num_columns(2)
+                    .spacing([20.0, 6.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, issue) in self.state.config_issues.iter().enumerate() {
+                            ui.label(&issue.message);
+                            if ui.button("🔧 Fix").on_hover_text(&issue.fix_description).clicked() {
+                                fix = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    fix_all = ui.button("🔧 Fix all").clicked();
+                    ignore = ui.button("Ignore").on_hover_text("Keep the configuration as it is").clicked();
+                });
+            });
+        
+        if ignore {
+            self.state.config_issues.clear();
+            return;
+        }
+        
+        if let Some(index) = fix {
+            self.state.config_issues[index].apply(&mut self.state.config);
+        } else if fix_all {
+            // Fixes can change what the others refer to, so lint again after every one
+            for _ in 0..100 {
+                match config_lint::lint(&self.state.config, self.state.system_profiles.as_ref()).first() {
+                    Some(issue) => issue.apply(&mut self.state.config),
+                    None => break,
+                }
+            }
+        } else {
+            return;
+        }
+        
+        self.state.config_issues = config_lint::lint(&self.state.config, self.state.system_profiles.as_ref());
+        let _ = self.state.save_config();
+    }
+    
+    // A Steam rule for a running game first, then the game mode profile
+    fn auto_switch_target(&self) -> Option<String> {
+        let settings = &self.state.config.auto_switch;
+        let steam = self.state.config.app_monitoring_enabled
+            .then(|| settings.steam_rules.iter().find(|rule| self.state.running_steam_apps.contains(&rule.appid)))
+            .flatten()
+            .map(|rule| rule.profile.clone());
+        steam.or_else(|| (settings.gamemode_enabled && self.state.game_running)
+            .then(|| settings.gamemode_profile.clone()))
+    }
+    
+    // The matching profile while a game runs, the previous one afterwards
+    fn update_auto_switch(&mut self) {
+        // The A/B test owns the profile until it ends, a game started meanwhile switches afterwards
+        if self.state.read_only || self.state.ab_test_status.as_ref().is_some_and(|s| s.running) {
+            return;
+        }
+        let target = self.auto_switch_target();
+        if target == self.state.auto_switched_profile {
+            return;
+        }
+        let changed_by_hand = self.state.auto_switched_profile.as_ref()
+            .is_some_and(|profile| *profile != self.state.config.current_profile);
+        let manual_wins = self.state.config.auto_switch.gamemode_priority == GameModePriority::Manual;
+        
+        match target {
+            Some(target) => {
+                if changed_by_hand && manual_wins {
+                    return;
+                }
+                let current = self.state.config.current_profile.clone();
+                if target != current && !self.switch_profile(&target) {
+                    return;
+                }
+                log::info!("Game started, switched from '{}' to '{}'", current, target);
+                self.state.auto_switch_previous_profile.get_or_insert(current);
+                self.state.auto_switched_profile = Some(target);
+            }
+            None => {
+                self.state.auto_switched_profile = None;
+                let Some(previous) = self.state.auto_switch_previous_profile.take() else {
+                    return;
+                };
+                if changed_by_hand && manual_wins {
+                    log::info!("Game ended, keeping '{}' picked during the game", self.state.config.current_profile);
+                } else if previous != self.state.config.current_profile && self.switch_profile(&previous) {
+                    log::info!("Game ended, restored '{}'", previous);
+                }
+            }
+        }
+    }
+    
+    // The daemon switches on its own copies, they're refreshed whenever the picked profiles
+    // or their settings change. Waits for the system profiles, their names take precedence.
+    fn sync_daemon_profiles(&mut self) {
+        let Some(ref client) = self.dbus_client else {
+            return;
+        };
+        if self.state.read_only || self.state.system_profiles.is_none() {
+            return;
+        }
+        let settings = self.state.power_source_profiles();
+        if let Ok(json) = serde_json::to_string(&settings) {
+            if self.state.power_source_synced.as_ref() != Some(&json) {
+                self.state.pending_power_source_change = Some(client.set_power_source_profiles(settings));
+                self.state.power_source_synced = Some(json);
+            }
+        }
+        let settings = self.state.app_profiles();
+        if let Ok(json) = serde_json::to_string(&settings) {
+            if self.state.app_profiles_synced.as_ref() != Some(&json) {
+                self.state.pending_app_profiles_change = Some(client.set_app_profiles(settings));
+                self.state.app_profiles_synced = Some(json);
+            }
+        }
+    }
+    
+    // Same as switching on the Profiles page
+    fn handle_tray_event(&mut self, ctx: &Context, event: crate::system_tray::TrayEvent) {
+        use crate::system_tray::TrayEvent;
+        match event {
+            TrayEvent::ShowWindow | TrayEvent::ShowStatistics => {
+                if matches!(event, TrayEvent::ShowStatistics) {
+                    self.state.current_page = Page::Statistics;
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            TrayEvent::SwitchProfile(name) => {
+                self.switch_profile(&name);
+            }
+            // Goes through the same path as the button in the battery settings
+            TrayEvent::ChargeToFull => self.state.full_charge_requested = Some(true),
+            TrayEvent::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+        }
+    }
+    
+    fn switch_profile(&mut self, name: &str) -> bool {
+        let (Some(client), Some(profile)) = (
+            self.dbus_client.as_ref(),
+            self.state.find_profile(name).cloned(),
+        ) else {
+            log::warn!("Cannot switch to profile '{}'", name);
+            return false;
+        };
+        crate::session_settings::apply_profile(&profile);
+        let _rx = client.apply_profile(profile);
+        self.state.config.current_profile = name.to_string();
+        let _ = self.state.save_config();
+        self.state.show_message(format!("Switched to profile '{}'", name), false);
+        true
+    }
+    
+    fn draw_safe_mode_bar(&mut self, ctx: &Context, status: &SafeModeStatus) {
+        let profile_name = status.profile.clone().unwrap_or_default();
+        let mut leave = false;
+        
+        TopBottomPanel::top("safe_mode_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                ui.colored_label(egui::Color32::from_rgb(255, 180, 0), format!(
+                    "⚠ Safe mode: the daemon crashed {} times right after applying '{}'. The profile was not restored, fans are on automatic.",
+                    status.crash_count, profile_name));
+                
+                let index = self.state.config.profiles.iter().position(|p| p.name == profile_name);
+                if let Some(index) = index {
+                    if ui.button("✏ Edit profile").clicked() {
+                        self.state.config.current_profile = profile_name.clone();
+                        self.state.current_page = Page::Tuning;
+                        leave = true;
+                    }
+                    if ui.button("↺ Reset profile").clicked() {
+                        let profile = &mut self.state.config.profiles[index];
+                        *profile = Profile {
+                            name: profile.name.clone(),
+                            is_default: profile.is_default,
+                            ..crate::pages::tuning::create_default_profile_for_reset(profile.is_default)
+                        };
+                        let _ = self.state.save_config();
+                        leave = true;
+                    }
+                }
+                if ui.button("Dismiss").clicked() {
+                    leave = true;
+                }
+            });
+        });
+        
+        if leave {
+            self.state.safe_mode = None;
+            if let Some(ref client) = self.dbus_client {
+                let _rx = client.leave_safe_mode();
+            }
+        }
     }
     
     fn draw_top_bar(&mut self, ctx: &Context) {
@@ -262,11 +1457,30 @@ impl TuxedoApp {
                 
                 // Navigation tabs
                 ui.selectable_value(&mut self.state.current_page, Page::Statistics, "📊 Statistics");
+                if self.state.read_only {
+                    ui.label(egui::RichText::new("👁 Monitoring only").weak())
+                        .on_hover_text("Settings can't be changed in this session");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        self.draw_thermal_headroom(ui);
+                        ui.label(format!("Profile: {}", self.state.config.current_profile));
+                    });
+                    return;
+                }
                 ui.selectable_value(&mut self.state.current_page, Page::Profiles, "📋 Profiles");
                 ui.selectable_value(&mut self.state.current_page, Page::Tuning, "🔧 Tuning");
+                ui.selectable_value(&mut self.state.current_page, Page::Events, "📜 Events");
+                ui.selectable_value(&mut self.state.current_page, Page::Capabilities, "🧩 Capabilities");
                 ui.selectable_value(&mut self.state.current_page, Page::Settings, "⚙️ Settings");
+                ui.selectable_value(&mut self.state.current_page, Page::Usage, "📈 Usage");
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.draw_search(ui);
+                    self.draw_thermal_headroom(ui);
+                    
+                    if ui.button("🛑 Revert").on_hover_text("Fans to auto, drop overrides, apply Standard profile (Ctrl+Shift+R)").clicked() {
+                        self.state.panic_revert_requested = true;
+                    }
+                    
                     // Current profile indicator
                     ui.label(format!("Profile: {}", self.state.config.current_profile));
                 });
@@ -274,9 +1488,39 @@ impl TuxedoApp {
             ui.add_space(8.0);
         });
         
+        if let Some(ref message) = self.state.daemon_unavailable {
+            TopBottomPanel::top("daemon_bar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(12.0);
+                    ui.colored_label(egui::Color32::from_rgb(255, 180, 0), format!("⚠ {}", message));
+                });
+            });
+        }
+        
+        if let Some(status) = self.state.safe_mode.clone().filter(|_| !self.state.read_only) {
+            self.draw_safe_mode_bar(ctx, &status);
+        }
+        
+        // Stays until the daemon sees the fan spin again
+        if !self.state.fan_failures.is_empty() {
+            let fans = self.state.fan_failures.iter()
+                .map(|f| format!("Fan {} (0 RPM at {}%)", f.fan_id, f.duty))
+                .collect::<Vec<_>>()
+                .join(", ");
+            TopBottomPanel::top("fan_failure_bar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(12.0);
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), format!(
+                        "⚠ Probable fan failure: {}. The other fans run faster to compensate, avoid heavy load.", fans));
+                });
+            });
+        }
+        
         // Status message bar (if any)
         if let Some(ref msg) = self.state.status_message.clone() {
-            if msg.shown_at.elapsed() < Duration::from_secs(5) {
+            // Errors with help stay until dismissed so the details can be read
+            if msg.hint.is_some() || msg.shown_at.elapsed() < Duration::from_secs(5) {
+                let mut dismissed = false;
                 TopBottomPanel::top("status_bar").show(ctx, |ui| {
                     ui.horizontal(|ui| {
                         ui.add_space(12.0);
@@ -286,8 +1530,20 @@ impl TuxedoApp {
                             egui::Color32::from_rgb(80, 200, 120)
                         };
                         ui.colored_label(color, &msg.text);
+                        if msg.hint.is_some() {
+                            dismissed = ui.small_button("✖").clicked();
+                        }
                     });
+                    if let Some(ref hint) = msg.hint {
+                        ui.horizontal(|ui| {
+                            ui.add_space(12.0);
+                            ui.vertical(|ui| crate::error_hints::draw_details(ui, "status_hint", hint));
+                        });
+                    }
                 });
+                if dismissed {
+                    self.state.status_message = None;
+                }
             } else {
                 self.state.status_message = None;
             }
@@ -300,12 +1556,24 @@ impl eframe::App for TuxedoApp {
         // Handle keyboard shortcuts
         self.shortcuts.handle_shortcuts(ctx, &mut self.state);
         
+        // Whatever asked for it, nothing but the statistics and no writes
+        if self.state.read_only {
+            self.state.current_page = Page::Statistics;
+            self.state.panic_revert_requested = false;
+            self.state.full_charge_requested = None;
+            self.state.config_issues.clear();
+        }
+        
         // Handle background hardware updates
-        self.handle_hardware_updates();
+        self.handle_hardware_updates(ctx);
         
         // Draw top bar
         self.draw_top_bar(ctx);
         
+        if !self.state.config_issues.is_empty() {
+            self.draw_config_issues(ctx);
+        }
+        
         // Draw main content
         CentralPanel::default().show(ctx, |ui| {
             match self.state.current_page {
@@ -318,15 +1586,59 @@ impl eframe::App for TuxedoApp {
                 Page::Tuning => {
                     tuning::draw(ui, &mut self.state, self.dbus_client.as_ref());
                 }
+                Page::Events => {
+                    events::draw(ui, &mut self.state);
+                }
+                Page::Capabilities => {
+                    capabilities::draw(ui, &mut self.state);
+                }
                 Page::Settings => {
                     settings::draw(ui, &mut self.state, &mut self.theme, ctx);
                 }
+                Page::WhatsNew => {
+                    whats_new::draw(ui, &mut self.state);
+                }
+                Page::Usage => {
+                    usage::draw(ui, &mut self.state, self.dbus_client.as_ref());
+                }
             }
         });
         
+        // Pending config changes go to disk after a pause or when the window loses focus
+        let focused = ctx.input(|i| i.focused);
+        if self.state.config_dirty_since.is_some_and(|since| since.elapsed() >= CONFIG_SAVE_INTERVAL || !focused) {
+            match self.state.flush_config() {
+                Ok(true) => self.sync_daemon_profiles(),
+                Ok(false) => {}
+                Err(e) => self.state.show_message(format!("Failed to save configuration: {}", e), true),
+            }
+        }
+        
         // Request repaint if there are pending updates
         ctx.request_repaint_after(Duration::from_millis(500));
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.state.flush_config() {
+            log::warn!("Failed to save configuration: {}", e);
+        }
+        if let Err(e) = self.state.history.save() {
+            log::warn!("Failed to save statistics history: {}", e);
+        }
+    }
+}
+
+// The GUI itself never touches /sys or /dev, explain where the daemon has to come from
+fn daemon_unavailable_message(error: &str) -> String {
+    let hint = if std::path::Path::new("/.flatpak-info").exists() {
+        "Running as Flatpak: install and start tuxedo-daemon on the host system"
+    } else if std::path::Path::new("/run/.containerenv").exists() || std::path::Path::new("/.dockerenv").exists() {
+        "Running in a container: tuxedo-daemon must run on the host and the system bus must be shared"
+    } else {
+        "Start the system daemon with: sudo systemctl enable --now tuxedo-daemon"
+    };
+    
+    format!("Needs system daemon ({}). {}", error, hint)
 }
 
 fn start_background_polling(
@@ -336,36 +1648,52 @@ fn start_background_polling(
 ) {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_millis(1000));
+        let mut tick: u32 = 0;
         
         loop {
             interval.tick().await;
+            tick = tick.wrapping_add(1);
 
             let client = client.clone();
             let tx = tx.clone();
+            // Frequencies and load still need GetCpuInfo, the temperature is signalled
+            let poll_cpu = !crate::dbus_client::receiving_cpu_temp_signals() || tick.is_multiple_of(CPU_POLL_TICKS);
+            let poll_fans = !crate::dbus_client::receiving_fan_signals();
 
             tokio::spawn(async move {
-                let (cpu, gpu, fans, battery, wifi, storage_device, mount) = tokio::join!(
-                    client.get_cpu_info(),
+                let (cpu, gpu, fans, battery, ac_adapter, wifi, storage_device, mount, dust_cleaning, auto_tune, ab_test, fan_calibration, temperatures, lid, fan_mode) = tokio::join!(
+                    async { if poll_cpu { client.get_cpu_info().await.ok() } else { None } },
                     client.get_gpu_info(),
-                    client.get_fan_info(),
+                    async { if poll_fans { client.get_fan_info().await.ok() } else { None } },
                     client.get_battery_info(),
+                    client.get_ac_adapter_info(),
                     client.get_wifi_info(),
                     client.get_storage_device_info(),
-                    client.get_mount_info()
+                    client.get_mount_info(),
+                    client.get_dust_cleaning_status(),
+                    client.get_auto_tune_status(),
+                    client.get_ab_test_status(),
+                    client.get_fan_calibration_status(),
+                    client.get_all_temperatures(),
+                    client.get_lid_status(),
+                    client.get_fan_mode()
                 );
 
-                if let Ok(Ok(info)) = cpu {
+                if let Some(Ok(info)) = cpu {
                     let _ = tx.send(HardwareUpdate::CpuInfo(info));
                 }
                 if let Ok(Ok(info)) = gpu {
                     let _ = tx.send(HardwareUpdate::GpuInfo(info));
                 }
-                if let Ok(Ok(info)) = fans {
+                if let Some(Ok(info)) = fans {
                     let _ = tx.send(HardwareUpdate::FanInfo(info));
                 }
                 if let Ok(Ok(info)) = battery {
                     let _ = tx.send(HardwareUpdate::BatteryInfo(info));
                 }
+                if let Ok(Ok(info)) = ac_adapter {
+                    let _ = tx.send(HardwareUpdate::AcAdapterInfo(info));
+                }
                 if let Ok(Ok(info)) = wifi {
                     let _ = tx.send(HardwareUpdate::WifiInfo(info));
                 }
@@ -375,23 +1703,50 @@ fn start_background_polling(
                 if let Ok(Ok(info)) = mount {
                     let _ = tx.send(HardwareUpdate::MountInfo(info));
                 }
+                if let Ok(Ok(status)) = dust_cleaning {
+                    let _ = tx.send(HardwareUpdate::DustCleaningStatus(status));
+                }
+                if let Ok(Ok(status)) = auto_tune {
+                    let _ = tx.send(HardwareUpdate::AutoTuneStatus(status));
+                }
+                if let Ok(Ok(status)) = ab_test {
+                    let _ = tx.send(HardwareUpdate::AbTestStatus(status));
+                }
+                if let Ok(Ok(status)) = fan_calibration {
+                    let _ = tx.send(HardwareUpdate::FanCalibrationStatus(status));
+                }
+                if let Ok(Ok(sensors)) = temperatures {
+                    let _ = tx.send(HardwareUpdate::Temperatures(sensors));
+                }
+                if let Ok(Ok(status)) = lid {
+                    let _ = tx.send(HardwareUpdate::LidStatus(status));
+                }
+                if let Ok(Ok(status)) = fan_mode {
+                    let _ = tx.send(HardwareUpdate::FanMode(status));
+                }
             });
         }
     });
 }
 
-fn load_config_from_disk() -> anyhow::Result<AppConfig> {
+pub fn load_config_from_disk() -> anyhow::Result<AppConfig> {
     let config_dir = std::env::var("HOME")? + "/.config/tuxedo-control-center";
     let config_path = format!("{}/config.json", config_dir);
     let json = std::fs::read_to_string(config_path)?;
     Ok(serde_json::from_str(&json)?)
 }
 
-fn save_config_to_disk(config: &AppConfig) -> anyhow::Result<()> {
+pub fn save_config_to_disk(config: &AppConfig) -> anyhow::Result<()> {
+    write_config_json(&serde_json::to_string_pretty(config)?)
+}
+
+// Through a temporary file, the search provider may read the config at any moment
+fn write_config_json(json: &str) -> anyhow::Result<()> {
     let config_dir = std::env::var("HOME")? + "/.config/tuxedo-control-center";
     std::fs::create_dir_all(&config_dir)?;
     let config_path = format!("{}/config.json", config_dir);
-    let json = serde_json::to_string_pretty(config)?;
-    std::fs::write(config_path, json)?;
+    let temp_path = format!("{}/.config.json.tmp", config_dir);
+    std::fs::write(&temp_path, json)?;
+    std::fs::rename(temp_path, config_path)?;
     Ok(())
 }
@@ -0,0 +1,161 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::app::HardwareUpdate;
+
+// Running application detection for automatic profile switching (Settings > App monitoring).
+//
+// Steam starts every game, native or Proton, under its `reaper` helper:
+//   reaper SteamLaunch AppId=1245620 -- .../proton waitforexitandrun .../game.exe
+// Older clients run Proton without it, those processes carry the appid in their environment.
+const PROC_PATH: &str = "/proc";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const APPID_VARS: [&str; 2] = ["STEAM_COMPAT_APP_ID", "SteamAppId"];
+// Tools Steam runs like games (Proton versions, runtimes, redistributables) are not games
+const TOOL_APPIDS: [u32; 2] = [228980, 1070560];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// A game from the local Steam libraries
+#[derive(Debug, Clone)]
+pub struct SteamGame {
+    pub appid: u32,
+    pub name: String,
+}
+
+pub async fn watch(tx: mpsc::UnboundedSender<HardwareUpdate>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last = BTreeSet::new();
+
+    loop {
+        interval.tick().await;
+        let running = if ENABLED.load(Ordering::Relaxed) {
+            tokio::task::spawn_blocking(running_steam_apps).await.unwrap_or_default()
+        } else {
+            BTreeSet::new()
+        };
+        if running != last {
+            if tx.send(HardwareUpdate::SteamApps(running.iter().copied().collect())).is_err() {
+                return;
+            }
+            last = running;
+        }
+    }
+}
+
+fn running_steam_apps() -> BTreeSet<u32> {
+    let mut apps = BTreeSet::new();
+    let Ok(entries) = fs::read_dir(PROC_PATH) else {
+        return apps;
+    };
+
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(cmdline) = fs::read(entry.path().join("cmdline")) else {
+            continue;
+        };
+        let args: Vec<&[u8]> = cmdline.split(|&b| b == 0).collect();
+
+        let is_reaper = args.first().is_some_and(|arg0| arg0.ends_with(b"reaper"))
+            && args.iter().any(|arg| *arg == b"SteamLaunch");
+        let appid = if is_reaper {
+            args.iter().find_map(|arg| parse_appid(arg.strip_prefix(b"AppId=")?))
+        } else if args.iter().any(|arg| arg.ends_with(b"/proton")) {
+            // Other users' processes aren't readable, they aren't this session's games anyway
+            fs::read(entry.path().join("environ")).ok().and_then(|environ| {
+                environ.split(|&b| b == 0).find_map(|var| {
+                    APPID_VARS.iter().find_map(|name| {
+                        parse_appid(var.strip_prefix(name.as_bytes())?.strip_prefix(b"=")?)
+                    })
+                })
+            })
+        } else {
+            None
+        };
+
+        if let Some(appid) = appid.filter(|id| *id != 0 && !TOOL_APPIDS.contains(id)) {
+            apps.insert(appid);
+        }
+    }
+    apps
+}
+
+fn parse_appid(value: &[u8]) -> Option<u32> {
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
+
+/// Installed games from the appmanifest files of every Steam library, sorted by name
+pub fn installed_steam_games() -> Vec<SteamGame> {
+    let mut games: Vec<SteamGame> = steam_libraries().iter()
+        .flat_map(|library| fs::read_dir(library.join("steamapps")).into_iter().flatten().flatten())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("appmanifest_") && name.ends_with(".acf")
+        })
+        .filter_map(|entry| {
+            let manifest = fs::read_to_string(entry.path()).ok()?;
+            let appid = vdf_value(&manifest, "appid")?.parse().ok()?;
+            let name = vdf_value(&manifest, "name")?;
+            Some(SteamGame { appid, name })
+        })
+        .filter(|game| !TOOL_APPIDS.contains(&game.appid) && !game.name.starts_with("Proton")
+            && !game.name.starts_with("Steam Linux Runtime") && game.name != "Steamworks Common Redistributables")
+        .collect();
+    games.sort_by_key(|game| game.name.to_lowercase());
+    games.dedup_by_key(|game| game.appid);
+    games
+}
+
+// The main installation plus any library folders added in Steam's storage settings
+fn steam_libraries() -> Vec<PathBuf> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+    let home = Path::new(&home);
+    let roots = [
+        home.join(".local/share/Steam"),
+        home.join(".steam/steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ];
+
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for root in roots.iter().filter(|root| root.join("steamapps").is_dir()) {
+        let root = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        let folders = fs::read_to_string(root.join("steamapps/libraryfolders.vdf")).unwrap_or_default();
+        let paths = folders.lines()
+            .filter_map(|line| vdf_pair(line).filter(|(key, _)| *key == "path"))
+            .map(|(_, path)| PathBuf::from(path));
+        for library in std::iter::once(root.clone()).chain(paths) {
+            let library = fs::canonicalize(&library).unwrap_or(library);
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+    }
+    libraries
+}
+
+// A `"key"    "value"` line of Valve's KeyValues format
+fn vdf_pair(line: &str) -> Option<(&str, &str)> {
+    let mut fields = line.trim().split('"').filter(|field| !field.trim().is_empty());
+    let key = fields.next()?;
+    let value = fields.next()?;
+    Some((key, value))
+}
+
+fn vdf_value(text: &str, key: &str) -> Option<String> {
+    text.lines()
+        .filter_map(vdf_pair)
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.to_string())
+}
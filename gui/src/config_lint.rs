@@ -0,0 +1,170 @@
+use tuxedo_common::types::{AppConfig, Profile, SystemProfiles};
+
+/// A problem found in the loaded configuration, with the change that repairs it
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub message: String,
+    pub fix_description: String,
+    fix: Fix,
+}
+
+#[derive(Debug, Clone)]
+enum Fix {
+    AddStandardProfile,
+    SelectProfile(String),
+    RenameProfile { index: usize, name: String },
+    ClampCurve { profile: usize, curve: usize },
+    SwapFrequencyLimits { profile: usize },
+    SwapChargeThresholds,
+    DisableGameModeSwitch,
+    RemoveSteamRule(u32),
+    ClearPowerSourceProfile { on_ac: bool },
+    RemoveAppRule(String),
+}
+
+impl ConfigIssue {
+    pub fn apply(&self, config: &mut AppConfig) {
+        match self.fix {
+            Fix::AddStandardProfile => config.profiles.push(Profile::default()),
+            Fix::SelectProfile(ref name) => config.current_profile = name.clone(),
+            Fix::RenameProfile { index, ref name } => {
+                if let Some(profile) = config.profiles.get_mut(index) {
+                    profile.name = name.clone();
+                }
+            }
+            Fix::ClampCurve { profile, curve } => {
+                if let Some(curve) = config.profiles.get_mut(profile)
+                    .and_then(|p| p.fan_settings.curves.get_mut(curve))
+                {
+                    for point in curve.points.iter_mut() {
+                        *point = (point.0.min(100), point.1.min(100));
+                    }
+                    curve.points.sort_by_key(|p| p.0);
+                    curve.points.dedup_by_key(|p| p.0);
+                }
+            }
+            Fix::SwapFrequencyLimits { profile } => {
+                if let Some(cpu) = config.profiles.get_mut(profile).map(|p| &mut p.cpu_settings) {
+                    std::mem::swap(&mut cpu.min_frequency, &mut cpu.max_frequency);
+                }
+            }
+            Fix::SwapChargeThresholds => {
+                let battery = &mut config.battery_settings;
+                std::mem::swap(&mut battery.charge_start_threshold, &mut battery.charge_end_threshold);
+            }
+            Fix::DisableGameModeSwitch => config.auto_switch.gamemode_enabled = false,
+            Fix::RemoveSteamRule(appid) => config.auto_switch.steam_rules.retain(|rule| rule.appid != appid),
+            Fix::ClearPowerSourceProfile { on_ac: true } => config.auto_switch.ac_profile.clear(),
+            Fix::ClearPowerSourceProfile { on_ac: false } => config.auto_switch.battery_profile.clear(),
+            Fix::RemoveAppRule(ref process) => config.auto_switch.app_rules.retain(|rule| &rule.process != process),
+        }
+    }
+}
+
+/// `system_profiles` is None until the daemon has been asked, names that may be system
+/// profiles aren't reported as missing before that
+pub fn lint(config: &AppConfig, system_profiles: Option<&SystemProfiles>) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let exists = |name: &str| config.profiles.iter().any(|p| p.name == name)
+        || system_profiles.is_none_or(|system| system.profiles.iter().any(|p| p.name == name));
+
+    if config.profiles.is_empty() {
+        issues.push(ConfigIssue {
+            message: "No profiles defined".to_string(),
+            fix_description: "Add the Standard profile".to_string(),
+            fix: Fix::AddStandardProfile,
+        });
+    } else if !exists(&config.current_profile) {
+        let fallback = config.profiles.iter()
+            .find(|p| p.is_default)
+            .unwrap_or(&config.profiles[0])
+            .name.clone();
+        issues.push(ConfigIssue {
+            message: format!("Current profile '{}' does not exist", config.current_profile),
+            fix_description: format!("Select '{}'", fallback),
+            fix: Fix::SelectProfile(fallback),
+        });
+    }
+
+    let auto_switch = &config.auto_switch;
+    if auto_switch.gamemode_enabled && !exists(&auto_switch.gamemode_profile) {
+        issues.push(ConfigIssue {
+            message: format!("Game mode profile '{}' does not exist", auto_switch.gamemode_profile),
+            fix_description: "Turn off switching while a game runs".to_string(),
+            fix: Fix::DisableGameModeSwitch,
+        });
+    }
+    for rule in auto_switch.steam_rules.iter().filter(|rule| !exists(&rule.profile)) {
+        issues.push(ConfigIssue {
+            message: format!("Profile '{}' for {} does not exist", rule.profile, rule.name),
+            fix_description: format!("Remove the rule for {}", rule.name),
+            fix: Fix::RemoveSteamRule(rule.appid),
+        });
+    }
+    for (on_ac, name, source) in [(true, &auto_switch.ac_profile, "AC"), (false, &auto_switch.battery_profile, "battery")] {
+        if !name.is_empty() && !exists(name) {
+            issues.push(ConfigIssue {
+                message: format!("Profile '{}' for running on {} does not exist", name, source),
+                fix_description: format!("Switch to no profile on {}", source),
+                fix: Fix::ClearPowerSourceProfile { on_ac },
+            });
+        }
+    }
+    for rule in auto_switch.app_rules.iter().filter(|rule| !exists(&rule.profile)) {
+        issues.push(ConfigIssue {
+            message: format!("Profile '{}' for {} does not exist", rule.profile, rule.process),
+            fix_description: format!("Remove the rule for {}", rule.process),
+            fix: Fix::RemoveAppRule(rule.process.clone()),
+        });
+    }
+
+    for (index, profile) in config.profiles.iter().enumerate() {
+        if config.profiles[..index].iter().any(|p| p.name == profile.name) {
+            let name = unique_name(config, &profile.name);
+            issues.push(ConfigIssue {
+                message: format!("Profile name '{}' is used more than once", profile.name),
+                fix_description: format!("Rename the duplicate to '{}'", name),
+                fix: Fix::RenameProfile { index, name },
+            });
+        }
+
+        for (curve_index, curve) in profile.fan_settings.curves.iter().enumerate() {
+            if curve.points.iter().any(|(temp, speed)| *temp > 100 || *speed > 100) {
+                issues.push(ConfigIssue {
+                    message: format!("'{}': fan {} curve has points above 100 °C / 100 %", profile.name, curve.fan_id),
+                    fix_description: "Clamp the points to 100".to_string(),
+                    fix: Fix::ClampCurve { profile: index, curve: curve_index },
+                });
+            }
+        }
+
+        if let (Some(min), Some(max)) = (profile.cpu_settings.min_frequency, profile.cpu_settings.max_frequency) {
+            if min > max {
+                issues.push(ConfigIssue {
+                    message: format!("'{}': minimum CPU frequency is above the maximum", profile.name),
+                    fix_description: "Swap minimum and maximum".to_string(),
+                    fix: Fix::SwapFrequencyLimits { profile: index },
+                });
+            }
+        }
+    }
+
+    let battery = &config.battery_settings;
+    if battery.charge_start_threshold > battery.charge_end_threshold {
+        issues.push(ConfigIssue {
+            message: format!("Charge start threshold ({} %) is above the end threshold ({} %)",
+                battery.charge_start_threshold, battery.charge_end_threshold),
+            fix_description: "Swap start and end thresholds".to_string(),
+            fix: Fix::SwapChargeThresholds,
+        });
+    }
+
+    issues
+}
+
+fn unique_name(config: &AppConfig, base: &str) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", base, n))
+        .find(|name| !config.profiles.iter().any(|p| &p.name == name))
+        .unwrap()
+}
@@ -1,8 +1,42 @@
 use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tuxedo_common::types::*;
 use zbus::Connection;
 use tokio::sync::{mpsc, oneshot};
 
+// Set once the daemon signals these, older daemons don't and are polled instead
+static FAN_SIGNALS: AtomicBool = AtomicBool::new(false);
+static CPU_TEMP_SIGNALS: AtomicBool = AtomicBool::new(false);
+
+/// Whether fan speeds arrive as FanSpeedChanged signals, GetFanInfo needn't be polled then
+pub fn receiving_fan_signals() -> bool {
+    FAN_SIGNALS.load(Ordering::Relaxed)
+}
+
+/// Whether the CPU temperature arrives as CpuTempChanged signals
+pub fn receiving_cpu_temp_signals() -> bool {
+    CPU_TEMP_SIGNALS.load(Ordering::Relaxed)
+}
+
+/// Values the daemon signals as they change
+pub enum Telemetry {
+    FanSpeed(Vec<FanInfo>),
+    CpuTemp(f32),
+    ProfileApplied(String),
+}
+
+/// ApplyProfile ran and failed, the report says in which step
+#[derive(Debug)]
+pub struct ApplyFailed(pub ApplyReport);
+
+impl std::fmt::Display for ApplyFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.error.as_deref().unwrap_or("unknown error"))
+    }
+}
+
+impl std::error::Error for ApplyFailed {}
+
 #[derive(Clone)]
 pub struct DbusClient {
     command_tx: mpsc::UnboundedSender<DbusCommand>,
@@ -18,7 +52,8 @@ pub enum DbusCommand {
     GetStorageDeviceInfo { reply: oneshot::Sender<Result<Vec<StorageDevice>>> },
     GetMountInfo { reply: oneshot::Sender<Result<Vec<MountInfo>>> },
     GetWifiInfo { reply: oneshot::Sender<Result<Vec<WiFiInfo>>> },
-    ApplyProfile { profile: Profile, reply: oneshot::Sender<Result<()>> },
+    ApplyProfile { profile: Profile, reply: oneshot::Sender<Result<Option<ApplyReport>>> },
+    PanicRevert { reply: oneshot::Sender<Result<()>> },
     SetCpuGovernor { governor: String, reply: oneshot::Sender<Result<()>> },
     SetCpuBoost { enabled: bool, reply: oneshot::Sender<Result<()>> },
     PreviewKeyboard { settings: KeyboardSettings, reply: oneshot::Sender<Result<()>> },
@@ -27,6 +62,55 @@ pub enum DbusCommand {
     GetBatteryAvailableStartThresholds { reply: oneshot::Sender<Result<Vec<u8>>> },
     GetBatteryAvailableEndThresholds { reply: oneshot::Sender<Result<Vec<u8>>> },
     SetBatterySettings { settings: BatterySettings, reply: oneshot::Sender<Result<()>> },
+    StartDustCleaning { cycles: u32, reply: oneshot::Sender<Result<()>> },
+    AbortDustCleaning { reply: oneshot::Sender<Result<()>> },
+    GetDustCleaningStatus { reply: oneshot::Sender<Result<DustCleaningStatus>> },
+    StartAutoTune { reply: oneshot::Sender<Result<()>> },
+    GetSafeMode { reply: oneshot::Sender<Result<SafeModeStatus>> },
+    LeaveSafeMode { reply: oneshot::Sender<Result<()>> },
+    GetDisplayInfo { reply: oneshot::Sender<Result<Vec<DisplayInfo>>> },
+    GetCapabilityMatrix { reply: oneshot::Sender<Result<Vec<Capability>>> },
+    GetProfileUsage { reply: oneshot::Sender<Result<ProfileUsage>> },
+    ResetProfileUsage { reply: oneshot::Sender<Result<()>> },
+    GetChargingOptions { reply: oneshot::Sender<Result<ChargingOptions>> },
+    GetAcAdapterInfo { reply: oneshot::Sender<Result<AcAdapterInfo>> },
+    GetTdpLimits { reply: oneshot::Sender<Result<TdpLimits>> },
+    PreviewProfile { profile: Profile, reply: oneshot::Sender<Result<ApplyPreview>> },
+    GetDaemonLog { since: u64, reply: oneshot::Sender<Result<DaemonLog>> },
+    GetChargeControlInfo { reply: oneshot::Sender<Result<ChargeControlInfo>> },
+    StartFullCharge { reply: oneshot::Sender<Result<()>> },
+    CancelFullCharge { reply: oneshot::Sender<Result<()>> },
+    GetFanFailures { reply: oneshot::Sender<Result<Vec<FanFailure>>> },
+    GetAmbientLightReading { reply: oneshot::Sender<Result<AmbientLightReading>> },
+    GetWriteAccess { reply: oneshot::Sender<Result<bool>> },
+    GetSystemProfiles { reply: oneshot::Sender<Result<SystemProfiles>> },
+    PromoteProfile { profile: Profile, replace: bool, reply: oneshot::Sender<Result<()>> },
+    RemoveSystemProfile { name: String, reply: oneshot::Sender<Result<()>> },
+    SetBootProfile { name: String, reply: oneshot::Sender<Result<()>> },
+    SetFanMinDuty { fan_id: u32, percent: u8, reply: oneshot::Sender<Result<()>> },
+    StartFanCalibration { fan_id: u32, reply: oneshot::Sender<Result<()>> },
+    GetFanCalibrationStatus { reply: oneshot::Sender<Result<FanCalibrationStatus>> },
+    CancelApply { reply: oneshot::Sender<Result<bool>> },
+    // Two whole profiles, boxed to keep the other commands small
+    SetPowerSourceProfiles { settings: Box<PowerSourceProfiles>, reply: oneshot::Sender<Result<()>> },
+    SetAppProfiles { settings: AppProfiles, reply: oneshot::Sender<Result<()>> },
+    GetEvents { since: u64, reply: oneshot::Sender<Result<Vec<EventLogEntry>>> },
+    AbortAutoTune { reply: oneshot::Sender<Result<()>> },
+    GetAutoTuneStatus { reply: oneshot::Sender<Result<AutoTuneStatus>> },
+    StartAbTest { a: Profile, b: Profile, session_minutes: u32, rounds: u32, reply: oneshot::Sender<Result<()>> },
+    AbortAbTest { reply: oneshot::Sender<Result<()>> },
+    GetAbTestStatus { reply: oneshot::Sender<Result<AbTestStatus>> },
+    GetAllTemperatures { reply: oneshot::Sender<Result<Vec<TemperatureSensor>>> },
+    GetTdpProfiles { reply: oneshot::Sender<Result<Vec<String>>> },
+    GetNetworkInterfaces { reply: oneshot::Sender<Result<Vec<String>>> },
+    GetKeyboardDevices { reply: oneshot::Sender<Result<Vec<KeyboardDevice>>> },
+    GetIndicatorLeds { reply: oneshot::Sender<Result<Vec<String>>> },
+    GetAquarisStatus { reply: oneshot::Sender<Result<AquarisStatus>> },
+    GetLidStatus { reply: oneshot::Sender<Result<LidStatus>> },
+    GetFanMode { reply: oneshot::Sender<Result<FanModeStatus>> },
+    GetIgpuFrequencyInfo { reply: oneshot::Sender<Result<IgpuFrequencyInfo>> },
+    GetFanCurveCapabilities { reply: oneshot::Sender<Result<FanCurveCapabilities>> },
+    GetFeatureAvailability { reply: oneshot::Sender<Result<Vec<FeatureStatus>>> },
 }
 
 impl DbusClient {
@@ -43,6 +127,41 @@ impl DbusClient {
         Ok(Self { command_tx })
     }
     
+    /// Changes the daemon noticed but didn't make itself, e.g. Fn hotkeys
+    pub fn hardware_events(&self) -> mpsc::UnboundedReceiver<HardwareEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(e) = watch_hardware_events(tx).await {
+                log::warn!("Not receiving hardware events: {}", e);
+            }
+        });
+        rx
+    }
+    
+    /// Current thermal headroom in °C and the limiting sensor, followed by every change
+    pub fn thermal_headroom(&self) -> mpsc::UnboundedReceiver<(Option<f64>, String)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(e) = watch_thermal_headroom(tx).await {
+                log::warn!("Not receiving thermal headroom: {}", e);
+            }
+        });
+        rx
+    }
+    
+    /// Fan speeds, CPU temperature and applied profiles as the daemon signals them
+    pub fn telemetry(&self) -> mpsc::UnboundedReceiver<Telemetry> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(e) = watch_telemetry(tx).await {
+                log::warn!("Not receiving telemetry signals: {}", e);
+            }
+            FAN_SIGNALS.store(false, Ordering::Relaxed);
+            CPU_TEMP_SIGNALS.store(false, Ordering::Relaxed);
+        });
+        rx
+    }
+    
     // Non-blocking methods - return immediately with oneshot receiver
     
     pub fn get_cpu_info(&self) -> oneshot::Receiver<Result<CpuInfo>> {
@@ -93,7 +212,7 @@ impl DbusClient {
         rx
     }
     
-    pub fn apply_profile(&self, profile: Profile) -> oneshot::Receiver<Result<()>> {
+    pub fn apply_profile(&self, profile: Profile) -> oneshot::Receiver<Result<Option<ApplyReport>>> {
         let (tx, rx) = oneshot::channel();
         let _ = self.command_tx.send(DbusCommand::ApplyProfile { 
             profile: profile.clone(), 
@@ -102,6 +221,12 @@ impl DbusClient {
         rx
     }
     
+    pub fn panic_revert(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::PanicRevert { reply: tx });
+        rx
+    }
+    
     pub fn set_cpu_governor(&self, governor: String) -> oneshot::Receiver<Result<()>> {
         let (tx, rx) = oneshot::channel();
         let _ = self.command_tx.send(DbusCommand::SetCpuGovernor { governor, reply: tx });
@@ -148,6 +273,294 @@ impl DbusClient {
         let _ = self.command_tx.send(DbusCommand::SetBatterySettings { settings, reply: tx });
         rx
     }
+
+    pub fn start_dust_cleaning(&self, cycles: u32) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::StartDustCleaning { cycles, reply: tx });
+        rx
+    }
+
+    pub fn abort_dust_cleaning(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::AbortDustCleaning { reply: tx });
+        rx
+    }
+
+    pub fn get_dust_cleaning_status(&self) -> oneshot::Receiver<Result<DustCleaningStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetDustCleaningStatus { reply: tx });
+        rx
+    }
+
+    pub fn get_safe_mode(&self) -> oneshot::Receiver<Result<SafeModeStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetSafeMode { reply: tx });
+        rx
+    }
+
+    pub fn leave_safe_mode(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::LeaveSafeMode { reply: tx });
+        rx
+    }
+
+    pub fn get_display_info(&self) -> oneshot::Receiver<Result<Vec<DisplayInfo>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetDisplayInfo { reply: tx });
+        rx
+    }
+
+    pub fn get_capability_matrix(&self) -> oneshot::Receiver<Result<Vec<Capability>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetCapabilityMatrix { reply: tx });
+        rx
+    }
+
+    pub fn get_profile_usage(&self) -> oneshot::Receiver<Result<ProfileUsage>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetProfileUsage { reply: tx });
+        rx
+    }
+
+    pub fn reset_profile_usage(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::ResetProfileUsage { reply: tx });
+        rx
+    }
+
+    pub fn get_charging_options(&self) -> oneshot::Receiver<Result<ChargingOptions>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetChargingOptions { reply: tx });
+        rx
+    }
+
+    pub fn get_ac_adapter_info(&self) -> oneshot::Receiver<Result<AcAdapterInfo>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAcAdapterInfo { reply: tx });
+        rx
+    }
+
+    pub fn get_tdp_limits(&self) -> oneshot::Receiver<Result<TdpLimits>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetTdpLimits { reply: tx });
+        rx
+    }
+
+    pub fn preview_profile(&self, profile: Profile) -> oneshot::Receiver<Result<ApplyPreview>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::PreviewProfile { profile, reply: tx });
+        rx
+    }
+
+    pub fn get_daemon_log(&self, since: u64) -> oneshot::Receiver<Result<DaemonLog>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetDaemonLog { since, reply: tx });
+        rx
+    }
+
+    pub fn get_charge_control_info(&self) -> oneshot::Receiver<Result<ChargeControlInfo>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetChargeControlInfo { reply: tx });
+        rx
+    }
+
+    pub fn start_full_charge(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::StartFullCharge { reply: tx });
+        rx
+    }
+
+    pub fn cancel_full_charge(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::CancelFullCharge { reply: tx });
+        rx
+    }
+
+    pub fn get_fan_failures(&self) -> oneshot::Receiver<Result<Vec<FanFailure>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetFanFailures { reply: tx });
+        rx
+    }
+
+    pub fn get_ambient_light_reading(&self) -> oneshot::Receiver<Result<AmbientLightReading>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAmbientLightReading { reply: tx });
+        rx
+    }
+
+    pub fn get_write_access(&self) -> oneshot::Receiver<Result<bool>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetWriteAccess { reply: tx });
+        rx
+    }
+
+    pub fn get_system_profiles(&self) -> oneshot::Receiver<Result<SystemProfiles>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetSystemProfiles { reply: tx });
+        rx
+    }
+
+    pub fn promote_profile(&self, profile: Profile, replace: bool) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::PromoteProfile { profile, replace, reply: tx });
+        rx
+    }
+
+    pub fn remove_system_profile(&self, name: String) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::RemoveSystemProfile { name, reply: tx });
+        rx
+    }
+
+    pub fn set_boot_profile(&self, name: String) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::SetBootProfile { name, reply: tx });
+        rx
+    }
+
+    pub fn set_fan_min_duty(&self, fan_id: u32, percent: u8) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::SetFanMinDuty { fan_id, percent, reply: tx });
+        rx
+    }
+
+    pub fn start_fan_calibration(&self, fan_id: u32) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::StartFanCalibration { fan_id, reply: tx });
+        rx
+    }
+
+    pub fn get_fan_calibration_status(&self) -> oneshot::Receiver<Result<FanCalibrationStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetFanCalibrationStatus { reply: tx });
+        rx
+    }
+
+    pub fn cancel_apply(&self) -> oneshot::Receiver<Result<bool>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::CancelApply { reply: tx });
+        rx
+    }
+
+    pub fn set_power_source_profiles(&self, settings: PowerSourceProfiles) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::SetPowerSourceProfiles { settings: Box::new(settings), reply: tx });
+        rx
+    }
+
+    pub fn set_app_profiles(&self, settings: AppProfiles) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::SetAppProfiles { settings, reply: tx });
+        rx
+    }
+
+    pub fn get_events(&self, since: u64) -> oneshot::Receiver<Result<Vec<EventLogEntry>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetEvents { since, reply: tx });
+        rx
+    }
+
+    pub fn start_auto_tune(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::StartAutoTune { reply: tx });
+        rx
+    }
+
+    pub fn abort_auto_tune(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::AbortAutoTune { reply: tx });
+        rx
+    }
+
+    pub fn get_auto_tune_status(&self) -> oneshot::Receiver<Result<AutoTuneStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAutoTuneStatus { reply: tx });
+        rx
+    }
+
+    pub fn start_ab_test(&self, a: Profile, b: Profile, session_minutes: u32, rounds: u32) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::StartAbTest { a, b, session_minutes, rounds, reply: tx });
+        rx
+    }
+
+    pub fn abort_ab_test(&self) -> oneshot::Receiver<Result<()>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::AbortAbTest { reply: tx });
+        rx
+    }
+
+    pub fn get_ab_test_status(&self) -> oneshot::Receiver<Result<AbTestStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAbTestStatus { reply: tx });
+        rx
+    }
+
+    pub fn get_all_temperatures(&self) -> oneshot::Receiver<Result<Vec<TemperatureSensor>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAllTemperatures { reply: tx });
+        rx
+    }
+
+    pub fn get_tdp_profiles(&self) -> oneshot::Receiver<Result<Vec<String>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetTdpProfiles { reply: tx });
+        rx
+    }
+
+    pub fn get_network_interfaces(&self) -> oneshot::Receiver<Result<Vec<String>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetNetworkInterfaces { reply: tx });
+        rx
+    }
+
+    pub fn get_keyboard_devices(&self) -> oneshot::Receiver<Result<Vec<KeyboardDevice>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetKeyboardDevices { reply: tx });
+        rx
+    }
+
+    pub fn get_indicator_leds(&self) -> oneshot::Receiver<Result<Vec<String>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetIndicatorLeds { reply: tx });
+        rx
+    }
+
+    pub fn get_aquaris_status(&self) -> oneshot::Receiver<Result<AquarisStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetAquarisStatus { reply: tx });
+        rx
+    }
+
+    pub fn get_lid_status(&self) -> oneshot::Receiver<Result<LidStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetLidStatus { reply: tx });
+        rx
+    }
+
+    pub fn get_fan_mode(&self) -> oneshot::Receiver<Result<FanModeStatus>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetFanMode { reply: tx });
+        rx
+    }
+
+    pub fn get_igpu_frequency_info(&self) -> oneshot::Receiver<Result<IgpuFrequencyInfo>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetIgpuFrequencyInfo { reply: tx });
+        rx
+    }
+
+    pub fn get_fan_curve_capabilities(&self) -> oneshot::Receiver<Result<FanCurveCapabilities>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetFanCurveCapabilities { reply: tx });
+        rx
+    }
+
+    pub fn get_feature_availability(&self) -> oneshot::Receiver<Result<Vec<FeatureStatus>>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(DbusCommand::GetFeatureAvailability { reply: tx });
+        rx
+    }
 }
 
 // Background worker - handles all DBus calls asynchronously
@@ -188,8 +601,16 @@ async fn dbus_worker(mut command_rx: mpsc::UnboundedReceiver<DbusCommand>) -> Re
                 let result = get_wifi_info_impl(&connection).await;
                 let _ = reply.send(result);
             }
+            // Applying can take a while, the other calls (and CancelApply) go on meanwhile
             DbusCommand::ApplyProfile { profile, reply } => {
-                let result = apply_profile_impl(&connection, &profile).await;
+                let connection = connection.clone();
+                tokio::spawn(async move {
+                    let result = apply_profile_impl(&connection, &profile).await;
+                    let _ = reply.send(result);
+                });
+            }
+            DbusCommand::PanicRevert { reply } => {
+                let result = panic_revert_impl(&connection).await;
                 let _ = reply.send(result);
             }
             DbusCommand::SetCpuGovernor { governor, reply } => {
@@ -224,35 +645,227 @@ async fn dbus_worker(mut command_rx: mpsc::UnboundedReceiver<DbusCommand>) -> Re
                 let result = set_battery_settings_impl(&connection, settings).await;
                 let _ = reply.send(result);
             }
-        }
-    }
-    
-    Ok(())
-}
-
-// Implementation functions
-async fn get_system_info_impl(conn: &Connection) -> Result<SystemInfo> {
-    let proxy = zbus::Proxy::new(
-        conn,
-        "com.tuxedo.Control",
-        "/com/tuxedo/Control",
-        "com.tuxedo.Control",
-    ).await?;
-    
-    let json: String = proxy.call("GetSystemInfo", &()).await?;
-    Ok(serde_json::from_str(&json)?)
-}
-
-async fn get_cpu_info_impl(conn: &Connection) -> Result<CpuInfo> {
-    let proxy = zbus::Proxy::new(
-        conn,
-        "com.tuxedo.Control",
-        "/com/tuxedo/Control",
-        "com.tuxedo.Control",
-    ).await?;
-    
-    let json: String = proxy.call("GetCpuInfo", &()).await?;
-    Ok(serde_json::from_str(&json)?)
+            DbusCommand::StartDustCleaning { cycles, reply } => {
+                let result = start_dust_cleaning_impl(&connection, cycles).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::AbortDustCleaning { reply } => {
+                let result = abort_dust_cleaning_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetDustCleaningStatus { reply } => {
+                let result = get_dust_cleaning_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetSafeMode { reply } => {
+                let result = get_safe_mode_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::LeaveSafeMode { reply } => {
+                let result = leave_safe_mode_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetDisplayInfo { reply } => {
+                let result = get_display_info_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetCapabilityMatrix { reply } => {
+                let result = get_capability_matrix_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetProfileUsage { reply } => {
+                let result = get_profile_usage_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::ResetProfileUsage { reply } => {
+                let result = reset_profile_usage_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetChargingOptions { reply } => {
+                let result = get_charging_options_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAcAdapterInfo { reply } => {
+                let result = get_ac_adapter_info_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetTdpLimits { reply } => {
+                let result = get_tdp_limits_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::PreviewProfile { profile, reply } => {
+                let result = preview_profile_impl(&connection, &profile).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetDaemonLog { since, reply } => {
+                let result = get_daemon_log_impl(&connection, since).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetChargeControlInfo { reply } => {
+                let result = get_charge_control_info_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::StartFullCharge { reply } => {
+                let result = start_full_charge_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::CancelFullCharge { reply } => {
+                let result = cancel_full_charge_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetFanFailures { reply } => {
+                let result = get_fan_failures_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAmbientLightReading { reply } => {
+                let result = get_ambient_light_reading_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetWriteAccess { reply } => {
+                let result = get_write_access_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetSystemProfiles { reply } => {
+                let result = get_system_profiles_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::PromoteProfile { profile, replace, reply } => {
+                let result = promote_profile_impl(&connection, &profile, replace).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::RemoveSystemProfile { name, reply } => {
+                let result = remove_system_profile_impl(&connection, &name).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::SetBootProfile { name, reply } => {
+                let result = set_boot_profile_impl(&connection, &name).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::SetFanMinDuty { fan_id, percent, reply } => {
+                let result = set_fan_min_duty_impl(&connection, fan_id, percent).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::StartFanCalibration { fan_id, reply } => {
+                let result = start_fan_calibration_impl(&connection, fan_id).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetFanCalibrationStatus { reply } => {
+                let result = get_fan_calibration_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::CancelApply { reply } => {
+                let result = cancel_apply_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::SetPowerSourceProfiles { settings, reply } => {
+                let result = set_power_source_profiles_impl(&connection, &settings).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::SetAppProfiles { settings, reply } => {
+                let result = set_app_profiles_impl(&connection, &settings).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetEvents { since, reply } => {
+                let result = get_events_impl(&connection, since).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::StartAutoTune { reply } => {
+                let result = start_auto_tune_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::AbortAutoTune { reply } => {
+                let result = abort_auto_tune_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAutoTuneStatus { reply } => {
+                let result = get_auto_tune_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::StartAbTest { a, b, session_minutes, rounds, reply } => {
+                let result = start_ab_test_impl(&connection, &a, &b, session_minutes, rounds).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::AbortAbTest { reply } => {
+                let result = abort_ab_test_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAbTestStatus { reply } => {
+                let result = get_ab_test_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAllTemperatures { reply } => {
+                let result = get_all_temperatures_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetTdpProfiles { reply } => {
+                let result = get_tdp_profiles_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetNetworkInterfaces { reply } => {
+                let result = get_network_interfaces_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetKeyboardDevices { reply } => {
+                let result = get_keyboard_devices_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetIndicatorLeds { reply } => {
+                let result = get_indicator_leds_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetAquarisStatus { reply } => {
+                let result = get_aquaris_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetLidStatus { reply } => {
+                let result = get_lid_status_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetFanMode { reply } => {
+                let result = get_fan_mode_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetIgpuFrequencyInfo { reply } => {
+                let result = get_igpu_frequency_info_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetFanCurveCapabilities { reply } => {
+                let result = get_fan_curve_capabilities_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+            DbusCommand::GetFeatureAvailability { reply } => {
+                let result = get_feature_availability_impl(&connection).await;
+                let _ = reply.send(result);
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+// Implementation functions
+async fn get_system_info_impl(conn: &Connection) -> Result<SystemInfo> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+    
+    let json: String = proxy.call("GetSystemInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_cpu_info_impl(conn: &Connection) -> Result<CpuInfo> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+    
+    let json: String = proxy.call("GetCpuInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
 }
 
 async fn get_gpu_info_impl(conn: &Connection) -> Result<Vec<GpuInfo>> {
@@ -327,7 +940,7 @@ async fn get_wifi_info_impl(conn: &Connection) -> Result<Vec<WiFiInfo>> {
     Ok(serde_json::from_str(&json)?)
 }
 
-async fn apply_profile_impl(conn: &Connection, profile: &Profile) -> Result<()> {
+async fn apply_profile_impl(conn: &Connection, profile: &Profile) -> Result<Option<ApplyReport>> {
     let proxy = zbus::Proxy::new(
         conn,
         "com.tuxedo.Control",
@@ -336,7 +949,31 @@ async fn apply_profile_impl(conn: &Connection, profile: &Profile) -> Result<()>
     ).await?;
     
     let json = serde_json::to_string(profile)?;
-    proxy.call::<_, _, ()>("ApplyProfile", &(json.as_str(),)).await?;
+    let reply = match proxy.call_method("ApplyProfile", &(json.as_str(),)).await {
+        Ok(reply) => reply,
+        Err(zbus::Error::MethodError(name, Some(message), _))
+            if name.as_str() == "com.tuxedo.Control.Error.ApplyFailed" =>
+        {
+            let report: ApplyReport = serde_json::from_str(&message)?;
+            return Err(ApplyFailed(report).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    
+    // Older daemons reply without a report
+    let report: Option<String> = reply.body().deserialize().ok();
+    Ok(report.and_then(|json| serde_json::from_str(&json).ok()).flatten())
+}
+
+async fn panic_revert_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+    
+    proxy.call::<_, _, ()>("PanicRevert", &()).await?;
     Ok(())
 }
 
@@ -439,3 +1076,689 @@ async fn set_battery_settings_impl(conn: &Connection, settings: BatterySettings)
     proxy.call::<_, _, ()>("SetBatterySettings", &(json.as_str(),)).await?;
     Ok(())
 }
+
+async fn start_dust_cleaning_impl(conn: &Connection, cycles: u32) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("StartDustCleaning", &(cycles,)).await?;
+    Ok(())
+}
+
+async fn abort_dust_cleaning_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("AbortDustCleaning", &()).await?;
+    Ok(())
+}
+
+async fn get_dust_cleaning_status_impl(conn: &Connection) -> Result<DustCleaningStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetDustCleaningStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_safe_mode_impl(conn: &Connection) -> Result<SafeModeStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetSafeMode", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn leave_safe_mode_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("LeaveSafeMode", &()).await?;
+    Ok(())
+}
+
+async fn get_display_info_impl(conn: &Connection) -> Result<Vec<DisplayInfo>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetDisplayInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_capability_matrix_impl(conn: &Connection) -> Result<Vec<Capability>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetCapabilityMatrix", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_profile_usage_impl(conn: &Connection) -> Result<ProfileUsage> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetProfileUsage", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn reset_profile_usage_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("ResetProfileUsage", &()).await?;
+    Ok(())
+}
+
+async fn get_charging_options_impl(conn: &Connection) -> Result<ChargingOptions> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetChargingOptions", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_ac_adapter_info_impl(conn: &Connection) -> Result<AcAdapterInfo> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAcAdapterInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_tdp_limits_impl(conn: &Connection) -> Result<TdpLimits> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetTdpLimits", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn preview_profile_impl(conn: &Connection, profile: &Profile) -> Result<ApplyPreview> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let profile_json = serde_json::to_string(profile)?;
+    let json: String = proxy.call("PreviewProfile", &(profile_json.as_str(),)).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_daemon_log_impl(conn: &Connection, since: u64) -> Result<DaemonLog> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetDaemonLog", &(since,)).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_charge_control_info_impl(conn: &Connection) -> Result<ChargeControlInfo> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetChargeControlInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn start_full_charge_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("StartFullCharge", &()).await?;
+    Ok(())
+}
+
+async fn cancel_full_charge_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("CancelFullCharge", &()).await?;
+    Ok(())
+}
+
+async fn get_fan_failures_impl(conn: &Connection) -> Result<Vec<FanFailure>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetFanFailures", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_ambient_light_reading_impl(conn: &Connection) -> Result<AmbientLightReading> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAmbientLightReading", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_write_access_impl(conn: &Connection) -> Result<bool> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    Ok(proxy.call("GetWriteAccess", &()).await?)
+}
+
+async fn get_system_profiles_impl(conn: &Connection) -> Result<SystemProfiles> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetSystemProfiles", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn promote_profile_impl(conn: &Connection, profile: &Profile, replace: bool) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json = serde_json::to_string(profile)?;
+    proxy.call::<_, _, ()>("PromoteProfile", &(json.as_str(), replace)).await?;
+    Ok(())
+}
+
+async fn remove_system_profile_impl(conn: &Connection, name: &str) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("RemoveSystemProfile", &(name,)).await?;
+    Ok(())
+}
+
+async fn set_boot_profile_impl(conn: &Connection, name: &str) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("SetBootProfile", &(name,)).await?;
+    Ok(())
+}
+
+async fn set_fan_min_duty_impl(conn: &Connection, fan_id: u32, percent: u8) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("SetFanMinDuty", &(fan_id, percent)).await?;
+    Ok(())
+}
+
+async fn start_fan_calibration_impl(conn: &Connection, fan_id: u32) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("StartFanCalibration", &(fan_id,)).await?;
+    Ok(())
+}
+
+async fn get_fan_calibration_status_impl(conn: &Connection) -> Result<FanCalibrationStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetFanCalibrationStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn cancel_apply_impl(conn: &Connection) -> Result<bool> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    Ok(proxy.call("CancelApply", &()).await?)
+}
+
+async fn set_power_source_profiles_impl(conn: &Connection, settings: &PowerSourceProfiles) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json = serde_json::to_string(settings)?;
+    proxy.call::<_, _, ()>("SetPowerSourceProfiles", &(json.as_str(),)).await?;
+    Ok(())
+}
+
+async fn set_app_profiles_impl(conn: &Connection, settings: &AppProfiles) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json = serde_json::to_string(settings)?;
+    proxy.call::<_, _, ()>("SetAppProfiles", &(json.as_str(),)).await?;
+    Ok(())
+}
+
+async fn get_events_impl(conn: &Connection, since: u64) -> Result<Vec<EventLogEntry>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetEvents", &(since,)).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn start_auto_tune_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("StartAutoTune", &()).await?;
+    Ok(())
+}
+
+async fn abort_auto_tune_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("AbortAutoTune", &()).await?;
+    Ok(())
+}
+
+async fn get_auto_tune_status_impl(conn: &Connection) -> Result<AutoTuneStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAutoTuneStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn start_ab_test_impl(conn: &Connection, a: &Profile, b: &Profile, session_minutes: u32, rounds: u32) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let a = serde_json::to_string(a)?;
+    let b = serde_json::to_string(b)?;
+    proxy.call::<_, _, ()>("StartAbTest", &(a.as_str(), b.as_str(), session_minutes, rounds)).await?;
+    Ok(())
+}
+
+async fn abort_ab_test_impl(conn: &Connection) -> Result<()> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    proxy.call::<_, _, ()>("AbortAbTest", &()).await?;
+    Ok(())
+}
+
+async fn get_ab_test_status_impl(conn: &Connection) -> Result<AbTestStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAbTestStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_all_temperatures_impl(conn: &Connection) -> Result<Vec<TemperatureSensor>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAllTemperatures", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_tdp_profiles_impl(conn: &Connection) -> Result<Vec<String>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetTdpProfiles", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_network_interfaces_impl(conn: &Connection) -> Result<Vec<String>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetNetworkInterfaces", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn watch_hardware_events(tx: mpsc::UnboundedSender<HardwareEvent>) -> Result<()> {
+    use futures_util::StreamExt;
+
+    let conn = Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let mut signals = proxy.receive_signal("HardwareStateChanged").await?;
+    while let Some(message) = signals.next().await {
+        let json: String = message.body().deserialize()?;
+        match serde_json::from_str(&json) {
+            Ok(event) => {
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+            Err(e) => log::warn!("Unknown hardware event {}: {}", json, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn watch_thermal_headroom(tx: mpsc::UnboundedSender<(Option<f64>, String)>) -> Result<()> {
+    use futures_util::StreamExt;
+
+    let conn = Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let mut changes = proxy.receive_property_changed::<f64>("ThermalHeadroom").await;
+    let mut degrees: f64 = proxy.get_property("ThermalHeadroom").await?;
+    loop {
+        let sensor: String = proxy.get_property("ThermalHeadroomSensor").await.unwrap_or_default();
+        if tx.send((Some(degrees).filter(|d| d.is_finite()), sensor)).is_err() {
+            break;
+        }
+        match changes.next().await {
+            Some(change) => degrees = change.get().await?,
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+async fn watch_telemetry(tx: mpsc::UnboundedSender<Telemetry>) -> Result<()> {
+    use futures_util::StreamExt;
+
+    let conn = Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let mut signals = futures_util::stream::select_all([
+        proxy.receive_signal("FanSpeedChanged").await?,
+        proxy.receive_signal("CpuTempChanged").await?,
+        proxy.receive_signal("ProfileApplied").await?,
+    ]);
+    // Older daemons signal without it, the subscription ends with this connection
+    if let Err(e) = proxy.call::<_, _, ()>("SubscribeTelemetry", &()).await {
+        log::debug!("Telemetry subscription not available: {}", e);
+    }
+    while let Some(message) = signals.next().await {
+        let header = message.header();
+        let telemetry = match header.member().map(|m| m.as_str()) {
+            Some("FanSpeedChanged") => {
+                let json: String = message.body().deserialize()?;
+                match serde_json::from_str(&json) {
+                    Ok(fans) => {
+                        FAN_SIGNALS.store(true, Ordering::Relaxed);
+                        Telemetry::FanSpeed(fans)
+                    }
+                    Err(e) => {
+                        log::warn!("Invalid fan speeds {}: {}", json, e);
+                        continue;
+                    }
+                }
+            }
+            Some("CpuTempChanged") => {
+                let celsius: f64 = message.body().deserialize()?;
+                CPU_TEMP_SIGNALS.store(true, Ordering::Relaxed);
+                Telemetry::CpuTemp(celsius as f32)
+            }
+            Some("ProfileApplied") => Telemetry::ProfileApplied(message.body().deserialize()?),
+            _ => continue,
+        };
+        if tx.send(telemetry).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_keyboard_devices_impl(conn: &Connection) -> Result<Vec<KeyboardDevice>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetKeyboardDevices", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_indicator_leds_impl(conn: &Connection) -> Result<Vec<String>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetIndicatorLeds", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_aquaris_status_impl(conn: &Connection) -> Result<AquarisStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetAquarisStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_lid_status_impl(conn: &Connection) -> Result<LidStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetLidStatus", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_fan_mode_impl(conn: &Connection) -> Result<FanModeStatus> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetFanMode", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_igpu_frequency_info_impl(conn: &Connection) -> Result<IgpuFrequencyInfo> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetIgpuFrequencyInfo", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_fan_curve_capabilities_impl(conn: &Connection) -> Result<FanCurveCapabilities> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetFanCurveCapabilities", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+async fn get_feature_availability_impl(conn: &Connection) -> Result<Vec<FeatureStatus>> {
+    let proxy = zbus::Proxy::new(
+        conn,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    let json: String = proxy.call("GetFeatureAvailability", &()).await?;
+    Ok(serde_json::from_str(&json)?)
+}
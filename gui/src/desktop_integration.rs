@@ -0,0 +1,149 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+// Autostart, notifications and opening files. The "flatpak" feature goes through
+// xdg-desktop-portal since the sandbox can't write ~/.config/autostart or spawn xdg-open.
+
+const APP_ID: &str = "com.tuxedo.ControlCenter";
+
+pub fn set_autostart(enabled: bool) {
+    tokio::spawn(async move {
+        match set_autostart_impl(enabled).await {
+            Ok(()) => log::info!("Autostart {}", if enabled { "enabled" } else { "disabled" }),
+            Err(e) => log::warn!("Failed to change autostart: {}", e),
+        }
+    });
+}
+
+pub fn notify(title: impl Into<String>, body: impl Into<String>) {
+    let (title, body) = (title.into(), body.into());
+    tokio::spawn(async move {
+        if let Err(e) = notify_impl(&title, &body).await {
+            log::warn!("Failed to send notification: {}", e);
+        }
+    });
+}
+
+pub fn open_path(path: PathBuf) {
+    tokio::spawn(async move {
+        if let Err(e) = open_path_impl(&path).await {
+            log::warn!("Failed to open {:?}: {}", path, e);
+        }
+    });
+}
+
+#[cfg(feature = "flatpak")]
+mod portal {
+    use anyhow::Result;
+    use zbus::Connection;
+
+    pub async fn call<B>(interface: &str, method: &str, body: &B) -> Result<()>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        let connection = Connection::session().await?;
+        let proxy = zbus::Proxy::new(
+            &connection,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            interface,
+        ).await?;
+
+        // Portals answer with a request handle, the outcome arrives later and isn't needed here
+        let _: zbus::zvariant::OwnedObjectPath = proxy.call(method, body).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "flatpak")]
+async fn set_autostart_impl(enabled: bool) -> Result<()> {
+    use zbus::zvariant::Value;
+
+    let options = std::collections::HashMap::from([
+        ("reason", Value::from("Start TUXEDO Control Center on login")),
+        ("autostart", Value::from(enabled)),
+        ("commandline", Value::from(vec!["tuxedo-control-center"])),
+    ]);
+    portal::call("org.freedesktop.portal.Background", "RequestBackground", &("", options)).await
+}
+
+#[cfg(not(feature = "flatpak"))]
+async fn set_autostart_impl(enabled: bool) -> Result<()> {
+    let dir = PathBuf::from(std::env::var("HOME")?).join(".config/autostart");
+    let path = dir.join(format!("{}.desktop", APP_ID));
+
+    if enabled {
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&path, concat!(
+            "[Desktop Entry]\n",
+            "Type=Application\n",
+            "Name=TUXEDO Control Center\n",
+            "Exec=tuxedo-control-center\n",
+            "X-GNOME-Autostart-enabled=true\n",
+        ))?;
+    } else if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "flatpak")]
+async fn notify_impl(title: &str, body: &str) -> Result<()> {
+    use zbus::zvariant::Value;
+
+    let notification = std::collections::HashMap::from([
+        ("title", Value::from(title)),
+        ("body", Value::from(body)),
+    ]);
+    let connection = zbus::Connection::session().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.portal.Desktop",
+        "/org/freedesktop/portal/desktop",
+        "org.freedesktop.portal.Notification",
+    ).await?;
+
+    // AddNotification has no return value, unlike the request based portals
+    proxy.call::<_, _, ()>("AddNotification", &(APP_ID, notification)).await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "flatpak"))]
+async fn notify_impl(title: &str, body: &str) -> Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+    ).await?;
+
+    let actions: Vec<&str> = Vec::new();
+    let hints: std::collections::HashMap<&str, zbus::zvariant::Value> = std::collections::HashMap::new();
+    let _: u32 = proxy.call("Notify", &(
+        "TUXEDO Control Center", 0u32, "preferences-system", title, body, actions, hints, -1i32,
+    )).await?;
+    Ok(())
+}
+
+#[cfg(feature = "flatpak")]
+async fn open_path_impl(path: &std::path::Path) -> Result<()> {
+    // OpenURI rejects file:// URIs, files are handed over as a file descriptor instead
+    let file = std::fs::File::open(path)?;
+    let fd = zbus::zvariant::Fd::from(&file);
+    let options: std::collections::HashMap<&str, zbus::zvariant::Value> = std::collections::HashMap::new();
+    portal::call("org.freedesktop.portal.OpenURI", "OpenFile", &("", fd, options)).await
+}
+
+#[cfg(not(feature = "flatpak"))]
+async fn open_path_impl(path: &std::path::Path) -> Result<()> {
+    let path = path.to_path_buf();
+    let status = tokio::task::spawn_blocking(move || {
+        std::process::Command::new("xdg-open").arg(path).status()
+    }).await??;
+    if !status.success() {
+        anyhow::bail!("xdg-open exited with {}", status);
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use egui::{CollapsingHeader, RichText, Ui};
+
+// Turns the raw error strings coming back from the daemon into something the user can act on
+
+#[derive(Debug, Clone)]
+pub struct ErrorHint {
+    pub help: &'static str,
+    pub commands: &'static [&'static str],
+}
+
+const EFI_SECURE_BOOT: &str = "/sys/firmware/efi/efivars/SecureBoot-8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+pub fn hint_for(error: &str) -> Option<ErrorHint> {
+    let error = error.to_lowercase();
+    let contains_any = |needles: &[&str]| needles.iter().any(|n| error.contains(n));
+
+    let hint = if contains_any(&["serviceunknown", "name has no owner", "no system bus"]) {
+        ErrorHint {
+            help: "The tuxedo-daemon system service is not running.",
+            commands: &["sudo systemctl enable --now tuxedo-daemon", "journalctl -u tuxedo-daemon -b"],
+        }
+    } else if contains_any(&["accessdenied", "access denied"]) {
+        ErrorHint {
+            help: "The D-Bus policy does not allow this call. Check that the policy file of the daemon is installed.",
+            commands: &["ls /usr/share/dbus-1/system.d/com.tuxedo.Control.conf", "sudo systemctl reload dbus"],
+        }
+    } else if contains_any(&["tuxedo_io", "no hardware interface"]) && secure_boot_enabled() {
+        ErrorHint {
+            help: "Secure Boot is enabled and the tuxedo-drivers DKMS modules are probably not signed with an enrolled key. \
+                   Enroll the DKMS key and confirm it in the MOK manager on the next boot, or disable Secure Boot.",
+            commands: &["mokutil --sb-state", "sudo mokutil --import /var/lib/dkms/mok.pub", "sudo modprobe tuxedo_io"],
+        }
+    } else if contains_any(&["tuxedo_io", "no hardware interface", "tuxedo-drivers"]) {
+        ErrorHint {
+            help: "The tuxedo_io kernel module is not loaded. Install tuxedo-drivers and load the module.",
+            commands: &["sudo modprobe tuxedo_io", "dkms status", "lsmod | grep tuxedo"],
+        }
+    } else if contains_any(&["acpi_call"]) {
+        ErrorHint {
+            help: "The acpi_call kernel module is not loaded.",
+            commands: &["sudo modprobe acpi_call"],
+        }
+    } else if contains_any(&["nvidia-smi"]) {
+        ErrorHint {
+            help: "nvidia-smi is missing or failed. It ships with the proprietary NVIDIA driver utilities.",
+            commands: &["nvidia-smi", "lsmod | grep nvidia"],
+        }
+    } else if contains_any(&["read-only file system", "read-only filesystem"]) {
+        ErrorHint {
+            help: "/sys is mounted read-only, which usually means the daemon runs inside a container. Run it on the host instead.",
+            commands: &["findmnt /sys"],
+        }
+    } else if contains_any(&["permission denied", "os error 13", "operation not permitted", "os error 1)", "no write permission"]) {
+        ErrorHint {
+            help: "The daemon was not allowed to write to sysfs. It has to run as root, and systemd sandboxing \
+                   (ReadWritePaths) or kernel lockdown can still block individual files.",
+            commands: &["systemctl show tuxedo-daemon -p User -p ReadWritePaths", "cat /sys/kernel/security/lockdown"],
+        }
+    } else {
+        return None;
+    };
+
+    Some(hint)
+}
+
+/// Expandable help text with copyable commands
+pub fn draw_details(ui: &mut Ui, id_salt: &str, hint: &ErrorHint) {
+    CollapsingHeader::new(RichText::new("What can I do?").small())
+        .id_salt(id_salt)
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.label(hint.help);
+            ui.add_space(4.0);
+            for command in hint.commands {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(*command).monospace());
+                    if ui.small_button("📋").on_hover_text("Copy").clicked() {
+                        ui.ctx().copy_text(command.to_string());
+                    }
+                });
+            }
+        });
+}
+
+fn secure_boot_enabled() -> bool {
+    // Hints are looked up every frame, the EFI variable can't change while running
+    static ENABLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        // 4 bytes of attributes followed by the value
+        std::fs::read(EFI_SECURE_BOOT)
+            .map(|data| data.get(4) == Some(&1))
+            .unwrap_or(false)
+    })
+}
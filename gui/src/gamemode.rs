@@ -0,0 +1,61 @@
+use anyhow::Result;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use zbus::{CacheProperties, Connection, ProxyBuilder};
+
+use crate::app::HardwareUpdate;
+
+// Feral GameMode runs gamemoded in the user session and counts the games that asked for it.
+// The count is re-read whenever a game registers or leaves, or gamemoded itself comes or goes.
+const BUS_NAME: &str = "com.feralinteractive.GameMode";
+const OBJECT_PATH: &str = "/com/feralinteractive/GameMode";
+// Without a session bus there is nothing to watch, try again later
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+pub async fn watch(tx: mpsc::UnboundedSender<HardwareUpdate>) {
+    loop {
+        if let Err(e) = watch_session(&tx).await {
+            log::debug!("GameMode watcher stopped ({}), retrying", e);
+        }
+        if tx.is_closed() {
+            return;
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+async fn watch_session(tx: &mpsc::UnboundedSender<HardwareUpdate>) -> Result<()> {
+    use futures_util::StreamExt;
+
+    let connection = Connection::session().await?;
+    // Property changes aren't signalled by every gamemoded version, so nothing is cached
+    let proxy: zbus::Proxy<'_> = ProxyBuilder::new(&connection)
+        .destination(BUS_NAME)?
+        .path(OBJECT_PATH)?
+        .interface(BUS_NAME)?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+    let mut registered = proxy.receive_signal("GameRegistered").await?;
+    let mut unregistered = proxy.receive_signal("GameUnregistered").await?;
+    let mut owner_changes = proxy.receive_owner_changed().await?;
+
+    let mut active = None;
+    loop {
+        // Not running or an old gamemoded without the property counts as no game
+        let count: i32 = proxy.get_property("ClientCount").await.unwrap_or(0);
+        if active != Some(count > 0) {
+            active = Some(count > 0);
+            if tx.send(HardwareUpdate::GameMode(count > 0)).is_err() {
+                return Ok(());
+            }
+        }
+
+        tokio::select! {
+            Some(_) = registered.next() => {}
+            Some(_) = unregistered.next() => {}
+            Some(_) = owner_changes.next() => {}
+            else => return Ok(()),
+        }
+    }
+}
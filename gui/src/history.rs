@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tuxedo_common::types::HistorySettings;
+
+use crate::app::AppState;
+
+// Downsampled buckets are written out every few buckets rather than on every sample
+const SAVE_EVERY: u32 = 5;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistorySample {
+    pub timestamp: i64,  // Unix seconds, start of the bucket
+    pub cpu_temp: f32,
+    pub cpu_load: f32,
+    pub gpu_temp: Option<f32>,
+    pub fan_percent: Option<f32>,
+    pub battery_draw_w: Option<f32>,
+    #[serde(default)]
+    pub battery_percent: Option<f32>,
+}
+
+impl HistorySample {
+    /// Snapshot of the current readings, None until CPU info arrived
+    pub fn from_state(state: &AppState) -> Option<Self> {
+        let cpu = state.cpu_info.as_ref()?;
+
+        let gpu_temp = state.gpu_info.iter()
+            .filter_map(|gpu| gpu.temperature)
+            .reduce(f32::max);
+        let fan_percent = state.fan_info.iter()
+            .filter(|fan| !fan.is_rpm)
+            .map(|fan| fan.rpm_or_percent as f32)
+            .reduce(f32::max);
+        // Only meaningful while discharging
+        let battery_draw_w = state.battery_info.as_ref()
+            .filter(|battery| battery.session.is_some())
+            .map(|battery| battery.power_draw_w.abs() as f32);
+
+        Some(Self {
+            timestamp: chrono::Local::now().timestamp(),
+            cpu_temp: cpu.package_temp,
+            cpu_load: cpu.median_load,
+            gpu_temp,
+            fan_percent,
+            battery_draw_w,
+            battery_percent: state.battery_info.as_ref().map(|battery| battery.charge_percent as f32),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    pub samples: VecDeque<HistorySample>,
+    bucket: Vec<HistorySample>,
+    unsaved: u32,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let samples = history_path()
+            .and_then(|path| Ok(std::fs::read_to_string(path)?))
+            .and_then(|json| Ok(serde_json::from_str(&json)?))
+            .unwrap_or_default();
+
+        Self { samples, ..Default::default() }
+    }
+
+    pub fn record(&mut self, sample: HistorySample, settings: &HistorySettings) {
+        let resolution = settings.resolution_secs.max(1) as i64;
+
+        let bucket_done = self.bucket.first()
+            .map(|first| first.timestamp / resolution != sample.timestamp / resolution)
+            .unwrap_or(false);
+        if bucket_done {
+            let average = average(&self.bucket, resolution);
+            self.bucket.clear();
+            self.samples.push_back(average);
+            self.trim(settings);
+
+            self.unsaved += 1;
+            if self.unsaved >= SAVE_EVERY {
+                if let Err(e) = self.save() {
+                    log::warn!("Failed to save statistics history: {}", e);
+                }
+            }
+        }
+
+        self.bucket.push(sample);
+    }
+
+    /// Drop samples older than the retention period
+    pub fn trim(&mut self, settings: &HistorySettings) {
+        let cutoff = chrono::Local::now().timestamp() - settings.retention_days as i64 * 86_400;
+        while self.samples.front().map_or(false, |s| s.timestamp < cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        let path = history_path()?;
+        if let Some(dir) = std::path::Path::new(&path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(&self.samples)?)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    pub fn purge(&mut self) -> Result<()> {
+        self.samples.clear();
+        self.bucket.clear();
+        self.unsaved = 0;
+
+        match std::fs::remove_file(history_path()?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn average(bucket: &[HistorySample], resolution: i64) -> HistorySample {
+    let n = bucket.len().max(1) as f32;
+    let mean = |values: Vec<f32>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    };
+
+    HistorySample {
+        timestamp: bucket.first().map(|s| s.timestamp / resolution * resolution).unwrap_or(0),
+        cpu_temp: bucket.iter().map(|s| s.cpu_temp).sum::<f32>() / n,
+        cpu_load: bucket.iter().map(|s| s.cpu_load).sum::<f32>() / n,
+        gpu_temp: mean(bucket.iter().filter_map(|s| s.gpu_temp).collect()),
+        fan_percent: mean(bucket.iter().filter_map(|s| s.fan_percent).collect()),
+        battery_draw_w: mean(bucket.iter().filter_map(|s| s.battery_draw_w).collect()),
+        battery_percent: mean(bucket.iter().filter_map(|s| s.battery_percent).collect()),
+    }
+}
+
+fn history_path() -> Result<String> {
+    Ok(std::env::var("HOME")? + "/.local/share/tuxedo-control-center/history.json")
+}
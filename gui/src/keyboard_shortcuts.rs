@@ -1,10 +1,80 @@
-use egui::{Context, Key};
+use std::collections::HashSet;
+use egui::{Align, Context, Key, Rect, Ui, Vec2};
 use crate::app::{AppState, Page};
 
+const PAGE_KEYS: [(Key, Page); 7] = [
+    (Key::Num1, Page::Statistics),
+    (Key::Num2, Page::Profiles),
+    (Key::Num3, Page::Tuning),
+    (Key::Num4, Page::Events),
+    (Key::Num5, Page::Capabilities),
+    (Key::Num6, Page::Settings),
+    (Key::Num7, Page::Usage),
+];
+
 pub struct KeyboardShortcuts {
     show_help: bool,
 }
 
+#[derive(Debug, Clone, Copy)]
+enum Jump {
+    Previous,
+    Next,
+}
+
+/// Section starts of the current page, so PageUp/PageDown and search can jump between them
+#[derive(Default)]
+pub struct SectionNav {
+    // Positions are from the previous frame, the jump target is picked before the page is drawn
+    previous: Vec<f32>,
+    previous_view_top: f32,
+    current: Vec<f32>,
+    view_top: f32,
+    target: Option<usize>,
+    named_target: Option<&'static str>,
+    // Sections left visible by the search box, None shows all
+    filter: Option<HashSet<&'static str>>,
+}
+
+impl SectionNav {
+    fn begin_frame(&mut self, jump: Option<Jump>) {
+        self.previous = std::mem::take(&mut self.current);
+        self.previous_view_top = self.view_top;
+        self.named_target = None;
+        
+        let view_top = self.previous_view_top;
+        self.target = jump.and_then(|jump| match jump {
+            Jump::Next => self.previous.iter().position(|top| *top > view_top + 1.0),
+            Jump::Previous => self.previous.iter().rposition(|top| *top < view_top - 1.0),
+        });
+    }
+    
+    /// Scrolls to the section with this id when the page is drawn in this frame
+    pub fn jump_to(&mut self, id: &'static str) {
+        self.named_target = Some(id);
+    }
+    
+    pub fn set_filter(&mut self, filter: Option<HashSet<&'static str>>) {
+        self.filter = filter;
+    }
+    
+    /// Marks the start of a section at the cursor, call inside the page's scroll area.
+    /// Returns false if the search filter hides the section.
+    pub fn mark(&mut self, ui: &Ui, id: &'static str) -> bool {
+        if self.filter.as_ref().is_some_and(|ids| !ids.contains(id)) {
+            return false;
+        }
+        
+        let start = ui.cursor().min;
+        if self.target == Some(self.current.len()) || self.named_target == Some(id) {
+            ui.scroll_to_rect(Rect::from_min_size(start, Vec2::ZERO), Some(Align::TOP));
+        }
+        self.current.push(start.y);
+        self.view_top = ui.clip_rect().top();
+        true
+    }
+}
+
 impl KeyboardShortcuts {
     pub fn new() -> Self {
         Self { show_help: false }
@@ -12,11 +82,36 @@ impl KeyboardShortcuts {
     
     pub fn handle_shortcuts(&mut self, ctx: &Context, state: &mut AppState) -> bool {
         let mut handled = false;
+        let mut jump = None;
         
         ctx.input(|i| {
-            // Ctrl+S - Save
-            if i.modifiers.command && i.key_pressed(Key::Num1) {
-                state.current_page = Page::Statistics;
+            // Ctrl+1..7 - Switch page
+            for (key, page) in PAGE_KEYS {
+                if i.modifiers.command && i.key_pressed(key) {
+                    state.current_page = page;
+                    handled = true;
+                }
+            }
+            
+            // Ctrl+F - Search
+            if i.modifiers.command && i.key_pressed(Key::F) {
+                state.focus_search = true;
+                handled = true;
+            }
+            
+            // PageUp / PageDown - Previous / next section, Tab moves between controls (egui)
+            if i.key_pressed(Key::PageUp) {
+                jump = Some(Jump::Previous);
+                handled = true;
+            }
+            if i.key_pressed(Key::PageDown) {
+                jump = Some(Jump::Next);
+                handled = true;
+            }
+            
+            // Ctrl+Shift+R - Panic revert
+            if i.modifiers.command && i.modifiers.shift && i.key_pressed(Key::R) {
+                state.panic_revert_requested = true;
                 handled = true;
             }
             
@@ -29,6 +124,8 @@ impl KeyboardShortcuts {
             }
         });
         
+        state.sections.begin_frame(jump);
+        
         // Show help window - OUTSIDE of input closure
         if self.show_help {
             self.draw_help_window(ctx);
@@ -54,8 +151,28 @@ impl KeyboardShortcuts {
                         ui.label("Save configuration");
                         ui.end_row();
                         
-                        ui.label(egui::RichText::new("Ctrl+1").monospace());
-                        ui.label("Statistics page");
+                        ui.label(egui::RichText::new("Ctrl+1 … Ctrl+7").monospace());
+                        ui.label("Statistics, Profiles, Tuning, Events, Capabilities, Settings, Usage");
+                        ui.end_row();
+                        
+                        ui.label(egui::RichText::new("Tab / Shift+Tab").monospace());
+                        ui.label("Next / previous control, Space or Enter activates it");
+                        ui.end_row();
+                        
+                        ui.label(egui::RichText::new("PageUp / PageDown").monospace());
+                        ui.label("Previous / next section of the page");
+                        ui.end_row();
+                        
+                        ui.label(egui::RichText::new("Ctrl+F").monospace());
+                        ui.label("Search settings and statistics, Enter jumps to the best match");
+                        ui.end_row();
+                        
+                        ui.label(egui::RichText::new("Esc").monospace());
+                        ui.label("Leave the focused control");
+                        ui.end_row();
+                        
+                        ui.label(egui::RichText::new("Ctrl+Shift+R").monospace());
+                        ui.label("Panic revert to Standard profile");
                         ui.end_row();
                         
                         ui.label(egui::RichText::new("F1").monospace());
@@ -4,6 +4,17 @@ mod theme;
 mod pages;
 mod keyboard_shortcuts;
 mod widgets;
+mod session_settings;
+mod desktop_integration;
+mod history;
+mod summary;
+mod config_lint;
+mod error_hints;
+mod search;
+mod search_provider;
+mod gamemode;
+mod app_monitor;
+mod system_tray;
 
 use app::TuxedoApp;
 
@@ -15,6 +26,15 @@ fn main() -> Result<(), eframe::Error> {
     let rt = tokio::runtime::Runtime::new().expect("Unable to create a Tokio runtime");
     let _enter = rt.enter();
     
+    // Started by GNOME Shell for overview searches, no window
+    if std::env::args().any(|arg| arg == search_provider::FLAG) {
+        if let Err(e) = rt.block_on(search_provider::run()) {
+            log::error!("Search provider failed: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([733.0, 500.0])
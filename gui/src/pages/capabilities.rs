@@ -0,0 +1,59 @@
+use egui::{Color32, Grid, RichText, ScrollArea, Ui};
+use crate::app::AppState;
+
+pub fn draw(ui: &mut Ui, state: &mut AppState) {
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.heading("🧩 Capabilities");
+        if ui.button("🔄 Refresh").clicked() {
+            state.capabilities_requested = true;
+        }
+    });
+    ui.label(RichText::new("Every control this app knows about, and the interface the daemon uses for it").small().italics());
+    ui.add_space(8.0);
+    
+    if let Some(ref error) = state.capabilities_error {
+        ui.colored_label(Color32::from_rgb(220, 80, 80), format!("Failed to query the daemon: {}", error));
+        return;
+    }
+    
+    if state.capabilities.is_empty() {
+        if state.pending_capabilities.is_none() {
+            state.capabilities_requested = true;
+        }
+        ui.spinner();
+        return;
+    }
+    
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            Grid::new("capabilities_grid")
+                .num_columns(3)
+                .spacing([30.0, 6.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Control").strong());
+                    ui.label(RichText::new("Supported").strong());
+                    ui.label(RichText::new("Interface").strong());
+                    ui.end_row();
+                    
+                    for capability in &state.capabilities {
+                        ui.label(&capability.name);
+                        if capability.supported {
+                            ui.colored_label(Color32::from_rgb(80, 200, 120), "✔ Yes");
+                        } else {
+                            ui.colored_label(Color32::from_rgb(220, 80, 80), "✖ No")
+                                .on_hover_text(capability.reason.as_deref().unwrap_or(""));
+                        }
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(&capability.interface).monospace().small());
+                            if let Some(ref reason) = capability.reason {
+                                ui.label(RichText::new(reason).small().weak());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+        });
+}
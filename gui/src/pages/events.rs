@@ -0,0 +1,217 @@
+use egui::{Ui, ScrollArea, Grid, RichText, Color32, ComboBox};
+use crate::app::AppState;
+use tuxedo_common::types::{EventKind, EventLogEntry, LogEntry, LogLevel};
+
+const KINDS: [EventKind; 5] = [
+    EventKind::ThermalThrottle,
+    EventKind::FanFailsafe,
+    EventKind::ProfileSwitch,
+    EventKind::SensorFailure,
+    EventKind::ChargeThresholdReset,
+];
+
+fn kind_label(kind: EventKind) -> &'static str {
+    match kind {
+        EventKind::ThermalThrottle => "🔥 Throttling",
+        EventKind::FanFailsafe => "🛑 Fan failsafe",
+        EventKind::ProfileSwitch => "📋 Profile switch",
+        EventKind::SensorFailure => "⚠ Sensor failure",
+        EventKind::ChargeThresholdReset => "🔋 Threshold reset",
+    }
+}
+
+fn kind_color(kind: EventKind) -> Color32 {
+    match kind {
+        EventKind::ThermalThrottle => Color32::from_rgb(255, 140, 0),
+        EventKind::FanFailsafe => Color32::from_rgb(255, 100, 100),
+        EventKind::ProfileSwitch => Color32::from_rgb(100, 180, 255),
+        EventKind::SensorFailure => Color32::from_rgb(255, 200, 0),
+        EventKind::ChargeThresholdReset => Color32::from_rgb(180, 140, 255),
+    }
+}
+
+const LEVELS: [LogLevel; 5] = [
+    LogLevel::Error,
+    LogLevel::Warn,
+    LogLevel::Info,
+    LogLevel::Debug,
+    LogLevel::Trace,
+];
+
+fn level_label(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Error => "ERROR",
+        LogLevel::Warn => "WARN",
+        LogLevel::Info => "INFO",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Trace => "TRACE",
+    }
+}
+
+fn level_color(level: LogLevel) -> Color32 {
+    match level {
+        LogLevel::Error => Color32::from_rgb(255, 100, 100),
+        LogLevel::Warn => Color32::from_rgb(255, 200, 0),
+        LogLevel::Info => Color32::from_rgb(100, 180, 255),
+        LogLevel::Debug | LogLevel::Trace => Color32::GRAY,
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+pub fn draw(ui: &mut Ui, state: &mut AppState) {
+    ui.add_space(8.0);
+    ui.heading("📜 Events");
+    ui.add_space(8.0);
+    
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut state.show_daemon_log, false, "Events");
+        ui.selectable_value(&mut state.show_daemon_log, true, "Daemon log");
+    });
+    ui.add_space(6.0);
+    
+    if state.show_daemon_log {
+        draw_daemon_log(ui, state);
+        return;
+    }
+    
+    ui.horizontal(|ui| {
+        ui.label("Type:");
+        ComboBox::from_id_salt("event_kind_filter")
+            .selected_text(state.event_kind_filter.map(kind_label).unwrap_or("All"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut state.event_kind_filter, None, "All");
+                for kind in KINDS {
+                    ui.selectable_value(&mut state.event_kind_filter, Some(kind), kind_label(kind));
+                }
+            });
+        
+        ui.label("Filter:");
+        ui.text_edit_singleline(&mut state.event_filter);
+        
+        if ui.button("💾 Export CSV").clicked() {
+            match export_csv(&filtered(state)) {
+                Ok(path) => state.show_message(format!("Events exported to {}", path), false),
+                Err(e) => state.show_message(format!("Export failed: {}", e), true),
+            }
+        }
+    });
+    ui.add_space(6.0);
+    
+    let events = filtered(state);
+    if events.is_empty() {
+        ui.label(RichText::new("No events recorded").weak());
+        return;
+    }
+    
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            Grid::new("events_grid")
+                .num_columns(3)
+                .spacing([20.0, 6.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    // Newest first
+                    for event in events.iter().rev() {
+                        ui.label(RichText::new(format_time(event.timestamp)).monospace());
+                        ui.label(RichText::new(kind_label(event.kind)).color(kind_color(event.kind)));
+                        ui.label(&event.message);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn draw_daemon_log(ui: &mut Ui, state: &mut AppState) {
+    ui.horizontal(|ui| {
+        ui.label("Level:");
+        ComboBox::from_id_salt("log_level_filter")
+            .selected_text(level_label(state.log_level_filter))
+            .show_ui(ui, |ui| {
+                for level in LEVELS {
+                    ui.selectable_value(&mut state.log_level_filter, level, level_label(level));
+                }
+            });
+        
+        ui.label("Filter:");
+        ui.text_edit_singleline(&mut state.event_filter);
+        
+        ui.checkbox(&mut state.log_follow, "Follow")
+            .on_hover_text("Keep scrolled to the newest entry");
+    });
+    ui.add_space(6.0);
+    
+    let entries = filtered_log(state);
+    if entries.is_empty() {
+        ui.label(RichText::new("No log entries at this level").weak());
+        return;
+    }
+    
+    // Oldest first like journalctl, follow mode sticks to the bottom
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .stick_to_bottom(state.log_follow)
+        .show(ui, |ui| {
+            Grid::new("daemon_log_grid")
+                .num_columns(4)
+                .spacing([12.0, 2.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for entry in &entries {
+                        ui.label(RichText::new(format_log_time(entry.timestamp_ms)).monospace());
+                        ui.label(RichText::new(level_label(entry.level)).monospace().color(level_color(entry.level)));
+                        ui.label(RichText::new(short_target(&entry.target)).weak());
+                        ui.label(&entry.message);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn format_log_time(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
+
+// tuxedo_daemon::fan_backend -> fan_backend
+fn short_target(target: &str) -> &str {
+    target.strip_prefix("tuxedo_daemon::").unwrap_or(target)
+}
+
+fn filtered_log(state: &AppState) -> Vec<&LogEntry> {
+    let filter = state.event_filter.to_lowercase();
+    state.daemon_log.iter()
+        .filter(|e| e.level <= state.log_level_filter)
+        .filter(|e| filter.is_empty() || e.message.to_lowercase().contains(&filter)
+            || e.target.to_lowercase().contains(&filter))
+        .collect()
+}
+
+fn filtered(state: &AppState) -> Vec<EventLogEntry> {
+    let filter = state.event_filter.to_lowercase();
+    state.events.iter()
+        .filter(|e| state.event_kind_filter.map_or(true, |kind| e.kind == kind))
+        .filter(|e| filter.is_empty() || e.message.to_lowercase().contains(&filter))
+        .cloned()
+        .collect()
+}
+
+fn export_csv(events: &[EventLogEntry]) -> anyhow::Result<String> {
+    let home = std::env::var("HOME")?;
+    let path = format!("{}/tuxedo-events-{}.csv", home, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    
+    let mut csv = String::from("time,type,message\n");
+    for event in events {
+        csv.push_str(&format!("{},{:?},\"{}\"\n",
+            format_time(event.timestamp), event.kind, event.message.replace('"', "\"\"")));
+    }
+    
+    std::fs::write(&path, csv)?;
+    Ok(path)
+}
@@ -2,3 +2,7 @@ pub mod statistics;
 pub mod profiles;
 pub mod tuning;
 pub mod settings;
+pub mod events;
+pub mod capabilities;
+pub mod whats_new;
+pub mod usage;
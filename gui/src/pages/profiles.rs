@@ -8,13 +8,19 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
         .show(ui, |ui| {
             ui.add_space(8.0);
             
-            ui.heading(format!("Current Profile: {}", state.config.current_profile));
+            let scope = if state.system_profile(&state.config.current_profile).is_some() { " (system)" } else { "" };
+            ui.heading(format!("Current Profile: {}{}", state.config.current_profile, scope));
             ui.add_space(12.0);
             
             // Profile list with radio buttons
             let mut profile_to_switch = None;
             let mut profile_to_delete = None;
             let mut profile_to_reset = None;
+            let mut profile_to_promote = None;
+            let mut profile_to_export = None;
+            let system_names: Vec<String> = state.system_profiles.iter()
+                .flat_map(|s| s.profiles.iter().map(|p| p.name.clone()))
+                .collect();
             
             for (idx, profile) in state.config.profiles.iter().enumerate() {
                 let is_current = profile.name == state.config.current_profile;
@@ -67,6 +73,13 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                                 }
                             }
                             
+                            if ui.button("⬇ Export")
+                                .on_hover_text("Save as a file for another machine")
+                                .clicked()
+                            {
+                                profile_to_export = Some(idx);
+                            }
+                            
                             // Edit button - switches to tuning page
                             if ui.button("✏️ Edit").clicked() {
                                 if !is_current {
@@ -74,9 +87,22 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                                 }
                                 state.current_page = Page::Tuning;
                             }
+                            
+                            if dbus_client.is_some() && !state.read_only
+                                && ui.button("⬆ Make system profile")
+                                    .on_hover_text("Copy to the daemon, for every user and for applying at boot")
+                                    .clicked()
+                            {
+                                profile_to_promote = Some(idx);
+                            }
                         });
                     });
                     
+                    if system_names.contains(&profile.name) {
+                        ui.label(RichText::new("A system profile of the same name takes precedence when switching to it")
+                            .small().color(egui::Color32::from_rgb(255, 180, 0)));
+                    }
+                    
                     // Profile details summary
                     ui.add_space(6.0);
                     ui.horizontal(|ui| {
@@ -119,12 +145,30 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                 
                 // Apply to hardware
                 if let Some(client) = dbus_client {
-                    let profile_clone = state.config.profiles[idx].clone();
+                    let profile_clone = state.find_profile(&state.config.current_profile)
+                        .cloned()
+                        .unwrap_or_else(|| state.config.profiles[idx].clone());
+                    crate::session_settings::apply_profile(&profile_clone);
                     let _rx = client.apply_profile(profile_clone);
                     state.show_message(format!("Switched to profile '{}'", state.config.profiles[idx].name), false);
                 }
             }
             
+            if let (Some(idx), Some(client)) = (profile_to_promote, dbus_client) {
+                let profile = state.config.profiles[idx].clone();
+                let replace = system_names.contains(&profile.name);
+                state.show_message(format!("Copying '{}' to the system profiles", profile.name), false);
+                state.pending_system_change = Some(client.promote_profile(profile, replace));
+            }
+            
+            if let Some(idx) = profile_to_export {
+                let profile = &state.config.profiles[idx];
+                match export_profile(profile) {
+                    Ok(path) => state.show_message(format!("Profile '{}' exported to {}", profile.name, path), false),
+                    Err(e) => state.show_message(format!("Failed to export profile: {}", e), true),
+                }
+            }
+            
             // Handle profile reset
             if let Some(idx) = profile_to_reset {
                 state.config.profiles[idx] = create_standard_profile();
@@ -134,6 +178,7 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                 if state.config.profiles[idx].name == state.config.current_profile {
                     if let Some(client) = dbus_client {
                         let profile_clone = state.config.profiles[idx].clone();
+                        crate::session_settings::apply_profile(&profile_clone);
                         let _rx = client.apply_profile(profile_clone);
                     }
                 }
@@ -149,6 +194,7 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                     state.config.current_profile = "Standard".to_string();
                     if let Some(standard) = state.config.profiles.iter().find(|p| p.name == "Standard") {
                         if let Some(client) = dbus_client {
+                            crate::session_settings::apply_profile(standard);
                             let _rx = client.apply_profile(standard.clone());
                         }
                     }
@@ -159,6 +205,9 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                 state.show_message(format!("Profile '{}' deleted", name), false);
             }
             
+            draw_system_profiles(ui, state, dbus_client);
+            draw_power_source_profiles(ui, state);
+            
             // Add new profile section
             ui.add_space(16.0);
             ui.separator();
@@ -192,7 +241,271 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                     }
                 }
             });
+            
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Import Profile:").strong());
+                ui.add(egui::TextEdit::singleline(&mut state.import_profile_path)
+                    .hint_text(format!("~/name.{}", tuxedo_common::profile_file::EXTENSION)));
+                
+                if ui.button("📂 Import").clicked() && !state.import_profile_path.trim().is_empty() {
+                    match import_profile(state) {
+                        Ok(name) => {
+                            state.import_profile_path.clear();
+                            state.show_message(format!("Profile '{}' imported", name), false);
+                        }
+                        Err(e) => state.show_message(format!("Failed to import profile: {}", e), true),
+                    }
+                }
+            });
+            
+            ui.add_space(16.0);
+            ui.separator();
+            ui.add_space(8.0);
+            
+            draw_auto_tune(ui, state, dbus_client);
+            
+            ui.add_space(16.0);
+            ui.separator();
+            ui.add_space(8.0);
+            
+            draw_ab_test(ui, state, dbus_client);
+        });
+}
+
+fn draw_auto_tune(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
+    ui.heading("🧪 Noise vs Performance Auto-Tune");
+    ui.add_space(8.0);
+    
+    ui.label(RichText::new("Loads all CPU cores for a few minutes at each TDP profile and several fixed fan speeds, \
+        then proposes profiles tuned to this machine. Close other applications while it runs.")
+        .small()
+        .italics());
+    ui.add_space(6.0);
+    
+    let status = state.auto_tune_status.clone().unwrap_or_default();
+    
+    if status.running {
+        ui.add(
+            egui::ProgressBar::new(status.progress_percent as f32 / 100.0)
+                .text(&status.message)
+        );
+        ui.add_space(6.0);
+        
+        if ui.button("⏹ Abort").clicked() {
+            if let Some(client) = dbus_client {
+                let _rx = client.abort_auto_tune();
+                state.show_message("Aborting auto-tune", false);
+            }
+        }
+    } else {
+        if ui.button("▶ Start Auto-Tune").clicked() {
+            if let Some(client) = dbus_client {
+                let _rx = client.start_auto_tune();
+                state.show_message("Auto-tune started", false);
+            }
+        }
+        
+        if !status.message.is_empty() {
+            ui.label(RichText::new(format!("Last run: {}", status.message)).small());
+        }
+    }
+    
+    if !status.samples.is_empty() {
+        ui.add_space(6.0);
+        egui::Grid::new("auto_tune_samples").striped(true).show(ui, |ui| {
+            ui.label(RichText::new("TDP").strong());
+            ui.label(RichText::new("Fans").strong());
+            ui.label(RichText::new("Temperature").strong());
+            ui.label(RichText::new("Clock").strong());
+            ui.end_row();
+            
+            for sample in &status.samples {
+                ui.label(sample.tdp_profile.as_deref().unwrap_or("-"));
+                ui.label(format!("{}%", sample.fan_duty));
+                if sample.too_hot {
+                    ui.label(RichText::new(format!("{:.0}°C (too hot)", sample.avg_temp))
+                        .color(egui::Color32::from_rgb(255, 100, 100)));
+                } else {
+                    ui.label(format!("{:.0}°C", sample.avg_temp));
+                }
+                ui.label(format!("{} MHz", sample.avg_frequency));
+                ui.end_row();
+            }
+        });
+    }
+    
+    for proposal in &status.proposals {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&proposal.name).strong());
+            ui.label(RichText::new(format!("TDP: {}", proposal.cpu_settings.tdp_profile.as_deref().unwrap_or("unchanged"))).small());
+            
+            if state.config.profiles.iter().any(|p| p.name == proposal.name) {
+                ui.label(RichText::new("added").small().weak());
+            } else if ui.button("➕ Add profile").clicked() {
+                state.config.profiles.push(proposal.clone());
+                let _ = state.save_config();
+                state.show_message(format!("Profile '{}' created", proposal.name), false);
+            }
+        });
+    }
+}
+
+fn draw_ab_test(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
+    ui.heading("⚖ A/B Profile Test");
+    ui.add_space(8.0);
+    
+    ui.label(RichText::new("Applies the two profiles in turn while you keep doing your usual work, \
+        then compares temperature, fan speed and battery drain. Keep the workload the same throughout.")
+        .small()
+        .italics());
+    ui.add_space(6.0);
+    
+    let status = state.ab_test_status.clone().unwrap_or_default();
+    
+    if status.running {
+        ui.add(
+            egui::ProgressBar::new(status.progress_percent as f32 / 100.0)
+                .text(&status.message)
+        );
+        ui.add_space(6.0);
+        
+        if ui.button("⏹ Abort").clicked() {
+            if let Some(client) = dbus_client {
+                let _rx = client.abort_ab_test();
+                state.show_message("Aborting A/B test", false);
+            }
+        }
+    } else {
+        let names = state.profile_names();
+        ui.horizontal(|ui| {
+            ui.label("A:");
+            egui::ComboBox::from_id_salt("ab_test_a")
+                .selected_text(state.ab_test_a.as_str())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut state.ab_test_a, name.clone(), name.as_str());
+                    }
+                });
+            ui.label("B:");
+            egui::ComboBox::from_id_salt("ab_test_b")
+                .selected_text(state.ab_test_b.as_str())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        ui.selectable_value(&mut state.ab_test_b, name.clone(), name.as_str());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Sessions of");
+            ui.add(egui::DragValue::new(&mut state.ab_test_minutes).range(1..=120).suffix(" min"));
+            ui.label("per profile, repeated");
+            ui.add(egui::DragValue::new(&mut state.ab_test_rounds).range(1..=10).suffix("×"));
+        });
+        ui.label(RichText::new(format!("Takes {} minutes", state.ab_test_minutes * state.ab_test_rounds * 2)).small().weak());
+        ui.add_space(4.0);
+        
+        let profiles = state.find_profile(&state.ab_test_a).cloned()
+            .zip(state.find_profile(&state.ab_test_b).cloned())
+            .filter(|(a, b)| a.name != b.name);
+        ui.add_enabled_ui(profiles.is_some() && !state.read_only, |ui| {
+            if ui.button("▶ Start A/B Test").clicked() {
+                if let (Some(client), Some((a, b))) = (dbus_client, profiles) {
+                    let _rx = client.start_ab_test(a, b, state.ab_test_minutes, state.ab_test_rounds);
+                    state.show_message("A/B test started", false);
+                }
+            }
+        });
+        
+        if !status.message.is_empty() {
+            ui.label(RichText::new(format!("Last run: {}", status.message)).small());
+        }
+    }
+    
+    if !status.sessions.is_empty() {
+        ui.add_space(6.0);
+        egui::Grid::new("ab_test_sessions").striped(true).show(ui, |ui| {
+            ui.label(RichText::new("Profile").strong());
+            ui.label(RichText::new("Temperature").strong());
+            ui.label(RichText::new("Fans").strong());
+            ui.label(RichText::new("Drain").strong());
+            ui.label(RichText::new("CPU load").strong());
+            ui.end_row();
+            
+            for session in &status.sessions {
+                ui.label(&session.profile);
+                ui.label(format!("{:.1}°C", session.avg_temp));
+                ui.label(session.avg_fan_duty.map(|d| format!("{:.0}%", d)).unwrap_or_else(|| "-".to_string()));
+                ui.label(session.avg_drain_w.map(|w| format!("{:.1} W", w)).unwrap_or_else(|| "-".to_string()));
+                ui.label(format!("{:.0}%", session.avg_load));
+                ui.end_row();
+            }
         });
+    }
+    
+    if let [a, b] = status.summary.as_slice() {
+        ui.add_space(6.0);
+        ui.label(RichText::new(format!("'{}' compared to '{}':", b.profile, a.profile)).strong());
+        ui.label(format!("Temperature: {:+.1}°C", b.avg_temp - a.avg_temp));
+        if let (Some(a), Some(b)) = (a.avg_fan_duty, b.avg_fan_duty) {
+            ui.label(format!("Fan speed: {:+.0} points", b - a));
+        }
+        if let (Some(a), Some(b)) = (a.avg_drain_w, b.avg_drain_w) {
+            ui.label(format!("Battery drain: {:+.1} W", b - a));
+        }
+    }
+}
+
+/// Write the profile to the home directory, returns the path. Existing files are kept, the
+/// new one gets a number, different names can sanitize to the same file name.
+fn export_profile(profile: &tuxedo_common::types::Profile) -> anyhow::Result<String> {
+    use std::io::Write;
+    
+    let home = std::env::var("HOME")?;
+    let file_name: String = profile.name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let extension = tuxedo_common::profile_file::EXTENSION;
+    
+    for n in 1..100 {
+        let path = match n {
+            1 => format!("{}/{}.{}", home, file_name, extension),
+            n => format!("{}/{} ({}).{}", home, file_name, n, extension),
+        };
+        // create_new fails on an existing file, no check-then-write race
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(tuxedo_common::profile_file::export(profile).as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(anyhow::anyhow!("Too many exports of '{}' in {}", profile.name, home))
+}
+
+// Added next to the existing profiles, a name that is taken gets a number
+fn import_profile(state: &mut AppState) -> anyhow::Result<String> {
+    let path = state.import_profile_path.trim();
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", std::env::var("HOME")?, rest),
+        None => path.to_string(),
+    };
+    let mut profile = tuxedo_common::profile_file::import(&std::fs::read_to_string(&path)?)?;
+    
+    let names = state.profile_names();
+    if names.contains(&profile.name) {
+        let base = profile.name.clone();
+        profile.name = (2..)
+            .map(|n| format!("{} ({})", base, n))
+            .find(|name| !names.contains(name))
+            .unwrap();
+    }
+    let name = profile.name.clone();
+    state.config.profiles.push(profile);
+    state.save_config()?;
+    Ok(name)
 }
 
 fn create_standard_profile() -> tuxedo_common::types::Profile {
@@ -206,14 +519,16 @@ fn create_standard_profile() -> tuxedo_common::types::Profile {
             min_frequency: None,
             max_frequency: None,
             boost: Some(true),
+            boost_groups: Default::default(),
             smt: Some(true),
             performance_profile: None,
             tdp_profile: None,
             energy_performance_preference: Some("balance_performance".to_string()),
             tdp: None,
             amd_pstate_status: Some("active".to_string()),
+            on_battery: None,
         },
-        gpu_settings: GpuSettings { dgpu_tdp: None },
+        gpu_settings: GpuSettings::default(),
         keyboard_settings: KeyboardSettings {
             control_enabled: false,
             mode: KeyboardMode::SingleColor {
@@ -222,14 +537,132 @@ fn create_standard_profile() -> tuxedo_common::types::Profile {
                 b: 255,
                 brightness: 50,
             },
+            devices: Vec::new(),
+            ambient: Default::default(),
         },
         screen_settings: ScreenSettings {
             brightness: 50,
             system_control: true,
+            blank_timeout: None,
+            ambient: Default::default(),
+            color_temperature: None,
+            refresh_rate: None,
         },
-        fan_settings: FanSettings {
-            control_enabled: false,
-            curves: vec![],
-        },
+        fan_settings: FanSettings::default(),
+        clamshell_override: ClamshellOverride::default(),
+        input_settings: InputSettings::default(),
+        network_settings: NetworkSettings::default(),
+        vm_settings: VmSettings::default(),
+        lightbar_settings: LightBarSettings::default(),
+        indicator_settings: IndicatorSettings::default(),
+        aquaris_settings: None,
+    }
+}
+
+// Owned by the daemon: shared by all users, one of them can be applied at boot
+fn draw_power_source_profiles(ui: &mut Ui, state: &mut AppState) {
+    ui.add_space(16.0);
+    ui.separator();
+    ui.add_space(8.0);
+    ui.label(RichText::new("AC and Battery Profiles").strong().heading());
+    ui.label(RichText::new("The daemon switches when the charger is plugged in or out, also while this app is closed. \
+        A profile picked by hand stays until the next change.").small().italics());
+    ui.add_space(8.0);
+    
+    let names = state.profile_names();
+    let mut changed = false;
+    ui.add_enabled_ui(!state.read_only, |ui| {
+        changed |= ui.checkbox(&mut state.config.auto_switch.power_source_enabled, "Switch profile with the power source").changed();
+        ui.add_enabled_ui(state.config.auto_switch.power_source_enabled, |ui| {
+            egui::Grid::new("power_source_profiles_grid")
+                .num_columns(2)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    let auto_switch = &mut state.config.auto_switch;
+                    for (label, id, selected) in [
+                        ("On AC:", "ac_profile_combo", &mut auto_switch.ac_profile),
+                        ("On battery:", "battery_profile_combo", &mut auto_switch.battery_profile),
+                    ] {
+                        ui.label(label);
+                        egui::ComboBox::from_id_salt(id)
+                            .selected_text(if selected.is_empty() { "Keep current" } else { selected.as_str() })
+                            .show_ui(ui, |ui| {
+                                changed |= ui.selectable_value(selected, String::new(), "Keep current").changed();
+                                for name in &names {
+                                    changed |= ui.selectable_value(selected, name.clone(), name).changed();
+                                }
+                            });
+                        ui.end_row();
+                    }
+                });
+        });
+    });
+    
+    if changed {
+        let _ = state.save_config();
+    }
+}
+
+fn draw_system_profiles(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
+    let system = match state.system_profiles {
+        Some(ref system) if !system.profiles.is_empty() => system.clone(),
+        _ => return,
+    };
+    
+    ui.add_space(8.0);
+    ui.label(RichText::new("System Profiles").strong().heading());
+    ui.label(RichText::new("Managed by the administrator, changes need the right to change system settings").small().italics());
+    ui.add_space(8.0);
+    
+    let mut switch_to = None;
+    let mut boot_profile = None;
+    let mut remove = None;
+    for profile in &system.profiles {
+        let is_current = profile.name == state.config.current_profile;
+        let is_boot = system.boot_profile.as_deref() == Some(profile.name.as_str());
+        Frame::none()
+            .fill(ui.style().visuals.faint_bg_color)
+            .rounding(6.0)
+            .inner_margin(12.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.radio(is_current, "").clicked() && !is_current {
+                        switch_to = Some(profile.clone());
+                    }
+                    if ui.selectable_label(is_current, format!("🔒 {}", profile.name)).clicked() && !is_current {
+                        switch_to = Some(profile.clone());
+                    }
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_enabled_ui(!state.read_only, |ui| {
+                            if ui.button("🗑️ Remove").clicked() {
+                                remove = Some(profile.name.clone());
+                            }
+                            let mut at_boot = is_boot;
+                            if ui.checkbox(&mut at_boot, "Apply at boot").changed() {
+                                boot_profile = Some(if at_boot { profile.name.clone() } else { String::new() });
+                            }
+                        });
+                    });
+                });
+            });
+        ui.add_space(8.0);
+    }
+    
+    let Some(client) = dbus_client else {
+        return;
+    };
+    if let Some(profile) = switch_to {
+        state.config.current_profile = profile.name.clone();
+        let _ = state.save_config();
+        crate::session_settings::apply_profile(&profile);
+        let _rx = client.apply_profile(profile.clone());
+        state.show_message(format!("Switched to system profile '{}'", profile.name), false);
+    }
+    if let Some(name) = boot_profile {
+        state.pending_system_change = Some(client.set_boot_profile(name));
+    }
+    if let Some(name) = remove {
+        state.pending_system_change = Some(client.remove_system_profile(name));
     }
 }
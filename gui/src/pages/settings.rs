@@ -1,5 +1,6 @@
 use egui::{Ui, ScrollArea, RichText, Slider, ComboBox, Context};
 use crate::app::AppState;
+use tuxedo_common::types::{AppProfileRule, GameModePriority, SteamAppRule};
 use crate::theme::TuxedoTheme;
 
 pub fn draw(ui: &mut Ui, state: &mut AppState, theme: &mut TuxedoTheme, ctx: &Context) {
@@ -11,212 +12,340 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, theme: &mut TuxedoTheme, ctx: &Co
             ui.add_space(16.0);
             
             // Appearance
-            ui.label(RichText::new("Appearance").strong().heading());
-            ui.add_space(8.0);
+            if state.sections.mark(ui, "settings.appearance") {
+                ui.label(RichText::new("Appearance").strong().heading());
+                ui.add_space(8.0);
             
-            ui.horizontal(|ui| {
-                ui.label("Theme:");
+                ui.horizontal(|ui| {
+                    ui.label("Theme:");
                 
-                use tuxedo_common::types::Theme;
-                let mut theme_changed = false;
-                let mut new_theme = state.config.theme.clone();
+                    use tuxedo_common::types::Theme;
+                    let mut theme_changed = false;
+                    let mut new_theme = state.config.theme.clone();
                 
-                if ui.selectable_value(&mut new_theme, Theme::Auto, "Auto").clicked() {
-                    theme_changed = true;
-                }
-                if ui.selectable_value(&mut new_theme, Theme::Light, "Light").clicked() {
-                    theme_changed = true;
-                }
-                if ui.selectable_value(&mut new_theme, Theme::Dark, "Dark").clicked() {
-                    theme_changed = true;
-                }
+                    if ui.selectable_value(&mut new_theme, Theme::Auto, "Auto").clicked() {
+                        theme_changed = true;
+                    }
+                    if ui.selectable_value(&mut new_theme, Theme::Light, "Light").clicked() {
+                        theme_changed = true;
+                    }
+                    if ui.selectable_value(&mut new_theme, Theme::Dark, "Dark").clicked() {
+                        theme_changed = true;
+                    }
                 
-                if theme_changed {
-                    state.config.theme = new_theme.clone();
-                    let _ = state.save_config();
+                    if theme_changed {
+                        state.config.theme = new_theme.clone();
+                        let _ = state.save_config();
                     
-                    // Apply theme immediately
-                    *theme = TuxedoTheme::new(&new_theme);
-                    theme.apply(ctx);
-                }
-            });
+                        // Apply theme immediately
+                        *theme = TuxedoTheme::new(&new_theme);
+                        theme.apply(ctx);
+                    }
+                });
             
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
             
             // Font Size
-            ui.label(RichText::new("Font Size").strong().heading());
-            ui.add_space(8.0);
+            if state.sections.mark(ui, "settings.font") {
+                ui.label(RichText::new("Font Size").strong().heading());
+                ui.add_space(8.0);
             
-            ui.horizontal(|ui| {
-                ui.label("UI Font Size:");
+                ui.horizontal(|ui| {
+                    ui.label("UI Font Size:");
                 
-                use tuxedo_common::types::FontSize;
-                let mut font_changed = false;
-                let mut new_font = state.config.font_size.clone();
+                    use tuxedo_common::types::FontSize;
+                    let mut font_changed = false;
+                    let mut new_font = state.config.font_size.clone();
                 
-                if ui.selectable_value(&mut new_font, FontSize::Small, "Small").clicked() {
-                    font_changed = true;
-                }
-                if ui.selectable_value(&mut new_font, FontSize::Medium, "Medium").clicked() {
-                    font_changed = true;
-                }
-                if ui.selectable_value(&mut new_font, FontSize::Large, "Large").clicked() {
-                    font_changed = true;
-                }
+                    if ui.selectable_value(&mut new_font, FontSize::Small, "Small").clicked() {
+                        font_changed = true;
+                    }
+                    if ui.selectable_value(&mut new_font, FontSize::Medium, "Medium").clicked() {
+                        font_changed = true;
+                    }
+                    if ui.selectable_value(&mut new_font, FontSize::Large, "Large").clicked() {
+                        font_changed = true;
+                    }
                 
-                if font_changed {
-                    state.config.font_size = new_font.clone();
-                    let _ = state.save_config();
+                    if font_changed {
+                        state.config.font_size = new_font.clone();
+                        let _ = state.save_config();
                     
-                    // Apply font size immediately
-                    apply_font_size(ctx, &new_font);
-                }
-            });
+                        // Apply font size immediately
+                        apply_font_size(ctx, &new_font);
+                    }
+                });
             
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
             
             // Startup
-            ui.label(RichText::new("Startup").strong().heading());
-            ui.add_space(8.0);
-            
-            if ui.checkbox(&mut state.config.start_minimized, "Start minimized").changed() {
-                let _ = state.save_config();
-            }
+            if state.sections.mark(ui, "settings.startup") {
+                ui.label(RichText::new("Startup").strong().heading());
+                ui.add_space(8.0);
             
-            if ui.checkbox(&mut state.config.autostart, "Enable autostart").changed() {
-                let _ = state.save_config();
-                // TODO: Create/remove autostart file
-            }
+                if ui.checkbox(&mut state.config.start_minimized, "Start minimized").changed() {
+                    let _ = state.save_config();
+                }
             
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+                if ui.checkbox(&mut state.config.autostart, "Enable autostart").changed() {
+                    let _ = state.save_config();
+                    crate::desktop_integration::set_autostart(state.config.autostart);
+                }
             
-            // Daemon Controls
-            ui.label(RichText::new("Daemon Controls").strong().heading());
-            ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(state.config_dirty_since.is_some(), egui::Button::new("💾 Save now"))
+                        .on_hover_text("Changes are saved a few seconds after the last one, or when the window loses focus")
+                        .clicked()
+                    {
+                        state.save_config_now();
+                    }
+                    if state.config_dirty_since.is_some() {
+                        ui.label(RichText::new("Unsaved changes").small().weak());
+                    }
+                });
+                
+                if ui.button("📂 Open configuration file").clicked() {
+                    // Show what is set now, not what was saved last
+                    if let Err(e) = state.flush_config() {
+                        state.show_message(format!("Failed to save configuration: {}", e), true);
+                    }
+                    match std::env::var("HOME") {
+                        Ok(home) => crate::desktop_integration::open_path(
+                            std::path::PathBuf::from(home).join(".config/tuxedo-control-center/config.json")),
+                        Err(_) => state.show_message("HOME is not set", true),
+                    }
+                }
             
-            if ui.checkbox(&mut state.config.fan_daemon_enabled, "Fan daemon").changed() {
-                let _ = state.save_config();
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
             }
-            ui.label(RichText::new("Monitor temperatures and apply fan curves").small().italics());
-            ui.add_space(6.0);
             
-            if ui.checkbox(&mut state.config.app_monitoring_enabled, "App monitoring").changed() {
-                let _ = state.save_config();
-            }
-            ui.label(RichText::new("Monitor running applications for automatic profile switching").small().italics());
+            // Daemon Controls
+            if state.sections.mark(ui, "settings.daemon") {
+                ui.label(RichText::new("Daemon Controls").strong().heading());
+                ui.add_space(8.0);
             
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+                if ui.checkbox(&mut state.config.fan_daemon_enabled, "Fan daemon").changed() {
+                    let _ = state.save_config();
+                }
+                ui.label(RichText::new("Monitor temperatures and apply fan curves").small().italics());
+                ui.add_space(6.0);
             
-            // Statistics Page Layout
-            ui.label(RichText::new("Statistics Page Layout").strong().heading());
-            ui.add_space(8.0);
+                if ui.checkbox(&mut state.config.app_monitoring_enabled, "App monitoring").changed() {
+                    crate::app_monitor::set_enabled(state.config.app_monitoring_enabled);
+                    let _ = state.save_config();
+                }
+                ui.label(RichText::new("Monitor running applications for automatic profile switching").small().italics());
             
-            if ui.checkbox(&mut state.config.statistics_sections.show_system_info, "Show system info").changed() {
-                let _ = state.save_config();
-            }
-            if ui.checkbox(&mut state.config.statistics_sections.show_cpu, "Show CPU").changed() {
-                let _ = state.save_config();
-            }
-            if ui.checkbox(&mut state.config.statistics_sections.show_gpu, "Show GPU").changed() {
-                let _ = state.save_config();
-            }
-            if ui.checkbox(&mut state.config.statistics_sections.show_battery, "Show battery").changed() {
-                let _ = state.save_config();
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
             }
-            if ui.checkbox(&mut state.config.statistics_sections.show_wifi, "Show WiFi").changed() {
-                let _ = state.save_config();
-            }
-            if ui.checkbox(&mut state.config.statistics_sections.show_storage, "Show storage").changed() {
-                let _ = state.save_config();
-            }
-            if ui.checkbox(&mut state.config.statistics_sections.show_fans, "Show fans").changed() {
-                let _ = state.save_config();
-            }
-            
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
             
-            // Battery Charge Control
-            draw_battery_settings(ui, state);
+            // Automatic switching
+            if state.sections.mark(ui, "settings.autoswitch") {
+                ui.label(RichText::new("Automatic Switching").strong().heading());
+                ui.add_space(8.0);
+                draw_gamemode(ui, state);
+                ui.add_space(12.0);
+                draw_steam_rules(ui, state);
+                ui.add_space(12.0);
+                draw_app_rules(ui, state);
             
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
             
-            // Polling Rates
-            ui.label(RichText::new("Polling Rates").strong().heading());
-            ui.add_space(8.0);
-            ui.label(RichText::new("How often to update each section (in seconds)").small().italics());
-            ui.add_space(6.0);
+            // Statistics Page Layout
+            if state.sections.mark(ui, "settings.layout") {
+                ui.label(RichText::new("Statistics Page Layout").strong().heading());
+                ui.add_space(8.0);
             
-            let mut cpu_poll = (state.config.statistics_sections.cpu_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("CPU:");
-                if ui.add(Slider::new(&mut cpu_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.cpu_poll_rate = (cpu_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_system_info, "Show system info").changed() {
                     let _ = state.save_config();
                 }
-            });
-            
-            let mut gpu_poll = (state.config.statistics_sections.gpu_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("GPU:");
-                if ui.add(Slider::new(&mut gpu_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.gpu_poll_rate = (gpu_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_display, "Show display").changed() {
                     let _ = state.save_config();
                 }
-            });
-            
-            let mut battery_poll = (state.config.statistics_sections.battery_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("Battery:");
-                if ui.add(Slider::new(&mut battery_poll, 0.5..=30.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.battery_poll_rate = (battery_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_cpu, "Show CPU").changed() {
                     let _ = state.save_config();
                 }
-            });
-            
-            let mut wifi_poll = (state.config.statistics_sections.wifi_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("WiFi:");
-                if ui.add(Slider::new(&mut wifi_poll, 0.5..=30.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.wifi_poll_rate = (wifi_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_gpu, "Show GPU").changed() {
                     let _ = state.save_config();
                 }
-            });
-            
-            let mut storage_poll = (state.config.statistics_sections.storage_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("Storage:");
-                if ui.add(Slider::new(&mut storage_poll, 5.0..=60.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.storage_poll_rate = (storage_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_battery, "Show battery").changed() {
                     let _ = state.save_config();
                 }
-            });
-            
-            let mut fans_poll = (state.config.statistics_sections.fans_poll_rate as f32) / 1000.0;
-            ui.horizontal(|ui| {
-                ui.label("Fans:");
-                if ui.add(Slider::new(&mut fans_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
-                    state.config.statistics_sections.fans_poll_rate = (fans_poll * 1000.0) as u64;
+                if ui.checkbox(&mut state.config.statistics_sections.show_wifi, "Show WiFi").changed() {
                     let _ = state.save_config();
                 }
-            });
+                if ui.checkbox(&mut state.config.statistics_sections.show_storage, "Show storage").changed() {
+                    let _ = state.save_config();
+                }
+                if ui.checkbox(&mut state.config.statistics_sections.show_fans, "Show fans").changed() {
+                    let _ = state.save_config();
+                }
+                if ui.checkbox(&mut state.config.statistics_sections.show_temperatures, "Show all temperatures").changed() {
+                    let _ = state.save_config();
+                }
+                if ui.checkbox(&mut state.config.statistics_sections.show_history, "Show history").changed() {
+                    let _ = state.save_config();
+                }
+            
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Statistics history
+            if state.sections.mark(ui, "settings.history") {
+                ui.label(RichText::new("History").strong().heading());
+                ui.add_space(8.0);
+                ui.label(RichText::new("Downsampled statistics kept across restarts").small().italics());
+                ui.add_space(6.0);
+            
+                ui.horizontal(|ui| {
+                    ui.label("Keep:");
+                    if ui.add(Slider::new(&mut state.config.history_settings.retention_days, 1..=30).suffix(" days")).changed() {
+                        state.history.trim(&state.config.history_settings);
+                        let _ = state.save_config();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Resolution:");
+                    if ui.add(Slider::new(&mut state.config.history_settings.resolution_secs, 10..=600).step_by(10.0).suffix(" s")).changed() {
+                        let _ = state.save_config();
+                    }
+                });
+            
+                if ui.button("🗑 Purge history").clicked() {
+                    match state.history.purge() {
+                        Ok(()) => state.show_message("History purged", false),
+                        Err(e) => state.show_message(format!("Failed to purge history: {}", e), true),
+                    }
+                }
+            
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Battery Charge Control
+            if state.sections.mark(ui, "settings.battery") {
+                draw_battery_settings(ui, state);
+            
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Polling Rates
+            if state.sections.mark(ui, "settings.polling") {
+                ui.label(RichText::new("Polling Rates").strong().heading());
+                ui.add_space(8.0);
+                ui.label(RichText::new("How often to update each section (in seconds)").small().italics());
+                ui.add_space(6.0);
+            
+                let mut cpu_poll = (state.config.statistics_sections.cpu_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("CPU:");
+                    if ui.add(Slider::new(&mut cpu_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.cpu_poll_rate = (cpu_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                let mut gpu_poll = (state.config.statistics_sections.gpu_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("GPU:");
+                    if ui.add(Slider::new(&mut gpu_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.gpu_poll_rate = (gpu_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                let mut battery_poll = (state.config.statistics_sections.battery_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("Battery:");
+                    if ui.add(Slider::new(&mut battery_poll, 0.5..=30.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.battery_poll_rate = (battery_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                let mut wifi_poll = (state.config.statistics_sections.wifi_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("WiFi:");
+                    if ui.add(Slider::new(&mut wifi_poll, 0.5..=30.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.wifi_poll_rate = (wifi_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                let mut storage_poll = (state.config.statistics_sections.storage_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("Storage:");
+                    if ui.add(Slider::new(&mut storage_poll, 5.0..=60.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.storage_poll_rate = (storage_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                let mut fans_poll = (state.config.statistics_sections.fans_poll_rate as f32) / 1000.0;
+                ui.horizontal(|ui| {
+                    ui.label("Fans:");
+                    if ui.add(Slider::new(&mut fans_poll, 0.5..=10.0).step_by(0.5).suffix(" s")).changed() {
+                        state.config.statistics_sections.fans_poll_rate = (fans_poll * 1000.0) as u64;
+                        let _ = state.save_config();
+                    }
+                });
+            
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // About
+            if state.sections.mark(ui, "settings.about") {
+                ui.label(RichText::new("About").strong().heading());
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("TUXEDO Control Center {}", crate::pages::whats_new::VERSION));
+                    if ui.button("✨ What's new").clicked() {
+                        state.current_page = crate::app::Page::WhatsNew;
+                    }
+                });
+            }
         });
 }
 
 fn draw_battery_settings(ui: &mut Ui, state: &mut AppState) {
     ui.heading("🔋 Battery Charge Control");
     ui.add_space(8.0);
+    
+    if let Some(reason) = state.feature_unavailable(tuxedo_common::types::Feature::BatteryCharging) {
+        ui.label(RichText::new(format!("⚠ {}", reason))
+            .color(egui::Color32::from_rgb(255, 180, 0))
+            .small());
+        ui.add_space(4.0);
+    }
+
+    if let Some(backend) = state.charge_control.backend {
+        ui.label(RichText::new(format!("Backend: {} ({})", backend.label(), state.charge_control.battery))
+            .small()
+            .weak());
+        ui.add_space(4.0);
+    }
+    // Unknown until the daemon answered, so only hide what it said is missing
+    let has_start_threshold = state.charge_control.backend.is_none() || state.charge_control.start_threshold;
 
     if ui.checkbox(&mut state.config.battery_settings.control_enabled, "Enable charge thresholds").changed() {
         let _ = state.save_config();
@@ -225,27 +354,29 @@ fn draw_battery_settings(ui: &mut Ui, state: &mut AppState) {
 
     if state.config.battery_settings.control_enabled {
         // Start Threshold
-        ui.horizontal(|ui| {
-            ui.label("Start Threshold:");
-            if ComboBox::from_id_source("start_threshold_combo")
-                .selected_text(format!("{}%", state.config.battery_settings.charge_start_threshold))
-                .show_ui(ui, |ui| {
-                    let mut changed = false;
-                    for &threshold in &state.available_start_thresholds {
-                        if ui.selectable_value(
-                            &mut state.config.battery_settings.charge_start_threshold,
-                            threshold,
-                            format!("{}%", threshold),
-                        ).clicked() {
-                            changed = true;
+        if has_start_threshold {
+            ui.horizontal(|ui| {
+                ui.label("Start Threshold:");
+                if ComboBox::from_id_salt("start_threshold_combo")
+                    .selected_text(format!("{}%", state.config.battery_settings.charge_start_threshold))
+                    .show_ui(ui, |ui| {
+                        let mut changed = false;
+                        for &threshold in &state.available_start_thresholds {
+                            if ui.selectable_value(
+                                &mut state.config.battery_settings.charge_start_threshold,
+                                threshold,
+                                format!("{}%", threshold),
+                            ).clicked() {
+                                changed = true;
+                            }
                         }
-                    }
-                    changed
-                }).inner.unwrap_or(false) 
-            {
-                let _ = state.save_config();
-            }
-        });
+                        changed
+                    }).inner.unwrap_or(false) 
+                {
+                    let _ = state.save_config();
+                }
+            });
+        }
 
         // End Threshold
         ui.horizontal(|ui| {
@@ -279,23 +410,177 @@ fn draw_battery_settings(ui: &mut Ui, state: &mut AppState) {
                 state.config.battery_settings.charge_start_threshold = *valid_start;
             }
         }
+    }
 
-        // Apply button
+    if !state.charge_control.behaviours.is_empty() {
         ui.add_space(6.0);
-        if ui.button("💾 Apply Battery Settings").clicked() {
-            // Create DBus client and apply settings
-            if let Ok(client) = crate::dbus_client::DbusClient::new() {
-                let settings = state.config.battery_settings.clone();
-                tokio::spawn(async move {
-                    let rx = client.set_battery_settings(settings);
-                    let _ = rx.await;
-                });
-                state.show_message("Battery settings applied", false);
+        let info = &state.charge_control;
+        let changed = ui.horizontal(|ui| {
+            ui.label("Charge Behaviour:");
+            charging_option_combo(ui, "charge_behaviour_combo", &mut state.config.battery_settings.charge_behaviour,
+                &info.behaviours, info.behaviour.as_deref())
+        }).inner;
+        if changed {
+            let _ = state.save_config();
+        }
+        ui.label(RichText::new("\"inhibit-charge\" keeps the battery level while plugged in, \"force-discharge\" runs from the battery")
+            .small()
+            .weak());
+    }
+
+    draw_full_charge(ui, state);
+    draw_charging_options(ui, state);
+
+    // Still shown when enabled, so it can be turned off again
+    if state.can_cap_tdp || state.config.battery_settings.cap_tdp_to_adapter {
+        ui.add_space(6.0);
+        if ui.checkbox(&mut state.config.battery_settings.cap_tdp_to_adapter,
+            "Cap CPU power limit to what the charger can supply").changed()
+        {
+            let _ = state.save_config();
+        }
+    }
+    if let Some(adapter) = state.ac_adapter.as_ref().filter(|a| a.underpowered()) {
+        ui.label(RichText::new(format!("⚠ Connected charger: {} W, current limits need about {} W",
+                adapter.watts.unwrap_or(0), adapter.required_watts.unwrap_or(0)))
+            .color(egui::Color32::from_rgb(255, 180, 0))
+            .small());
+    }
+
+    // Only offered when the fuel gauge reports a temperature, still shown once set
+    let reports_temperature = state.battery_info.as_ref().is_some_and(|b| b.temperature.is_some());
+    if reports_temperature || state.config.battery_temp_alarm.is_some() {
+        ui.add_space(6.0);
+        let mut alarm = state.config.battery_temp_alarm.is_some();
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut alarm, "Notify when the battery gets hotter than").changed() {
+                state.config.battery_temp_alarm = alarm.then_some(45);
+                changed = true;
+            }
+            if let Some(ref mut limit) = state.config.battery_temp_alarm {
+                changed |= ui.add(egui::Slider::new(limit, 35..=60).suffix("°C")).changed();
             }
+        });
+        if changed {
+            let _ = state.save_config();
+        }
+    }
+
+    // Nothing the daemon could apply on this machine, no button that only pretends to
+    let has_settings = state.feature_unavailable(tuxedo_common::types::Feature::BatteryCharging).is_none()
+        || !state.charging_options.priorities.is_empty()
+        || !state.charging_options.profiles.is_empty()
+        || state.can_cap_tdp
+        || state.config.battery_settings.cap_tdp_to_adapter;
+    if !has_settings {
+        return;
+    }
+
+    // Apply button
+    ui.add_space(6.0);
+    if ui.button("💾 Apply Battery Settings").clicked() {
+        // Create DBus client and apply settings
+        if let Ok(client) = crate::dbus_client::DbusClient::new() {
+            let settings = state.config.battery_settings.clone();
+            tokio::spawn(async move {
+                let rx = client.set_battery_settings(settings);
+                let _ = rx.await;
+            });
+            state.show_message("Battery settings applied", false);
         }
     }
 }
 
+fn draw_full_charge(ui: &mut Ui, state: &mut AppState) {
+    let has_thresholds = state.charge_control.backend
+        .is_some_and(|b| b != tuxedo_common::types::ChargeBackend::ChargeBehaviour);
+    if !has_thresholds || !state.config.battery_settings.control_enabled {
+        return;
+    }
+
+    ui.add_space(6.0);
+    let since = state.battery_info.as_ref().and_then(|b| b.full_charge_since);
+    ui.horizontal(|ui| {
+        let busy = state.pending_full_charge.is_some();
+        match since {
+            Some(since) => {
+                let started = chrono::DateTime::from_timestamp(since, 0)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                    .unwrap_or_default();
+                ui.label(format!("🔌 Charging to 100% since {}", started));
+                if ui.add_enabled(!busy, egui::Button::new("Restore limits")).clicked() {
+                    state.full_charge_requested = Some(false);
+                }
+            }
+            None => {
+                if ui.add_enabled(!busy, egui::Button::new("🔌 Charge to 100% once")).clicked() {
+                    state.full_charge_requested = Some(true);
+                }
+            }
+        }
+    });
+    ui.label(RichText::new("For travel: the thresholds return by themselves once the battery is full or the charger is unplugged")
+        .small()
+        .weak());
+}
+
+// Only shown on models whose driver exposes USB-C charging controls
+fn draw_charging_options(ui: &mut Ui, state: &mut AppState) {
+    let options = state.charging_options.clone();
+    let battery = &mut state.config.battery_settings;
+    let mut changed = false;
+
+    if !options.priorities.is_empty() {
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.label("Charging Priority:");
+            changed |= charging_option_combo(ui, "charging_priority_combo",
+                &mut battery.charging_priority, &options.priorities, options.priority.as_deref());
+        });
+        ui.label(RichText::new("On a low-wattage USB-C charger, choose whether the battery or system performance gets the power")
+            .small()
+            .weak());
+    }
+
+    if !options.profiles.is_empty() {
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.label("Charging Profile:");
+            changed |= charging_option_combo(ui, "charging_profile_combo",
+                &mut battery.charging_profile, &options.profiles, options.profile.as_deref());
+        });
+    }
+
+    if changed {
+        let _ = state.save_config();
+    }
+}
+
+fn charging_option_combo(
+    ui: &mut Ui,
+    id: &str,
+    value: &mut Option<String>,
+    available: &[String],
+    current: Option<&str>,
+) -> bool {
+    let label = |v: &str| v.replace('_', " ");
+    let selected = match value {
+        Some(v) => label(v),
+        None => format!("Firmware default ({})", current.map(label).unwrap_or_else(|| "unknown".to_string())),
+    };
+
+    ComboBox::from_id_salt(id)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            let mut changed = ui.selectable_value(value, None, "Firmware default").clicked();
+            for option in available {
+                changed |= ui.selectable_value(value, Some(option.clone()), label(option)).clicked();
+            }
+            changed
+        }).inner.unwrap_or(false)
+}
+
 fn apply_font_size(ctx: &Context, font_size: &tuxedo_common::types::FontSize) {
     use egui::{FontId, FontFamily, TextStyle};
     use tuxedo_common::types::FontSize;
@@ -318,3 +603,163 @@ fn apply_font_size(ctx: &Context, font_size: &tuxedo_common::types::FontSize) {
     
     ctx.set_style(style);
 }
+
+fn draw_gamemode(ui: &mut Ui, state: &mut AppState) {
+    let mut changed = ui.checkbox(&mut state.config.auto_switch.gamemode_enabled, "Switch profile while a game runs").changed();
+    ui.label(RichText::new("Games started with Feral GameMode (gamemoderun) switch to this profile, the previous one is restored afterwards").small().italics());
+    
+    let names = state.profile_names();
+    ui.add_enabled_ui(state.config.auto_switch.gamemode_enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Game profile:");
+            let selected = &state.config.auto_switch.gamemode_profile;
+            ComboBox::from_id_salt("gamemode_profile_combo")
+                .selected_text(if selected.is_empty() { "Select a profile" } else { selected.as_str() })
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        changed |= ui.selectable_value(
+                            &mut state.config.auto_switch.gamemode_profile,
+                            name.clone(),
+                            name,
+                        ).changed();
+                    }
+                });
+        });
+        
+        ui.label("A profile picked by hand during a game:");
+        changed |= ui.radio_value(&mut state.config.auto_switch.gamemode_priority, GameModePriority::Manual,
+            "Stays after the game").changed();
+        changed |= ui.radio_value(&mut state.config.auto_switch.gamemode_priority, GameModePriority::GameMode,
+            "Is replaced by the profile from before the game").changed();
+    });
+    
+    if state.game_running {
+        ui.label(RichText::new("🎮 A game is running").small());
+    }
+    if changed {
+        let _ = state.save_config();
+    }
+}
+
+fn draw_steam_rules(ui: &mut Ui, state: &mut AppState) {
+    ui.label(RichText::new("Steam games").strong());
+    ui.label(RichText::new("A profile for specific games, used instead of the game mode profile").small().italics());
+    if !state.config.app_monitoring_enabled {
+        ui.label(RichText::new("Needs App monitoring under Daemon Controls").small());
+    }
+    
+    let names = state.profile_names();
+    let mut changed = false;
+    let mut remove = None;
+    for (index, rule) in state.config.auto_switch.steam_rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let running = state.running_steam_apps.contains(&rule.appid);
+            ui.label(format!("{}{}", if running { "🎮 " } else { "" }, rule.name));
+            ComboBox::from_id_salt(("steam_rule_profile", rule.appid))
+                .selected_text(rule.profile.as_str())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        changed |= ui.selectable_value(&mut rule.profile, name.clone(), name).changed();
+                    }
+                });
+            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        state.config.auto_switch.steam_rules.remove(index);
+        changed = true;
+    }
+    
+    let games = state.steam_games.get_or_insert_with(crate::app_monitor::installed_steam_games);
+    let rules = &state.config.auto_switch.steam_rules;
+    let mut added = None;
+    let mut reload = false;
+    ui.horizontal(|ui| {
+        ComboBox::from_id_salt("steam_rule_add")
+            .selected_text("Add game")
+            .show_ui(ui, |ui| {
+                for game in games.iter() {
+                    if rules.iter().any(|rule| rule.appid == game.appid) {
+                        continue;
+                    }
+                    if ui.selectable_label(false, &game.name).clicked() {
+                        added = Some(game.clone());
+                    }
+                }
+            });
+        if ui.small_button("⟳").on_hover_text("Read the Steam libraries again").clicked() {
+            reload = true;
+        }
+    });
+    if reload {
+        state.steam_games = None;
+    }
+    if let Some(game) = added {
+        state.config.auto_switch.steam_rules.push(SteamAppRule {
+            appid: game.appid,
+            name: game.name,
+            profile: state.config.current_profile.clone(),
+        });
+        changed = true;
+    }
+    
+    if changed {
+        let _ = state.save_config();
+    }
+}
+
+fn draw_app_rules(ui: &mut Ui, state: &mut AppState) {
+    ui.label(RichText::new("Applications").strong());
+    ui.label(RichText::new("The daemon switches while one of these runs and goes back once it exits, also with this window closed. Higher rules win when several run.").small().italics());
+    
+    let names = state.profile_names();
+    let rules = &mut state.config.auto_switch.app_rules;
+    let count = rules.len();
+    let mut changed = false;
+    let mut remove = None;
+    let mut swap = None;
+    for (index, rule) in rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui.add(egui::TextEdit::singleline(&mut rule.process)
+                .hint_text("Process name")
+                .desired_width(140.0)).changed();
+            ComboBox::from_id_salt(("app_rule_profile", index))
+                .selected_text(rule.profile.as_str())
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        changed |= ui.selectable_value(&mut rule.profile, name.clone(), name).changed();
+                    }
+                });
+            if ui.add_enabled(index > 0, egui::Button::new("⬆").small()).on_hover_text("Higher priority").clicked() {
+                swap = Some(index - 1);
+            }
+            if ui.add_enabled(index + 1 < count, egui::Button::new("⬇").small()).on_hover_text("Lower priority").clicked() {
+                swap = Some(index);
+            }
+            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = swap {
+        rules.swap(index, index + 1);
+        changed = true;
+    }
+    if let Some(index) = remove {
+        rules.remove(index);
+        changed = true;
+    }
+    if ui.small_button("➕ Add application").clicked() {
+        rules.push(AppProfileRule {
+            process: String::new(),
+            profile: state.config.current_profile.clone(),
+        });
+        changed = true;
+    }
+    
+    if changed {
+        let _ = state.save_config();
+    }
+}
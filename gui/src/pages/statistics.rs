@@ -1,7 +1,13 @@
-use egui::{Ui, ScrollArea, CollapsingHeader, Grid, ProgressBar, RichText};
+use egui::{Ui, ScrollArea, CollapsingHeader, ComboBox, Grid, ProgressBar, RichText};
 use egui::Color32;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
 use crate::app::AppState;
-use crate::theme::{temp_color, load_color, power_color};
+use crate::history::HistorySample;
+use crate::summary::Summary;
+use tuxedo_common::types::{CpuVulnerability, MemoryModule, FanControlMode, FanModeSource, FanModeStatus, FrequencyResidency, BatteryInfo, AcAdapterInfo, GpuType};
+use crate::theme::{temp_color, battery_temp_color, load_color, power_color};
+
+const RESIDENCY_BINS: usize = 12;
 
 pub fn draw(ui: &mut Ui, state: &mut AppState) {
     ScrollArea::vertical()
@@ -9,40 +15,60 @@ pub fn draw(ui: &mut Ui, state: &mut AppState) {
         .show(ui, |ui| {
             ui.add_space(8.0);
             
-            if state.config.statistics_sections.show_system_info {
+            if state.config.statistics_sections.show_system_info && state.sections.mark(ui, "stats.system") {
                 draw_system_info(ui, state);
                 ui.add_space(12.0);
             }
             
-            if state.config.statistics_sections.show_cpu {
+            if state.config.statistics_sections.show_display && !state.display_info.is_empty() && state.sections.mark(ui, "stats.display") {
+                draw_display_info(ui, state);
+                ui.add_space(12.0);
+            }
+            
+            if state.config.statistics_sections.show_cpu && state.sections.mark(ui, "stats.cpu") {
                 draw_cpu_info(ui, state);
                 ui.add_space(12.0);
             }
             
-            if state.config.statistics_sections.show_gpu {
+            if state.config.statistics_sections.show_gpu && state.sections.mark(ui, "stats.gpu") {
                 draw_gpu_info(ui, state);
                 ui.add_space(12.0);
             }
             
-            if state.config.statistics_sections.show_battery {
+            if state.config.statistics_sections.show_battery && state.sections.mark(ui, "stats.battery") {
                 draw_battery_info(ui, state);
                 ui.add_space(12.0);
             }
 
-            if state.config.statistics_sections.show_wifi {
+            if state.config.statistics_sections.show_wifi && state.sections.mark(ui, "stats.wifi") {
                 draw_wifi_info(ui, state);
                 ui.add_space(12.0);
             }
 
-            if state.config.statistics_sections.show_storage {
+            if state.config.statistics_sections.show_storage && state.sections.mark(ui, "stats.storage") {
                 draw_storage_info(ui, state);
                 ui.add_space(12.0);
             }
             
-            if state.config.statistics_sections.show_fans {
+            if state.config.statistics_sections.show_fans && state.sections.mark(ui, "stats.fans") {
                 draw_fan_info(ui, state);
                 ui.add_space(12.0);
             }
+            
+            if state.config.statistics_sections.show_temperatures && state.sections.mark(ui, "stats.temperatures") {
+                draw_temperature_overview(ui, state);
+                ui.add_space(12.0);
+            }
+
+            if state.config.statistics_sections.show_history && state.sections.mark(ui, "stats.history") {
+                draw_history(ui, state);
+                ui.add_space(12.0);
+            }
+            
+            if state.config.statistics_sections.show_history && state.sections.mark(ui, "stats.summary") {
+                draw_summary(ui, state);
+                ui.add_space(12.0);
+            }
         });
 }
 
@@ -67,7 +93,23 @@ fn draw_system_info(ui: &mut Ui, state: &AppState) {
                         ui.label("BIOS Version:");
                         ui.label(&info.bios_version);
                         ui.end_row();
+                        
+                        if let Some(ref microcode) = info.microcode {
+                            ui.label("CPU Microcode:");
+                            ui.label(microcode);
+                            ui.end_row();
+                        }
                     });
+                
+                if !info.memory_modules.is_empty() {
+                    ui.add_space(4.0);
+                    draw_memory_modules(ui, &info.memory_modules);
+                }
+                
+                if !info.vulnerabilities.is_empty() {
+                    ui.add_space(4.0);
+                    draw_vulnerabilities(ui, &info.vulnerabilities);
+                }
             } else {
                 ui.spinner();
                 ui.label("Loading system information...");
@@ -75,7 +117,121 @@ fn draw_system_info(ui: &mut Ui, state: &AppState) {
         });
 }
 
-fn draw_cpu_info(ui: &mut Ui, state: &AppState) {
+fn draw_display_info(ui: &mut Ui, state: &AppState) {
+    CollapsingHeader::new(RichText::new("🖵 Display").heading())
+        .default_open(true)
+        .show(ui, |ui| {
+            for (index, display) in state.display_info.iter().enumerate() {
+                if index > 0 {
+                    ui.add_space(6.0);
+                }
+                ui.label(RichText::new(format!("{} ({})", display.connector,
+                    if display.internal { "built-in" } else { "external" })).strong());
+                
+                Grid::new(format!("display_grid_{}", index))
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Panel:");
+                        ui.label(format!("{} {}", display.manufacturer, display.model.as_deref().unwrap_or("")));
+                        ui.end_row();
+                        
+                        if let Some((width, height)) = display.native_resolution {
+                            ui.label("Native Resolution:");
+                            ui.label(format!("{} × {}", width, height));
+                            ui.end_row();
+                        }
+                        
+                        if !display.refresh_rates.is_empty() {
+                            ui.label("Refresh Rates:");
+                            ui.label(display.refresh_rates.iter()
+                                .map(|hz| format!("{} Hz", hz))
+                                .collect::<Vec<_>>()
+                                .join(", "));
+                            ui.end_row();
+                        }
+                        
+                        if let Some(nits) = display.max_brightness_nits {
+                            ui.label("Max Brightness:");
+                            ui.label(format!("{} nits", nits));
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+}
+
+fn draw_memory_modules(ui: &mut Ui, modules: &[MemoryModule]) {
+    let total_gb = modules.iter().map(|m| m.size_mb).sum::<u64>() as f64 / 1024.0;
+    
+    CollapsingHeader::new(format!("Memory Modules ({} installed, {:.0} GB)", modules.len(), total_gb))
+        .default_open(false)
+        .show(ui, |ui| {
+            Grid::new("memory_grid")
+                .num_columns(5)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Slot").strong());
+                    ui.label(RichText::new("Size").strong());
+                    ui.label(RichText::new("Type / Speed").strong());
+                    ui.label(RichText::new("Vendor").strong());
+                    ui.label(RichText::new("Part Number").strong());
+                    ui.end_row();
+                    
+                    for module in modules {
+                        ui.label(&module.slot);
+                        if module.size_mb < 1024 {
+                            ui.label(format!("{} MB", module.size_mb));
+                        } else {
+                            ui.label(format!("{} GB", module.size_mb / 1024));
+                        }
+                        let speed = match (module.configured_speed_mts, module.speed_mts) {
+                            (Some(configured), Some(max)) if configured != max =>
+                                format!("{} MT/s (max {})", configured, max),
+                            (Some(speed), _) | (None, Some(speed)) => format!("{} MT/s", speed),
+                            (None, None) => String::new(),
+                        };
+                        ui.label(format!("{} {}", module.memory_type.as_deref().unwrap_or(""), speed).trim().to_string());
+                        ui.label(module.manufacturer.as_deref().unwrap_or("—"));
+                        ui.label(module.part_number.as_deref().unwrap_or("—"));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn draw_vulnerabilities(ui: &mut Ui, vulnerabilities: &[CpuVulnerability]) {
+    let vulnerable = vulnerabilities.iter()
+        .filter(|v| v.status.starts_with("Vulnerable"))
+        .count();
+    
+    CollapsingHeader::new(format!("CPU Vulnerabilities ({} vulnerable)", vulnerable))
+        .default_open(false)
+        .show(ui, |ui| {
+            Grid::new("vulnerabilities_grid")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for vulnerability in vulnerabilities {
+                        ui.label(&vulnerability.name);
+                        let color = if vulnerability.status.starts_with("Vulnerable") {
+                            Color32::from_rgb(220, 80, 80)
+                        } else if vulnerability.status.starts_with("Mitigation") {
+                            Color32::from_rgb(255, 180, 0)
+                        } else {
+                            Color32::from_rgb(80, 200, 120)
+                        };
+                        ui.label(RichText::new(&vulnerability.status).color(color).small());
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn draw_cpu_info(ui: &mut Ui, state: &mut AppState) {
     CollapsingHeader::new(RichText::new("🖥️ CPU").heading())
         .default_open(true)
         .show(ui, |ui| {
@@ -173,7 +329,20 @@ fn draw_cpu_info(ui: &mut Ui, state: &AppState) {
                         
                         if cpu.capabilities.has_boost {
                             ui.label("CPU Boost:");
-                            ui.label(if cpu.boost_enabled { "✅ Enabled" } else { "❌ Disabled" });
+                            let mixed = cpu.boost_policies.iter().any(|g| g.enabled.is_none())
+                                || cpu.boost_policies.windows(2).any(|w| w[0].enabled != w[1].enabled);
+                            if mixed {
+                                let details: Vec<String> = cpu.boost_policies.iter()
+                                    .map(|g| format!("{}: {}", g.label(), match g.enabled {
+                                        Some(true) => "on",
+                                        Some(false) => "off",
+                                        None => "mixed",
+                                    }))
+                                    .collect();
+                                ui.label("⚠ Mixed").on_hover_text(details.join("\n"));
+                            } else {
+                                ui.label(if cpu.boost_enabled { "✅ Enabled" } else { "❌ Disabled" });
+                            }
                             ui.end_row();
                         }
                         
@@ -225,6 +394,47 @@ fn draw_cpu_info(ui: &mut Ui, state: &AppState) {
                                 }
                             });
                     });
+                
+                ui.add_space(8.0);
+                CollapsingHeader::new("Frequency Residency")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        if cpu.time_in_state.is_empty() {
+                            ui.label(RichText::new(format!("Not available: the {} driver keeps no cpufreq statistics",
+                                cpu.scaling_driver)).weak());
+                            return;
+                        }
+                        
+                        ui.horizontal(|ui| {
+                            match state.residency_baseline {
+                                Some((since, _)) => ui.label(format!("Time at each frequency over the last {} min",
+                                    since.elapsed().as_secs() / 60)),
+                                None => ui.label("Time at each frequency since boot"),
+                            };
+                            if ui.small_button("↺ Reset").on_hover_text("Start counting from now, e.g. after switching profiles").clicked() {
+                                state.residency_baseline = Some((std::time::Instant::now(), cpu.time_in_state.clone()));
+                            }
+                        });
+                        
+                        let baseline = state.residency_baseline.as_ref().map(|(_, b)| b.as_slice());
+                        let bins = residency_histogram(&cpu.time_in_state, baseline, RESIDENCY_BINS);
+                        if bins.is_empty() {
+                            ui.label(RichText::new("Collecting…").weak());
+                            return;
+                        }
+                        let bars: Vec<Bar> = bins.iter()
+                            .map(|(center, width, percent)| Bar::new(*center, *percent).width(width * 0.9))
+                            .collect();
+                        Plot::new("frequency_residency")
+                            .height(140.0)
+                            .x_axis_label("MHz")
+                            .y_axis_label("%")
+                            .include_y(0.0)
+                            .allow_scroll(false)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .show(ui, |plot_ui| plot_ui.bar_chart(BarChart::new(bars)));
+                    });
             } else {
                 ui.spinner();
                 ui.label("Loading CPU information...");
@@ -232,12 +442,57 @@ fn draw_cpu_info(ui: &mut Ui, state: &AppState) {
         });
 }
 
-fn draw_gpu_info(ui: &mut Ui, state: &AppState) {
+// Equal-width frequency bins between the lowest and highest listed frequency,
+// as (center MHz, width MHz, percent of the time counted since the baseline)
+fn residency_histogram(current: &[FrequencyResidency], baseline: Option<&[FrequencyResidency]>, bins: usize) -> Vec<(f64, f64, f64)> {
+    let deltas: Vec<(f64, f64)> = current.iter()
+        .map(|r| {
+            let before = baseline
+                .and_then(|b| b.iter().find(|old| old.freq_khz == r.freq_khz))
+                .map_or(0, |old| old.time_ms);
+            // Counters restart when a CPU goes offline
+            (r.freq_khz as f64 / 1000.0, r.time_ms.saturating_sub(before) as f64)
+        })
+        .collect();
+    
+    let total: f64 = deltas.iter().map(|(_, time)| time).sum();
+    if total <= 0.0 {
+        return vec![];
+    }
+    
+    let min = deltas.iter().map(|(freq, _)| *freq).fold(f64::MAX, f64::min);
+    let max = deltas.iter().map(|(freq, _)| *freq).fold(f64::MIN, f64::max);
+    let width = ((max - min) / bins as f64).max(1.0);
+    let count = (((max - min) / width).floor() as usize + 1).min(bins);
+    
+    let mut histogram = vec![0.0; count];
+    for (freq, time) in deltas {
+        let index = (((freq - min) / width) as usize).min(count - 1);
+        histogram[index] += time;
+    }
+    
+    histogram.into_iter()
+        .enumerate()
+        .map(|(i, time)| (min + width * (i as f64 + 0.5), width, time / total * 100.0))
+        .collect()
+}
+
+fn draw_gpu_info(ui: &mut Ui, state: &mut AppState) {
     CollapsingHeader::new(RichText::new("🎮 GPU").heading())
         .default_open(true)  // Changed to true
         .show(ui, |ui| {
+            if state.gpu_info.len() > 1 {
+                draw_gpu_filter(ui, state);
+                ui.add_space(6.0);
+            }
+            
+            // A GPU picked before that is gone (undocked) shows all of them again
+            let filter = state.config.statistics_sections.gpu_filter.as_ref()
+                .filter(|id| state.gpu_info.iter().any(|gpu| gpu.id == **id));
+            let shown = state.gpu_info.iter()
+                .filter(|gpu| filter.is_none_or(|id| gpu.id == *id));
             if !state.gpu_info.is_empty() {
-                for (idx, gpu) in state.gpu_info.iter().enumerate() {
+                for (idx, gpu) in shown.enumerate() {
                     if idx > 0 {
                         ui.separator();
                         ui.add_space(6.0);
@@ -249,13 +504,25 @@ fn draw_gpu_info(ui: &mut Ui, state: &AppState) {
                         .spacing([40.0, 6.0])
                         .show(ui, |ui| {
                             ui.label("Type:");
-                            ui.label(if gpu.gpu_type == tuxedo_common::types::GpuType::Integrated {
-                                "Integrated"
-                            } else {
-                                "Discrete"
+                            ui.label(match (&gpu.gpu_type, gpu.external) {
+                                (GpuType::Integrated, _) => "Integrated",
+                                (GpuType::Discrete, false) => "Discrete",
+                                (GpuType::Discrete, true) => "Discrete, external",
                             });
                             ui.end_row();
                             
+                            if !gpu.id.is_empty() {
+                                ui.label("PCI address:");
+                                ui.label(&gpu.id);
+                                ui.end_row();
+                            }
+                            
+                            if !gpu.driver.is_empty() {
+                                ui.label("Driver:");
+                                ui.label(&gpu.driver);
+                                ui.end_row();
+                            }
+                            
                             ui.label("Status:");
                             ui.label(&gpu.status);
                             ui.end_row();
@@ -266,6 +533,12 @@ fn draw_gpu_info(ui: &mut Ui, state: &AppState) {
                                 ui.end_row();
                             }
                             
+                            if let Some(freq) = gpu.memory_frequency {
+                                ui.label("Memory clock:");
+                                ui.label(format!("{} MHz", freq));
+                                ui.end_row();
+                            }
+                            
                             if let Some(temp) = gpu.temperature {
                                 ui.label("Temperature:");
                                 ui.colored_label(
@@ -298,11 +571,38 @@ fn draw_gpu_info(ui: &mut Ui, state: &AppState) {
         });
 }
 
+fn draw_gpu_filter(ui: &mut Ui, state: &mut AppState) {
+    let filter = &mut state.config.statistics_sections.gpu_filter;
+    let selected = state.gpu_info.iter()
+        .find(|gpu| filter.as_ref() == Some(&gpu.id))
+        .map(|gpu| gpu.name.clone())
+        .unwrap_or_else(|| "All GPUs".to_string());
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Show:");
+        ComboBox::from_id_salt("gpu_filter")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                changed |= ui.selectable_value(filter, None, "All GPUs").changed();
+                for gpu in &state.gpu_info {
+                    changed |= ui.selectable_value(filter, Some(gpu.id.clone()),
+                        format!("{} ({})", gpu.name, gpu.id)).changed();
+                }
+            });
+    });
+    if changed {
+        let _ = state.save_config();
+    }
+}
+
 fn draw_battery_info(ui: &mut Ui, state: &AppState) {
     CollapsingHeader::new(RichText::new("🔋 Battery").heading())
         .default_open(true)
         .show(ui, |ui| {
             if let Some(ref battery) = state.battery_info {
+                draw_charge_state(ui, battery, state.ac_adapter.as_ref());
+                ui.add_space(4.0);
+
                 Grid::new("battery_grid")
                     .num_columns(2)
                     .spacing([40.0, 8.0])
@@ -323,23 +623,24 @@ fn draw_battery_info(ui: &mut Ui, state: &AppState) {
                         ui.end_row();
                         
                         ui.label("Current:");
-                        let current_a = battery.current_ma as f64 / 1000.0;
-                        ui.label(format!("{:.2} A", current_a.abs()));
+                        ui.label(format!("{:+.2} A", battery.current_ma as f64 / 1000.0));
                         ui.end_row();
                         
-                        let power_w = (battery.voltage_mv as f64 * battery.current_ma as f64) / 1_000_000.0;
-                        if power_w.abs() > 0.1 {
+                        if battery.power_draw_w.abs() > 0.1 {
                             ui.label("Power:");
                             ui.colored_label(
-                                power_color(power_w.abs() as f32),
-                                format!("{:.1} W {}", 
-                                    power_w.abs(),
-                                    if power_w > 0.0 { "(charging)" } else { "(discharging)" }
-                                )
+                                power_color(battery.power_draw_w.abs() as f32),
+                                format!("{:+.1} W", battery.power_draw_w)
                             );
                             ui.end_row();
                         }
                         
+                        if let Some(temp) = battery.temperature {
+                            ui.label("Temperature:");
+                            ui.colored_label(battery_temp_color(temp), format!("{:.1}°C", temp));
+                            ui.end_row();
+                        }
+                        
                         ui.label("Manufacturer:");
                         ui.label(&battery.manufacturer);
                         ui.end_row();
@@ -359,13 +660,82 @@ fn draw_battery_info(ui: &mut Ui, state: &AppState) {
                             ui.label(format!("{}%", end));
                             ui.end_row();
                         }
+                        
+                        if let Some(ref session) = battery.session {
+                            let minutes = session.duration_secs / 60;
+                            ui.label("Since Unplug:");
+                            ui.label(format!("{}h {:02}m", minutes / 60, minutes % 60));
+                            ui.end_row();
+                            
+                            ui.label("Energy Used:");
+                            ui.label(format!("{:.1} Wh", session.energy_used_wh));
+                            ui.end_row();
+                            
+                            ui.label("Average Draw:");
+                            ui.colored_label(
+                                power_color(session.average_draw_w as f32),
+                                format!("{:.1} W", session.average_draw_w)
+                            );
+                            ui.end_row();
+                        }
+                        
+                        if let Some(ref adapter) = state.ac_adapter {
+                            ui.label("Charger:");
+                            let kind = if adapter.usb_c { "USB-C" } else { "AC adapter" };
+                            match adapter.watts {
+                                Some(watts) => ui.label(format!("{} W {}", watts, kind)),
+                                None => ui.label(kind),
+                            };
+                            ui.end_row();
+                        }
                     });
+                
+                if let Some(ref adapter) = state.ac_adapter {
+                    draw_adapter_warning(ui, adapter);
+                }
             } else {
                 ui.label("No battery detected");
             }
         });
 }
 
+fn draw_charge_state(ui: &mut Ui, battery: &BatteryInfo, adapter: Option<&AcAdapterInfo>) {
+    let source = match adapter.filter(|a| a.online) {
+        Some(a) if a.usb_c => "USB-C",
+        _ => "AC",
+    };
+    let power = battery.power_draw_w.abs();
+    let (text, color) = match battery.status.as_str() {
+        "Charging" => (format!("⚡ Charging from {} at {:.1} W", source, power), Color32::from_rgb(100, 200, 100)),
+        "Discharging" => (format!("🔋 On battery, drawing {:.1} W", power), Color32::from_rgb(255, 180, 0)),
+        "Full" => (format!("🔌 On {}, fully charged", source), Color32::from_rgb(100, 200, 100)),
+        // Held at the end threshold, or a charger too weak to do more than run the system
+        "Not charging" if battery.on_ac_power => (format!("🔌 On {}, not charging", source), Color32::from_rgb(150, 180, 255)),
+        _ if battery.on_ac_power => (format!("🔌 On {}", source), Color32::from_rgb(150, 180, 255)),
+        _ => ("🔋 On battery".to_string(), Color32::from_rgb(255, 180, 0)),
+    };
+    ui.label(RichText::new(text).strong().color(color));
+}
+
+fn draw_adapter_warning(ui: &mut Ui, adapter: &tuxedo_common::types::AcAdapterInfo) {
+    if !adapter.underpowered() {
+        return;
+    }
+    
+    ui.add_space(6.0);
+    ui.colored_label(
+        Color32::from_rgb(255, 180, 0),
+        format!("⚠ The {} W charger can't sustain the current power limits (about {} W). \
+            The battery may drain under load.",
+            adapter.watts.unwrap_or(0), adapter.required_watts.unwrap_or(0)),
+    );
+    if let Some(capped) = adapter.tdp_capped_watts {
+        ui.label(RichText::new(format!("CPU power limit capped to {} W while this charger is connected", capped))
+            .small()
+            .weak());
+    }
+}
+
 fn draw_wifi_info(ui: &mut Ui, state: &AppState) {
     CollapsingHeader::new(RichText::new("📶 WiFi").heading())
         .default_open(true)  // Changed to true
@@ -432,6 +802,12 @@ fn draw_wifi_info(ui: &mut Ui, state: &AppState) {
                                 ui.end_row();
                             }
                             
+                            if let Some(power_save) = wifi.power_save {
+                                ui.label("Power Save:");
+                                ui.label(if power_save { "On" } else { "Off" });
+                                ui.end_row();
+                            }
+                            
                             if let Some(temp) = wifi.temperature {
                                 ui.label("Temperature:");
                                 ui.colored_label(
@@ -478,6 +854,48 @@ fn draw_storage_info(ui: &mut Ui, state: &AppState) {
                                 );
                                 ui.end_row();
                             }
+                            
+                            if let Some(ref smart) = device.smart {
+                                ui.label("Health:");
+                                if smart.passed {
+                                    ui.colored_label(Color32::from_rgb(100, 200, 120), "PASSED");
+                                } else {
+                                    ui.colored_label(Color32::from_rgb(255, 80, 80), "FAILING");
+                                }
+                                ui.end_row();
+                                
+                                if let Some(used) = smart.percentage_used {
+                                    ui.label("Wear:");
+                                    ui.label(format!("{}%", used));
+                                    ui.end_row();
+                                }
+                                
+                                if let Some(sectors) = smart.reallocated_sectors {
+                                    ui.label("Reallocated Sectors:");
+                                    if sectors > 0 {
+                                        ui.colored_label(Color32::from_rgb(255, 200, 60), sectors.to_string());
+                                    } else {
+                                        ui.label("0");
+                                    }
+                                    ui.end_row();
+                                }
+                                
+                                if let Some(hours) = smart.power_on_hours {
+                                    ui.label("Power On:");
+                                    ui.label(format!("{} h", hours));
+                                    ui.end_row();
+                                }
+                            }
+                            
+                            if let (Some(read), Some(write)) = (device.read_mb_s, device.write_mb_s) {
+                                ui.label("Read:");
+                                draw_io_rate_bar(ui, read);
+                                ui.end_row();
+                                
+                                ui.label("Write:");
+                                draw_io_rate_bar(ui, write);
+                                ui.end_row();
+                            }
                         });
                     ui.add_space(8.0);
                 }
@@ -518,10 +936,52 @@ fn draw_storage_info(ui: &mut Ui, state: &AppState) {
         });
 }
 
+fn draw_io_rate_bar(ui: &mut Ui, mb_s: f64) {
+    // Bars are scaled against a typical SATA SSD, faster NVMe drives simply saturate them
+    const FULL_SCALE_MB_S: f64 = 550.0;
+    
+    ui.add(
+        ProgressBar::new((mb_s / FULL_SCALE_MB_S).min(1.0) as f32)
+            .text(format!("{:.1} MB/s", mb_s))
+            .desired_width(150.0)
+    );
+}
+
+fn draw_fan_mode(ui: &mut Ui, status: &FanModeStatus) {
+    let (mode, color) = match status.mode {
+        FanControlMode::Auto => ("Automatic (EC)", Color32::from_rgb(100, 200, 100)),
+        FanControlMode::Manual => ("Manual", Color32::from_rgb(100, 150, 255)),
+        FanControlMode::Unknown => ("Unknown", Color32::GRAY),
+    };
+    let set_by = match status.set_by {
+        FanModeSource::Daemon => "this daemon",
+        FanModeSource::DustCleaning => "dust cleaning",
+        FanModeSource::EcDefault => "EC default",
+        FanModeSource::External => "external tool",
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Mode:").strong());
+        ui.label(RichText::new(mode).color(color).strong().size(16.0));
+        ui.label(format!("(set by {})", set_by));
+    });
+
+    if status.set_by == FanModeSource::External {
+        ui.label(RichText::new("⚠ Another program changed the fans, curves may be overridden")
+            .color(Color32::from_rgb(255, 180, 0))
+            .small());
+    }
+}
+
 fn draw_fan_info(ui: &mut Ui, state: &AppState) {
     CollapsingHeader::new(RichText::new("💨 Fans").heading())
         .default_open(true)
         .show(ui, |ui| {
+            if let Some(ref status) = state.fan_mode {
+                draw_fan_mode(ui, status);
+                ui.add_space(4.0);
+            }
+
             if !state.fan_info.is_empty() {
                 Grid::new("fans_grid")
                     .num_columns(3)
@@ -571,3 +1031,135 @@ fn draw_fan_info(ui: &mut Ui, state: &AppState) {
             }
         });
 }
+
+fn draw_temperature_overview(ui: &mut Ui, state: &mut AppState) {
+    CollapsingHeader::new(RichText::new("🌡 Temperatures").heading())
+        .default_open(true)
+        .show(ui, |ui| {
+            if state.temperature_sensors.is_empty() {
+                ui.label("No temperature sensors detected");
+                return;
+            }
+            
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                ui.text_edit_singleline(&mut state.temperature_filter);
+            });
+            ui.add_space(6.0);
+            
+            let filter = state.temperature_filter.to_lowercase();
+            
+            Grid::new("temperatures_grid")
+                .num_columns(4)
+                .spacing([40.0, 8.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Sensor").strong());
+                    ui.label(RichText::new("Source").strong());
+                    ui.label(RichText::new("Temperature").strong());
+                    ui.label(RichText::new("Critical").strong());
+                    ui.end_row();
+                    
+                    for sensor in state.temperature_sensors.iter()
+                        .filter(|s| filter.is_empty() || s.label.to_lowercase().contains(&filter))
+                    {
+                        ui.label(&sensor.label);
+                        ui.label(RichText::new(&sensor.source).weak());
+                        ui.colored_label(
+                            temp_color(sensor.temperature),
+                            format!("{:.1}°C", sensor.temperature)
+                        );
+                        if let Some(crit) = sensor.critical {
+                            ui.label(format!("{:.0}°C", crit));
+                        } else {
+                            ui.label("—");
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn draw_history(ui: &mut Ui, state: &AppState) {
+    CollapsingHeader::new(RichText::new("📈 History").heading())
+        .default_open(false)
+        .show(ui, |ui| {
+            let samples = &state.history.samples;
+            if samples.is_empty() {
+                ui.label("No history recorded yet");
+                return;
+            }
+
+            let now = chrono::Local::now().timestamp();
+            // X axis in hours before now
+            let hours_ago = |timestamp: i64| -((now - timestamp) as f64 / 3600.0);
+            let series = |value: fn(&HistorySample) -> Option<f32>| -> PlotPoints {
+                samples.iter()
+                    .filter_map(|s| value(s).map(|v| [hours_ago(s.timestamp), v as f64]))
+                    .collect()
+            };
+
+            ui.label(RichText::new(format!(
+                "{} samples, one per {} s, kept for {} days",
+                samples.len(),
+                state.config.history_settings.resolution_secs,
+                state.config.history_settings.retention_days,
+            )).small().italics());
+
+            Plot::new("statistics_history")
+                .height(250.0)
+                .legend(Legend::default())
+                .x_axis_label("Hours")
+                .allow_scroll(false)
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(series(|s| Some(s.cpu_temp)))
+                        .name("CPU °C"));
+                    plot_ui.line(Line::new(series(|s| Some(s.cpu_load)))
+                        .name("CPU load %"));
+                    plot_ui.line(Line::new(series(|s| s.gpu_temp))
+                        .name("GPU °C"));
+                    plot_ui.line(Line::new(series(|s| s.fan_percent))
+                        .name("Fan %"));
+                    plot_ui.line(Line::new(series(|s| s.battery_draw_w))
+                        .name("Battery W"));
+                });
+        });
+}
+
+fn draw_summary(ui: &mut Ui, state: &mut AppState) {
+    CollapsingHeader::new(RichText::new("🗒 Weekly Summary").heading())
+        .default_open(false)
+        .show(ui, |ui| {
+            let summary = match Summary::from_history(&state.history, state.config.history_settings.resolution_secs) {
+                Some(summary) => summary,
+                None => {
+                    ui.label("No history recorded in the last week");
+                    return;
+                }
+            };
+
+            Grid::new("summary_grid")
+                .num_columns(2)
+                .spacing([40.0, 6.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for (label, value) in summary.rows() {
+                        ui.label(label);
+                        ui.label(RichText::new(value).strong());
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                for (html, label) in [(false, "💾 Export Markdown"), (true, "💾 Export HTML")] {
+                    if ui.button(label).clicked() {
+                        match summary.export(html) {
+                            Ok(path) => state.show_message(format!("Summary exported to {}", path), false),
+                            Err(e) => state.show_message(format!("Export failed: {}", e), true),
+                        }
+                    }
+                }
+            });
+        });
+}
@@ -1,14 +1,19 @@
 use egui::{Ui, ScrollArea, RichText, Slider, ComboBox, TopBottomPanel};
-use crate::app::AppState;
+use std::collections::HashMap;
+use crate::app::{fan_sensor_id, AppState};
 use crate::dbus_client::DbusClient;
-use tuxedo_common::types::{KeyboardMode, Profile, FanCurve};
+use tuxedo_common::types::{FanInfo, WriteKind, KeyboardMode, Profile, FanCurve, FanSettings, FanSmoothing, FanMinDuty, TemperatureSensor, DynamicPowerLimit, IgpuFrequencyInfo, RadioType, CpuBatterySettings, TdpLimits, Feature, KeyboardDevice, KeyboardDeviceSettings, AmbientKeyboardSettings, AmbientScreenSettings, AmbientLightReading, BoostPolicyGroup, DisplayInfo, GpuInfo, GpuType, AquarisSettings, AquarisStatus, PumpVoltage};
 use crate::widgets::fan_curve_editor::FanCurveEditor;
 
 pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
     let profile_idx = state.current_profile_index();
     
     if profile_idx.is_none() {
-        ui.label("No profile selected");
+        if state.system_profile(&state.config.current_profile).is_some() {
+            ui.label(format!("'{}' is a system profile, it is managed by the administrator", state.config.current_profile));
+        } else {
+            ui.label("No profile selected");
+        }
         return;
     }
     
@@ -30,7 +35,37 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
                     // Also apply to hardware
                     if let Some(client) = dbus_client {
                         let profile_clone = state.config.profiles[idx].clone();
-                        let _rx = client.apply_profile(profile_clone);
+                        crate::session_settings::apply_profile(&profile_clone);
+                        state.pending_apply = Some(client.apply_profile(profile_clone));
+                    }
+                }
+                
+                // Older daemons don't report progress, only the pending call shows then
+                if state.pending_apply.is_some() {
+                    let step = state.apply_progress.as_ref()
+                        .filter(|p| p.running && !p.step.is_empty())
+                        .map(|p| p.step.clone());
+                    if ui.small_button("✖ Cancel").clicked() {
+                        if let Some(client) = dbus_client {
+                            let _rx = client.cancel_apply();
+                        }
+                    }
+                    ui.label(RichText::new(match step {
+                        Some(step) => format!("Applying: {}", step),
+                        None => "Applying...".to_string(),
+                    }).weak());
+                    ui.spinner();
+                }
+                
+                if ui.add_enabled(state.apply_report.is_some(), egui::Button::new("⏱ Apply timings")).clicked() {
+                    state.show_apply_report = true;
+                }
+                
+                // Dry run on the daemon, shows what Save would write
+                let previewing = state.pending_preview.is_some();
+                if ui.add_enabled(dbus_client.is_some() && !previewing, egui::Button::new("🔍 Preview changes")).clicked() {
+                    if let Some(client) = dbus_client {
+                        state.pending_preview = Some(client.preview_profile(state.config.profiles[idx].clone()));
                     }
                 }
                 
@@ -44,6 +79,10 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
         ui.add_space(8.0);
     });
     
+    draw_apply_preview(ui.ctx(), state, &profile_name);
+    draw_apply_report(ui.ctx(), state);
+    draw_smt_confirmation(ui.ctx(), state, idx);
+    
     // Main content
     ScrollArea::vertical()
         .auto_shrink([false, false])
@@ -51,36 +90,313 @@ pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>)
             ui.add_space(8.0);
             
             // CPU tuning
-            let cpu_info_clone = state.cpu_info.clone();
-            if let Some(cpu_info) = &cpu_info_clone {
-                let cpu_caps = Some(&cpu_info.capabilities);
-                draw_cpu_tuning(ui, &mut state.config.profiles[idx], cpu_caps, cpu_info);
-            } else {
-                ui.heading("🖥️ CPU Tuning");
-                ui.add_space(8.0);
-                ui.label("CPU information not available");
+            if state.sections.mark(ui, "tuning.cpu") {
+                draw_feature_notice(ui, state, Feature::CpuFrequency);
+                let cpu_info_clone = state.cpu_info.clone();
+                if let Some(cpu_info) = &cpu_info_clone {
+                    let cpu_caps = Some(&cpu_info.capabilities);
+                    draw_cpu_tuning(ui, &mut state.config.profiles[idx], cpu_caps, cpu_info,
+                        state.tdp_limits.as_ref(), &mut state.pending_smt_change);
+                } else {
+                    ui.heading("🖥️ CPU Tuning");
+                    ui.add_space(8.0);
+                    ui.label("CPU information not available");
+                }
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // GPU tuning
+            if state.sections.mark(ui, "tuning.gpu") {
+                draw_feature_notice(ui, state, Feature::GpuPowerLimit);
+                draw_gpu_tuning(ui, &mut state.config.profiles[idx], &state.gpu_info, state.igpu_frequency_info.as_ref());
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
             }
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
             
             // Keyboard tuning
-            draw_keyboard_tuning(ui, &mut state.config.profiles[idx], dbus_client);
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+            if state.sections.mark(ui, "tuning.keyboard") {
+                draw_feature_notice(ui, state, Feature::KeyboardBacklight);
+                let has_als = state.feature_status.iter().any(|s| s.feature == Feature::AmbientLight);
+                draw_keyboard_tuning(ui, &mut state.config.profiles[idx], &state.keyboard_devices, has_als, dbus_client);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Lightbar, only on chassis that have one
+            if state.feature_status.iter().any(|s| s.feature == Feature::LightBar)
+                && state.sections.mark(ui, "tuning.lightbar") {
+                draw_feature_notice(ui, state, Feature::LightBar);
+                draw_lightbar_tuning(ui, &mut state.config.profiles[idx]);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Indicator LEDs
+            if !state.indicator_leds.is_empty() && state.sections.mark(ui, "tuning.indicators") {
+                draw_indicator_tuning(ui, &mut state.config.profiles[idx], &state.indicator_leds);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Input device power
+            if state.sections.mark(ui, "tuning.input") {
+                draw_input_tuning(ui, &mut state.config.profiles[idx]);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
             
             // Screen tuning
-            draw_screen_tuning(ui, &mut state.config.profiles[idx]);
-            ui.add_space(16.0);
-            ui.separator();
-            ui.add_space(16.0);
+            if state.sections.mark(ui, "tuning.screen") {
+                let ambient_reading = state.feature_status.iter()
+                    .any(|s| s.feature == Feature::AmbientLight)
+                    .then(|| state.ambient_reading.clone());
+                draw_screen_tuning(ui, &mut state.config.profiles[idx], ambient_reading, &state.display_info);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Network tuning
+            if state.sections.mark(ui, "tuning.network") {
+                draw_network_tuning(ui, &mut state.config.profiles[idx], &state.network_interfaces);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Kernel VM tuning
+            if state.sections.mark(ui, "tuning.memory") {
+                draw_vm_tuning(ui, &mut state.config.profiles[idx]);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
             
             // Fan tuning
-            let fan_count = state.fan_info.len().max(2);
-            draw_fan_tuning(ui, &mut state.config.profiles[idx], fan_count);
-            ui.add_space(16.0);
+            if state.sections.mark(ui, "tuning.fans") {
+                draw_feature_notice(ui, state, Feature::FanControl);
+                // Prefer the backend's count, fan_info may still be empty on startup
+                let fan_count = state.fan_curve_capabilities.as_ref()
+                    .map(|caps| caps.fan_count as usize)
+                    .filter(|count| *count > 0)
+                    .unwrap_or_else(|| state.fan_info.len().max(2));
+                let other_profiles: Vec<(String, FanSettings)> = state.config.profiles.iter()
+                    .enumerate()
+                    .filter(|(i, p)| *i != idx && !p.fan_settings.curves.is_empty())
+                    .map(|(_, p)| (p.name.clone(), p.fan_settings.clone()))
+                    .collect();
+                let max_points = state.fan_curve_capabilities.as_ref()
+                    .and_then(|caps| caps.max_points)
+                    .map(|max| max as usize);
+                let min_duty = state.fan_curve_capabilities.as_ref()
+                    .map(|caps| caps.min_duty.clone())
+                    .unwrap_or_default();
+                let readings = CurveReadings {
+                    sensors: &state.temperature_sensors,
+                    fan_info: &state.fan_info,
+                    ranges: &state.temperature_ranges,
+                    min_duty: &min_duty,
+                };
+                draw_fan_tuning(ui, &mut state.config.profiles[idx], fan_count, max_points, &readings,
+                    &mut state.config.fan_curve_observed_axis, &other_profiles);
+                draw_fan_min_duty(ui, state, dbus_client, fan_count, &min_duty);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Aquaris water cooler, once it's paired
+            if state.aquaris.present && state.sections.mark(ui, "tuning.aquaris") {
+                draw_aquaris_tuning(ui, &mut state.config.profiles[idx], &state.aquaris);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Clamshell overrides
+            if state.sections.mark(ui, "tuning.clamshell") {
+                draw_clamshell_override(ui, state, idx);
+                ui.add_space(16.0);
+                ui.separator();
+                ui.add_space(16.0);
+            }
+            
+            // Dust cleaning
+            if state.sections.mark(ui, "tuning.dust") {
+                draw_dust_cleaning(ui, state, dbus_client);
+                ui.add_space(16.0);
+            }
+        });
+}
+
+fn draw_smt_confirmation(ctx: &egui::Context, state: &mut AppState, idx: usize) {
+    let enable = match state.pending_smt_change {
+        Some(enable) => enable,
+        None => return,
+    };
+    
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new(if enable { "Turn SMT on?" } else { "Turn SMT off?" })
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.set_max_width(420.0);
+            if enable {
+                ui.label("The sibling threads of every core are brought back online. \
+                    Multithreaded workloads get faster, single cores may run slightly hotter.");
+            } else {
+                ui.label("Half of the logical CPUs are taken offline. \
+                    Running programs are moved to the remaining CPUs, which can stall or \
+                    destabilize heavy workloads such as VMs, compilers or games while it happens.");
+            }
+            ui.add_space(6.0);
+            ui.label(RichText::new("The change takes effect when the profile is saved. \
+                The daemon ignores SMT changes from automatic profile switches within a minute of the last one.")
+                .small().italics());
+            ui.add_space(8.0);
+            
+            ui.horizontal(|ui| {
+                confirmed = ui.button(if enable { "Turn SMT on" } else { "Turn SMT off" }).clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+    
+    if confirmed {
+        state.config.profiles[idx].cpu_settings.smt = Some(enable);
+    }
+    if confirmed || cancelled {
+        state.pending_smt_change = None;
+    }
+}
+
+fn draw_apply_preview(ctx: &egui::Context, state: &mut AppState, profile_name: &str) {
+    let preview = match state.apply_preview {
+        Some(ref preview) => preview,
+        None => return,
+    };
+    
+    let mut open = true;
+    egui::Window::new(format!("Preview: {}", profile_name))
+        .open(&mut open)
+        .default_width(640.0)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            if let Some(ref error) = preview.error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100),
+                    format!("⚠ Applying would stop with an error: {}", error));
+                ui.add_space(6.0);
+            }
+            
+            let changes = preview.writes.iter()
+                .filter(|w| w.old_value.as_deref() != Some(w.new_value.as_str()))
+                .count();
+            ui.label(format!("{} writes, {} of them change the current value", preview.writes.len(), changes));
+            ui.add_space(6.0);
+            
+            ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("apply_preview_grid")
+                    .num_columns(3)
+                    .spacing([16.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Target").strong());
+                        ui.label(RichText::new("Current").strong());
+                        ui.label(RichText::new("New").strong());
+                        ui.end_row();
+                        
+                        for write in &preview.writes {
+                            let unchanged = write.old_value.as_deref() == Some(write.new_value.as_str());
+                            let text = |s: &str| {
+                                let text = RichText::new(s).monospace();
+                                if unchanged { text.weak() } else { text }
+                            };
+                            
+                            let target = match write.kind {
+                                WriteKind::File => write.target.clone(),
+                                WriteKind::Ioctl => format!("ioctl {}", write.target),
+                                WriteKind::Command => format!("run {}", write.target),
+                            };
+                            ui.label(text(&target));
+                            ui.label(text(write.old_value.as_deref().unwrap_or("—")));
+                            ui.label(text(&write.new_value));
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    
+    if !open {
+        state.apply_preview = None;
+    }
+}
+
+fn draw_apply_report(ctx: &egui::Context, state: &mut AppState) {
+    let report = match state.apply_report {
+        Some(ref report) if state.show_apply_report => report,
+        _ => return,
+    };
+    
+    let mut open = true;
+    egui::Window::new(format!("Applied: {}", report.profile))
+        .open(&mut open)
+        .default_width(480.0)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            if let Some(ref error) = report.error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("⚠ Stopped with an error: {}", error));
+                ui.add_space(6.0);
+            }
+            if !report.slow_writes.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(255, 180, 0),
+                    "⚠ Some writes were very slow. This usually points at a firmware (EC or ACPI) issue, a BIOS update may help.");
+                for write in &report.slow_writes {
+                    ui.label(RichText::new(format!("{:.0} ms  {}", write.millis, write.target)).monospace().small());
+                }
+                ui.add_space(6.0);
+            }
+            ui.label(format!("Total: {:.1} ms", report.total_ms));
+            ui.add_space(6.0);
+            
+            let slowest = report.steps.iter().map(|s| s.millis).fold(0.0, f64::max).max(1.0);
+            egui::Grid::new("apply_report_grid")
+                .num_columns(3)
+                .spacing([16.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for step in &report.steps {
+                        let name = if step.failed { format!("{} (failed)", step.name) } else { step.name.clone() };
+                        ui.label(name);
+                        ui.label(RichText::new(format!("{:.1} ms", step.millis)).monospace());
+                        ui.add(egui::ProgressBar::new((step.millis / slowest) as f32).desired_width(120.0));
+                        ui.end_row();
+                    }
+                });
         });
+    
+    if !open {
+        state.show_apply_report = false;
+    }
+}
+
+fn draw_feature_notice(ui: &mut Ui, state: &AppState, feature: Feature) {
+    if let Some(reason) = state.feature_unavailable(feature) {
+        ui.label(RichText::new(format!("⚠ {}", reason))
+            .color(egui::Color32::from_rgb(255, 180, 0))
+            .small());
+        if let Some(hint) = crate::error_hints::hint_for(&reason) {
+            crate::error_hints::draw_details(ui, &format!("feature_hint_{:?}", feature), &hint);
+        }
+        ui.add_space(4.0);
+    }
 }
 
 fn draw_cpu_tuning(
@@ -88,6 +404,8 @@ fn draw_cpu_tuning(
     profile: &mut Profile,
     cpu_caps: Option<&tuxedo_common::types::CpuCapabilities>,
     cpu_info: &tuxedo_common::types::CpuInfo,
+    tdp_limits: Option<&TdpLimits>,
+    pending_smt: &mut Option<bool>,
 ) {
     ui.heading("🖥️ CPU Tuning");
     ui.add_space(8.0);
@@ -228,153 +546,1164 @@ fn draw_cpu_tuning(
                 .small()
                 .italics());
         }
+        
+        // Only worth it with more than one kind of core
+        if cpu_info.boost_policies.len() > 1 {
+            draw_boost_groups(ui, profile, &cpu_info.boost_policies);
+        }
     }
     
     // SMT checkbox
     if caps.has_smt {
-        let mut smt = profile.cpu_settings.smt.unwrap_or(true);
-        ui.checkbox(&mut smt, "SMT / Hyperthreading");
-        profile.cpu_settings.smt = Some(smt);
+        let current = profile.cpu_settings.smt.unwrap_or(true);
+        let mut smt = current;
+        // Only changed after confirming, see draw_smt_confirmation
+        if ui.checkbox(&mut smt, "SMT / Hyperthreading").changed() {
+            *pending_smt = Some(smt);
+        }
+        profile.cpu_settings.smt = Some(current);
+    }
+    
+    if let Some(limits) = tdp_limits {
+        ui.add_space(6.0);
+        ui.label(RichText::new("CPU Power Limit:").strong());
+        draw_tdp_selector(ui, "cpu_tdp", &mut profile.cpu_settings.tdp, limits, "Unchanged");
     }
+    
+    ui.add_space(6.0);
+    draw_cpu_battery_overrides(ui, profile, caps, cpu_info, tdp_limits);
 }
 
-fn draw_keyboard_tuning(
+// Named presets from the EC range, with a slider for anything in between
+fn draw_boost_groups(ui: &mut Ui, profile: &mut Profile, groups: &[BoostPolicyGroup]) {
+    ui.indent("boost_groups", |ui| {
+        for group in groups {
+            let mut setting = profile.cpu_settings.boost_groups.get(&group.id).copied();
+            let text = |s: Option<bool>| match s {
+                None => "Same as above",
+                Some(true) => "On",
+                Some(false) => "Off",
+            };
+            
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", group.label()));
+                ComboBox::from_id_salt(("boost_group", group.id.as_str()))
+                    .selected_text(text(setting))
+                    .show_ui(ui, |ui| {
+                        for option in [None, Some(true), Some(false)] {
+                            ui.selectable_value(&mut setting, option, text(option));
+                        }
+                    });
+            });
+            
+            match setting {
+                Some(enabled) => profile.cpu_settings.boost_groups.insert(group.id.clone(), enabled),
+                None => profile.cpu_settings.boost_groups.remove(&group.id),
+            };
+        }
+    });
+}
+
+fn draw_tdp_selector(ui: &mut Ui, id: &str, tdp: &mut Option<u32>, limits: &TdpLimits, unset_label: &str) {
+    let preset_name = |watts: u32| limits.presets.iter()
+        .find(|p| p.watts == watts)
+        .map(|p| format!("{} ({} W)", p.name, p.watts))
+        .unwrap_or_else(|| format!("Custom ({} W)", watts));
+    
+    ui.horizontal(|ui| {
+        ComboBox::from_id_salt(id)
+            .selected_text(tdp.map(preset_name).unwrap_or_else(|| unset_label.to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(tdp, None, unset_label);
+                for preset in &limits.presets {
+                    ui.selectable_value(tdp, Some(preset.watts), format!("{} ({} W)", preset.name, preset.watts));
+                }
+                // Starts from the current value, fine-tuned with the slider
+                if ui.selectable_label(false, "Custom…").clicked() {
+                    *tdp = Some(tdp.unwrap_or(limits.current_watts).clamp(limits.min_watts, limits.max_watts));
+                }
+            });
+        
+        if let Some(watts) = tdp.as_mut() {
+            ui.add(Slider::new(watts, limits.min_watts..=limits.max_watts).suffix(" W"));
+        }
+    });
+}
+
+fn draw_cpu_battery_overrides(
     ui: &mut Ui,
     profile: &mut Profile,
-    dbus_client: Option<&DbusClient>,
+    caps: &tuxedo_common::types::CpuCapabilities,
+    cpu_info: &tuxedo_common::types::CpuInfo,
+    tdp_limits: Option<&TdpLimits>,
 ) {
-    ui.heading("⌨️ Keyboard Backlight");
-    ui.add_space(8.0);
+    let mut separate = profile.cpu_settings.on_battery.is_some();
+    if ui.checkbox(&mut separate, "Different settings on battery").changed() {
+        profile.cpu_settings.on_battery = if separate { Some(CpuBatterySettings::default()) } else { None };
+    }
     
-    ui.checkbox(&mut profile.keyboard_settings.control_enabled, "Control keyboard backlight");
-    ui.add_space(6.0);
+    let battery = match profile.cpu_settings.on_battery.as_mut() {
+        Some(battery) => battery,
+        None => return,
+    };
     
-    if profile.keyboard_settings.control_enabled {
-        // Mode selector
+    ui.label(RichText::new("Switched automatically when the power source changes, unset values follow the settings above")
+        .small().weak());
+    
+    let same_as_ac = "Same as AC".to_string();
+    
+    if caps.has_scaling_governor && !cpu_info.available_governors.is_empty() {
         ui.horizontal(|ui| {
-            ui.label("Mode:");
-            
-            let current_mode_name = match &profile.keyboard_settings.mode {
-                KeyboardMode::SingleColor { .. } => "Single Color",
-                KeyboardMode::Breathe { .. } => "Breathe",
-                KeyboardMode::Cycle { .. } => "Cycle",
-                KeyboardMode::Wave { .. } => "Wave",
-                _ => "Other",
-            };
-            
-            ComboBox::from_id_source("keyboard_mode")
-                .selected_text(current_mode_name)
+            ui.label("Governor on battery:");
+            ComboBox::from_id_salt("battery_governor_combo")
+                .selected_text(battery.governor.as_ref().unwrap_or(&same_as_ac))
                 .show_ui(ui, |ui| {
-                    if ui.selectable_label(current_mode_name == "Single Color", "Single Color").clicked() {
-                        profile.keyboard_settings.mode = KeyboardMode::SingleColor { r: 255, g: 255, b: 255, brightness: 50 };
-                    }
-                    if ui.selectable_label(current_mode_name == "Breathe", "Breathe").clicked() {
-                        profile.keyboard_settings.mode = KeyboardMode::Breathe { r: 255, g: 255, b: 255, brightness: 50, speed: 50 };
-                    }
-                    if ui.selectable_label(current_mode_name == "Cycle", "Cycle").clicked() {
-                        profile.keyboard_settings.mode = KeyboardMode::Cycle { brightness: 50, speed: 50 };
-                    }
-                    if ui.selectable_label(current_mode_name == "Wave", "Wave").clicked() {
-                        profile.keyboard_settings.mode = KeyboardMode::Wave { brightness: 50, speed: 50 };
+                    ui.selectable_value(&mut battery.governor, None, &same_as_ac);
+                    for gov in &cpu_info.available_governors {
+                        ui.selectable_value(&mut battery.governor, Some(gov.clone()), gov);
                     }
                 });
         });
-        ui.add_space(6.0);
-        
-        // Mode-specific controls
-        match &mut profile.keyboard_settings.mode {
-            KeyboardMode::SingleColor { r, g, b, brightness } => {
-                ui.horizontal(|ui| {
-                    ui.label("Red:");
-                    ui.add(Slider::new(r, 0..=255));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Green:");
-                    ui.add(Slider::new(g, 0..=255));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Blue:");
-                    ui.add(Slider::new(b, 0..=255));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Brightness:");
-                    ui.add(Slider::new(brightness, 0..=100).suffix("%"));
-                });
-                
-                // Color preview
-                let color = egui::Color32::from_rgb(*r, *g, *b);
-                ui.horizontal(|ui| {
-                    ui.label("Preview:");
-                    ui.colored_label(color, "■■■■■");
+    }
+    
+    if caps.has_energy_performance_preference && !cpu_info.available_epp_options.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label("EPP on battery:");
+            ComboBox::from_id_salt("battery_epp_combo")
+                .selected_text(battery.energy_performance_preference.as_ref().unwrap_or(&same_as_ac))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut battery.energy_performance_preference, None, &same_as_ac);
+                    for epp in &cpu_info.available_epp_options {
+                        ui.selectable_value(&mut battery.energy_performance_preference, Some(epp.clone()), epp);
+                    }
                 });
+        });
+    }
+    
+    if caps.has_scaling_max_freq {
+        let mut cap_freq = battery.max_frequency.is_some();
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut cap_freq, "Max frequency on battery").changed() {
+                battery.max_frequency = if cap_freq { Some(cpu_info.hw_max_freq) } else { None };
             }
-            _ => {}
-        }
-        
-        // Preview button
-        if ui.button("👁️ Preview").clicked() {
-            if let Some(client) = dbus_client {
-                let _ = client.preview_keyboard_settings(profile.keyboard_settings.clone());
+            if let Some(max) = battery.max_frequency {
+                let mut max_mhz = max as f64 / 1000.0;
+                if ui.add(Slider::new(&mut max_mhz,
+                    (cpu_info.hw_min_freq / 1000) as f64..=(cpu_info.hw_max_freq / 1000) as f64)
+                    .suffix(" MHz")).changed() {
+                    battery.max_frequency = Some((max_mhz * 1000.0) as u64);
+                }
             }
-        }
+        });
+    }
+    
+    if let Some(limits) = tdp_limits {
+        ui.horizontal(|ui| {
+            ui.label("Power limit on battery:");
+            draw_tdp_selector(ui, "battery_tdp", &mut battery.tdp, limits, &same_as_ac);
+        });
     }
 }
 
-fn draw_screen_tuning(ui: &mut Ui, profile: &mut Profile) {
-    ui.heading("🖥️ Screen");
+fn draw_gpu_tuning(ui: &mut Ui, profile: &mut Profile, gpus: &[GpuInfo], igpu_info: Option<&IgpuFrequencyInfo>) {
+    ui.heading("🎮 GPU Tuning");
     ui.add_space(8.0);
     
-    ui.checkbox(&mut profile.screen_settings.system_control, "Use system brightness control");
-    ui.add_space(6.0);
+    let gpu = &mut profile.gpu_settings;
+    draw_dgpu_target(ui, &mut gpu.dgpu_target, gpus);
     
-    if !profile.screen_settings.system_control {
+    let mut dynamic = gpu.dynamic_power_limit.is_some();
+    if ui.checkbox(&mut dynamic, "Dynamic dGPU power limit (by temperature)").changed() {
+        gpu.dynamic_power_limit = if dynamic {
+            Some(DynamicPowerLimit {
+                target_temp: 80,
+                min_watts: 35,
+                max_watts: gpu.dgpu_tdp.unwrap_or(80),
+                step_watts: 5,
+            })
+        } else {
+            None
+        };
+    }
+    
+    if let Some(ref mut limit) = gpu.dynamic_power_limit {
+        ui.label(RichText::new("Lowers the power limit above the target temperature and raises it again when there is headroom")
+            .small().weak());
+        ui.add_space(6.0);
+        
         ui.horizontal(|ui| {
-            ui.label("Brightness:");
-            ui.add(Slider::new(&mut profile.screen_settings.brightness, 0..=100).suffix("%"));
+            ui.label("Target temperature:");
+            ui.add(Slider::new(&mut limit.target_temp, 60..=95).suffix("°C"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Minimum power:");
+            ui.add(egui::DragValue::new(&mut limit.min_watts).range(5..=limit.max_watts).suffix(" W"));
+            ui.label("Maximum power:");
+            ui.add(egui::DragValue::new(&mut limit.max_watts).range(limit.min_watts..=250).suffix(" W"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Step:");
+            ui.add(Slider::new(&mut limit.step_watts, 1..=20).suffix(" W"));
+        });
+    } else {
+        let mut static_limit = gpu.dgpu_tdp.is_some();
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut static_limit, "Fixed dGPU power limit").changed() {
+                gpu.dgpu_tdp = if static_limit { Some(80) } else { None };
+            }
+            if let Some(ref mut tdp) = gpu.dgpu_tdp {
+                ui.add(egui::DragValue::new(tdp).range(5..=250).suffix(" W"));
+            }
         });
     }
+    
+    if let Some(info) = igpu_info {
+        ui.add_space(8.0);
+        let mut limit_igpu = gpu.igpu_max_freq.is_some();
+        if ui.checkbox(&mut limit_igpu, "Limit iGPU frequency")
+            .on_hover_text("Off leaves the current limit as it is")
+            .changed()
+        {
+            gpu.igpu_max_freq = if limit_igpu { Some(info.max_mhz) } else { None };
+        }
+        
+        if let Some(ref mut max_freq) = gpu.igpu_max_freq {
+            ui.horizontal(|ui| {
+                ui.label("Max iGPU frequency:");
+                ui.add(Slider::new(max_freq, info.min_mhz..=info.max_mhz).suffix(" MHz"));
+            });
+            ui.label(RichText::new(format!("Supported range: {} - {} MHz ({})", info.min_mhz, info.max_mhz, info.driver))
+                .small().weak());
+        }
+    }
 }
 
-fn draw_fan_tuning(ui: &mut Ui, profile: &mut Profile, fan_count: usize) {
-    ui.heading("💨 Fan Control");
+// Only asked when there is a choice: dual dGPUs or an external GPU next to the internal one
+fn draw_dgpu_target(ui: &mut Ui, target: &mut Option<String>, gpus: &[GpuInfo]) {
+    let candidates: Vec<&GpuInfo> = gpus.iter()
+        .filter(|gpu| gpu.gpu_type == GpuType::Discrete && gpu.power_limit_control)
+        .collect();
+    if candidates.len() < 2 && target.is_none() {
+        return;
+    }
+    
+    let label = |gpu: &GpuInfo| format!("{} ({}{})", gpu.name, gpu.id, if gpu.external { ", external" } else { "" });
+    let selected = match target {
+        Some(id) => candidates.iter()
+            .find(|gpu| gpu.id == *id)
+            .map(|gpu| label(gpu))
+            .unwrap_or_else(|| format!("{} (not connected)", id)),
+        None => "First dGPU found".to_string(),
+    };
+    ui.horizontal(|ui| {
+        ui.label("dGPU power limits apply to:");
+        ComboBox::from_id_salt("dgpu_target")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(target, None, "First dGPU found");
+                for gpu in &candidates {
+                    ui.selectable_value(target, Some(gpu.id.clone()), label(gpu));
+                }
+            });
+    });
+    ui.add_space(6.0);
+}
+
+fn draw_keyboard_tuning(
+    ui: &mut Ui,
+    profile: &mut Profile,
+    devices: &[KeyboardDevice],
+    has_als: bool,
+    dbus_client: Option<&DbusClient>,
+) {
+    ui.heading("⌨️ Keyboard Backlight");
     ui.add_space(8.0);
     
-    ui.checkbox(&mut profile.fan_settings.control_enabled, "Enable custom fan curves");
+    ui.checkbox(&mut profile.keyboard_settings.control_enabled, "Control keyboard backlight");
     ui.add_space(6.0);
     
-    if profile.fan_settings.control_enabled {
-        // Ensure curves exist
-        while profile.fan_settings.curves.len() < fan_count {
-            let fan_id = profile.fan_settings.curves.len() as u32;
-            profile.fan_settings.curves.push(FanCurve {
-                fan_id,
-                points: vec![(0, 0), (50, 50), (70, 75), (85, 100)],
-            });
-        }
+    if profile.keyboard_settings.control_enabled {
+        // Show the RGB controls until the daemon reported its devices
+        let any_rgb = devices.is_empty() || devices.iter().any(|d| d.rgb);
+        draw_keyboard_mode(ui, "keyboard_mode", &mut profile.keyboard_settings.mode, any_rgb);
         
-        // Show editor for each fan
-        for curve in profile.fan_settings.curves.iter_mut() {
-            if (curve.fan_id as usize) < fan_count {
-                ui.separator();
-                ui.add_space(8.0);
+        // With more than one backlight each one can get its own settings
+        if devices.len() > 1 {
+            ui.add_space(8.0);
+            for device in devices {
+                let label = format!("{} ({}{})", device.name,
+                    if device.external { "USB keyboard" } else { "built-in" },
+                    if device.rgb { ", RGB" } else { "" });
+                let entry = profile.keyboard_settings.devices.iter().position(|d| d.device == device.name);
                 
-                egui::CollapsingHeader::new(format!("Fan {} Configuration", curve.fan_id))
-                    .default_open(curve.fan_id == 0)
-                    .show(ui, |ui| {
-                        let mut editor = FanCurveEditor::new(curve.fan_id, curve.clone());
-                        editor.show(ui);
-                        *curve = editor.get_curve();
+                let mut separate = entry.is_some();
+                if ui.checkbox(&mut separate, format!("Separate settings for {}", label)).changed() {
+                    if separate {
+                        profile.keyboard_settings.devices.push(KeyboardDeviceSettings {
+                            device: device.name.clone(),
+                            mode: profile.keyboard_settings.mode.clone(),
+                        });
+                    } else if let Some(i) = entry {
+                        profile.keyboard_settings.devices.remove(i);
+                    }
+                }
+                
+                if let Some(settings) = profile.keyboard_settings.devices.iter_mut().find(|d| d.device == device.name) {
+                    ui.indent(&device.name, |ui| {
+                        draw_keyboard_mode(ui, &device.name, &mut settings.mode, device.rgb);
                     });
+                }
+            }
+            ui.add_space(6.0);
+        }
+        
+        if has_als {
+            draw_keyboard_ambient(ui, &mut profile.keyboard_settings.ambient);
+        }
+        
+        // Preview button
+        if ui.button("👁️ Preview").clicked() {
+            if let Some(client) = dbus_client {
+                let _ = client.preview_keyboard_settings(profile.keyboard_settings.clone());
             }
         }
     }
 }
 
-fn create_default_profile_for_reset(is_standard: bool) -> Profile {
-    use tuxedo_common::types::*;
-    
-    if is_standard {
-        Profile {
+fn draw_keyboard_ambient(ui: &mut Ui, ambient: &mut AmbientKeyboardSettings) {
+    ui.checkbox(&mut ambient.enabled, "Dim with ambient light")
+        .on_hover_text("Scales the brightness above with the ambient light sensor, brightest in the dark");
+    if ambient.enabled {
+        ui.indent("keyboard_ambient", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Full brightness below:");
+                ui.add(Slider::new(&mut ambient.dark_lux, 0..=200).suffix(" lx"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Off above:");
+                ui.add(Slider::new(&mut ambient.bright_lux, 50..=2000).logarithmic(true).suffix(" lx"));
+            });
+            if ambient.bright_lux <= ambient.dark_lux {
+                ambient.bright_lux = ambient.dark_lux + 1;
+            }
+        });
+    }
+    ui.add_space(6.0);
+}
+
+fn draw_keyboard_mode(ui: &mut Ui, id_salt: &str, mode: &mut KeyboardMode, rgb: bool) {
+    if !rgb {
+        // White-only backlight, brightness is all there is to set
+        let mut brightness = mode.brightness();
+        ui.horizontal(|ui| {
+            ui.label("Brightness:");
+            if ui.add(Slider::new(&mut brightness, 0..=100).suffix("%")).changed()
+                || !matches!(mode, KeyboardMode::SingleColor { .. })
+            {
+                *mode = KeyboardMode::SingleColor { r: 255, g: 255, b: 255, brightness };
+            }
+        });
+        ui.add_space(6.0);
+        return;
+    }
+    
+    // Mode selector
+    ui.horizontal(|ui| {
+        ui.label("Mode:");
+        
+        let current_mode_name = match mode {
+            KeyboardMode::SingleColor { .. } => "Single Color",
+            KeyboardMode::Breathe { .. } => "Breathe",
+            KeyboardMode::Cycle { .. } => "Cycle",
+            KeyboardMode::Wave { .. } => "Wave",
+            _ => "Other",
+        };
+        
+        ComboBox::from_id_salt(id_salt)
+            .selected_text(current_mode_name)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(current_mode_name == "Single Color", "Single Color").clicked() {
+                    *mode = KeyboardMode::SingleColor { r: 255, g: 255, b: 255, brightness: 50 };
+                }
+                if ui.selectable_label(current_mode_name == "Breathe", "Breathe").clicked() {
+                    *mode = KeyboardMode::Breathe { r: 255, g: 255, b: 255, brightness: 50, speed: 50 };
+                }
+                if ui.selectable_label(current_mode_name == "Cycle", "Cycle").clicked() {
+                    *mode = KeyboardMode::Cycle { brightness: 50, speed: 50 };
+                }
+                if ui.selectable_label(current_mode_name == "Wave", "Wave").clicked() {
+                    *mode = KeyboardMode::Wave { brightness: 50, speed: 50 };
+                }
+            });
+    });
+    ui.add_space(6.0);
+    
+    // Mode-specific controls
+    if let KeyboardMode::SingleColor { r, g, b, brightness } = mode {
+        ui.horizontal(|ui| {
+            ui.label("Red:");
+            ui.add(Slider::new(r, 0..=255));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Green:");
+            ui.add(Slider::new(g, 0..=255));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Blue:");
+            ui.add(Slider::new(b, 0..=255));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Brightness:");
+            ui.add(Slider::new(brightness, 0..=100).suffix("%"));
+        });
+        
+        // Color preview
+        let color = egui::Color32::from_rgb(*r, *g, *b);
+        ui.horizontal(|ui| {
+            ui.label("Preview:");
+            ui.colored_label(color, "■■■■■");
+        });
+    }
+}
+
+fn draw_lightbar_tuning(ui: &mut Ui, profile: &mut Profile) {
+    ui.heading("💡 Lightbar");
+    ui.add_space(8.0);
+    
+    let lightbar = &mut profile.lightbar_settings;
+    ui.checkbox(&mut lightbar.control_enabled, "Control lightbar");
+    
+    if lightbar.control_enabled {
+        ui.checkbox(&mut lightbar.on, "Lightbar on");
+        
+        if lightbar.on {
+            ui.checkbox(&mut lightbar.animation, "Color animation");
+            
+            if !lightbar.animation {
+                let mut color = [lightbar.r, lightbar.g, lightbar.b];
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    if ui.color_edit_button_srgb(&mut color).changed() {
+                        [lightbar.r, lightbar.g, lightbar.b] = color;
+                    }
+                });
+            }
+        }
+    }
+}
+
+fn draw_indicator_tuning(ui: &mut Ui, profile: &mut Profile, leds: &[String]) {
+    ui.heading("🔔 Indicators");
+    ui.add_space(8.0);
+    
+    let disabled = &mut profile.indicator_settings.disabled_leds;
+    for led in leds {
+        let label = match led.rsplit(':').next().unwrap_or("") {
+            "mute" => "Mute LED",
+            "micmute" => "Microphone mute LED",
+            "power" => "Power LED",
+            "charging" => "Charging LED",
+            _ => led.as_str(),
+        };
+        
+        let mut on = !disabled.contains(led);
+        if ui.checkbox(&mut on, label).on_hover_text(led).changed() {
+            if on {
+                disabled.retain(|l| l != led);
+            } else {
+                disabled.push(led.clone());
+            }
+        }
+    }
+    ui.label(RichText::new("Turned off LEDs stay dark until a profile that leaves them on is applied")
+        .small().weak());
+    ui.label(RichText::new("Power LED breathing during suspend is set by the firmware and can't be changed here")
+        .small().weak());
+}
+
+fn draw_input_tuning(ui: &mut Ui, profile: &mut Profile) {
+    ui.heading("🖱 Input Devices");
+    ui.add_space(8.0);
+    
+    let input = &mut profile.input_settings;
+    
+    let mut limit_polling = input.mouse_poll_interval.is_some();
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut limit_polling, "USB mouse polling interval").changed() {
+            input.mouse_poll_interval = if limit_polling { Some(8) } else { None };
+        }
+        if let Some(ref mut interval) = input.mouse_poll_interval {
+            ui.add(Slider::new(interval, 1..=16).suffix(" ms"));
+        }
+    });
+    if input.mouse_poll_interval.is_some() {
+        ui.label(RichText::new("Longer intervals save power, takes effect after re-plugging the mouse")
+            .small().weak());
+    }
+    
+    let label = |value: Option<bool>| match value {
+        None => "Unchanged",
+        Some(true) => "Allowed",
+        Some(false) => "Off",
+    };
+    ui.horizontal(|ui| {
+        ui.label("USB autosuspend for keyboards and mice:");
+        ComboBox::from_id_salt("usb_hid_autosuspend")
+            .selected_text(label(input.usb_hid_autosuspend))
+            .show_ui(ui, |ui| {
+                for value in [None, Some(true), Some(false)] {
+                    ui.selectable_value(&mut input.usb_hid_autosuspend, value, label(value));
+                }
+            });
+    });
+    ui.checkbox(&mut input.keyboard_backlight_off_on_battery, "Turn keyboard backlight off on battery");
+}
+
+fn draw_network_tuning(ui: &mut Ui, profile: &mut Profile, interfaces: &[String]) {
+    ui.heading("📶 Network");
+    ui.add_space(8.0);
+    
+    let network = &mut profile.network_settings;
+    let label = |value: Option<bool>| match value {
+        None => "Unchanged",
+        Some(true) => "On",
+        Some(false) => "Off",
+    };
+    
+    ui.horizontal(|ui| {
+        ui.label("WiFi power save:");
+        ComboBox::from_id_salt("wifi_power_save")
+            .selected_text(label(network.wifi_power_save))
+            .show_ui(ui, |ui| {
+                for value in [None, Some(true), Some(false)] {
+                    ui.selectable_value(&mut network.wifi_power_save, value, label(value));
+                }
+            });
+    });
+    ui.label(RichText::new("Power save extends battery life at the cost of higher latency")
+        .small().weak());
+    
+    ui.add_space(8.0);
+    ui.label("Turn off when this profile is applied:");
+    ui.horizontal_wrapped(|ui| {
+        for (radio, name) in [
+            (RadioType::Wifi, "WiFi radio"),
+            (RadioType::Bluetooth, "Bluetooth"),
+            (RadioType::Wwan, "Mobile broadband"),
+        ] {
+            let mut blocked = network.blocked_radios.contains(&radio);
+            if ui.checkbox(&mut blocked, name).changed() {
+                if blocked {
+                    network.blocked_radios.push(radio);
+                } else {
+                    network.blocked_radios.retain(|r| *r != radio);
+                }
+            }
+        }
+    });
+    
+    if !interfaces.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Interfaces:");
+            for interface in interfaces {
+                let mut disabled = network.disabled_interfaces.contains(interface);
+                if ui.checkbox(&mut disabled, interface).changed() {
+                    if disabled {
+                        network.disabled_interfaces.push(interface.clone());
+                    } else {
+                        network.disabled_interfaces.retain(|i| i != interface);
+                    }
+                }
+            }
+        });
+    }
+    ui.label(RichText::new("Radios and interfaces are turned back on when switching to a profile that doesn't disable them")
+        .small().weak());
+}
+
+fn draw_vm_tuning(ui: &mut Ui, profile: &mut Profile) {
+    ui.heading("🧠 Memory");
+    ui.add_space(8.0);
+    
+    let vm = &mut profile.vm_settings;
+    
+    let mut custom_swappiness = vm.swappiness.is_some();
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut custom_swappiness, "Swappiness").changed() {
+            vm.swappiness = if custom_swappiness { Some(60) } else { None };
+        }
+        if let Some(ref mut swappiness) = vm.swappiness {
+            ui.add(Slider::new(swappiness, 0..=100));
+        }
+    });
+    
+    let mut custom_writeback = vm.dirty_writeback_centisecs.is_some();
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut custom_writeback, "Dirty page writeback interval").changed() {
+            vm.dirty_writeback_centisecs = if custom_writeback { Some(500) } else { None };
+        }
+        if let Some(centisecs) = vm.dirty_writeback_centisecs {
+            let mut seconds = centisecs as f32 / 100.0;
+            if ui.add(Slider::new(&mut seconds, 1.0..=60.0).suffix(" s")).changed() {
+                vm.dirty_writeback_centisecs = Some((seconds * 100.0).round() as u32);
+            }
+        }
+    });
+    ui.label(RichText::new("Longer writeback intervals let the disk sleep, at the risk of losing more data on a crash")
+        .small().weak());
+}
+
+// `ambient` is None without an ambient light sensor, Some(None) until the first reading arrived
+fn draw_screen_tuning(ui: &mut Ui, profile: &mut Profile, ambient: Option<Option<AmbientLightReading>>,
+    displays: &[DisplayInfo]) {
+    ui.heading("🖥️ Screen");
+    ui.add_space(8.0);
+    
+    ui.checkbox(&mut profile.screen_settings.system_control, "Use system brightness control");
+    ui.add_space(6.0);
+    
+    if !profile.screen_settings.system_control {
+        ui.horizontal(|ui| {
+            ui.label("Brightness:");
+            ui.add(Slider::new(&mut profile.screen_settings.brightness, 0..=100).suffix("%"));
+        });
+        if let Some(reading) = ambient {
+            draw_screen_ambient(ui, &mut profile.screen_settings.ambient, reading.as_ref());
+        }
+    }
+    
+    ui.add_space(6.0);
+    let mut warm = profile.screen_settings.color_temperature.is_some();
+    if ui.checkbox(&mut warm, "Set color temperature").changed() {
+        profile.screen_settings.color_temperature = if warm { Some(4000) } else { None };
+    }
+    if let Some(ref mut kelvin) = profile.screen_settings.color_temperature {
+        ui.horizontal(|ui| {
+            ui.label("Color temperature:");
+            ui.add(Slider::new(kelvin, 2500..=6500).step_by(100.0).suffix(" K"));
+        });
+        ui.label(RichText::new("Uses the desktop's night light (GNOME, KDE Plasma), gammastep/hyprsunset on \
+            other Wayland compositors or xrandr on X11. Profiles without it reset the display to neutral.")
+            .small().weak());
+    }
+    
+    // Rates from the panel's EDID, a single one leaves nothing to choose
+    if let Some(panel) = displays.iter().find(|d| d.internal && d.refresh_rates.len() > 1) {
+        ui.add_space(6.0);
+        let rate_label = |rate: Option<u32>| rate.map_or("Desktop default".to_string(), |hz| format!("{} Hz", hz));
+        ui.horizontal(|ui| {
+            ui.label("Refresh rate:");
+            ComboBox::from_id_salt("refresh_rate_combo")
+                .selected_text(rate_label(profile.screen_settings.refresh_rate))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut profile.screen_settings.refresh_rate, None, rate_label(None));
+                    for hz in &panel.refresh_rates {
+                        ui.selectable_value(&mut profile.screen_settings.refresh_rate, Some(*hz), rate_label(Some(*hz)));
+                    }
+                });
+        });
+        if profile.screen_settings.refresh_rate.is_some() {
+            ui.label(RichText::new("Applied to the built-in display through the desktop (GNOME, KDE Plasma), \
+                wlr-randr on other Wayland compositors or xrandr on X11. Profiles without it switch back.")
+                .small().weak());
+        }
+    }
+    
+    ui.add_space(6.0);
+    let mut custom_timeout = profile.screen_settings.blank_timeout.is_some();
+    if ui.checkbox(&mut custom_timeout, "Set screen blank timeout").changed() {
+        profile.screen_settings.blank_timeout = if custom_timeout { Some(300) } else { None };
+    }
+    
+    if let Some(seconds) = profile.screen_settings.blank_timeout {
+        let mut minutes = (seconds / 60).max(1);
+        ui.horizontal(|ui| {
+            ui.label("Blank screen after:");
+            if ui.add(Slider::new(&mut minutes, 1..=60).suffix(" min")).changed() {
+                profile.screen_settings.blank_timeout = Some(minutes * 60);
+            }
+        });
+        ui.label(RichText::new("Applied to the desktop session (GNOME, Cinnamon, KDE Plasma), profiles without it switch back").small().weak());
+    }
+}
+
+fn draw_screen_ambient(ui: &mut Ui, ambient: &mut AmbientScreenSettings, reading: Option<&AmbientLightReading>) {
+    ui.checkbox(&mut ambient.enabled, "Follow ambient light")
+        .on_hover_text("The daemon sets the brightness from the ambient light sensor, \
+            brightness keys pause it for 10 minutes");
+    if !ambient.enabled {
+        return;
+    }
+    
+    ui.indent("screen_ambient", |ui| {
+        // Calibration: set the brightness you like with the keys, then take the reading over
+        let lux = reading.and_then(|r| r.lux);
+        let screen = reading.and_then(|r| r.screen_percent);
+        ui.horizontal(|ui| {
+            ui.label(match lux {
+                Some(lux) => format!("Now: {:.0} lx", lux),
+                None => "Now: no reading".to_string(),
+            });
+            if let Some(screen) = screen {
+                ui.label(format!("at {}% brightness", screen));
+            }
+            if let (Some(lux), Some(screen)) = (lux, screen) {
+                if ui.button("📍 Add current reading")
+                    .on_hover_text("Adjust the brightness by hand first, then store it for the current light level")
+                    .clicked() {
+                    let lux = lux.round() as u32;
+                    ambient.curve.retain(|p| p.0 != lux);
+                    ambient.curve.push((lux, screen));
+                    ambient.curve.sort_by_key(|p| p.0);
+                }
+            }
+        });
+        
+        // Rows keep their order while editing, the daemon sorts the points
+        let mut remove = None;
+        egui::Grid::new("screen_ambient_curve").num_columns(3).spacing([12.0, 4.0]).show(ui, |ui| {
+            ui.label(RichText::new("Ambient").small().weak());
+            ui.label(RichText::new("Brightness").small().weak());
+            ui.end_row();
+            for (i, (lux, percent)) in ambient.curve.iter_mut().enumerate() {
+                ui.add(egui::DragValue::new(lux).range(0..=100_000).suffix(" lx"));
+                ui.add(Slider::new(percent, 1..=100).suffix("%"));
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = remove {
+            ambient.curve.remove(i);
+        }
+        
+        ui.horizontal(|ui| {
+            if ui.button("➕ Add point").clicked() {
+                let (lux, percent) = ambient.curve.last().copied().unwrap_or((0, 50));
+                ambient.curve.push((lux.saturating_mul(10).max(10), percent));
+            }
+            if ui.button("↺ Default curve").clicked() {
+                ambient.curve = AmbientScreenSettings::default().curve;
+            }
+        });
+    });
+    ui.add_space(6.0);
+}
+
+// Live readings the curve editors show on top of the plot
+struct CurveReadings<'a> {
+    sensors: &'a [TemperatureSensor],
+    fan_info: &'a [FanInfo],
+    ranges: &'a HashMap<String, (f32, f32)>,
+    min_duty: &'a [FanMinDuty],
+}
+
+impl CurveReadings<'_> {
+    fn sensor_id(curve: &FanCurve) -> String {
+        curve.temp_source.clone().unwrap_or_else(|| fan_sensor_id(curve.fan_id))
+    }
+    
+    fn current_temp(&self, curve: &FanCurve) -> Option<f32> {
+        match curve.temp_source {
+            Some(ref id) => self.sensors.iter().find(|s| &s.id == id).map(|s| s.temperature),
+            None => self.fan_info.iter().find(|f| f.id == curve.fan_id).and_then(|f| f.temperature),
+        }
+    }
+    
+    fn current_duty(&self, fan_id: u32) -> Option<u32> {
+        self.fan_info.iter().find(|f| f.id == fan_id && !f.is_rpm).map(|f| f.rpm_or_percent)
+    }
+    
+    fn range(&self, curve: &FanCurve) -> Option<(f32, f32)> {
+        self.ranges.get(&Self::sensor_id(curve)).copied()
+    }
+    
+    fn min_duty(&self, fan_id: u32) -> Option<u8> {
+        self.min_duty.iter().find(|f| f.fan_id == fan_id).map(|f| f.percent)
+    }
+}
+
+fn draw_fan_tuning(
+    ui: &mut Ui,
+    profile: &mut Profile,
+    fan_count: usize,
+    max_points: Option<usize>,
+    readings: &CurveReadings,
+    observed_axis: &mut bool,
+    other_profiles: &[(String, FanSettings)],
+) {
+    ui.heading("💨 Fan Control");
+    ui.add_space(8.0);
+    
+    ui.checkbox(&mut profile.fan_settings.control_enabled, "Enable custom fan curves");
+    ui.checkbox(observed_axis, "Scale temperature axis to observed readings")
+        .on_hover_text("Zooms the curve editors to the temperatures seen since the app started");
+    ui.add_space(6.0);
+    
+    if !other_profiles.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label("Copy curves from profile:");
+            ComboBox::from_id_salt("copy_curves_from_profile")
+                .selected_text("Select profile")
+                .show_ui(ui, |ui| {
+                    for (name, fan_settings) in other_profiles {
+                        if ui.selectable_label(false, name).clicked() {
+                            profile.fan_settings.copy_curves_from(fan_settings);
+                        }
+                    }
+                });
+        });
+        ui.add_space(6.0);
+    }
+    
+    draw_fan_offsets(ui, &mut profile.fan_settings, fan_count);
+    
+    if profile.fan_settings.control_enabled {
+        draw_fan_smoothing(ui, &mut profile.fan_settings.smoothing);
+        
+        // Ensure curves exist
+        while profile.fan_settings.curves.len() < fan_count {
+            let fan_id = profile.fan_settings.curves.len() as u32;
+            profile.fan_settings.curves.push(FanCurve {
+                fan_id,
+                points: vec![(0, 0), (50, 50), (70, 75), (85, 100)],
+                temp_source: None,
+            });
+        }
+        
+        // Copying is applied after the loop, the curves are borrowed by the editors
+        let mut copy_request: Option<(u32, u32)> = None;
+        
+        // Show editor for each fan
+        for curve in profile.fan_settings.curves.iter_mut() {
+            if (curve.fan_id as usize) < fan_count {
+                ui.separator();
+                ui.add_space(8.0);
+                
+                egui::CollapsingHeader::new(format!("Fan {} Configuration", curve.fan_id))
+                    .default_open(curve.fan_id == 0)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            draw_temp_source_selector(ui, curve, readings.sensors);
+                            ui.menu_button("📋 Copy curve to", |ui| {
+                                for target in (0..fan_count as u32).filter(|id| *id != curve.fan_id) {
+                                    if ui.button(format!("Fan {}", target)).clicked() {
+                                        copy_request = Some((curve.fan_id, target));
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                        });
+                        ui.add_space(6.0);
+                        
+                        let mut editor = FanCurveEditor::new(curve.fan_id, curve.clone())
+                            .with_max_points(max_points)
+                            .with_temp_range(readings.range(curve).filter(|_| *observed_axis))
+                            .with_current_temp(readings.current_temp(curve))
+                            .with_current_duty(readings.current_duty(curve.fan_id))
+                            .with_min_duty(readings.min_duty(curve.fan_id));
+                        editor.show(ui);
+                        *curve = editor.get_curve();
+                    });
+            }
+        }
+        
+        if let Some((from, to)) = copy_request {
+            profile.fan_settings.copy_curve(from, to);
+        }
+    }
+}
+
+fn draw_fan_offsets(ui: &mut Ui, fan_settings: &mut FanSettings, fan_count: usize) {
+    egui::CollapsingHeader::new("Fan offsets")
+        .default_open(!fan_settings.offsets.is_empty())
+        .show(ui, |ui| {
+            if fan_settings.control_enabled {
+                ui.label(RichText::new("Shifts each custom curve by a fixed amount").small());
+            } else {
+                ui.label(RichText::new("The EC's automatic fan table can't be shifted, offsets apply to custom curves only")
+                    .small().italics());
+            }
+            ui.add_space(4.0);
+            
+            for fan_id in 0..fan_count as u32 {
+                let mut offset = fan_settings.offset(fan_id);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Fan {}:", fan_id));
+                    ui.add(egui::Slider::new(&mut offset, -30..=30).suffix("%"));
+                    if offset != 0 && ui.small_button("Reset").clicked() {
+                        offset = 0;
+                    }
+                });
+                if offset != fan_settings.offset(fan_id) {
+                    fan_settings.set_offset(fan_id, offset);
+                }
+            }
+        });
+    ui.add_space(6.0);
+}
+
+fn draw_fan_smoothing(ui: &mut Ui, smoothing: &mut FanSmoothing) {
+    egui::CollapsingHeader::new("Curve smoothing")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Keeps the fans from speeding up and slowing down around a curve point").small());
+            ui.add_space(4.0);
+            
+            egui::Grid::new("fan_smoothing").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Hysteresis:");
+                ui.add(Slider::new(&mut smoothing.hysteresis, 0..=10).suffix("°C"))
+                    .on_hover_text("How far the temperature has to fall before a fan slows down again");
+                ui.end_row();
+                
+                ui.label("Ramp up:");
+                ui.add(Slider::new(&mut smoothing.ramp_up, 0..=50).suffix("%/s")
+                    .custom_formatter(|v, _| if v == 0.0 { "Instant".to_string() } else { format!("{}", v) }));
+                ui.end_row();
+                
+                ui.label("Ramp down:");
+                ui.add(Slider::new(&mut smoothing.ramp_down, 0..=50).suffix("%/s")
+                    .custom_formatter(|v, _| if v == 0.0 { "Instant".to_string() } else { format!("{}", v) }));
+                ui.end_row();
+                
+                ui.label("Sampling interval:");
+                ui.add(Slider::new(&mut smoothing.interval_ms, 250..=5000).step_by(250.0).suffix(" ms"));
+                ui.end_row();
+            });
+            
+            if *smoothing != FanSmoothing::default() && ui.small_button("Reset to defaults").clicked() {
+                *smoothing = FanSmoothing::default();
+            }
+        });
+    ui.add_space(6.0);
+}
+
+// Floors belong to the machine, not the profile, the daemon applies them to every curve
+fn draw_fan_min_duty(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>, fan_count: usize,
+    min_duty: &[FanMinDuty]) {
+    let status = state.fan_calibration_status.clone().unwrap_or_default();
+    egui::CollapsingHeader::new("Minimum fan duty")
+        .default_open(status.running)
+        .show(ui, |ui| {
+            ui.label(RichText::new("Some ECs stall a fan at low duty. Non-zero speeds below the floor \
+                are raised to it, 0% still stops the fan. Calibration lowers the speed until the fan stops.")
+                .small());
+            ui.add_space(4.0);
+            
+            for fan_id in 0..fan_count as u32 {
+                let current = min_duty.iter().find(|f| f.fan_id == fan_id);
+                let mut percent = current.map(|f| f.percent).unwrap_or(0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Fan {}:", fan_id));
+                    let response = ui.add(egui::Slider::new(&mut percent, 0..=60).suffix("%"));
+                    // Sent once the slider is let go, not on every step of a drag
+                    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                        if let Some(client) = dbus_client {
+                            state.pending_min_duty_change = Some(client.set_fan_min_duty(fan_id, percent));
+                        }
+                    }
+                    if current.is_some_and(|f| f.calibrated) {
+                        ui.label(RichText::new("measured").small().weak());
+                    }
+                    
+                    let calibrating = status.running && status.fan_id == Some(fan_id);
+                    if ui.add_enabled(!status.running, egui::Button::new("📏 Calibrate"))
+                        .on_hover_text("Runs the fan down in steps, takes about half a minute")
+                        .clicked()
+                    {
+                        if let Some(client) = dbus_client {
+                            let _rx = client.start_fan_calibration(fan_id);
+                            state.show_message(format!("Calibrating fan {}", fan_id), false);
+                        }
+                    }
+                    if calibrating {
+                        ui.add(egui::ProgressBar::new(status.progress_percent as f32 / 100.0)
+                            .text(status.message.clone())
+                            .desired_width(200.0));
+                    }
+                });
+            }
+            
+            if !status.running && !status.message.is_empty() {
+                ui.label(RichText::new(format!("Last calibration: {}", status.message)).small());
+            }
+        });
+    ui.add_space(6.0);
+}
+
+fn draw_temp_source_selector(ui: &mut Ui, curve: &mut FanCurve, sensors: &[TemperatureSensor]) {
+    let selected_text = match curve.temp_source {
+        Some(ref id) => sensors.iter()
+            .find(|s| &s.id == id)
+            .map(|s| s.label.clone())
+            .unwrap_or_else(|| id.clone()),
+        None => "Fan sensor (default)".to_string(),
+    };
+    
+    ui.label("Temperature source:");
+    ComboBox::from_id_salt(format!("fan_temp_source_{}", curve.fan_id))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut curve.temp_source, None, "Fan sensor (default)");
+            for sensor in sensors {
+                ui.selectable_value(
+                    &mut curve.temp_source,
+                    Some(sensor.id.clone()),
+                    format!("{} ({:.0}°C)", sensor.label, sensor.temperature),
+                );
+            }
+        });
+}
+
+fn draw_aquaris_tuning(ui: &mut Ui, profile: &mut Profile, status: &AquarisStatus) {
+    ui.heading("💧 Aquaris Water Cooler");
+    ui.add_space(8.0);
+    
+    let connection = if status.connected { "connected" } else { "not connected" };
+    ui.label(RichText::new(format!("{} ({}), {}", status.name, status.address, connection)).weak());
+    ui.add_space(6.0);
+    
+    let mut control = profile.aquaris_settings.is_some();
+    if ui.checkbox(&mut control, "Control the cooler with this profile").changed() {
+        profile.aquaris_settings = control.then(|| AquarisSettings {
+            fan_duty: 50,
+            pump_duty: 60,
+            pump_voltage: PumpVoltage::default(),
+        });
+    }
+    
+    if let Some(ref mut settings) = profile.aquaris_settings {
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.label("Fan speed:");
+            ui.add(Slider::new(&mut settings.fan_duty, 0..=100).suffix("%"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Pump speed:");
+            ui.add(Slider::new(&mut settings.pump_duty, 0..=100).suffix("%"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Pump voltage:");
+            ComboBox::from_id_salt("aquaris_pump_voltage")
+                .selected_text(pump_voltage_label(settings.pump_voltage))
+                .show_ui(ui, |ui| {
+                    for voltage in [PumpVoltage::V7, PumpVoltage::V8, PumpVoltage::V11, PumpVoltage::V12] {
+                        ui.selectable_value(&mut settings.pump_voltage, voltage, pump_voltage_label(voltage));
+                    }
+                });
+        });
+        ui.label(RichText::new("0% turns the fan or pump off. Skipped while the cooler isn't connected, pair and connect it in the Bluetooth settings")
+            .small().weak());
+    }
+}
+
+fn pump_voltage_label(voltage: PumpVoltage) -> &'static str {
+    match voltage {
+        PumpVoltage::V7 => "7 V",
+        PumpVoltage::V8 => "8 V",
+        PumpVoltage::V11 => "11 V",
+        PumpVoltage::V12 => "12 V",
+    }
+}
+
+fn draw_clamshell_override(ui: &mut Ui, state: &mut AppState, idx: usize) {
+    ui.heading("💻 Clamshell Mode");
+    ui.add_space(8.0);
+    
+    if let Some(ref status) = state.lid_status {
+        let text = if status.clamshell {
+            "Lid closed with external display"
+        } else if status.lid_closed {
+            "Lid closed"
+        } else {
+            "Lid open"
+        };
+        ui.label(RichText::new(format!("Current state: {}{}", text,
+            if status.override_active { " (override active)" } else { "" })).weak());
+        ui.add_space(6.0);
+    }
+    
+    let override_settings = &mut state.config.profiles[idx].clamshell_override;
+    ui.checkbox(&mut override_settings.enabled,
+        "Override settings while the lid is closed with an external display or dock");
+    
+    if override_settings.enabled {
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.label("Minimum fan speed:");
+            ui.add(Slider::new(&mut override_settings.fan_min_speed, 0..=100).suffix("%"));
+        });
+        ui.label(RichText::new("Applied on top of custom fan curves, the keyboard vent is covered when the lid is closed")
+            .small().weak());
+        
+        if !state.available_tdp_profiles.is_empty() {
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.label("TDP profile:");
+                ComboBox::from_id_salt("clamshell_tdp_profile")
+                    .selected_text(override_settings.tdp_profile.clone()
+                        .unwrap_or_else(|| "Unchanged".to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut override_settings.tdp_profile, None, "Unchanged");
+                        for tdp in &state.available_tdp_profiles {
+                            ui.selectable_value(&mut override_settings.tdp_profile, Some(tdp.clone()), tdp);
+                        }
+                    });
+            });
+        }
+    }
+}
+
+fn draw_dust_cleaning(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
+    ui.heading("🧹 Dust Cleaning");
+    ui.add_space(8.0);
+    
+    ui.label(RichText::new("Cycles the fans between stopped and full speed to loosen dust. \
+        Fans are never stopped while the system is warm, and the routine aborts on high temperatures.")
+        .small()
+        .italics());
+    ui.add_space(6.0);
+    
+    let status = state.dust_cleaning_status.clone().unwrap_or_default();
+    
+    if status.running {
+        ui.add(
+            egui::ProgressBar::new(status.progress_percent as f32 / 100.0)
+                .text(format!("Cycle {}/{} - {}", status.current_cycle, status.total_cycles, status.message))
+        );
+        ui.add_space(6.0);
+        
+        if ui.button("⏹ Abort").clicked() {
+            if let Some(client) = dbus_client {
+                let _rx = client.abort_dust_cleaning();
+                state.show_message("Aborting dust cleaning", false);
+            }
+        }
+    } else {
+        ui.horizontal(|ui| {
+            ui.label("Cycles:");
+            ui.add(Slider::new(&mut state.dust_cleaning_cycles, 1..=10));
+            
+            if ui.button("▶ Start Cleaning").clicked() {
+                if let Some(client) = dbus_client {
+                    let _rx = client.start_dust_cleaning(state.dust_cleaning_cycles);
+                    state.show_message("Dust cleaning started", false);
+                }
+            }
+        });
+        
+        if !status.message.is_empty() {
+            ui.label(RichText::new(format!("Last run: {}", status.message)).small());
+        }
+    }
+}
+
+pub fn create_default_profile_for_reset(is_standard: bool) -> Profile {
+    use tuxedo_common::types::*;
+    
+    if is_standard {
+        Profile {
             name: "Standard".to_string(),
             is_default: true,
             cpu_settings: CpuSettings {
@@ -382,14 +1711,16 @@ fn create_default_profile_for_reset(is_standard: bool) -> Profile {
                 min_frequency: None,
                 max_frequency: None,
                 boost: Some(true),
+                boost_groups: Default::default(),
                 smt: Some(true),
                 performance_profile: None,
                 tdp_profile: None,
                 energy_performance_preference: Some("balance_performance".to_string()),
                 tdp: None,
                 amd_pstate_status: Some("active".to_string()),
+                on_battery: None,
             },
-            gpu_settings: GpuSettings { dgpu_tdp: None },
+            gpu_settings: GpuSettings::default(),
             keyboard_settings: KeyboardSettings {
                 control_enabled: false,
                 mode: KeyboardMode::SingleColor {
@@ -398,15 +1729,25 @@ fn create_default_profile_for_reset(is_standard: bool) -> Profile {
                     b: 255,
                     brightness: 50,
                 },
+                devices: Vec::new(),
+                ambient: Default::default(),
             },
             screen_settings: ScreenSettings {
                 brightness: 50,
                 system_control: true,
+                blank_timeout: None,
+                ambient: Default::default(),
+                color_temperature: None,
+                refresh_rate: None,
             },
-            fan_settings: FanSettings {
-                control_enabled: false,
-                curves: vec![],
-            },
+            fan_settings: FanSettings::default(),
+            clamshell_override: ClamshellOverride::default(),
+            input_settings: InputSettings::default(),
+            network_settings: NetworkSettings::default(),
+            vm_settings: VmSettings::default(),
+            lightbar_settings: LightBarSettings::default(),
+            indicator_settings: IndicatorSettings::default(),
+            aquaris_settings: None,
         }
     } else {
         Profile::default()
@@ -0,0 +1,101 @@
+use egui::{Grid, RichText, ScrollArea, Ui};
+use crate::app::AppState;
+use crate::dbus_client::DbusClient;
+
+pub fn draw(ui: &mut Ui, state: &mut AppState, dbus_client: Option<&DbusClient>) {
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.heading("📈 Profile Usage");
+        if ui.button("🔄 Refresh").clicked() {
+            state.profile_usage_requested = true;
+        }
+    });
+    ui.label(RichText::new("Counted by the daemon on this machine only, nothing is sent anywhere").small().italics());
+    ui.add_space(8.0);
+
+    let Some(usage) = state.profile_usage.clone() else {
+        if state.pending_profile_usage.is_none() {
+            state.profile_usage_requested = true;
+        }
+        ui.spinner();
+        return;
+    };
+
+    ui.label(format!("Since {}", format_date(usage.since)));
+    ui.add_space(8.0);
+
+    if usage.profiles.is_empty() {
+        ui.label(RichText::new("No profile has been applied yet").weak());
+    }
+
+    let mut profiles = usage.profiles.clone();
+    profiles.sort_by(|a, b| b.active_secs.cmp(&a.active_secs));
+    let total_secs = profiles.iter().map(|p| p.active_secs).sum::<u64>().max(1);
+
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            if !profiles.is_empty() {
+                Grid::new("profile_usage_grid")
+                    .num_columns(5)
+                    .spacing([30.0, 6.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Profile").strong());
+                        ui.label(RichText::new("Switched to").strong());
+                        ui.label(RichText::new("Time applied").strong());
+                        ui.label(RichText::new("Share").strong());
+                        ui.label(RichText::new("Average CPU").strong());
+                        ui.end_row();
+
+                        for profile in &profiles {
+                            let name = if profile.name == state.config.current_profile {
+                                RichText::new(&profile.name).strong()
+                            } else {
+                                RichText::new(&profile.name)
+                            };
+                            ui.label(name);
+                            ui.label(format!("{}×", profile.switches));
+                            ui.label(format_duration(profile.active_secs));
+                            ui.add(egui::ProgressBar::new(profile.active_secs as f32 / total_secs as f32)
+                                .desired_width(120.0)
+                                .show_percentage());
+                            ui.label(profile.average_cpu_temp
+                                .map(|t| format!("{:.1} °C", t))
+                                .unwrap_or_else(|| "-".to_string()));
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            let unused: Vec<String> = state.profile_names().into_iter()
+                .filter(|name| !profiles.iter().any(|p| &p.name == name && p.active_secs > 0))
+                .collect();
+            if !unused.is_empty() {
+                ui.add_space(12.0);
+                ui.label(RichText::new(format!("Not used in this time: {}", unused.join(", "))).small().weak());
+            }
+
+            ui.add_space(16.0);
+            ui.add_enabled_ui(!state.read_only, |ui| {
+                if ui.button("🗑️ Reset Statistics").clicked() {
+                    if let Some(client) = dbus_client {
+                        // Commands run in order, the refetch this triggers sees the reset
+                        let _rx = client.reset_profile_usage();
+                        state.profile_usage = None;
+                        state.show_message("Profile usage statistics reset", false);
+                    }
+                }
+            });
+        });
+}
+
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn format_duration(secs: u64) -> String {
+    format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+}
@@ -0,0 +1,129 @@
+use egui::{Color32, RichText, ScrollArea, Ui};
+use tuxedo_common::types::Feature;
+use crate::app::{AppState, Page};
+
+// Release notes built into the binary. Only the subset of Markdown CHANGELOG.md uses is
+// rendered: "## version", "### group", "- entry" and plain lines.
+const CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+enum Block<'a> {
+    Version(&'a str),
+    Group(&'a str),
+    Entry { text: &'a str, feature: Option<Feature> },
+    Text(&'a str),
+}
+
+fn parse(markdown: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut in_comment = false;
+    for line in markdown.lines().map(str::trim) {
+        // Multi-line comments are notes for whoever edits the file
+        if in_comment || line.starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+        if line.is_empty() || line.starts_with("# ") {
+            continue;
+        }
+
+        if let Some(version) = line.strip_prefix("## ") {
+            blocks.push(Block::Version(version.trim()));
+        } else if let Some(group) = line.strip_prefix("### ") {
+            blocks.push(Block::Group(group.trim()));
+        } else if let Some(entry) = line.strip_prefix("- ") {
+            let (text, feature) = match entry.split_once("<!--") {
+                Some((text, comment)) => (text.trim(), parse_feature(comment)),
+                None => (entry, None),
+            };
+            blocks.push(Block::Entry { text, feature });
+        } else {
+            blocks.push(Block::Text(line));
+        }
+    }
+    blocks
+}
+
+// "feature: FanControl -->", by the name Feature is serialized with
+fn parse_feature(comment: &str) -> Option<Feature> {
+    let name = comment.trim().strip_prefix("feature:")?.trim().trim_end_matches("-->").trim();
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+// "1.2.0" → [1, 2, 0], so versions compare numerically
+fn version_key(version: &str) -> Vec<u32> {
+    version.split(['.', '-']).map_while(|part| part.parse().ok()).collect()
+}
+
+/// Versions after the one seen last are marked as new, every version if none was recorded
+fn is_new(version: &str, seen: &str) -> bool {
+    version_key(version) > version_key(seen)
+}
+
+pub fn draw(ui: &mut Ui, state: &mut AppState) {
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.heading("✨ What's new");
+        ui.label(RichText::new(format!("Version {}", VERSION)).weak());
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Close").clicked() {
+                state.current_page = Page::Statistics;
+            }
+        });
+    });
+    ui.label(RichText::new("Features this machine supports are marked, based on what the daemon detected")
+        .small().italics());
+    ui.add_space(8.0);
+
+    let accent = Color32::from_rgb(80, 200, 120);
+    let mut version_is_new = false;
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for block in parse(CHANGELOG) {
+                match block {
+                    Block::Version(version) => {
+                        version_is_new = state.whats_new_since.as_deref().is_some_and(|seen| is_new(version, seen));
+                        ui.add_space(12.0);
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(version).heading().strong());
+                            if version_is_new {
+                                ui.label(RichText::new("New").color(accent).strong());
+                            }
+                        });
+                        ui.separator();
+                    }
+                    Block::Group(group) => {
+                        ui.add_space(6.0);
+                        ui.label(RichText::new(group).strong());
+                    }
+                    Block::Entry { text, feature } => {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("•");
+                            let status = feature.and_then(|feature| {
+                                state.feature_status.iter().find(|s| s.feature == feature)
+                            });
+                            match status {
+                                Some(status) if status.available => {
+                                    let text = RichText::new(text);
+                                    ui.label(if version_is_new { text.strong() } else { text });
+                                    ui.label(RichText::new("✔ Supported here").small().color(accent));
+                                }
+                                Some(status) => {
+                                    ui.label(RichText::new(text).weak());
+                                    ui.label(RichText::new("Not available on this machine").small().weak())
+                                        .on_hover_text(status.reason.as_deref().unwrap_or(""));
+                                }
+                                None => {
+                                    ui.label(text);
+                                }
+                            }
+                        });
+                    }
+                    Block::Text(text) => {
+                        ui.label(text);
+                    }
+                }
+            }
+        });
+}
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use crate::app::Page;
+
+// Searchable names per page section, the ids match the sections marked by the pages.
+// Only section granularity: a match shows (or jumps to) the section containing the control.
+struct Section {
+    id: &'static str,
+    page: Page,
+    title: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const SECTIONS: &[Section] = &[
+    Section { id: "stats.system", page: Page::Statistics, title: "System Information",
+        keywords: &["Notebook model", "Manufacturer", "BIOS version", "CPU microcode", "Memory", "Vulnerabilities"] },
+    Section { id: "stats.display", page: Page::Statistics, title: "Display",
+        keywords: &["Panel", "Native resolution", "Refresh rates", "Max brightness"] },
+    Section { id: "stats.cpu", page: Page::Statistics, title: "CPU",
+        keywords: &["Processor", "Frequency", "Load", "Package temperature", "Package power", "Scaling driver",
+            "Governor", "EPP", "CPU boost", "SMT / Hyperthreading", "AMD P-State"] },
+    Section { id: "stats.gpu", page: Page::Statistics, title: "GPU",
+        keywords: &["GPU status", "GPU frequency", "GPU temperature", "GPU load", "GPU power",
+            "PCI address", "GPU driver", "External GPU"] },
+    Section { id: "stats.battery", page: Page::Statistics, title: "Battery",
+        keywords: &["Capacity", "Voltage", "Current", "Battery power", "Battery temperature", "Charge start",
+            "Charge end", "Since unplug", "Energy used", "Average draw", "Charger"] },
+    Section { id: "stats.wifi", page: Page::Statistics, title: "WiFi",
+        keywords: &["WiFi driver", "Signal level", "Channel", "TX rate", "RX rate", "Power save"] },
+    Section { id: "stats.storage", page: Page::Statistics, title: "Storage",
+        keywords: &["Disk", "SSD", "NVMe", "Health", "Wear", "Reallocated sectors", "Power on hours",
+            "Free space", "Filesystem"] },
+    Section { id: "stats.fans", page: Page::Statistics, title: "Fans",
+        keywords: &["Fan speed", "RPM", "Fan mode"] },
+    Section { id: "stats.temperatures", page: Page::Statistics, title: "Temperatures",
+        keywords: &["Sensors", "hwmon", "Thermal zones"] },
+    Section { id: "stats.history", page: Page::Statistics, title: "History",
+        keywords: &["Temperature history", "Power history", "Graph"] },
+    Section { id: "stats.summary", page: Page::Statistics, title: "Weekly Summary",
+        keywords: &["Hours", "Weekly"] },
+
+    Section { id: "tuning.cpu", page: Page::Tuning, title: "CPU Tuning",
+        keywords: &["Governor", "EPP", "Energy Performance Preference", "AMD P-State mode", "Frequency limits",
+            "CPU boost / Turbo", "SMT / Hyperthreading", "TDP", "Power limit", "Different settings on battery"] },
+    Section { id: "tuning.gpu", page: Page::Tuning, title: "GPU Tuning",
+        keywords: &["Dynamic dGPU power limit", "Target temperature", "Fixed dGPU power limit",
+            "Limit iGPU frequency", "Max iGPU frequency", "dGPU power limits apply to", "eGPU"] },
+    Section { id: "tuning.keyboard", page: Page::Tuning, title: "Keyboard Backlight",
+        keywords: &["Control keyboard backlight", "Keyboard brightness", "Keyboard mode", "RGB color", "Ambient light"] },
+    Section { id: "tuning.lightbar", page: Page::Tuning, title: "Lightbar",
+        keywords: &["Control lightbar", "Color animation", "Lightbar color"] },
+    Section { id: "tuning.indicators", page: Page::Tuning, title: "Indicators",
+        keywords: &["LEDs", "Mute LED", "Microphone mute LED", "Power LED", "Charging LED"] },
+    Section { id: "tuning.input", page: Page::Tuning, title: "Input Devices",
+        keywords: &["USB mouse polling interval", "USB autosuspend", "Keyboard backlight off on battery"] },
+    Section { id: "tuning.screen", page: Page::Tuning, title: "Screen",
+        keywords: &["System brightness control", "Screen brightness", "Screen blank timeout", "Ambient light", "Color temperature", "Night light", "Refresh rate"] },
+    Section { id: "tuning.network", page: Page::Tuning, title: "Network",
+        keywords: &["WiFi power save", "WiFi radio", "Bluetooth", "Mobile broadband", "Interfaces"] },
+    Section { id: "tuning.memory", page: Page::Tuning, title: "Memory",
+        keywords: &["Swappiness", "Dirty page writeback interval", "VM"] },
+    Section { id: "tuning.fans", page: Page::Tuning, title: "Fan Control",
+        keywords: &["Custom fan curves", "Fan curve", "Temperature source", "Copy curves from profile",
+            "Observed readings", "Fan offsets", "Minimum fan duty", "Fan stall", "Calibrate",
+            "Curve smoothing", "Hysteresis", "Ramp up", "Ramp down", "Sampling interval"] },
+    Section { id: "tuning.aquaris", page: Page::Tuning, title: "Aquaris Water Cooler",
+        keywords: &["Water cooling", "Fan speed", "Pump speed", "Pump voltage", "Bluetooth"] },
+    Section { id: "tuning.clamshell", page: Page::Tuning, title: "Clamshell Mode",
+        keywords: &["Lid closed", "Docked", "Minimum fan speed", "Clamshell TDP profile"] },
+    Section { id: "tuning.dust", page: Page::Tuning, title: "Dust Cleaning",
+        keywords: &["Cycles", "Fan cleaning"] },
+
+    Section { id: "settings.appearance", page: Page::Settings, title: "Appearance",
+        keywords: &["Theme", "Dark", "Light"] },
+    Section { id: "settings.font", page: Page::Settings, title: "Font Size",
+        keywords: &["Text size", "Scale"] },
+    Section { id: "settings.startup", page: Page::Settings, title: "Startup",
+        keywords: &["Enable autostart", "Start minimized", "Open configuration file", "Save now"] },
+    Section { id: "settings.daemon", page: Page::Settings, title: "Daemon Controls",
+        keywords: &["Fan daemon", "App monitoring"] },
+    Section { id: "settings.autoswitch", page: Page::Settings, title: "Automatic Switching",
+        keywords: &["Game mode", "GameMode", "Gaming profile", "Feral", "Steam", "Proton", "Games",
+            "Applications", "Process"] },
+    Section { id: "settings.layout", page: Page::Settings, title: "Statistics Page Layout",
+        keywords: &["Show sections", "Hide sections"] },
+    Section { id: "settings.history", page: Page::Settings, title: "History",
+        keywords: &["Retention", "Resolution", "Purge history"] },
+    Section { id: "settings.battery", page: Page::Settings, title: "Battery Charge Control",
+        keywords: &["Charge thresholds", "Charge start", "Charge end", "Charging priority", "Charging profile",
+            "Charge behaviour", "Inhibit charge", "Charge to 100% once", "Travel", "Cap TDP to charger", "Battery temperature alarm"] },
+    Section { id: "settings.polling", page: Page::Settings, title: "Polling Rates",
+        keywords: &["Update interval", "Refresh rate"] },
+    Section { id: "settings.about", page: Page::Settings, title: "About",
+        keywords: &["What's new", "Changelog", "Release notes", "Version"] },
+];
+
+pub struct SearchHit {
+    pub page: Page,
+    pub section: &'static str,
+    pub label: String,
+    score: i32,
+}
+
+/// Case-insensitive subsequence match, higher is better, None if not all characters occur in order.
+/// Substrings beat scattered matches and matches at word starts beat ones mid-word,
+/// so "epp" finds both "EPP" and "Energy Performance Preference".
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    let text = text.to_lowercase();
+
+    if let Some(pos) = text.find(&query) {
+        let word_start = pos == 0 || !text[..pos].ends_with(char::is_alphanumeric);
+        return Some(100 + if word_start { 50 } else { 0 } - pos.min(50) as i32);
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut matched = 0;
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = (next..chars.len()).find(|&i| chars[i] == wanted)?;
+        score += if found == 0 || !chars[found - 1].is_alphanumeric() {
+            10
+        } else if previous == Some(found - 1) {
+            5
+        } else {
+            1
+        };
+        matched += 1;
+        previous = Some(found);
+        next = found + 1;
+    }
+
+    // Letters scattered across unrelated words aren't a match
+    (score >= 3 * matched).then_some(score)
+}
+
+fn page_name(page: Page) -> &'static str {
+    match page {
+        Page::Statistics => "Statistics",
+        Page::Profiles => "Profiles",
+        Page::Tuning => "Tuning",
+        Page::Events => "Events",
+        Page::Capabilities => "Capabilities",
+        Page::Settings => "Settings",
+        Page::WhatsNew => "What's new",
+        Page::Usage => "Profile usage",
+    }
+}
+
+/// Best matches first, at most one per section
+pub fn search(query: &str, limit: usize) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = SECTIONS.iter()
+        .filter_map(|section| {
+            std::iter::once(section.title)
+                .chain(section.keywords.iter().copied())
+                .filter_map(|name| fuzzy_score(query, name).map(|score| (name, score)))
+                .max_by_key(|(_, score)| *score)
+                .map(|(name, score)| SearchHit {
+                    page: section.page,
+                    section: section.id,
+                    label: if name == section.title {
+                        format!("{} › {}", page_name(section.page), section.title)
+                    } else {
+                        format!("{} — {} › {}", name, page_name(section.page), section.title)
+                    },
+                    score,
+                })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+/// Section ids that stay visible while filtering, None shows everything
+pub fn matching_sections(query: &str) -> Option<HashSet<&'static str>> {
+    if query.trim().is_empty() {
+        return None;
+    }
+    Some(search(query, SECTIONS.len()).into_iter().map(|hit| hit.section).collect())
+}
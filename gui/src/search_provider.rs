@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tuxedo_common::types::{AppConfig, Profile, SystemProfiles};
+use zbus::zvariant::Value;
+use zbus::{interface, Connection, ConnectionBuilder};
+
+// GNOME Shell search provider: typing a profile name in the Activities overview offers to
+// apply it. The shell starts `tuxedo-control-center --search-provider` through session bus
+// activation, it runs without a window and exits again when the overview stops asking.
+
+pub const FLAG: &str = "--search-provider";
+const BUS_NAME: &str = "com.tuxedo.ControlCenter.SearchProvider";
+const OBJECT_PATH: &str = "/com/tuxedo/ControlCenter/SearchProvider";
+const IDLE_EXIT: Duration = Duration::from_secs(120);
+
+static LAST_CALL: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn touch() {
+    *LAST_CALL.lock().unwrap() = Some(Instant::now());
+}
+
+struct SearchProvider;
+
+fn load_profiles() -> Vec<Profile> {
+    crate::app::load_config_from_disk()
+        .map(|config| config.profiles)
+        .unwrap_or_default()
+}
+
+fn matches(profile: &str, terms: &[String]) -> bool {
+    let name = profile.to_lowercase();
+    terms.iter().all(|term| name.contains(&term.to_lowercase()))
+}
+
+#[interface(name = "org.gnome.Shell.SearchProvider2")]
+impl SearchProvider {
+    async fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+        touch();
+        load_profiles().into_iter()
+            .map(|p| p.name)
+            .filter(|name| matches(name, &terms))
+            .collect()
+    }
+
+    async fn get_subsearch_result_set(&self, previous_results: Vec<String>, terms: Vec<String>) -> Vec<String> {
+        touch();
+        previous_results.into_iter()
+            .filter(|name| matches(name, &terms))
+            .collect()
+    }
+
+    async fn get_result_metas(&self, identifiers: Vec<String>) -> Vec<HashMap<String, Value<'static>>> {
+        touch();
+        let current = crate::app::load_config_from_disk()
+            .map(|config| config.current_profile)
+            .unwrap_or_default();
+
+        identifiers.into_iter()
+            .map(|id| {
+                let description = if id == current { "Current profile" } else { "TUXEDO Control Center profile" };
+                HashMap::from([
+                    ("name".to_string(), Value::from(format!("Apply profile {}", id))),
+                    ("description".to_string(), Value::from(description)),
+                    ("gicon".to_string(), Value::from("tuxedo-control-center")),
+                    ("id".to_string(), Value::from(id)),
+                ])
+            })
+            .collect()
+    }
+
+    async fn activate_result(&self, identifier: String, _terms: Vec<String>, _timestamp: u32) {
+        touch();
+        match apply(&identifier).await {
+            Ok(()) => crate::desktop_integration::notify("Profile applied", format!("Switched to profile '{}'", identifier)),
+            Err(e) => crate::desktop_integration::notify("Failed to apply profile", e.to_string()),
+        }
+    }
+
+    async fn launch_search(&self, _terms: Vec<String>, _timestamp: u32) {
+        touch();
+        if let Err(e) = std::process::Command::new("tuxedo-control-center").spawn() {
+            log::warn!("Failed to start TUXEDO Control Center: {}", e);
+        }
+    }
+}
+
+// Same as switching on the Profiles page: session settings, the daemon, then the config
+async fn apply(name: &str) -> Result<()> {
+    let mut config: AppConfig = crate::app::load_config_from_disk()?;
+    if config.read_only {
+        return Err(anyhow!("Profiles can't be changed on this machine"));
+    }
+
+    let connection = Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "com.tuxedo.Control",
+        "/com/tuxedo/Control",
+        "com.tuxedo.Control",
+    ).await?;
+
+    // A system profile of the same name takes precedence
+    let system: SystemProfiles = proxy.call::<_, _, String>("GetSystemProfiles", &()).await
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let profile = system.profiles.into_iter()
+        .chain(config.profiles.iter().cloned())
+        .find(|p| p.name == name)
+        .ok_or_else(|| anyhow!("Profile '{}' no longer exists", name))?;
+
+    crate::session_settings::apply_profile(&profile);
+
+    let json = serde_json::to_string(&profile)?;
+    proxy.call_method("ApplyProfile", &(json.as_str(),)).await?;
+
+    config.current_profile = profile.name;
+    crate::app::save_config_to_disk(&config)?;
+    Ok(())
+}
+
+pub async fn run() -> Result<()> {
+    let _connection = ConnectionBuilder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, SearchProvider)?
+        .build()
+        .await?;
+    log::info!("Search provider running");
+
+    touch();
+    let mut interval = tokio::time::interval(Duration::from_secs(10));
+    loop {
+        interval.tick().await;
+        if LAST_CALL.lock().unwrap().is_some_and(|t| t.elapsed() >= IDLE_EXIT) {
+            log::info!("Search provider idle, exiting");
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,548 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use tuxedo_common::types::Profile;
+use zbus::zvariant::{OwnedValue, Value};
+
+// Settings that belong to the user session and can't be applied by the root daemon
+
+pub fn apply_profile(profile: &Profile) {
+    let seconds = profile.screen_settings.blank_timeout;
+    // A profile without a timeout only gives back the one an earlier profile replaced
+    if seconds.is_some() || snapshot_path(BLANK_TIMEOUT_SNAPSHOT).is_ok_and(|p| p.exists()) {
+        // Spawning the desktop tools must not stall the UI thread
+        std::thread::spawn(move || {
+            match set_screen_blank_timeout(seconds) {
+                Ok(true) => match seconds {
+                    Some(seconds) => log::info!("Set screen blank timeout to {} s", seconds),
+                    None => log::info!("Restored screen blank timeout"),
+                },
+                Ok(false) => {}
+                Err(e) => log::warn!("Failed to set screen blank timeout: {}", e),
+            }
+        });
+    }
+
+    let kelvin = profile.screen_settings.color_temperature;
+    // A profile without a color temperature only undoes what an earlier profile set
+    if kelvin.is_some() || *COLOR_TEMPERATURE_SET.lock().unwrap() || snapshot_path(NIGHT_LIGHT_SNAPSHOT).is_ok_and(|p| p.exists()) {
+        std::thread::spawn(move || {
+            match set_color_temperature(kelvin) {
+                Ok(()) => {
+                    *COLOR_TEMPERATURE_SET.lock().unwrap() = kelvin.is_some();
+                    match kelvin {
+                        Some(kelvin) => log::info!("Set display color temperature to {} K", kelvin),
+                        None => log::info!("Reset display color temperature"),
+                    }
+                }
+                Err(e) => log::warn!("Failed to set display color temperature: {}", e),
+            }
+        });
+    }
+
+    let hz = profile.screen_settings.refresh_rate;
+    if hz.is_some() || ORIGINAL_REFRESH_RATE.lock().unwrap().is_some() {
+        std::thread::spawn(move || {
+            match set_refresh_rate(hz) {
+                Ok(()) => match hz {
+                    Some(hz) => log::info!("Set built-in display refresh rate to {} Hz", hz),
+                    None => log::info!("Restored built-in display refresh rate"),
+                },
+                Err(e) => log::warn!("Failed to set display refresh rate: {}", e),
+            }
+        });
+    }
+}
+
+fn current_desktop() -> String {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_uppercase()
+}
+
+const BLANK_TIMEOUT_SNAPSHOT: &str = "blank-timeout";
+const NIGHT_LIGHT_SNAPSHOT: &str = "night-light";
+
+/// None restores the timeout the first profile replaced, false if the desktop has no such setting
+pub fn set_screen_blank_timeout(seconds: Option<u32>) -> Result<bool> {
+    let desktop = current_desktop();
+
+    if desktop.contains("KDE") {
+        set_kde_blank_timeout(seconds)?;
+    } else if desktop.contains("CINNAMON") {
+        set_gsettings_blank_timeout("org.cinnamon.desktop.session", seconds)?;
+    } else if desktop.contains("GNOME") || desktop.contains("UNITY") || desktop.contains("BUDGIE") {
+        set_gsettings_blank_timeout("org.gnome.desktop.session", seconds)?;
+    } else {
+        // wlroots compositors configure swayidle & co. in their own config files
+        log::info!("Screen blank timeout not supported on desktop '{}', skipped", desktop);
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+fn set_gsettings_blank_timeout(schema: &str, seconds: Option<u32>) -> Result<()> {
+    let Some(seconds) = seconds else {
+        restore_snapshot(BLANK_TIMEOUT_SNAPSHOT, |key, value| match value {
+            Some(value) => run("gsettings", &["set", schema, key, value]),
+            None => run("gsettings", &["reset", schema, key]),
+        })?;
+        return Ok(());
+    };
+    save_snapshot(BLANK_TIMEOUT_SNAPSHOT, |key| output("gsettings", &["get", schema, key]), &["idle-delay"])?;
+    run("gsettings", &["set", schema, "idle-delay", &seconds.to_string()])
+}
+
+// Plasma 6 keeps one Display group per power state, Plasma 5 a DPMSControl group
+fn set_kde_blank_timeout(seconds: Option<u32>) -> Result<()> {
+    const GROUPS: [&str; 3] = ["AC", "Battery", "LowBattery"];
+    let plasma6 = output("kreadconfig6", &["--help"]).is_some();
+    let (read_tool, write_tool, file, subgroup, key) = if plasma6 {
+        ("kreadconfig6", "kwriteconfig6", "powerdevilrc", "Display", "TurnOffDisplayIdleTimeoutSec")
+    } else {
+        ("kreadconfig5", "kwriteconfig5", "powermanagementprofilesrc", "DPMSControl", "idleTime")
+    };
+    let write = |group: &str, value: Option<&str>| {
+        let mut args = vec!["--file", file, "--group", group, "--group", subgroup, "--key", key];
+        args.push(value.unwrap_or("--delete"));
+        run(write_tool, &args)
+    };
+
+    match seconds {
+        Some(seconds) => {
+            // kreadconfig prints nothing for a key that isn't set
+            save_snapshot(BLANK_TIMEOUT_SNAPSHOT, |group| output(read_tool, &[
+                "--file", file, "--group", group, "--group", subgroup, "--key", key,
+            ]).filter(|value| !value.is_empty()), &GROUPS)?;
+            let seconds = seconds.to_string();
+            GROUPS.iter().try_for_each(|group| write(group, Some(&seconds)))?;
+        }
+        None => {
+            restore_snapshot(BLANK_TIMEOUT_SNAPSHOT, write)?;
+        }
+    }
+
+    // Make PowerDevil pick up the new configuration
+    run("dbus-send", &[
+        "--session", "--type=method_call",
+        "--dest=org.kde.Solid.PowerManagement",
+        "/org/kde/Solid/PowerManagement",
+        "org.kde.Solid.PowerManagement.reparseConfiguration",
+    ])
+}
+
+// Whether a profile changed the color temperature during this session
+static COLOR_TEMPERATURE_SET: Mutex<bool> = Mutex::new(false);
+// wlroots compositors drop a gamma ramp when the client that set it exits
+static GAMMA_CLIENT: Mutex<Option<Child>> = Mutex::new(None);
+
+pub fn set_color_temperature(kelvin: Option<u32>) -> Result<()> {
+    let desktop = current_desktop();
+    let wayland = std::env::var("XDG_SESSION_TYPE").map(|t| t == "wayland").unwrap_or(false);
+
+    if desktop.contains("GNOME") {
+        set_gnome_night_light(kelvin)
+    } else if desktop.contains("KDE") {
+        set_kde_night_color(kelvin)
+    } else if wayland {
+        set_wlr_gamma(kelvin)
+    } else {
+        set_xrandr_gamma(kelvin)
+    }
+}
+
+// Desktop settings as they were before the first profile changed them, kept on disk so a
+// profile without the setting gives the user's own value back even after a restart
+fn snapshot_path(name: &str) -> Result<std::path::PathBuf> {
+    Ok(std::path::PathBuf::from(format!("{}/.config/tuxedo-control-center/{}.json", std::env::var("HOME")?, name)))
+}
+
+fn save_snapshot(name: &str, read: impl Fn(&str) -> Option<String>, keys: &[&str]) -> Result<()> {
+    let path = snapshot_path(name)?;
+    if path.exists() {
+        return Ok(());
+    }
+    let values: Vec<(String, Option<String>)> = keys.iter()
+        .map(|key| (key.to_string(), read(key)))
+        .collect();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, serde_json::to_string(&values)?)?;
+    Ok(())
+}
+
+// Writes the saved values back and forgets them, false if there was nothing saved
+fn restore_snapshot(name: &str, write: impl Fn(&str, Option<&str>) -> Result<()>) -> Result<bool> {
+    let path = snapshot_path(name)?;
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return Ok(false);
+    };
+    let values: Vec<(String, Option<String>)> = serde_json::from_str(&json)?;
+    for (key, value) in &values {
+        write(key, value.as_deref())?;
+    }
+    std::fs::remove_file(&path)?;
+    Ok(true)
+}
+
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn set_gnome_night_light(kelvin: Option<u32>) -> Result<()> {
+    const SCHEMA: &str = "org.gnome.settings-daemon.plugins.color";
+    const KEYS: [&str; 5] = [
+        "night-light-enabled", "night-light-schedule-automatic", "night-light-schedule-from",
+        "night-light-schedule-to", "night-light-temperature",
+    ];
+    let kelvin = match kelvin {
+        Some(kelvin) => kelvin,
+        None => {
+            // gsettings get prints GVariant text, which set takes back as is
+            let restored = restore_snapshot(NIGHT_LIGHT_SNAPSHOT, |key, value| match value {
+                Some(value) => run("gsettings", &["set", SCHEMA, key, value]),
+                None => run("gsettings", &["reset", SCHEMA, key]),
+            })?;
+            return if restored { Ok(()) } else { run("gsettings", &["set", SCHEMA, "night-light-enabled", "false"]) };
+        }
+    };
+    save_snapshot(NIGHT_LIGHT_SNAPSHOT, |key| output("gsettings", &["get", SCHEMA, key]), &KEYS)?;
+    // Night light with a schedule spanning the whole day
+    run("gsettings", &["set", SCHEMA, "night-light-schedule-automatic", "false"])?;
+    run("gsettings", &["set", SCHEMA, "night-light-schedule-from", "0.0"])?;
+    run("gsettings", &["set", SCHEMA, "night-light-schedule-to", "23.99"])?;
+    run("gsettings", &["set", SCHEMA, "night-light-temperature", &format!("uint32 {}", kelvin)])?;
+    run("gsettings", &["set", SCHEMA, "night-light-enabled", "true"])
+}
+
+fn set_kde_night_color(kelvin: Option<u32>) -> Result<()> {
+    let write = |key: &str, value: &str| run("kwriteconfig6", &[
+        "--file", "kwinrc", "--group", "NightColor", "--key", key, value,
+    ]);
+    match kelvin {
+        Some(kelvin) => {
+            // kreadconfig6 prints nothing for a key that isn't set
+            save_snapshot(NIGHT_LIGHT_SNAPSHOT, |key| output("kreadconfig6", &["--file", "kwinrc", "--group", "NightColor", "--key", key])
+                .filter(|value| !value.is_empty()), &["Active", "Mode", "NightTemperature"])?;
+            write("Active", "true")?;
+            write("Mode", "Constant")?;
+            write("NightTemperature", &kelvin.to_string())?;
+        }
+        None => {
+            let restored = restore_snapshot(NIGHT_LIGHT_SNAPSHOT, |key, value| match value {
+                Some(value) => write(key, value),
+                None => run("kwriteconfig6", &[
+                    "--file", "kwinrc", "--group", "NightColor", "--key", key, "--delete",
+                ]),
+            })?;
+            if !restored {
+                write("Active", "false")?;
+            }
+        }
+    }
+
+    // Make KWin pick up the new configuration
+    run("dbus-send", &[
+        "--session", "--type=method_call",
+        "--dest=org.kde.KWin", "/KWin", "org.kde.KWin.reconfigure",
+    ])
+}
+
+// Through a gamma control client that keeps running, gammastep or Hyprland's hyprsunset
+fn set_wlr_gamma(kelvin: Option<u32>) -> Result<()> {
+    let mut client = GAMMA_CLIENT.lock().unwrap();
+    if let Some(mut previous) = client.take() {
+        let _ = previous.kill();
+        let _ = previous.wait();
+    }
+    let kelvin = match kelvin {
+        Some(kelvin) => kelvin.to_string(),
+        None => return Ok(()),
+    };
+
+    let candidates: [(&str, Vec<&str>); 2] = [
+        ("gammastep", vec!["-m", "wayland", "-P", "-O", &kelvin]),
+        ("hyprsunset", vec!["-t", &kelvin]),
+    ];
+    for (program, args) in candidates {
+        if let Ok(child) = Command::new(program).args(&args).spawn() {
+            *client = Some(child);
+            return Ok(());
+        }
+    }
+    Err(anyhow!("Neither gammastep nor hyprsunset is installed"))
+}
+
+fn set_xrandr_gamma(kelvin: Option<u32>) -> Result<()> {
+    let (r, g, b) = kelvin.map(kelvin_to_rgb).unwrap_or((1.0, 1.0, 1.0));
+    let gamma = format!("{:.3}:{:.3}:{:.3}", r.max(0.1), g.max(0.1), b.max(0.1));
+
+    let output = Command::new("xrandr").arg("--query").output()
+        .map_err(|e| anyhow!("Failed to run xrandr: {}", e))?;
+    let outputs: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.contains(" connected"))
+        .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+        .collect();
+    if outputs.is_empty() {
+        return Err(anyhow!("xrandr reports no connected outputs"));
+    }
+    for name in outputs {
+        run("xrandr", &["--output", &name, "--gamma", &gamma])?;
+    }
+    Ok(())
+}
+
+// White point of a black body relative to 6500 K, Tanner Helland's approximation
+fn kelvin_to_rgb(kelvin: u32) -> (f64, f64, f64) {
+    let t = kelvin.clamp(1000, 6500) as f64 / 100.0;
+    let g = (99.470_802_586_1 * t.ln() - 161.119_568_166_1) / 255.0;
+    let b = if t <= 19.0 {
+        0.0
+    } else {
+        (138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7) / 255.0
+    };
+    (1.0, g.clamp(0.0, 1.0), b.clamp(0.0, 1.0))
+}
+
+// Refresh rate the built-in display had before the first profile changed it
+static ORIGINAL_REFRESH_RATE: Mutex<Option<u32>> = Mutex::new(None);
+
+struct DisplayMode {
+    id: String,
+    width: u32,
+    height: u32,
+    refresh: f64,
+    current: bool,
+}
+
+/// None gives back the rate an earlier profile replaced
+pub fn set_refresh_rate(hz: Option<u32>) -> Result<()> {
+    // Not held while the desktop switches modes, apply_profile checks it on the UI thread
+    let original = *ORIGINAL_REFRESH_RATE.lock().unwrap();
+    let Some(target) = hz.or(original) else {
+        return Ok(());
+    };
+    let desktop = current_desktop();
+    let wayland = std::env::var("XDG_SESSION_TYPE").map(|t| t == "wayland").unwrap_or(false);
+
+    let previous = if desktop.contains("GNOME") {
+        set_mutter_refresh_rate(target)
+    } else if desktop.contains("KDE") {
+        set_kde_refresh_rate(target)
+    } else if wayland {
+        set_wlr_refresh_rate(target)
+    } else {
+        set_xrandr_refresh_rate(target)
+    }?;
+    let mut original = ORIGINAL_REFRESH_RATE.lock().unwrap();
+    match hz {
+        Some(_) => {
+            original.get_or_insert(previous);
+        }
+        None => *original = None,
+    }
+    Ok(())
+}
+
+fn is_internal(connector: &str) -> bool {
+    ["eDP", "LVDS", "DSI"].iter().any(|prefix| connector.starts_with(prefix))
+}
+
+// The active mode and the one at the same resolution whose rate is nearest to the target
+fn pick_mode(modes: &[DisplayMode], hz: u32) -> Result<(&DisplayMode, &DisplayMode)> {
+    let current = modes.iter().find(|m| m.current)
+        .ok_or_else(|| anyhow!("The built-in display has no active mode"))?;
+    let distance = |mode: &DisplayMode| (mode.refresh - hz as f64).abs();
+    let mode = modes.iter()
+        .filter(|m| m.width == current.width && m.height == current.height)
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or(current);
+    Ok((current, mode))
+}
+
+type MutterProperties = HashMap<String, OwnedValue>;
+// Connector, vendor, product, serial
+type MutterMonitorSpec = (String, String, String, String);
+type MutterMode = (String, i32, i32, f64, f64, Vec<f64>, MutterProperties);
+type MutterState = (
+    u32,
+    Vec<(MutterMonitorSpec, Vec<MutterMode>, MutterProperties)>,
+    Vec<(i32, i32, f64, u32, bool, Vec<MutterMonitorSpec>, MutterProperties)>,
+    MutterProperties,
+);
+
+// Mutter takes a complete layout, every other monitor keeps its current mode
+fn set_mutter_refresh_rate(hz: u32) -> Result<u32> {
+    let connection = zbus::blocking::Connection::session()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &connection,
+        "org.gnome.Mutter.DisplayConfig",
+        "/org/gnome/Mutter/DisplayConfig",
+        "org.gnome.Mutter.DisplayConfig",
+    )?;
+    let (serial, monitors, logical_monitors, _): MutterState = proxy.call("GetCurrentState", &())?;
+
+    let modes_of = |connector: &str| -> Vec<DisplayMode> {
+        monitors.iter()
+            .find(|(spec, ..)| spec.0 == connector)
+            .map(|(_, modes, _)| modes.iter().map(|(id, width, height, refresh, .., properties)| DisplayMode {
+                id: id.clone(),
+                width: *width as u32,
+                height: *height as u32,
+                refresh: *refresh,
+                current: properties.get("is-current").and_then(|v| bool::try_from(v).ok()).unwrap_or(false),
+            }).collect())
+            .unwrap_or_default()
+    };
+    let internal = monitors.iter()
+        .map(|(spec, ..)| spec.0.clone())
+        .find(|connector| is_internal(connector))
+        .ok_or_else(|| anyhow!("Mutter reports no built-in display"))?;
+    let modes = modes_of(&internal);
+    let (current, mode) = pick_mode(&modes, hz)?;
+    if std::ptr::eq(current, mode) {
+        return Ok(current.refresh.round() as u32);
+    }
+
+    let layout: Vec<(i32, i32, f64, u32, bool, Vec<(String, String, HashMap<&str, Value>)>)> = logical_monitors.iter()
+        .map(|(x, y, scale, transform, primary, specs, _)| {
+            let monitors = specs.iter()
+                .filter_map(|spec| {
+                    let mode_id = if spec.0 == internal {
+                        Some(mode.id.clone())
+                    } else {
+                        modes_of(&spec.0).into_iter().find(|m| m.current).map(|m| m.id)
+                    };
+                    mode_id.map(|id| (spec.0.clone(), id, HashMap::new()))
+                })
+                .collect();
+            (*x, *y, *scale, *transform, *primary, monitors)
+        })
+        .collect();
+    // 1 = temporary, the layout saved in GNOME's display settings stays untouched
+    let properties: HashMap<&str, Value> = HashMap::new();
+    proxy.call_method("ApplyMonitorsConfig", &(serial, 1u32, layout, properties))?;
+    Ok(current.refresh.round() as u32)
+}
+
+fn set_kde_refresh_rate(hz: u32) -> Result<u32> {
+    let json = output("kscreen-doctor", &["--json"]).ok_or_else(|| anyhow!("Failed to run kscreen-doctor"))?;
+    let state: serde_json::Value = serde_json::from_str(&json)?;
+    let display = state["outputs"].as_array().into_iter().flatten()
+        .find(|o| o["name"].as_str().is_some_and(is_internal))
+        .ok_or_else(|| anyhow!("kscreen-doctor reports no built-in display"))?;
+    // Mode ids are strings in some Plasma versions and numbers in others
+    let id = |value: &serde_json::Value| value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+    let current_id = id(&display["currentModeId"]);
+    let modes: Vec<DisplayMode> = display["modes"].as_array().into_iter().flatten()
+        .map(|m| DisplayMode {
+            id: id(&m["id"]),
+            width: m["size"]["width"].as_u64().unwrap_or(0) as u32,
+            height: m["size"]["height"].as_u64().unwrap_or(0) as u32,
+            refresh: m["refreshRate"].as_f64().unwrap_or(0.0),
+            current: id(&m["id"]) == current_id,
+        })
+        .collect();
+
+    let (current, mode) = pick_mode(&modes, hz)?;
+    if !std::ptr::eq(current, mode) {
+        let name = display["name"].as_str().unwrap_or_default();
+        run("kscreen-doctor", &[&format!("output.{}.mode.{}", name, mode.id)])?;
+    }
+    Ok(current.refresh.round() as u32)
+}
+
+// wlr-randr 0.4 and later print JSON
+fn set_wlr_refresh_rate(hz: u32) -> Result<u32> {
+    let json = output("wlr-randr", &["--json"])
+        .ok_or_else(|| anyhow!("wlr-randr is not installed or too old for --json"))?;
+    let outputs: serde_json::Value = serde_json::from_str(&json)?;
+    let display = outputs.as_array().into_iter().flatten()
+        .find(|o| o["name"].as_str().is_some_and(is_internal))
+        .ok_or_else(|| anyhow!("wlr-randr reports no built-in display"))?;
+    let modes: Vec<DisplayMode> = display["modes"].as_array().into_iter().flatten()
+        .map(|m| {
+            let width = m["width"].as_u64().unwrap_or(0) as u32;
+            let height = m["height"].as_u64().unwrap_or(0) as u32;
+            let refresh = m["refresh"].as_f64().unwrap_or(0.0);
+            DisplayMode {
+                id: format!("{}x{}@{}Hz", width, height, refresh),
+                width,
+                height,
+                refresh,
+                current: m["current"].as_bool().unwrap_or(false),
+            }
+        })
+        .collect();
+
+    let (current, mode) = pick_mode(&modes, hz)?;
+    if !std::ptr::eq(current, mode) {
+        let name = display["name"].as_str().unwrap_or_default();
+        run("wlr-randr", &["--output", name, "--mode", &mode.id])?;
+    }
+    Ok(current.refresh.round() as u32)
+}
+
+// Mode lines under the output, "   2560x1600    165.00*+  60.00 +", * marks the active rate
+fn set_xrandr_refresh_rate(hz: u32) -> Result<u32> {
+    let text = output("xrandr", &["--query"]).ok_or_else(|| anyhow!("Failed to run xrandr"))?;
+    let mut name: Option<String> = None;
+    let mut modes = Vec::new();
+    for line in text.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if name.is_some() {
+                break;
+            }
+            let connector = line.split_whitespace().next().unwrap_or("");
+            if is_internal(connector) && line.contains(" connected") {
+                name = Some(connector.to_string());
+            }
+            continue;
+        }
+        if name.is_none() {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let Some((width, height)) = fields.next().and_then(|r| r.split_once('x')) else {
+            continue;
+        };
+        let (Ok(width), Ok(height)) = (width.parse::<u32>(), height.parse::<u32>()) else {
+            continue;
+        };
+        for rate in fields {
+            if let Ok(refresh) = rate.trim_end_matches(['*', '+']).parse::<f64>() {
+                modes.push(DisplayMode {
+                    id: format!("{}x{}", width, height),
+                    width,
+                    height,
+                    refresh,
+                    current: rate.contains('*'),
+                });
+            }
+        }
+    }
+    let name = name.ok_or_else(|| anyhow!("xrandr reports no built-in display"))?;
+
+    let (current, mode) = pick_mode(&modes, hz)?;
+    if !std::ptr::eq(current, mode) {
+        run("xrandr", &["--output", &name, "--mode", &mode.id, "--rate", &format!("{:.2}", mode.refresh)])?;
+    }
+    Ok(current.refresh.round() as u32)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| anyhow!("Failed to run {}: {}", program, e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} exited with {}", program, status))
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use crate::history::History;
+
+// Time above this CPU temperature is reported separately
+pub const HOT_THRESHOLD: f32 = 80.0;
+pub const PERIOD_DAYS: i64 = 7;
+
+/// Battery and thermal summary computed from the persisted history
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub from: i64,
+    pub to: i64,
+    pub recorded_secs: i64,
+    pub average_cpu_temp: f32,
+    pub max_cpu_temp: f32,
+    pub average_cpu_load: f32,
+    pub average_gpu_temp: Option<f32>,
+    pub hot_secs: i64,
+    pub battery_secs: i64,
+    pub energy_used_wh: f64,
+    pub charge_cycles: f32,
+}
+
+impl Summary {
+    /// Summarize the last `PERIOD_DAYS`, None if nothing was recorded in that time
+    pub fn from_history(history: &History, resolution_secs: u32) -> Option<Self> {
+        let to = chrono::Local::now().timestamp();
+        let from = to - PERIOD_DAYS * 86_400;
+        let resolution = resolution_secs.max(1) as i64;
+
+        let samples: Vec<_> = history.samples.iter()
+            .filter(|s| s.timestamp >= from)
+            .collect();
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut summary = Summary { from, to, ..Default::default() };
+        let mut temp_sum = 0.0;
+        let mut load_sum = 0.0;
+        let mut gpu_temps = Vec::new();
+
+        for (i, sample) in samples.iter().enumerate() {
+            // A sample covers the time until the next one, gaps (GUI closed) are not counted
+            let covered = samples.get(i + 1)
+                .map(|next| (next.timestamp - sample.timestamp).min(resolution))
+                .unwrap_or(resolution);
+
+            summary.recorded_secs += covered;
+            temp_sum += sample.cpu_temp as f64 * covered as f64;
+            load_sum += sample.cpu_load as f64 * covered as f64;
+            summary.max_cpu_temp = summary.max_cpu_temp.max(sample.cpu_temp);
+            gpu_temps.extend(sample.gpu_temp);
+
+            if sample.cpu_temp > HOT_THRESHOLD {
+                summary.hot_secs += covered;
+            }
+            if let Some(draw) = sample.battery_draw_w {
+                summary.battery_secs += covered;
+                summary.energy_used_wh += draw as f64 * covered as f64 / 3600.0;
+            }
+
+            // Every 100 % discharged adds up to one charge cycle
+            if let (Some(now), Some(next)) = (sample.battery_percent, samples.get(i + 1).and_then(|n| n.battery_percent)) {
+                if next < now {
+                    summary.charge_cycles += (now - next) / 100.0;
+                }
+            }
+        }
+
+        let recorded = summary.recorded_secs.max(1) as f64;
+        summary.average_cpu_temp = (temp_sum / recorded) as f32;
+        summary.average_cpu_load = (load_sum / recorded) as f32;
+        summary.average_gpu_temp = (!gpu_temps.is_empty())
+            .then(|| gpu_temps.iter().sum::<f32>() / gpu_temps.len() as f32);
+
+        Some(summary)
+    }
+
+    /// Label / value pairs shared by the GUI and the exports
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("Period".to_string(), format!("{} – {}", format_date(self.from), format_date(self.to))),
+            ("Time recorded".to_string(), format_duration(self.recorded_secs)),
+            ("Average CPU temperature".to_string(), format!("{:.1} °C", self.average_cpu_temp)),
+            ("Maximum CPU temperature".to_string(), format!("{:.1} °C", self.max_cpu_temp)),
+            (format!("Time above {:.0} °C", HOT_THRESHOLD), format_duration(self.hot_secs)),
+            ("Average CPU load".to_string(), format!("{:.1} %", self.average_cpu_load)),
+        ];
+        if let Some(temp) = self.average_gpu_temp {
+            rows.push(("Average GPU temperature".to_string(), format!("{:.1} °C", temp)));
+        }
+        rows.push(("Time on battery".to_string(), format_duration(self.battery_secs)));
+        rows.push(("Energy used on battery".to_string(), format!("{:.1} Wh", self.energy_used_wh)));
+        rows.push(("Charge cycles consumed".to_string(), format!("{:.2}", self.charge_cycles)));
+        rows
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# Weekly battery / thermal summary\n\n| | |\n|---|---|\n");
+        for (label, value) in self.rows() {
+            md.push_str(&format!("| {} | {} |\n", label, value));
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Weekly summary</title></head>\n<body>\n\
+             <h1>Weekly battery / thermal summary</h1>\n<table>\n");
+        for (label, value) in self.rows() {
+            html.push_str(&format!("<tr><th align=\"left\">{}</th><td>{}</td></tr>\n", label, value));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// Write the report to the home directory, returns the path
+    pub fn export(&self, html: bool) -> Result<String> {
+        let home = std::env::var("HOME")?;
+        let extension = if html { "html" } else { "md" };
+        let path = format!("{}/tuxedo-summary-{}.{}", home, chrono::Local::now().format("%Y%m%d"), extension);
+
+        std::fs::write(&path, if html { self.to_html() } else { self.to_markdown() })?;
+        Ok(path)
+    }
+}
+
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn format_duration(secs: i64) -> String {
+    format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+}
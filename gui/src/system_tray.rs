@@ -1,116 +1,83 @@
+use tokio::sync::mpsc::UnboundedSender;
 use tray_icon::{
-    TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState,
-    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+    TrayIconBuilder,
+    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu},
 };
-use tuxedo_common::types::Profile;
+use crate::app::HardwareUpdate;
 
-pub struct SystemTray {
-    _tray_icon: tray_icon::TrayIcon,
-    menu_rx: std::sync::mpsc::Receiver<MenuEvent>,
-    profile_items: Vec<MenuItem>,
-}
-
-impl SystemTray {
-    pub fn new(profiles: &[Profile], current_profile: &str) -> anyhow::Result<Self> {
-        // Create menu
-        let menu = Menu::new();
-
-        // Add profile submenu
-        let profiles_menu = Menu::new();
-        let mut profile_items = Vec::new();
-
-        for profile in profiles {
-            let item = MenuItem::new(
-                &profile.name,
-                profile.name == current_profile,
-                None
-            );
-            profiles_menu.append(&item)?;
-            profile_items.push(item);
-        }
-
-        let profiles_submenu = Submenu::with_items(
-            "Profiles",
-            true,
-            &profile_items,
-        )?;
-        menu.append(&profiles_submenu)?;
-
-        menu.append(&PredefinedMenuItem::separator())?;
-
-        // Quick actions
-        let show_item = MenuItem::new("Show Window", true, None);
-        menu.append(&show_item)?;
+// The tray lives on a thread of its own: libappindicator needs a running GTK main loop, and
+// the menu items can't leave the thread that created them. Clicks come back as TrayEvents
+// through the channel the app reads its hardware updates from.
 
-        let statistics_item = MenuItem::new("Statistics", true, None);
-        menu.append(&statistics_item)?;
-
-        menu.append(&PredefinedMenuItem::separator())?;
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    ShowWindow,
+    SwitchProfile(String),
+    ShowStatistics,
+    ChargeToFull,
+    Quit,
+}
 
-        let quit_item = MenuItem::new("Quit", true, None);
-        menu.append(&quit_item)?;
+/// Without a StatusNotifier host (plain X11 window managers) this only logs
+pub fn spawn(profiles: Vec<String>, events: UnboundedSender<HardwareUpdate>, ctx: egui::Context) {
+    let result = std::thread::Builder::new()
+        .name("tray".to_string())
+        .spawn(move || {
+            if let Err(e) = run(&profiles, events, ctx) {
+                log::warn!("System tray not available: {}", e);
+            }
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to start the system tray thread: {}", e);
+    }
+}
 
-        // Build tray icon
-        let tray_icon = TrayIconBuilder::new()
-            .with_menu(Box::new(menu))
-            .with_tooltip("TUXEDO Control Center")
-            .with_icon(load_tray_icon())
-            .build()?;
+fn run(profiles: &[String], events: UnboundedSender<HardwareUpdate>, ctx: egui::Context) -> anyhow::Result<()> {
+    gtk::init()?;
 
-        let menu_rx = MenuEvent::receiver();
+    let menu = Menu::new();
+    let mut actions: Vec<(MenuId, TrayEvent)> = Vec::new();
 
-        Ok(Self {
-            _tray_icon: tray_icon,
-            menu_rx: menu_rx.clone(),
-            profile_items,
-        })
+    let profiles_menu = Submenu::new("Profiles", true);
+    for name in profiles {
+        let item = MenuItem::new(name, true, None);
+        profiles_menu.append(&item)?;
+        actions.push((item.id().clone(), TrayEvent::SwitchProfile(name.clone())));
     }
+    menu.append(&profiles_menu)?;
+    menu.append(&PredefinedMenuItem::separator())?;
+
+    for (label, event) in [
+        ("Show Window", TrayEvent::ShowWindow),
+        ("Statistics", TrayEvent::ShowStatistics),
+        ("Charge to 100% Once", TrayEvent::ChargeToFull),
+    ] {
+        let item = MenuItem::new(label, true, None);
+        menu.append(&item)?;
+        actions.push((item.id().clone(), event));
+    }
+    menu.append(&PredefinedMenuItem::separator())?;
 
-    pub fn handle_events(&mut self) -> Option<TrayEvent> {
-        // Check for tray icon clicks
-        if let Ok(event) = TrayIconEvent::receiver().try_recv() {
-            match event {
-                TrayIconEvent::Click { button, button_state, .. } => {
-                    if button == MouseButton::Left && button_state == MouseButtonState::Up {
-                        return Some(TrayEvent::ShowWindow);
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        // Check for menu events
-        if let Ok(event) = self.menu_rx.try_recv() {
-            let id = event.id();
-
-            // Check profile items
-            for (idx, item) in self.profile_items.iter().enumerate() {
-                if item.id() == id {
-                    return Some(TrayEvent::SwitchProfile(idx));
-                }
-            }
+    let quit_item = MenuItem::new("Quit", true, None);
+    menu.append(&quit_item)?;
+    actions.push((quit_item.id().clone(), TrayEvent::Quit));
 
-            // Check other items by text
-            // (In production, you'd store item IDs)
-            // For now, use event order
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        if let Some((_, action)) = actions.iter().find(|(id, _)| *id == event.id) {
+            let _ = events.send(HardwareUpdate::Tray(action.clone()));
+            // The window may be idle and not repainting
+            ctx.request_repaint();
         }
+    }));
 
-        None
-    }
+    let _tray_icon = TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip("TUXEDO Control Center")
+        .with_icon(load_tray_icon())
+        .build()?;
 
-    pub fn update_profiles(&mut self, profiles: &[Profile], current: &str) -> anyhow::Result<()> {
-        // Rebuild menu with new profiles
-        // This is a simplified version - full implementation would update existing menu
-        Ok(())
-    }
-}
-
-pub enum TrayEvent {
-    ShowWindow,
-    HideWindow,
-    SwitchProfile(usize),
-    ShowStatistics,
-    Quit,
+    gtk::main();
+    Ok(())
 }
 
 fn load_tray_icon() -> tray_icon::Icon {
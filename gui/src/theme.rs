@@ -268,6 +268,17 @@ pub fn temp_color(temp: f32) -> Color32 {
     }
 }
 
+// Li-ion cells age quickly above ~45°C, so the bands are much lower than for chips
+pub fn battery_temp_color(temp: f32) -> Color32 {
+    if temp < 35.0 {
+        Color32::from_rgb(100, 200, 120) // Green
+    } else if temp < 45.0 {
+        Color32::from_rgb(255, 200, 60)  // Yellow/orange
+    } else {
+        Color32::from_rgb(255, 80, 80)   // Hot red
+    }
+}
+
 pub fn load_color(load: f32) -> Color32 {
     if load < 30.0 {
         Color32::from_rgb(80, 180, 240)  // Low - blue
@@ -1,5 +1,5 @@
-use egui::{Ui, RichText, Color32};
-use egui_plot::{Plot, PlotPoints, Line, Points, Polygon, PlotPoint};
+use egui::{Ui, RichText, Color32, Key, Modifiers, Id};
+use egui_plot::{HLine, Line, LineStyle, MarkerShape, Plot, PlotBounds, PlotPoint, PlotPoints, Points, Polygon, VLine};
 use tuxedo_common::types::FanCurve;
 
 pub struct FanCurveEditor {
@@ -7,8 +7,17 @@ pub struct FanCurveEditor {
     pub curve: FanCurve,
     selected_point: Option<usize>,
     dragging_point: Option<usize>,
+    max_points: Option<usize>,
+    temp_range: Option<(f32, f32)>,
+    current_temp: Option<f32>,
+    current_duty: Option<u32>,
+    min_duty: Option<u8>,
 }
 
+// Keep some room around the observed readings so points just outside stay reachable
+const OBSERVED_AXIS_MARGIN: f32 = 5.0;
+const OBSERVED_AXIS_MIN_SPAN: f32 = 20.0;
+
 impl FanCurveEditor {
     pub fn new(fan_id: u32, curve: FanCurve) -> Self {
         Self {
@@ -16,12 +25,83 @@ impl FanCurveEditor {
             curve,
             selected_point: None,
             dragging_point: None,
+            max_points: None,
+            temp_range: None,
+            current_temp: None,
+            current_duty: None,
+            min_duty: None,
+        }
+    }
+    
+    // Min/max temperature to show on the X axis instead of the full 0-100 °C
+    pub fn with_temp_range(mut self, range: Option<(f32, f32)>) -> Self {
+        self.temp_range = range;
+        self
+    }
+    
+    pub fn with_current_temp(mut self, temp: Option<f32>) -> Self {
+        self.current_temp = temp;
+        self
+    }
+    
+    // Duty the fan is actually running at, which may differ from the curve (EC mode, clamshell floor)
+    pub fn with_current_duty(mut self, duty: Option<u32>) -> Self {
+        self.current_duty = duty;
+        self
+    }
+    
+    // Floor the daemon raises every non-zero duty to, shaded below the curve
+    pub fn with_min_duty(mut self, min_duty: Option<u8>) -> Self {
+        self.min_duty = min_duty.filter(|duty| *duty > 0);
+        self
+    }
+    
+    fn x_bounds(&self) -> (f64, f64) {
+        let (min, max) = match self.temp_range {
+            Some(range) => range,
+            None => return (0.0, 100.0),
+        };
+        
+        // Round out to 5 °C steps so the grid lines stay on whole numbers
+        let mut lo = ((min - OBSERVED_AXIS_MARGIN) / 5.0).floor() * 5.0;
+        let mut hi = ((max + OBSERVED_AXIS_MARGIN) / 5.0).ceil() * 5.0;
+        if hi - lo < OBSERVED_AXIS_MIN_SPAN {
+            let center = (lo + hi) / 2.0;
+            lo = center - OBSERVED_AXIS_MIN_SPAN / 2.0;
+            hi = center + OBSERVED_AXIS_MIN_SPAN / 2.0;
+        }
+        (lo.clamp(0.0, 100.0) as f64, hi.clamp(0.0, 100.0) as f64)
+    }
+    
+    // None = no limit, the daemon evaluates the curve in software
+    pub fn with_max_points(mut self, max_points: Option<usize>) -> Self {
+        self.max_points = max_points;
+        self
+    }
+    
+    fn can_add_point(&self) -> bool {
+        match self.max_points {
+            Some(max) => self.curve.points.len() < max,
+            None => true,
         }
     }
     
     pub fn show(&mut self, ui: &mut Ui) {
+        // The editor is rebuilt every frame, keep the selection in egui memory
+        let selection_id = Id::new(("fan_curve_selected", self.fan_id));
+        if self.selected_point.is_none() {
+            self.selected_point = ui.data(|d| d.get_temp::<usize>(selection_id))
+                .filter(|idx| *idx < self.curve.points.len());
+        }
+        
         ui.vertical(|ui| {
-            ui.heading(format!("Fan {} Curve", self.fan_id));
+            ui.horizontal(|ui| {
+                ui.heading(format!("Fan {} Curve", self.fan_id));
+                if let (Some(temp), Some(duty)) = (self.current_temp, self.current_duty) {
+                    ui.label(RichText::new(format!("Now: {:.0}°C → {}%", temp, duty))
+                        .color(Color32::from_rgb(120, 200, 120)));
+                }
+            });
             ui.add_space(8.0);
             
             // Graph with dragging
@@ -36,18 +116,82 @@ impl FanCurveEditor {
             
             // Controls
             ui.horizontal(|ui| {
-                if ui.button("➕ Add Point").clicked() {
+                if ui.add_enabled(self.can_add_point(), egui::Button::new("➕ Add Point")).clicked() {
                     self.add_point();
                 }
                 
                 if ui.button("↺ Reset to Default").clicked() {
                     self.reset_to_default();
                 }
+                
+                self.draw_exact_values_popup(ui);
             });
         });
+        
+        ui.data_mut(|d| match self.selected_point {
+            Some(idx) => d.insert_temp(selection_id, idx),
+            None => d.remove::<usize>(selection_id),
+        });
+    }
+    
+    fn draw_exact_values_popup(&mut self, ui: &mut Ui) {
+        let idx = match self.selected_point {
+            Some(idx) => idx,
+            None => return,
+        };
+        
+        ui.menu_button(format!("✏ Point {} values", idx + 1), |ui| {
+            let (mut temp, mut speed) = self.curve.points[idx];
+            egui::Grid::new(format!("exact_point_{}", self.fan_id))
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Temperature:");
+                    ui.add(egui::DragValue::new(&mut temp).range(0..=100).suffix("°C"));
+                    ui.end_row();
+                    
+                    ui.label("Fan speed:");
+                    ui.add(egui::DragValue::new(&mut speed).range(0..=100).suffix("%"));
+                    ui.end_row();
+                });
+            self.curve.points[idx] = (temp, speed);
+        });
+    }
+    
+    // Arrow keys and scroll wheel move the selected point by 1 °C / 1 %
+    fn handle_nudging(&mut self, ui: &Ui, plot_hovered: bool) {
+        let idx = match self.selected_point {
+            Some(idx) if plot_hovered => idx,
+            _ => return,
+        };
+        
+        let (mut dtemp, mut dspeed) = (0i32, 0i32);
+        ui.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::ArrowLeft) { dtemp -= 1; }
+            if i.consume_key(Modifiers::NONE, Key::ArrowRight) { dtemp += 1; }
+            if i.consume_key(Modifiers::NONE, Key::ArrowDown) { dspeed -= 1; }
+            if i.consume_key(Modifiers::NONE, Key::ArrowUp) { dspeed += 1; }
+            
+            // Shift + wheel adjusts the temperature instead of the speed
+            let scroll = i.raw_scroll_delta.y + i.raw_scroll_delta.x;
+            if scroll != 0.0 {
+                let step = scroll.signum() as i32;
+                if i.modifiers.shift { dtemp += step; } else { dspeed += step; }
+            }
+            // Keep the surrounding scroll area still while nudging
+            i.smooth_scroll_delta = egui::Vec2::ZERO;
+        });
+        
+        if dtemp != 0 || dspeed != 0 {
+            let (temp, speed) = self.curve.points[idx];
+            self.curve.points[idx] = (
+                (temp as i32 + dtemp).clamp(0, 100) as u8,
+                (speed as i32 + dspeed).clamp(0, 100) as u8,
+            );
+        }
     }
     
     fn draw_graph(&mut self, ui: &mut Ui) {
+        let (x_min, x_max) = self.x_bounds();
         let plot = Plot::new(format!("fan_curve_{}", self.fan_id))
             .height(300.0)
             .width(ui.available_width())
@@ -59,15 +203,22 @@ impl FanCurveEditor {
             .allow_drag(false)
             .allow_boxed_zoom(false)
             .allow_scroll(false)
-            .include_x(0.0)
-            .include_x(100.0)
+            .include_x(x_min)
+            .include_x(x_max)
             .include_y(0.0)
             .include_y(100.0)
             .set_margin_fraction(egui::vec2(0.05, 0.05));
         
         let response = plot.show(ui, |plot_ui| {
+            // Points outside the observed range must not widen the axis again
+            if self.temp_range.is_some() {
+                plot_ui.set_plot_bounds(PlotBounds::from_min_max([x_min, -5.0], [x_max, 105.0]));
+            }
+            
+
             // Draw reference zones first
             self.draw_reference_zones(plot_ui);
+            self.draw_min_duty_floor(plot_ui, x_min, x_max);
             
             // Sort points by temperature
             let mut sorted = self.curve.points.clone();
@@ -85,6 +236,35 @@ impl FanCurveEditor {
                     .width(2.0)
             );
             
+            if let Some(temp) = self.current_temp {
+                plot_ui.vline(
+                    VLine::new(temp as f64)
+                        .color(Color32::from_rgb(120, 200, 120))
+                        .style(LineStyle::dashed_loose())
+                        .name(format!("Current {:.0}°C", temp))
+                );
+            }
+            
+            if let Some(duty) = self.current_duty {
+                plot_ui.hline(
+                    HLine::new(duty as f64)
+                        .color(Color32::from_rgb(120, 200, 120))
+                        .style(LineStyle::dotted_loose())
+                        .name(format!("Applied {}%", duty))
+                );
+                
+                // Where on the plot the fan is operating right now
+                if let Some(temp) = self.current_temp {
+                    plot_ui.points(
+                        Points::new(PlotPoints::new(vec![[temp as f64, duty as f64]]))
+                            .color(Color32::from_rgb(120, 200, 120))
+                            .shape(MarkerShape::Diamond)
+                            .radius(7.0)
+                            .name("Operating point")
+                    );
+                }
+            }
+            
             // Draw and handle point interactions
             for (idx, (temp, speed)) in self.curve.points.iter().enumerate() {
                 let point = PlotPoint::new(*temp as f64, *speed as f64);
@@ -153,6 +333,8 @@ impl FanCurveEditor {
                 }
             }
         });
+        
+        self.handle_nudging(ui, response.response.hovered());
     }
     
     fn draw_reference_zones(&self, plot_ui: &mut egui_plot::PlotUi) {
@@ -211,6 +393,32 @@ impl FanCurveEditor {
         );
     }
     
+    fn draw_min_duty_floor(&self, plot_ui: &mut egui_plot::PlotUi, x_min: f64, x_max: f64) {
+        let floor = match self.min_duty {
+            Some(floor) => floor as f64,
+            None => return,
+        };
+        
+        let region = vec![
+            PlotPoint::new(x_min, 0.0),
+            PlotPoint::new(x_max, 0.0),
+            PlotPoint::new(x_max, floor),
+            PlotPoint::new(x_min, floor),
+        ];
+        plot_ui.polygon(
+            Polygon::new(PlotPoints::Owned(region))
+                .fill_color(Color32::from_rgba_unmultiplied(128, 128, 128, 60))
+                .stroke(egui::Stroke::NONE)
+                .name(format!("Minimum duty {}%", floor))
+        );
+        plot_ui.hline(
+            HLine::new(floor)
+                .color(Color32::from_gray(140))
+                .style(LineStyle::dashed_dense())
+                .name(format!("Minimum duty {}%", floor))
+        );
+    }
+    
     fn draw_points_editor(&mut self, ui: &mut Ui) {
         ui.label(RichText::new("Control Points:").strong());
         
@@ -285,19 +493,31 @@ impl FanCurveEditor {
         }
         
         ui.add_space(6.0);
-        ui.label(RichText::new(format!("Total points: {} (min: 2, max: 16)", self.curve.points.len()))
+        let limit = match self.max_points {
+            Some(max) => format!("max: {}", max),
+            None => "no maximum".to_string(),
+        };
+        ui.label(RichText::new(format!("Total points: {} (min: 2, {})", self.curve.points.len(), limit))
             .small()
             .italics());
         
+        if let Some(floor) = self.min_duty {
+            if self.curve.points.iter().any(|(_, speed)| *speed > 0 && *speed < floor) {
+                ui.label(RichText::new(format!("Speeds between 1 and {}% run at {}%, this fan stalls below it", floor - 1, floor))
+                    .small()
+                    .color(Color32::from_rgb(230, 180, 60)));
+            }
+        }
+        
         if self.selected_point.is_some() {
-            ui.label(RichText::new("💡 Tip: Click and drag points on the graph to adjust them")
+            ui.label(RichText::new("💡 Tip: Drag points on the graph, or hover it and use arrow keys / scroll wheel (Shift for temperature)")
                 .small()
                 .italics());
         }
     }
     
     fn add_point(&mut self) {
-        if self.curve.points.len() >= 16 {
+        if !self.can_add_point() {
             return;
         }
         